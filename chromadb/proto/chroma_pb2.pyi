@@ -22,6 +22,33 @@ class SegmentScope(int, metaclass=_enum_type_wrapper.EnumTypeWrapper):
     __slots__ = []
     VECTOR: _ClassVar[SegmentScope]
     METADATA: _ClassVar[SegmentScope]
+
+class BooleanOperator(int, metaclass=_enum_type_wrapper.EnumTypeWrapper):
+    __slots__ = []
+    AND: _ClassVar[BooleanOperator]
+    OR: _ClassVar[BooleanOperator]
+
+class GenericComparator(int, metaclass=_enum_type_wrapper.EnumTypeWrapper):
+    __slots__ = []
+    EQ: _ClassVar[GenericComparator]
+    NE: _ClassVar[GenericComparator]
+
+class NumberComparator(int, metaclass=_enum_type_wrapper.EnumTypeWrapper):
+    __slots__ = []
+    GT: _ClassVar[NumberComparator]
+    GTE: _ClassVar[NumberComparator]
+    LT: _ClassVar[NumberComparator]
+    LTE: _ClassVar[NumberComparator]
+
+class ListOperator(int, metaclass=_enum_type_wrapper.EnumTypeWrapper):
+    __slots__ = []
+    IN: _ClassVar[ListOperator]
+    NIN: _ClassVar[ListOperator]
+
+class WhereDocumentOperator(int, metaclass=_enum_type_wrapper.EnumTypeWrapper):
+    __slots__ = []
+    CONTAINS: _ClassVar[WhereDocumentOperator]
+    NOT_CONTAINS: _ClassVar[WhereDocumentOperator]
 ADD: Operation
 UPDATE: Operation
 UPSERT: Operation
@@ -30,6 +57,18 @@ FLOAT32: ScalarEncoding
 INT32: ScalarEncoding
 VECTOR: SegmentScope
 METADATA: SegmentScope
+AND: BooleanOperator
+OR: BooleanOperator
+EQ: GenericComparator
+NE: GenericComparator
+GT: NumberComparator
+GTE: NumberComparator
+LT: NumberComparator
+LTE: NumberComparator
+IN: ListOperator
+NIN: ListOperator
+CONTAINS: WhereDocumentOperator
+NOT_CONTAINS: WhereDocumentOperator
 
 class Status(_message.Message):
     __slots__ = ["reason", "code"]
@@ -164,6 +203,138 @@ class VectorQueryResults(_message.Message):
     results: _containers.RepeatedCompositeFieldContainer[VectorQueryResult]
     def __init__(self, results: _Optional[_Iterable[_Union[VectorQueryResult, _Mapping]]] = ...) -> None: ...
 
+class SingleStringComparison(_message.Message):
+    __slots__ = ["value", "comparator"]
+    VALUE_FIELD_NUMBER: _ClassVar[int]
+    COMPARATOR_FIELD_NUMBER: _ClassVar[int]
+    value: str
+    comparator: GenericComparator
+    def __init__(self, value: _Optional[str] = ..., comparator: _Optional[_Union[GenericComparator, str]] = ...) -> None: ...
+
+class SingleIntComparison(_message.Message):
+    __slots__ = ["value", "generic_comparator", "number_comparator"]
+    VALUE_FIELD_NUMBER: _ClassVar[int]
+    GENERIC_COMPARATOR_FIELD_NUMBER: _ClassVar[int]
+    NUMBER_COMPARATOR_FIELD_NUMBER: _ClassVar[int]
+    value: int
+    generic_comparator: GenericComparator
+    number_comparator: NumberComparator
+    def __init__(self, value: _Optional[int] = ..., generic_comparator: _Optional[_Union[GenericComparator, str]] = ..., number_comparator: _Optional[_Union[NumberComparator, str]] = ...) -> None: ...
+
+class SingleDoubleComparison(_message.Message):
+    __slots__ = ["value", "generic_comparator", "number_comparator"]
+    VALUE_FIELD_NUMBER: _ClassVar[int]
+    GENERIC_COMPARATOR_FIELD_NUMBER: _ClassVar[int]
+    NUMBER_COMPARATOR_FIELD_NUMBER: _ClassVar[int]
+    value: float
+    generic_comparator: GenericComparator
+    number_comparator: NumberComparator
+    def __init__(self, value: _Optional[float] = ..., generic_comparator: _Optional[_Union[GenericComparator, str]] = ..., number_comparator: _Optional[_Union[NumberComparator, str]] = ...) -> None: ...
+
+class SingleBoolComparison(_message.Message):
+    __slots__ = ["value", "comparator"]
+    VALUE_FIELD_NUMBER: _ClassVar[int]
+    COMPARATOR_FIELD_NUMBER: _ClassVar[int]
+    value: bool
+    comparator: GenericComparator
+    def __init__(self, value: bool = ..., comparator: _Optional[_Union[GenericComparator, str]] = ...) -> None: ...
+
+class StringListComparison(_message.Message):
+    __slots__ = ["values", "list_operator"]
+    VALUES_FIELD_NUMBER: _ClassVar[int]
+    LIST_OPERATOR_FIELD_NUMBER: _ClassVar[int]
+    values: _containers.RepeatedScalarFieldContainer[str]
+    list_operator: ListOperator
+    def __init__(self, values: _Optional[_Iterable[str]] = ..., list_operator: _Optional[_Union[ListOperator, str]] = ...) -> None: ...
+
+class IntListComparison(_message.Message):
+    __slots__ = ["values", "list_operator"]
+    VALUES_FIELD_NUMBER: _ClassVar[int]
+    LIST_OPERATOR_FIELD_NUMBER: _ClassVar[int]
+    values: _containers.RepeatedScalarFieldContainer[int]
+    list_operator: ListOperator
+    def __init__(self, values: _Optional[_Iterable[int]] = ..., list_operator: _Optional[_Union[ListOperator, str]] = ...) -> None: ...
+
+class DoubleListComparison(_message.Message):
+    __slots__ = ["values", "list_operator"]
+    VALUES_FIELD_NUMBER: _ClassVar[int]
+    LIST_OPERATOR_FIELD_NUMBER: _ClassVar[int]
+    values: _containers.RepeatedScalarFieldContainer[float]
+    list_operator: ListOperator
+    def __init__(self, values: _Optional[_Iterable[float]] = ..., list_operator: _Optional[_Union[ListOperator, str]] = ...) -> None: ...
+
+class BoolListComparison(_message.Message):
+    __slots__ = ["values", "list_operator"]
+    VALUES_FIELD_NUMBER: _ClassVar[int]
+    LIST_OPERATOR_FIELD_NUMBER: _ClassVar[int]
+    values: _containers.RepeatedScalarFieldContainer[bool]
+    list_operator: ListOperator
+    def __init__(self, values: _Optional[_Iterable[bool]] = ..., list_operator: _Optional[_Union[ListOperator, str]] = ...) -> None: ...
+
+class DirectComparison(_message.Message):
+    __slots__ = ["key", "single_string_operand", "string_list_operand", "single_int_operand", "int_list_operand", "single_double_operand", "double_list_operand", "single_bool_operand", "bool_list_operand"]
+    KEY_FIELD_NUMBER: _ClassVar[int]
+    SINGLE_STRING_OPERAND_FIELD_NUMBER: _ClassVar[int]
+    STRING_LIST_OPERAND_FIELD_NUMBER: _ClassVar[int]
+    SINGLE_INT_OPERAND_FIELD_NUMBER: _ClassVar[int]
+    INT_LIST_OPERAND_FIELD_NUMBER: _ClassVar[int]
+    SINGLE_DOUBLE_OPERAND_FIELD_NUMBER: _ClassVar[int]
+    DOUBLE_LIST_OPERAND_FIELD_NUMBER: _ClassVar[int]
+    SINGLE_BOOL_OPERAND_FIELD_NUMBER: _ClassVar[int]
+    BOOL_LIST_OPERAND_FIELD_NUMBER: _ClassVar[int]
+    key: str
+    single_string_operand: SingleStringComparison
+    string_list_operand: StringListComparison
+    single_int_operand: SingleIntComparison
+    int_list_operand: IntListComparison
+    single_double_operand: SingleDoubleComparison
+    double_list_operand: DoubleListComparison
+    single_bool_operand: SingleBoolComparison
+    bool_list_operand: BoolListComparison
+    def __init__(self, key: _Optional[str] = ..., single_string_operand: _Optional[_Union[SingleStringComparison, _Mapping]] = ..., string_list_operand: _Optional[_Union[StringListComparison, _Mapping]] = ..., single_int_operand: _Optional[_Union[SingleIntComparison, _Mapping]] = ..., int_list_operand: _Optional[_Union[IntListComparison, _Mapping]] = ..., single_double_operand: _Optional[_Union[SingleDoubleComparison, _Mapping]] = ..., double_list_operand: _Optional[_Union[DoubleListComparison, _Mapping]] = ..., single_bool_operand: _Optional[_Union[SingleBoolComparison, _Mapping]] = ..., bool_list_operand: _Optional[_Union[BoolListComparison, _Mapping]] = ...) -> None: ...
+
+class WhereChildren(_message.Message):
+    __slots__ = ["children", "operator"]
+    CHILDREN_FIELD_NUMBER: _ClassVar[int]
+    OPERATOR_FIELD_NUMBER: _ClassVar[int]
+    children: _containers.RepeatedCompositeFieldContainer[Where]
+    operator: BooleanOperator
+    def __init__(self, children: _Optional[_Iterable[_Union[Where, _Mapping]]] = ..., operator: _Optional[_Union[BooleanOperator, str]] = ...) -> None: ...
+
+class Where(_message.Message):
+    __slots__ = ["direct_comparison", "children", "not_clause"]
+    DIRECT_COMPARISON_FIELD_NUMBER: _ClassVar[int]
+    CHILDREN_FIELD_NUMBER: _ClassVar[int]
+    NOT_CLAUSE_FIELD_NUMBER: _ClassVar[int]
+    direct_comparison: DirectComparison
+    children: WhereChildren
+    not_clause: Where
+    def __init__(self, direct_comparison: _Optional[_Union[DirectComparison, _Mapping]] = ..., children: _Optional[_Union[WhereChildren, _Mapping]] = ..., not_clause: _Optional[_Union[Where, _Mapping]] = ...) -> None: ...
+
+class DirectWhereDocument(_message.Message):
+    __slots__ = ["document", "operator"]
+    DOCUMENT_FIELD_NUMBER: _ClassVar[int]
+    OPERATOR_FIELD_NUMBER: _ClassVar[int]
+    document: str
+    operator: WhereDocumentOperator
+    def __init__(self, document: _Optional[str] = ..., operator: _Optional[_Union[WhereDocumentOperator, str]] = ...) -> None: ...
+
+class WhereDocumentChildren(_message.Message):
+    __slots__ = ["children", "operator"]
+    CHILDREN_FIELD_NUMBER: _ClassVar[int]
+    OPERATOR_FIELD_NUMBER: _ClassVar[int]
+    children: _containers.RepeatedCompositeFieldContainer[WhereDocument]
+    operator: BooleanOperator
+    def __init__(self, children: _Optional[_Iterable[_Union[WhereDocument, _Mapping]]] = ..., operator: _Optional[_Union[BooleanOperator, str]] = ...) -> None: ...
+
+class WhereDocument(_message.Message):
+    __slots__ = ["direct", "children"]
+    DIRECT_FIELD_NUMBER: _ClassVar[int]
+    CHILDREN_FIELD_NUMBER: _ClassVar[int]
+    direct: DirectWhereDocument
+    children: WhereDocumentChildren
+    def __init__(self, direct: _Optional[_Union[DirectWhereDocument, _Mapping]] = ..., children: _Optional[_Union[WhereDocumentChildren, _Mapping]] = ...) -> None: ...
+
 class GetVectorsRequest(_message.Message):
     __slots__ = ["ids", "segment_id"]
     IDS_FIELD_NUMBER: _ClassVar[int]
@@ -179,18 +350,26 @@ class GetVectorsResponse(_message.Message):
     def __init__(self, records: _Optional[_Iterable[_Union[VectorEmbeddingRecord, _Mapping]]] = ...) -> None: ...
 
 class QueryVectorsRequest(_message.Message):
-    __slots__ = ["vectors", "k", "allowed_ids", "include_embeddings", "segment_id"]
+    __slots__ = ["vectors", "k", "allowed_ids", "include_embeddings", "segment_id", "query_texts", "metadata_segment_id", "where", "where_document"]
     VECTORS_FIELD_NUMBER: _ClassVar[int]
     K_FIELD_NUMBER: _ClassVar[int]
     ALLOWED_IDS_FIELD_NUMBER: _ClassVar[int]
     INCLUDE_EMBEDDINGS_FIELD_NUMBER: _ClassVar[int]
     SEGMENT_ID_FIELD_NUMBER: _ClassVar[int]
+    QUERY_TEXTS_FIELD_NUMBER: _ClassVar[int]
+    METADATA_SEGMENT_ID_FIELD_NUMBER: _ClassVar[int]
+    WHERE_FIELD_NUMBER: _ClassVar[int]
+    WHERE_DOCUMENT_FIELD_NUMBER: _ClassVar[int]
     vectors: _containers.RepeatedCompositeFieldContainer[Vector]
     k: int
     allowed_ids: _containers.RepeatedScalarFieldContainer[str]
     include_embeddings: bool
     segment_id: str
-    def __init__(self, vectors: _Optional[_Iterable[_Union[Vector, _Mapping]]] = ..., k: _Optional[int] = ..., allowed_ids: _Optional[_Iterable[str]] = ..., include_embeddings: bool = ..., segment_id: _Optional[str] = ...) -> None: ...
+    query_texts: _containers.RepeatedScalarFieldContainer[str]
+    metadata_segment_id: str
+    where: Where
+    where_document: WhereDocument
+    def __init__(self, vectors: _Optional[_Iterable[_Union[Vector, _Mapping]]] = ..., k: _Optional[int] = ..., allowed_ids: _Optional[_Iterable[str]] = ..., include_embeddings: bool = ..., segment_id: _Optional[str] = ..., query_texts: _Optional[_Iterable[str]] = ..., metadata_segment_id: _Optional[str] = ..., where: _Optional[_Union[Where, _Mapping]] = ..., where_document: _Optional[_Union[WhereDocument, _Mapping]] = ...) -> None: ...
 
 class QueryVectorsResponse(_message.Message):
     __slots__ = ["results"]
@@ -164,6 +164,242 @@ func (SegmentScope) EnumDescriptor() ([]byte, []int) {
 	return file_chromadb_proto_chroma_proto_rawDescGZIP(), []int{2}
 }
 
+type BooleanOperator int32
+
+const (
+	BooleanOperator_AND BooleanOperator = 0
+	BooleanOperator_OR  BooleanOperator = 1
+)
+
+// Enum value maps for BooleanOperator.
+var (
+	BooleanOperator_name = map[int32]string{
+		0: "AND",
+		1: "OR",
+	}
+	BooleanOperator_value = map[string]int32{
+		"AND": 0,
+		"OR":  1,
+	}
+)
+
+func (x BooleanOperator) Enum() *BooleanOperator {
+	p := new(BooleanOperator)
+	*p = x
+	return p
+}
+
+func (x BooleanOperator) String() string {
+	return protoimpl.X.EnumStringOf(x.Descriptor(), protoreflect.EnumNumber(x))
+}
+
+func (BooleanOperator) Descriptor() protoreflect.EnumDescriptor {
+	return file_chromadb_proto_chroma_proto_enumTypes[3].Descriptor()
+}
+
+func (BooleanOperator) Type() protoreflect.EnumType {
+	return &file_chromadb_proto_chroma_proto_enumTypes[3]
+}
+
+func (x BooleanOperator) Number() protoreflect.EnumNumber {
+	return protoreflect.EnumNumber(x)
+}
+
+// Deprecated: Use BooleanOperator.Descriptor instead.
+func (BooleanOperator) EnumDescriptor() ([]byte, []int) {
+	return file_chromadb_proto_chroma_proto_rawDescGZIP(), []int{3}
+}
+
+type GenericComparator int32
+
+const (
+	GenericComparator_EQ GenericComparator = 0
+	GenericComparator_NE GenericComparator = 1
+)
+
+// Enum value maps for GenericComparator.
+var (
+	GenericComparator_name = map[int32]string{
+		0: "EQ",
+		1: "NE",
+	}
+	GenericComparator_value = map[string]int32{
+		"EQ": 0,
+		"NE": 1,
+	}
+)
+
+func (x GenericComparator) Enum() *GenericComparator {
+	p := new(GenericComparator)
+	*p = x
+	return p
+}
+
+func (x GenericComparator) String() string {
+	return protoimpl.X.EnumStringOf(x.Descriptor(), protoreflect.EnumNumber(x))
+}
+
+func (GenericComparator) Descriptor() protoreflect.EnumDescriptor {
+	return file_chromadb_proto_chroma_proto_enumTypes[4].Descriptor()
+}
+
+func (GenericComparator) Type() protoreflect.EnumType {
+	return &file_chromadb_proto_chroma_proto_enumTypes[4]
+}
+
+func (x GenericComparator) Number() protoreflect.EnumNumber {
+	return protoreflect.EnumNumber(x)
+}
+
+// Deprecated: Use GenericComparator.Descriptor instead.
+func (GenericComparator) EnumDescriptor() ([]byte, []int) {
+	return file_chromadb_proto_chroma_proto_rawDescGZIP(), []int{4}
+}
+
+type NumberComparator int32
+
+const (
+	NumberComparator_GT  NumberComparator = 0
+	NumberComparator_GTE NumberComparator = 1
+	NumberComparator_LT  NumberComparator = 2
+	NumberComparator_LTE NumberComparator = 3
+)
+
+// Enum value maps for NumberComparator.
+var (
+	NumberComparator_name = map[int32]string{
+		0: "GT",
+		1: "GTE",
+		2: "LT",
+		3: "LTE",
+	}
+	NumberComparator_value = map[string]int32{
+		"GT":  0,
+		"GTE": 1,
+		"LT":  2,
+		"LTE": 3,
+	}
+)
+
+func (x NumberComparator) Enum() *NumberComparator {
+	p := new(NumberComparator)
+	*p = x
+	return p
+}
+
+func (x NumberComparator) String() string {
+	return protoimpl.X.EnumStringOf(x.Descriptor(), protoreflect.EnumNumber(x))
+}
+
+func (NumberComparator) Descriptor() protoreflect.EnumDescriptor {
+	return file_chromadb_proto_chroma_proto_enumTypes[5].Descriptor()
+}
+
+func (NumberComparator) Type() protoreflect.EnumType {
+	return &file_chromadb_proto_chroma_proto_enumTypes[5]
+}
+
+func (x NumberComparator) Number() protoreflect.EnumNumber {
+	return protoreflect.EnumNumber(x)
+}
+
+// Deprecated: Use NumberComparator.Descriptor instead.
+func (NumberComparator) EnumDescriptor() ([]byte, []int) {
+	return file_chromadb_proto_chroma_proto_rawDescGZIP(), []int{5}
+}
+
+type ListOperator int32
+
+const (
+	ListOperator_IN  ListOperator = 0
+	ListOperator_NIN ListOperator = 1
+)
+
+// Enum value maps for ListOperator.
+var (
+	ListOperator_name = map[int32]string{
+		0: "IN",
+		1: "NIN",
+	}
+	ListOperator_value = map[string]int32{
+		"IN":  0,
+		"NIN": 1,
+	}
+)
+
+func (x ListOperator) Enum() *ListOperator {
+	p := new(ListOperator)
+	*p = x
+	return p
+}
+
+func (x ListOperator) String() string {
+	return protoimpl.X.EnumStringOf(x.Descriptor(), protoreflect.EnumNumber(x))
+}
+
+func (ListOperator) Descriptor() protoreflect.EnumDescriptor {
+	return file_chromadb_proto_chroma_proto_enumTypes[6].Descriptor()
+}
+
+func (ListOperator) Type() protoreflect.EnumType {
+	return &file_chromadb_proto_chroma_proto_enumTypes[6]
+}
+
+func (x ListOperator) Number() protoreflect.EnumNumber {
+	return protoreflect.EnumNumber(x)
+}
+
+// Deprecated: Use ListOperator.Descriptor instead.
+func (ListOperator) EnumDescriptor() ([]byte, []int) {
+	return file_chromadb_proto_chroma_proto_rawDescGZIP(), []int{6}
+}
+
+type WhereDocumentOperator int32
+
+const (
+	WhereDocumentOperator_CONTAINS     WhereDocumentOperator = 0
+	WhereDocumentOperator_NOT_CONTAINS WhereDocumentOperator = 1
+)
+
+// Enum value maps for WhereDocumentOperator.
+var (
+	WhereDocumentOperator_name = map[int32]string{
+		0: "CONTAINS",
+		1: "NOT_CONTAINS",
+	}
+	WhereDocumentOperator_value = map[string]int32{
+		"CONTAINS":     0,
+		"NOT_CONTAINS": 1,
+	}
+)
+
+func (x WhereDocumentOperator) Enum() *WhereDocumentOperator {
+	p := new(WhereDocumentOperator)
+	*p = x
+	return p
+}
+
+func (x WhereDocumentOperator) String() string {
+	return protoimpl.X.EnumStringOf(x.Descriptor(), protoreflect.EnumNumber(x))
+}
+
+func (WhereDocumentOperator) Descriptor() protoreflect.EnumDescriptor {
+	return file_chromadb_proto_chroma_proto_enumTypes[7].Descriptor()
+}
+
+func (WhereDocumentOperator) Type() protoreflect.EnumType {
+	return &file_chromadb_proto_chroma_proto_enumTypes[7]
+}
+
+func (x WhereDocumentOperator) Number() protoreflect.EnumNumber {
+	return protoreflect.EnumNumber(x)
+}
+
+// Deprecated: Use WhereDocumentOperator.Descriptor instead.
+func (WhereDocumentOperator) EnumDescriptor() ([]byte, []int) {
+	return file_chromadb_proto_chroma_proto_rawDescGZIP(), []int{7}
+}
+
 type Status struct {
 	state         protoimpl.MessageState
 	sizeCache     protoimpl.SizeCache
@@ -978,17 +1214,17 @@ func (x *VectorQueryResults) GetResults() []*VectorQueryResult {
 	return nil
 }
 
-type GetVectorsRequest struct {
+type SingleStringComparison struct {
 	state         protoimpl.MessageState
 	sizeCache     protoimpl.SizeCache
 	unknownFields protoimpl.UnknownFields
 
-	Ids       []string `protobuf:"bytes,1,rep,name=ids,proto3" json:"ids,omitempty"`
-	SegmentId string   `protobuf:"bytes,2,opt,name=segment_id,json=segmentId,proto3" json:"segment_id,omitempty"`
+	Value      string            `protobuf:"bytes,1,opt,name=value,proto3" json:"value,omitempty"`
+	Comparator GenericComparator `protobuf:"varint,2,opt,name=comparator,proto3,enum=chroma.GenericComparator" json:"comparator,omitempty"`
 }
 
-func (x *GetVectorsRequest) Reset() {
-	*x = GetVectorsRequest{}
+func (x *SingleStringComparison) Reset() {
+	*x = SingleStringComparison{}
 	if protoimpl.UnsafeEnabled {
 		mi := &file_chromadb_proto_chroma_proto_msgTypes[12]
 		ms := protoimpl.X.MessageStateOf(protoimpl.Pointer(x))
@@ -996,13 +1232,13 @@ func (x *GetVectorsRequest) Reset() {
 	}
 }
 
-func (x *GetVectorsRequest) String() string {
+func (x *SingleStringComparison) String() string {
 	return protoimpl.X.MessageStringOf(x)
 }
 
-func (*GetVectorsRequest) ProtoMessage() {}
+func (*SingleStringComparison) ProtoMessage() {}
 
-func (x *GetVectorsRequest) ProtoReflect() protoreflect.Message {
+func (x *SingleStringComparison) ProtoReflect() protoreflect.Message {
 	mi := &file_chromadb_proto_chroma_proto_msgTypes[12]
 	if protoimpl.UnsafeEnabled && x != nil {
 		ms := protoimpl.X.MessageStateOf(protoimpl.Pointer(x))
@@ -1014,35 +1250,40 @@ func (x *GetVectorsRequest) ProtoReflect() protoreflect.Message {
 	return mi.MessageOf(x)
 }
 
-// Deprecated: Use GetVectorsRequest.ProtoReflect.Descriptor instead.
-func (*GetVectorsRequest) Descriptor() ([]byte, []int) {
+// Deprecated: Use SingleStringComparison.ProtoReflect.Descriptor instead.
+func (*SingleStringComparison) Descriptor() ([]byte, []int) {
 	return file_chromadb_proto_chroma_proto_rawDescGZIP(), []int{12}
 }
 
-func (x *GetVectorsRequest) GetIds() []string {
+func (x *SingleStringComparison) GetValue() string {
 	if x != nil {
-		return x.Ids
+		return x.Value
 	}
-	return nil
+	return ""
 }
 
-func (x *GetVectorsRequest) GetSegmentId() string {
+func (x *SingleStringComparison) GetComparator() GenericComparator {
 	if x != nil {
-		return x.SegmentId
+		return x.Comparator
 	}
-	return ""
+	return GenericComparator_EQ
 }
 
-type GetVectorsResponse struct {
+type SingleIntComparison struct {
 	state         protoimpl.MessageState
 	sizeCache     protoimpl.SizeCache
 	unknownFields protoimpl.UnknownFields
 
-	Records []*VectorEmbeddingRecord `protobuf:"bytes,1,rep,name=records,proto3" json:"records,omitempty"`
+	Value int64 `protobuf:"varint,1,opt,name=value,proto3" json:"value,omitempty"`
+	// Types that are assignable to Comparator:
+	//
+	//	*SingleIntComparison_GenericComparator
+	//	*SingleIntComparison_NumberComparator
+	Comparator isSingleIntComparison_Comparator `protobuf_oneof:"comparator"`
 }
 
-func (x *GetVectorsResponse) Reset() {
-	*x = GetVectorsResponse{}
+func (x *SingleIntComparison) Reset() {
+	*x = SingleIntComparison{}
 	if protoimpl.UnsafeEnabled {
 		mi := &file_chromadb_proto_chroma_proto_msgTypes[13]
 		ms := protoimpl.X.MessageStateOf(protoimpl.Pointer(x))
@@ -1050,13 +1291,13 @@ func (x *GetVectorsResponse) Reset() {
 	}
 }
 
-func (x *GetVectorsResponse) String() string {
+func (x *SingleIntComparison) String() string {
 	return protoimpl.X.MessageStringOf(x)
 }
 
-func (*GetVectorsResponse) ProtoMessage() {}
+func (*SingleIntComparison) ProtoMessage() {}
 
-func (x *GetVectorsResponse) ProtoReflect() protoreflect.Message {
+func (x *SingleIntComparison) ProtoReflect() protoreflect.Message {
 	mi := &file_chromadb_proto_chroma_proto_msgTypes[13]
 	if protoimpl.UnsafeEnabled && x != nil {
 		ms := protoimpl.X.MessageStateOf(protoimpl.Pointer(x))
@@ -1068,32 +1309,70 @@ func (x *GetVectorsResponse) ProtoReflect() protoreflect.Message {
 	return mi.MessageOf(x)
 }
 
-// Deprecated: Use GetVectorsResponse.ProtoReflect.Descriptor instead.
-func (*GetVectorsResponse) Descriptor() ([]byte, []int) {
+// Deprecated: Use SingleIntComparison.ProtoReflect.Descriptor instead.
+func (*SingleIntComparison) Descriptor() ([]byte, []int) {
 	return file_chromadb_proto_chroma_proto_rawDescGZIP(), []int{13}
 }
 
-func (x *GetVectorsResponse) GetRecords() []*VectorEmbeddingRecord {
+func (x *SingleIntComparison) GetValue() int64 {
 	if x != nil {
-		return x.Records
+		return x.Value
+	}
+	return 0
+}
+
+func (m *SingleIntComparison) GetComparator() isSingleIntComparison_Comparator {
+	if m != nil {
+		return m.Comparator
 	}
 	return nil
 }
 
-type QueryVectorsRequest struct {
+func (x *SingleIntComparison) GetGenericComparator() GenericComparator {
+	if x, ok := x.GetComparator().(*SingleIntComparison_GenericComparator); ok {
+		return x.GenericComparator
+	}
+	return GenericComparator_EQ
+}
+
+func (x *SingleIntComparison) GetNumberComparator() NumberComparator {
+	if x, ok := x.GetComparator().(*SingleIntComparison_NumberComparator); ok {
+		return x.NumberComparator
+	}
+	return NumberComparator_GT
+}
+
+type isSingleIntComparison_Comparator interface {
+	isSingleIntComparison_Comparator()
+}
+
+type SingleIntComparison_GenericComparator struct {
+	GenericComparator GenericComparator `protobuf:"varint,2,opt,name=generic_comparator,json=genericComparator,proto3,enum=chroma.GenericComparator,oneof"`
+}
+
+type SingleIntComparison_NumberComparator struct {
+	NumberComparator NumberComparator `protobuf:"varint,3,opt,name=number_comparator,json=numberComparator,proto3,enum=chroma.NumberComparator,oneof"`
+}
+
+func (*SingleIntComparison_GenericComparator) isSingleIntComparison_Comparator() {}
+
+func (*SingleIntComparison_NumberComparator) isSingleIntComparison_Comparator() {}
+
+type SingleDoubleComparison struct {
 	state         protoimpl.MessageState
 	sizeCache     protoimpl.SizeCache
 	unknownFields protoimpl.UnknownFields
 
-	Vectors           []*Vector `protobuf:"bytes,1,rep,name=vectors,proto3" json:"vectors,omitempty"`
-	K                 int32     `protobuf:"varint,2,opt,name=k,proto3" json:"k,omitempty"`
-	AllowedIds        []string  `protobuf:"bytes,3,rep,name=allowed_ids,json=allowedIds,proto3" json:"allowed_ids,omitempty"`
-	IncludeEmbeddings bool      `protobuf:"varint,4,opt,name=include_embeddings,json=includeEmbeddings,proto3" json:"include_embeddings,omitempty"`
-	SegmentId         string    `protobuf:"bytes,5,opt,name=segment_id,json=segmentId,proto3" json:"segment_id,omitempty"` // TODO: options as in types.py, its currently unused so can add later
+	Value float64 `protobuf:"fixed64,1,opt,name=value,proto3" json:"value,omitempty"`
+	// Types that are assignable to Comparator:
+	//
+	//	*SingleDoubleComparison_GenericComparator
+	//	*SingleDoubleComparison_NumberComparator
+	Comparator isSingleDoubleComparison_Comparator `protobuf_oneof:"comparator"`
 }
 
-func (x *QueryVectorsRequest) Reset() {
-	*x = QueryVectorsRequest{}
+func (x *SingleDoubleComparison) Reset() {
+	*x = SingleDoubleComparison{}
 	if protoimpl.UnsafeEnabled {
 		mi := &file_chromadb_proto_chroma_proto_msgTypes[14]
 		ms := protoimpl.X.MessageStateOf(protoimpl.Pointer(x))
@@ -1101,13 +1380,13 @@ func (x *QueryVectorsRequest) Reset() {
 	}
 }
 
-func (x *QueryVectorsRequest) String() string {
+func (x *SingleDoubleComparison) String() string {
 	return protoimpl.X.MessageStringOf(x)
 }
 
-func (*QueryVectorsRequest) ProtoMessage() {}
+func (*SingleDoubleComparison) ProtoMessage() {}
 
-func (x *QueryVectorsRequest) ProtoReflect() protoreflect.Message {
+func (x *SingleDoubleComparison) ProtoReflect() protoreflect.Message {
 	mi := &file_chromadb_proto_chroma_proto_msgTypes[14]
 	if protoimpl.UnsafeEnabled && x != nil {
 		ms := protoimpl.X.MessageStateOf(protoimpl.Pointer(x))
@@ -1119,56 +1398,66 @@ func (x *QueryVectorsRequest) ProtoReflect() protoreflect.Message {
 	return mi.MessageOf(x)
 }
 
-// Deprecated: Use QueryVectorsRequest.ProtoReflect.Descriptor instead.
-func (*QueryVectorsRequest) Descriptor() ([]byte, []int) {
+// Deprecated: Use SingleDoubleComparison.ProtoReflect.Descriptor instead.
+func (*SingleDoubleComparison) Descriptor() ([]byte, []int) {
 	return file_chromadb_proto_chroma_proto_rawDescGZIP(), []int{14}
 }
 
-func (x *QueryVectorsRequest) GetVectors() []*Vector {
-	if x != nil {
-		return x.Vectors
-	}
-	return nil
-}
-
-func (x *QueryVectorsRequest) GetK() int32 {
+func (x *SingleDoubleComparison) GetValue() float64 {
 	if x != nil {
-		return x.K
+		return x.Value
 	}
 	return 0
 }
 
-func (x *QueryVectorsRequest) GetAllowedIds() []string {
-	if x != nil {
-		return x.AllowedIds
+func (m *SingleDoubleComparison) GetComparator() isSingleDoubleComparison_Comparator {
+	if m != nil {
+		return m.Comparator
 	}
 	return nil
 }
 
-func (x *QueryVectorsRequest) GetIncludeEmbeddings() bool {
-	if x != nil {
-		return x.IncludeEmbeddings
+func (x *SingleDoubleComparison) GetGenericComparator() GenericComparator {
+	if x, ok := x.GetComparator().(*SingleDoubleComparison_GenericComparator); ok {
+		return x.GenericComparator
 	}
-	return false
+	return GenericComparator_EQ
 }
 
-func (x *QueryVectorsRequest) GetSegmentId() string {
-	if x != nil {
-		return x.SegmentId
+func (x *SingleDoubleComparison) GetNumberComparator() NumberComparator {
+	if x, ok := x.GetComparator().(*SingleDoubleComparison_NumberComparator); ok {
+		return x.NumberComparator
 	}
-	return ""
+	return NumberComparator_GT
 }
 
-type QueryVectorsResponse struct {
+type isSingleDoubleComparison_Comparator interface {
+	isSingleDoubleComparison_Comparator()
+}
+
+type SingleDoubleComparison_GenericComparator struct {
+	GenericComparator GenericComparator `protobuf:"varint,2,opt,name=generic_comparator,json=genericComparator,proto3,enum=chroma.GenericComparator,oneof"`
+}
+
+type SingleDoubleComparison_NumberComparator struct {
+	NumberComparator NumberComparator `protobuf:"varint,3,opt,name=number_comparator,json=numberComparator,proto3,enum=chroma.NumberComparator,oneof"`
+}
+
+func (*SingleDoubleComparison_GenericComparator) isSingleDoubleComparison_Comparator() {}
+
+func (*SingleDoubleComparison_NumberComparator) isSingleDoubleComparison_Comparator() {}
+
+type SingleBoolComparison struct {
 	state         protoimpl.MessageState
 	sizeCache     protoimpl.SizeCache
 	unknownFields protoimpl.UnknownFields
 
-	Results []*VectorQueryResults `protobuf:"bytes,1,rep,name=results,proto3" json:"results,omitempty"`
+	Value      bool              `protobuf:"varint,1,opt,name=value,proto3" json:"value,omitempty"`
+	Comparator GenericComparator `protobuf:"varint,2,opt,name=comparator,proto3,enum=chroma.GenericComparator" json:"comparator,omitempty"`
 }
 
-func (x *QueryVectorsResponse) Reset() {
-	*x = QueryVectorsResponse{}
+func (x *SingleBoolComparison) Reset() {
+	*x = SingleBoolComparison{}
 	if protoimpl.UnsafeEnabled {
 		mi := &file_chromadb_proto_chroma_proto_msgTypes[15]
 		ms := protoimpl.X.MessageStateOf(protoimpl.Pointer(x))
@@ -1176,13 +1465,13 @@ func (x *QueryVectorsResponse) Reset() {
 	}
 }
 
-func (x *QueryVectorsResponse) String() string {
+func (x *SingleBoolComparison) String() string {
 	return protoimpl.X.MessageStringOf(x)
 }
 
-func (*QueryVectorsResponse) ProtoMessage() {}
+func (*SingleBoolComparison) ProtoMessage() {}
 
-func (x *QueryVectorsResponse) ProtoReflect() protoreflect.Message {
+func (x *SingleBoolComparison) ProtoReflect() protoreflect.Message {
 	mi := &file_chromadb_proto_chroma_proto_msgTypes[15]
 	if protoimpl.UnsafeEnabled && x != nil {
 		ms := protoimpl.X.MessageStateOf(protoimpl.Pointer(x))
@@ -1194,40 +1483,1045 @@ func (x *QueryVectorsResponse) ProtoReflect() protoreflect.Message {
 	return mi.MessageOf(x)
 }
 
-// Deprecated: Use QueryVectorsResponse.ProtoReflect.Descriptor instead.
-func (*QueryVectorsResponse) Descriptor() ([]byte, []int) {
+// Deprecated: Use SingleBoolComparison.ProtoReflect.Descriptor instead.
+func (*SingleBoolComparison) Descriptor() ([]byte, []int) {
 	return file_chromadb_proto_chroma_proto_rawDescGZIP(), []int{15}
 }
 
-func (x *QueryVectorsResponse) GetResults() []*VectorQueryResults {
+func (x *SingleBoolComparison) GetValue() bool {
 	if x != nil {
-		return x.Results
+		return x.Value
 	}
-	return nil
+	return false
 }
 
-var File_chromadb_proto_chroma_proto protoreflect.FileDescriptor
+func (x *SingleBoolComparison) GetComparator() GenericComparator {
+	if x != nil {
+		return x.Comparator
+	}
+	return GenericComparator_EQ
+}
 
-var file_chromadb_proto_chroma_proto_rawDesc = []byte{
-	0x0a, 0x1b, 0x63, 0x68, 0x72, 0x6f, 0x6d, 0x61, 0x64, 0x62, 0x2f, 0x70, 0x72, 0x6f, 0x74, 0x6f,
-	0x2f, 0x63, 0x68, 0x72, 0x6f, 0x6d, 0x61, 0x2e, 0x70, 0x72, 0x6f, 0x74, 0x6f, 0x12, 0x06, 0x63,
-	0x68, 0x72, 0x6f, 0x6d, 0x61, 0x22, 0x34, 0x0a, 0x06, 0x53, 0x74, 0x61, 0x74, 0x75, 0x73, 0x12,
-	0x16, 0x0a, 0x06, 0x72, 0x65, 0x61, 0x73, 0x6f, 0x6e, 0x18, 0x01, 0x20, 0x01, 0x28, 0x09, 0x52,
-	0x06, 0x72, 0x65, 0x61, 0x73, 0x6f, 0x6e, 0x12, 0x12, 0x0a, 0x04, 0x63, 0x6f, 0x64, 0x65, 0x18,
-	0x02, 0x20, 0x01, 0x28, 0x05, 0x52, 0x04, 0x63, 0x6f, 0x64, 0x65, 0x22, 0x72, 0x0a, 0x06, 0x56,
-	0x65, 0x63, 0x74, 0x6f, 0x72, 0x12, 0x1c, 0x0a, 0x09, 0x64, 0x69, 0x6d, 0x65, 0x6e, 0x73, 0x69,
-	0x6f, 0x6e, 0x18, 0x01, 0x20, 0x01, 0x28, 0x05, 0x52, 0x09, 0x64, 0x69, 0x6d, 0x65, 0x6e, 0x73,
-	0x69, 0x6f, 0x6e, 0x12, 0x16, 0x0a, 0x06, 0x76, 0x65, 0x63, 0x74, 0x6f, 0x72, 0x18, 0x02, 0x20,
-	0x01, 0x28, 0x0c, 0x52, 0x06, 0x76, 0x65, 0x63, 0x74, 0x6f, 0x72, 0x12, 0x32, 0x0a, 0x08, 0x65,
-	0x6e, 0x63, 0x6f, 0x64, 0x69, 0x6e, 0x67, 0x18, 0x03, 0x20, 0x01, 0x28, 0x0e, 0x32, 0x16, 0x2e,
-	0x63, 0x68, 0x72, 0x6f, 0x6d, 0x61, 0x2e, 0x53, 0x63, 0x61, 0x6c, 0x61, 0x72, 0x45, 0x6e, 0x63,
-	0x6f, 0x64, 0x69, 0x6e, 0x67, 0x52, 0x08, 0x65, 0x6e, 0x63, 0x6f, 0x64, 0x69, 0x6e, 0x67, 0x22,
-	0xf8, 0x01, 0x0a, 0x07, 0x53, 0x65, 0x67, 0x6d, 0x65, 0x6e, 0x74, 0x12, 0x0e, 0x0a, 0x02, 0x69,
-	0x64, 0x18, 0x01, 0x20, 0x01, 0x28, 0x09, 0x52, 0x02, 0x69, 0x64, 0x12, 0x12, 0x0a, 0x04, 0x74,
-	0x79, 0x70, 0x65, 0x18, 0x02, 0x20, 0x01, 0x28, 0x09, 0x52, 0x04, 0x74, 0x79, 0x70, 0x65, 0x12,
-	0x2a, 0x0a, 0x05, 0x73, 0x63, 0x6f, 0x70, 0x65, 0x18, 0x03, 0x20, 0x01, 0x28, 0x0e, 0x32, 0x14,
-	0x2e, 0x63, 0x68, 0x72, 0x6f, 0x6d, 0x61, 0x2e, 0x53, 0x65, 0x67, 0x6d, 0x65, 0x6e, 0x74, 0x53,
-	0x63, 0x6f, 0x70, 0x65, 0x52, 0x05, 0x73, 0x63, 0x6f, 0x70, 0x65, 0x12, 0x19, 0x0a, 0x05, 0x74,
+type StringListComparison struct {
+	state         protoimpl.MessageState
+	sizeCache     protoimpl.SizeCache
+	unknownFields protoimpl.UnknownFields
+
+	Values       []string     `protobuf:"bytes,1,rep,name=values,proto3" json:"values,omitempty"`
+	ListOperator ListOperator `protobuf:"varint,2,opt,name=list_operator,json=listOperator,proto3,enum=chroma.ListOperator" json:"list_operator,omitempty"`
+}
+
+func (x *StringListComparison) Reset() {
+	*x = StringListComparison{}
+	if protoimpl.UnsafeEnabled {
+		mi := &file_chromadb_proto_chroma_proto_msgTypes[16]
+		ms := protoimpl.X.MessageStateOf(protoimpl.Pointer(x))
+		ms.StoreMessageInfo(mi)
+	}
+}
+
+func (x *StringListComparison) String() string {
+	return protoimpl.X.MessageStringOf(x)
+}
+
+func (*StringListComparison) ProtoMessage() {}
+
+func (x *StringListComparison) ProtoReflect() protoreflect.Message {
+	mi := &file_chromadb_proto_chroma_proto_msgTypes[16]
+	if protoimpl.UnsafeEnabled && x != nil {
+		ms := protoimpl.X.MessageStateOf(protoimpl.Pointer(x))
+		if ms.LoadMessageInfo() == nil {
+			ms.StoreMessageInfo(mi)
+		}
+		return ms
+	}
+	return mi.MessageOf(x)
+}
+
+// Deprecated: Use StringListComparison.ProtoReflect.Descriptor instead.
+func (*StringListComparison) Descriptor() ([]byte, []int) {
+	return file_chromadb_proto_chroma_proto_rawDescGZIP(), []int{16}
+}
+
+func (x *StringListComparison) GetValues() []string {
+	if x != nil {
+		return x.Values
+	}
+	return nil
+}
+
+func (x *StringListComparison) GetListOperator() ListOperator {
+	if x != nil {
+		return x.ListOperator
+	}
+	return ListOperator_IN
+}
+
+type IntListComparison struct {
+	state         protoimpl.MessageState
+	sizeCache     protoimpl.SizeCache
+	unknownFields protoimpl.UnknownFields
+
+	Values       []int64      `protobuf:"varint,1,rep,packed,name=values,proto3" json:"values,omitempty"`
+	ListOperator ListOperator `protobuf:"varint,2,opt,name=list_operator,json=listOperator,proto3,enum=chroma.ListOperator" json:"list_operator,omitempty"`
+}
+
+func (x *IntListComparison) Reset() {
+	*x = IntListComparison{}
+	if protoimpl.UnsafeEnabled {
+		mi := &file_chromadb_proto_chroma_proto_msgTypes[17]
+		ms := protoimpl.X.MessageStateOf(protoimpl.Pointer(x))
+		ms.StoreMessageInfo(mi)
+	}
+}
+
+func (x *IntListComparison) String() string {
+	return protoimpl.X.MessageStringOf(x)
+}
+
+func (*IntListComparison) ProtoMessage() {}
+
+func (x *IntListComparison) ProtoReflect() protoreflect.Message {
+	mi := &file_chromadb_proto_chroma_proto_msgTypes[17]
+	if protoimpl.UnsafeEnabled && x != nil {
+		ms := protoimpl.X.MessageStateOf(protoimpl.Pointer(x))
+		if ms.LoadMessageInfo() == nil {
+			ms.StoreMessageInfo(mi)
+		}
+		return ms
+	}
+	return mi.MessageOf(x)
+}
+
+// Deprecated: Use IntListComparison.ProtoReflect.Descriptor instead.
+func (*IntListComparison) Descriptor() ([]byte, []int) {
+	return file_chromadb_proto_chroma_proto_rawDescGZIP(), []int{17}
+}
+
+func (x *IntListComparison) GetValues() []int64 {
+	if x != nil {
+		return x.Values
+	}
+	return nil
+}
+
+func (x *IntListComparison) GetListOperator() ListOperator {
+	if x != nil {
+		return x.ListOperator
+	}
+	return ListOperator_IN
+}
+
+type DoubleListComparison struct {
+	state         protoimpl.MessageState
+	sizeCache     protoimpl.SizeCache
+	unknownFields protoimpl.UnknownFields
+
+	Values       []float64    `protobuf:"fixed64,1,rep,packed,name=values,proto3" json:"values,omitempty"`
+	ListOperator ListOperator `protobuf:"varint,2,opt,name=list_operator,json=listOperator,proto3,enum=chroma.ListOperator" json:"list_operator,omitempty"`
+}
+
+func (x *DoubleListComparison) Reset() {
+	*x = DoubleListComparison{}
+	if protoimpl.UnsafeEnabled {
+		mi := &file_chromadb_proto_chroma_proto_msgTypes[18]
+		ms := protoimpl.X.MessageStateOf(protoimpl.Pointer(x))
+		ms.StoreMessageInfo(mi)
+	}
+}
+
+func (x *DoubleListComparison) String() string {
+	return protoimpl.X.MessageStringOf(x)
+}
+
+func (*DoubleListComparison) ProtoMessage() {}
+
+func (x *DoubleListComparison) ProtoReflect() protoreflect.Message {
+	mi := &file_chromadb_proto_chroma_proto_msgTypes[18]
+	if protoimpl.UnsafeEnabled && x != nil {
+		ms := protoimpl.X.MessageStateOf(protoimpl.Pointer(x))
+		if ms.LoadMessageInfo() == nil {
+			ms.StoreMessageInfo(mi)
+		}
+		return ms
+	}
+	return mi.MessageOf(x)
+}
+
+// Deprecated: Use DoubleListComparison.ProtoReflect.Descriptor instead.
+func (*DoubleListComparison) Descriptor() ([]byte, []int) {
+	return file_chromadb_proto_chroma_proto_rawDescGZIP(), []int{18}
+}
+
+func (x *DoubleListComparison) GetValues() []float64 {
+	if x != nil {
+		return x.Values
+	}
+	return nil
+}
+
+func (x *DoubleListComparison) GetListOperator() ListOperator {
+	if x != nil {
+		return x.ListOperator
+	}
+	return ListOperator_IN
+}
+
+type BoolListComparison struct {
+	state         protoimpl.MessageState
+	sizeCache     protoimpl.SizeCache
+	unknownFields protoimpl.UnknownFields
+
+	Values       []bool       `protobuf:"varint,1,rep,packed,name=values,proto3" json:"values,omitempty"`
+	ListOperator ListOperator `protobuf:"varint,2,opt,name=list_operator,json=listOperator,proto3,enum=chroma.ListOperator" json:"list_operator,omitempty"`
+}
+
+func (x *BoolListComparison) Reset() {
+	*x = BoolListComparison{}
+	if protoimpl.UnsafeEnabled {
+		mi := &file_chromadb_proto_chroma_proto_msgTypes[19]
+		ms := protoimpl.X.MessageStateOf(protoimpl.Pointer(x))
+		ms.StoreMessageInfo(mi)
+	}
+}
+
+func (x *BoolListComparison) String() string {
+	return protoimpl.X.MessageStringOf(x)
+}
+
+func (*BoolListComparison) ProtoMessage() {}
+
+func (x *BoolListComparison) ProtoReflect() protoreflect.Message {
+	mi := &file_chromadb_proto_chroma_proto_msgTypes[19]
+	if protoimpl.UnsafeEnabled && x != nil {
+		ms := protoimpl.X.MessageStateOf(protoimpl.Pointer(x))
+		if ms.LoadMessageInfo() == nil {
+			ms.StoreMessageInfo(mi)
+		}
+		return ms
+	}
+	return mi.MessageOf(x)
+}
+
+// Deprecated: Use BoolListComparison.ProtoReflect.Descriptor instead.
+func (*BoolListComparison) Descriptor() ([]byte, []int) {
+	return file_chromadb_proto_chroma_proto_rawDescGZIP(), []int{19}
+}
+
+func (x *BoolListComparison) GetValues() []bool {
+	if x != nil {
+		return x.Values
+	}
+	return nil
+}
+
+func (x *BoolListComparison) GetListOperator() ListOperator {
+	if x != nil {
+		return x.ListOperator
+	}
+	return ListOperator_IN
+}
+
+type DirectComparison struct {
+	state         protoimpl.MessageState
+	sizeCache     protoimpl.SizeCache
+	unknownFields protoimpl.UnknownFields
+
+	Key string `protobuf:"bytes,1,opt,name=key,proto3" json:"key,omitempty"`
+	// Types that are assignable to Comparison:
+	//
+	//	*DirectComparison_SingleStringOperand
+	//	*DirectComparison_StringListOperand
+	//	*DirectComparison_SingleIntOperand
+	//	*DirectComparison_IntListOperand
+	//	*DirectComparison_SingleDoubleOperand
+	//	*DirectComparison_DoubleListOperand
+	//	*DirectComparison_SingleBoolOperand
+	//	*DirectComparison_BoolListOperand
+	Comparison isDirectComparison_Comparison `protobuf_oneof:"comparison"`
+}
+
+func (x *DirectComparison) Reset() {
+	*x = DirectComparison{}
+	if protoimpl.UnsafeEnabled {
+		mi := &file_chromadb_proto_chroma_proto_msgTypes[20]
+		ms := protoimpl.X.MessageStateOf(protoimpl.Pointer(x))
+		ms.StoreMessageInfo(mi)
+	}
+}
+
+func (x *DirectComparison) String() string {
+	return protoimpl.X.MessageStringOf(x)
+}
+
+func (*DirectComparison) ProtoMessage() {}
+
+func (x *DirectComparison) ProtoReflect() protoreflect.Message {
+	mi := &file_chromadb_proto_chroma_proto_msgTypes[20]
+	if protoimpl.UnsafeEnabled && x != nil {
+		ms := protoimpl.X.MessageStateOf(protoimpl.Pointer(x))
+		if ms.LoadMessageInfo() == nil {
+			ms.StoreMessageInfo(mi)
+		}
+		return ms
+	}
+	return mi.MessageOf(x)
+}
+
+// Deprecated: Use DirectComparison.ProtoReflect.Descriptor instead.
+func (*DirectComparison) Descriptor() ([]byte, []int) {
+	return file_chromadb_proto_chroma_proto_rawDescGZIP(), []int{20}
+}
+
+func (x *DirectComparison) GetKey() string {
+	if x != nil {
+		return x.Key
+	}
+	return ""
+}
+
+func (m *DirectComparison) GetComparison() isDirectComparison_Comparison {
+	if m != nil {
+		return m.Comparison
+	}
+	return nil
+}
+
+func (x *DirectComparison) GetSingleStringOperand() *SingleStringComparison {
+	if x, ok := x.GetComparison().(*DirectComparison_SingleStringOperand); ok {
+		return x.SingleStringOperand
+	}
+	return nil
+}
+
+func (x *DirectComparison) GetStringListOperand() *StringListComparison {
+	if x, ok := x.GetComparison().(*DirectComparison_StringListOperand); ok {
+		return x.StringListOperand
+	}
+	return nil
+}
+
+func (x *DirectComparison) GetSingleIntOperand() *SingleIntComparison {
+	if x, ok := x.GetComparison().(*DirectComparison_SingleIntOperand); ok {
+		return x.SingleIntOperand
+	}
+	return nil
+}
+
+func (x *DirectComparison) GetIntListOperand() *IntListComparison {
+	if x, ok := x.GetComparison().(*DirectComparison_IntListOperand); ok {
+		return x.IntListOperand
+	}
+	return nil
+}
+
+func (x *DirectComparison) GetSingleDoubleOperand() *SingleDoubleComparison {
+	if x, ok := x.GetComparison().(*DirectComparison_SingleDoubleOperand); ok {
+		return x.SingleDoubleOperand
+	}
+	return nil
+}
+
+func (x *DirectComparison) GetDoubleListOperand() *DoubleListComparison {
+	if x, ok := x.GetComparison().(*DirectComparison_DoubleListOperand); ok {
+		return x.DoubleListOperand
+	}
+	return nil
+}
+
+func (x *DirectComparison) GetSingleBoolOperand() *SingleBoolComparison {
+	if x, ok := x.GetComparison().(*DirectComparison_SingleBoolOperand); ok {
+		return x.SingleBoolOperand
+	}
+	return nil
+}
+
+func (x *DirectComparison) GetBoolListOperand() *BoolListComparison {
+	if x, ok := x.GetComparison().(*DirectComparison_BoolListOperand); ok {
+		return x.BoolListOperand
+	}
+	return nil
+}
+
+type isDirectComparison_Comparison interface {
+	isDirectComparison_Comparison()
+}
+
+type DirectComparison_SingleStringOperand struct {
+	SingleStringOperand *SingleStringComparison `protobuf:"bytes,2,opt,name=single_string_operand,json=singleStringOperand,proto3,oneof"`
+}
+
+type DirectComparison_StringListOperand struct {
+	StringListOperand *StringListComparison `protobuf:"bytes,3,opt,name=string_list_operand,json=stringListOperand,proto3,oneof"`
+}
+
+type DirectComparison_SingleIntOperand struct {
+	SingleIntOperand *SingleIntComparison `protobuf:"bytes,4,opt,name=single_int_operand,json=singleIntOperand,proto3,oneof"`
+}
+
+type DirectComparison_IntListOperand struct {
+	IntListOperand *IntListComparison `protobuf:"bytes,5,opt,name=int_list_operand,json=intListOperand,proto3,oneof"`
+}
+
+type DirectComparison_SingleDoubleOperand struct {
+	SingleDoubleOperand *SingleDoubleComparison `protobuf:"bytes,6,opt,name=single_double_operand,json=singleDoubleOperand,proto3,oneof"`
+}
+
+type DirectComparison_DoubleListOperand struct {
+	DoubleListOperand *DoubleListComparison `protobuf:"bytes,7,opt,name=double_list_operand,json=doubleListOperand,proto3,oneof"`
+}
+
+type DirectComparison_SingleBoolOperand struct {
+	SingleBoolOperand *SingleBoolComparison `protobuf:"bytes,8,opt,name=single_bool_operand,json=singleBoolOperand,proto3,oneof"`
+}
+
+type DirectComparison_BoolListOperand struct {
+	BoolListOperand *BoolListComparison `protobuf:"bytes,9,opt,name=bool_list_operand,json=boolListOperand,proto3,oneof"`
+}
+
+func (*DirectComparison_SingleStringOperand) isDirectComparison_Comparison() {}
+
+func (*DirectComparison_StringListOperand) isDirectComparison_Comparison() {}
+
+func (*DirectComparison_SingleIntOperand) isDirectComparison_Comparison() {}
+
+func (*DirectComparison_IntListOperand) isDirectComparison_Comparison() {}
+
+func (*DirectComparison_SingleDoubleOperand) isDirectComparison_Comparison() {}
+
+func (*DirectComparison_DoubleListOperand) isDirectComparison_Comparison() {}
+
+func (*DirectComparison_SingleBoolOperand) isDirectComparison_Comparison() {}
+
+func (*DirectComparison_BoolListOperand) isDirectComparison_Comparison() {}
+
+type WhereChildren struct {
+	state         protoimpl.MessageState
+	sizeCache     protoimpl.SizeCache
+	unknownFields protoimpl.UnknownFields
+
+	Children []*Where        `protobuf:"bytes,1,rep,name=children,proto3" json:"children,omitempty"`
+	Operator BooleanOperator `protobuf:"varint,2,opt,name=operator,proto3,enum=chroma.BooleanOperator" json:"operator,omitempty"`
+}
+
+func (x *WhereChildren) Reset() {
+	*x = WhereChildren{}
+	if protoimpl.UnsafeEnabled {
+		mi := &file_chromadb_proto_chroma_proto_msgTypes[21]
+		ms := protoimpl.X.MessageStateOf(protoimpl.Pointer(x))
+		ms.StoreMessageInfo(mi)
+	}
+}
+
+func (x *WhereChildren) String() string {
+	return protoimpl.X.MessageStringOf(x)
+}
+
+func (*WhereChildren) ProtoMessage() {}
+
+func (x *WhereChildren) ProtoReflect() protoreflect.Message {
+	mi := &file_chromadb_proto_chroma_proto_msgTypes[21]
+	if protoimpl.UnsafeEnabled && x != nil {
+		ms := protoimpl.X.MessageStateOf(protoimpl.Pointer(x))
+		if ms.LoadMessageInfo() == nil {
+			ms.StoreMessageInfo(mi)
+		}
+		return ms
+	}
+	return mi.MessageOf(x)
+}
+
+// Deprecated: Use WhereChildren.ProtoReflect.Descriptor instead.
+func (*WhereChildren) Descriptor() ([]byte, []int) {
+	return file_chromadb_proto_chroma_proto_rawDescGZIP(), []int{21}
+}
+
+func (x *WhereChildren) GetChildren() []*Where {
+	if x != nil {
+		return x.Children
+	}
+	return nil
+}
+
+func (x *WhereChildren) GetOperator() BooleanOperator {
+	if x != nil {
+		return x.Operator
+	}
+	return BooleanOperator_AND
+}
+
+type Where struct {
+	state         protoimpl.MessageState
+	sizeCache     protoimpl.SizeCache
+	unknownFields protoimpl.UnknownFields
+
+	// Types that are assignable to Clause:
+	//
+	//	*Where_DirectComparison
+	//	*Where_Children
+	//	*Where_NotClause
+	Clause isWhere_Clause `protobuf_oneof:"clause"`
+}
+
+func (x *Where) Reset() {
+	*x = Where{}
+	if protoimpl.UnsafeEnabled {
+		mi := &file_chromadb_proto_chroma_proto_msgTypes[22]
+		ms := protoimpl.X.MessageStateOf(protoimpl.Pointer(x))
+		ms.StoreMessageInfo(mi)
+	}
+}
+
+func (x *Where) String() string {
+	return protoimpl.X.MessageStringOf(x)
+}
+
+func (*Where) ProtoMessage() {}
+
+func (x *Where) ProtoReflect() protoreflect.Message {
+	mi := &file_chromadb_proto_chroma_proto_msgTypes[22]
+	if protoimpl.UnsafeEnabled && x != nil {
+		ms := protoimpl.X.MessageStateOf(protoimpl.Pointer(x))
+		if ms.LoadMessageInfo() == nil {
+			ms.StoreMessageInfo(mi)
+		}
+		return ms
+	}
+	return mi.MessageOf(x)
+}
+
+// Deprecated: Use Where.ProtoReflect.Descriptor instead.
+func (*Where) Descriptor() ([]byte, []int) {
+	return file_chromadb_proto_chroma_proto_rawDescGZIP(), []int{22}
+}
+
+func (m *Where) GetClause() isWhere_Clause {
+	if m != nil {
+		return m.Clause
+	}
+	return nil
+}
+
+func (x *Where) GetDirectComparison() *DirectComparison {
+	if x, ok := x.GetClause().(*Where_DirectComparison); ok {
+		return x.DirectComparison
+	}
+	return nil
+}
+
+func (x *Where) GetChildren() *WhereChildren {
+	if x, ok := x.GetClause().(*Where_Children); ok {
+		return x.Children
+	}
+	return nil
+}
+
+func (x *Where) GetNotClause() *Where {
+	if x, ok := x.GetClause().(*Where_NotClause); ok {
+		return x.NotClause
+	}
+	return nil
+}
+
+type isWhere_Clause interface {
+	isWhere_Clause()
+}
+
+type Where_DirectComparison struct {
+	DirectComparison *DirectComparison `protobuf:"bytes,1,opt,name=direct_comparison,json=directComparison,proto3,oneof"`
+}
+
+type Where_Children struct {
+	Children *WhereChildren `protobuf:"bytes,2,opt,name=children,proto3,oneof"`
+}
+
+type Where_NotClause struct {
+	// Matches the records the child clause does not match.
+	NotClause *Where `protobuf:"bytes,3,opt,name=not_clause,json=notClause,proto3,oneof"`
+}
+
+func (*Where_DirectComparison) isWhere_Clause() {}
+
+func (*Where_Children) isWhere_Clause() {}
+
+func (*Where_NotClause) isWhere_Clause() {}
+
+type DirectWhereDocument struct {
+	state         protoimpl.MessageState
+	sizeCache     protoimpl.SizeCache
+	unknownFields protoimpl.UnknownFields
+
+	Document string                `protobuf:"bytes,1,opt,name=document,proto3" json:"document,omitempty"`
+	Operator WhereDocumentOperator `protobuf:"varint,2,opt,name=operator,proto3,enum=chroma.WhereDocumentOperator" json:"operator,omitempty"`
+}
+
+func (x *DirectWhereDocument) Reset() {
+	*x = DirectWhereDocument{}
+	if protoimpl.UnsafeEnabled {
+		mi := &file_chromadb_proto_chroma_proto_msgTypes[23]
+		ms := protoimpl.X.MessageStateOf(protoimpl.Pointer(x))
+		ms.StoreMessageInfo(mi)
+	}
+}
+
+func (x *DirectWhereDocument) String() string {
+	return protoimpl.X.MessageStringOf(x)
+}
+
+func (*DirectWhereDocument) ProtoMessage() {}
+
+func (x *DirectWhereDocument) ProtoReflect() protoreflect.Message {
+	mi := &file_chromadb_proto_chroma_proto_msgTypes[23]
+	if protoimpl.UnsafeEnabled && x != nil {
+		ms := protoimpl.X.MessageStateOf(protoimpl.Pointer(x))
+		if ms.LoadMessageInfo() == nil {
+			ms.StoreMessageInfo(mi)
+		}
+		return ms
+	}
+	return mi.MessageOf(x)
+}
+
+// Deprecated: Use DirectWhereDocument.ProtoReflect.Descriptor instead.
+func (*DirectWhereDocument) Descriptor() ([]byte, []int) {
+	return file_chromadb_proto_chroma_proto_rawDescGZIP(), []int{23}
+}
+
+func (x *DirectWhereDocument) GetDocument() string {
+	if x != nil {
+		return x.Document
+	}
+	return ""
+}
+
+func (x *DirectWhereDocument) GetOperator() WhereDocumentOperator {
+	if x != nil {
+		return x.Operator
+	}
+	return WhereDocumentOperator_CONTAINS
+}
+
+type WhereDocumentChildren struct {
+	state         protoimpl.MessageState
+	sizeCache     protoimpl.SizeCache
+	unknownFields protoimpl.UnknownFields
+
+	Children []*WhereDocument `protobuf:"bytes,1,rep,name=children,proto3" json:"children,omitempty"`
+	Operator BooleanOperator  `protobuf:"varint,2,opt,name=operator,proto3,enum=chroma.BooleanOperator" json:"operator,omitempty"`
+}
+
+func (x *WhereDocumentChildren) Reset() {
+	*x = WhereDocumentChildren{}
+	if protoimpl.UnsafeEnabled {
+		mi := &file_chromadb_proto_chroma_proto_msgTypes[24]
+		ms := protoimpl.X.MessageStateOf(protoimpl.Pointer(x))
+		ms.StoreMessageInfo(mi)
+	}
+}
+
+func (x *WhereDocumentChildren) String() string {
+	return protoimpl.X.MessageStringOf(x)
+}
+
+func (*WhereDocumentChildren) ProtoMessage() {}
+
+func (x *WhereDocumentChildren) ProtoReflect() protoreflect.Message {
+	mi := &file_chromadb_proto_chroma_proto_msgTypes[24]
+	if protoimpl.UnsafeEnabled && x != nil {
+		ms := protoimpl.X.MessageStateOf(protoimpl.Pointer(x))
+		if ms.LoadMessageInfo() == nil {
+			ms.StoreMessageInfo(mi)
+		}
+		return ms
+	}
+	return mi.MessageOf(x)
+}
+
+// Deprecated: Use WhereDocumentChildren.ProtoReflect.Descriptor instead.
+func (*WhereDocumentChildren) Descriptor() ([]byte, []int) {
+	return file_chromadb_proto_chroma_proto_rawDescGZIP(), []int{24}
+}
+
+func (x *WhereDocumentChildren) GetChildren() []*WhereDocument {
+	if x != nil {
+		return x.Children
+	}
+	return nil
+}
+
+func (x *WhereDocumentChildren) GetOperator() BooleanOperator {
+	if x != nil {
+		return x.Operator
+	}
+	return BooleanOperator_AND
+}
+
+type WhereDocument struct {
+	state         protoimpl.MessageState
+	sizeCache     protoimpl.SizeCache
+	unknownFields protoimpl.UnknownFields
+
+	// Types that are assignable to Clause:
+	//
+	//	*WhereDocument_Direct
+	//	*WhereDocument_Children
+	Clause isWhereDocument_Clause `protobuf_oneof:"clause"`
+}
+
+func (x *WhereDocument) Reset() {
+	*x = WhereDocument{}
+	if protoimpl.UnsafeEnabled {
+		mi := &file_chromadb_proto_chroma_proto_msgTypes[25]
+		ms := protoimpl.X.MessageStateOf(protoimpl.Pointer(x))
+		ms.StoreMessageInfo(mi)
+	}
+}
+
+func (x *WhereDocument) String() string {
+	return protoimpl.X.MessageStringOf(x)
+}
+
+func (*WhereDocument) ProtoMessage() {}
+
+func (x *WhereDocument) ProtoReflect() protoreflect.Message {
+	mi := &file_chromadb_proto_chroma_proto_msgTypes[25]
+	if protoimpl.UnsafeEnabled && x != nil {
+		ms := protoimpl.X.MessageStateOf(protoimpl.Pointer(x))
+		if ms.LoadMessageInfo() == nil {
+			ms.StoreMessageInfo(mi)
+		}
+		return ms
+	}
+	return mi.MessageOf(x)
+}
+
+// Deprecated: Use WhereDocument.ProtoReflect.Descriptor instead.
+func (*WhereDocument) Descriptor() ([]byte, []int) {
+	return file_chromadb_proto_chroma_proto_rawDescGZIP(), []int{25}
+}
+
+func (m *WhereDocument) GetClause() isWhereDocument_Clause {
+	if m != nil {
+		return m.Clause
+	}
+	return nil
+}
+
+func (x *WhereDocument) GetDirect() *DirectWhereDocument {
+	if x, ok := x.GetClause().(*WhereDocument_Direct); ok {
+		return x.Direct
+	}
+	return nil
+}
+
+func (x *WhereDocument) GetChildren() *WhereDocumentChildren {
+	if x, ok := x.GetClause().(*WhereDocument_Children); ok {
+		return x.Children
+	}
+	return nil
+}
+
+type isWhereDocument_Clause interface {
+	isWhereDocument_Clause()
+}
+
+type WhereDocument_Direct struct {
+	Direct *DirectWhereDocument `protobuf:"bytes,1,opt,name=direct,proto3,oneof"`
+}
+
+type WhereDocument_Children struct {
+	Children *WhereDocumentChildren `protobuf:"bytes,2,opt,name=children,proto3,oneof"`
+}
+
+func (*WhereDocument_Direct) isWhereDocument_Clause() {}
+
+func (*WhereDocument_Children) isWhereDocument_Clause() {}
+
+type GetVectorsRequest struct {
+	state         protoimpl.MessageState
+	sizeCache     protoimpl.SizeCache
+	unknownFields protoimpl.UnknownFields
+
+	Ids       []string `protobuf:"bytes,1,rep,name=ids,proto3" json:"ids,omitempty"`
+	SegmentId string   `protobuf:"bytes,2,opt,name=segment_id,json=segmentId,proto3" json:"segment_id,omitempty"`
+}
+
+func (x *GetVectorsRequest) Reset() {
+	*x = GetVectorsRequest{}
+	if protoimpl.UnsafeEnabled {
+		mi := &file_chromadb_proto_chroma_proto_msgTypes[26]
+		ms := protoimpl.X.MessageStateOf(protoimpl.Pointer(x))
+		ms.StoreMessageInfo(mi)
+	}
+}
+
+func (x *GetVectorsRequest) String() string {
+	return protoimpl.X.MessageStringOf(x)
+}
+
+func (*GetVectorsRequest) ProtoMessage() {}
+
+func (x *GetVectorsRequest) ProtoReflect() protoreflect.Message {
+	mi := &file_chromadb_proto_chroma_proto_msgTypes[26]
+	if protoimpl.UnsafeEnabled && x != nil {
+		ms := protoimpl.X.MessageStateOf(protoimpl.Pointer(x))
+		if ms.LoadMessageInfo() == nil {
+			ms.StoreMessageInfo(mi)
+		}
+		return ms
+	}
+	return mi.MessageOf(x)
+}
+
+// Deprecated: Use GetVectorsRequest.ProtoReflect.Descriptor instead.
+func (*GetVectorsRequest) Descriptor() ([]byte, []int) {
+	return file_chromadb_proto_chroma_proto_rawDescGZIP(), []int{26}
+}
+
+func (x *GetVectorsRequest) GetIds() []string {
+	if x != nil {
+		return x.Ids
+	}
+	return nil
+}
+
+func (x *GetVectorsRequest) GetSegmentId() string {
+	if x != nil {
+		return x.SegmentId
+	}
+	return ""
+}
+
+type GetVectorsResponse struct {
+	state         protoimpl.MessageState
+	sizeCache     protoimpl.SizeCache
+	unknownFields protoimpl.UnknownFields
+
+	Records []*VectorEmbeddingRecord `protobuf:"bytes,1,rep,name=records,proto3" json:"records,omitempty"`
+}
+
+func (x *GetVectorsResponse) Reset() {
+	*x = GetVectorsResponse{}
+	if protoimpl.UnsafeEnabled {
+		mi := &file_chromadb_proto_chroma_proto_msgTypes[27]
+		ms := protoimpl.X.MessageStateOf(protoimpl.Pointer(x))
+		ms.StoreMessageInfo(mi)
+	}
+}
+
+func (x *GetVectorsResponse) String() string {
+	return protoimpl.X.MessageStringOf(x)
+}
+
+func (*GetVectorsResponse) ProtoMessage() {}
+
+func (x *GetVectorsResponse) ProtoReflect() protoreflect.Message {
+	mi := &file_chromadb_proto_chroma_proto_msgTypes[27]
+	if protoimpl.UnsafeEnabled && x != nil {
+		ms := protoimpl.X.MessageStateOf(protoimpl.Pointer(x))
+		if ms.LoadMessageInfo() == nil {
+			ms.StoreMessageInfo(mi)
+		}
+		return ms
+	}
+	return mi.MessageOf(x)
+}
+
+// Deprecated: Use GetVectorsResponse.ProtoReflect.Descriptor instead.
+func (*GetVectorsResponse) Descriptor() ([]byte, []int) {
+	return file_chromadb_proto_chroma_proto_rawDescGZIP(), []int{27}
+}
+
+func (x *GetVectorsResponse) GetRecords() []*VectorEmbeddingRecord {
+	if x != nil {
+		return x.Records
+	}
+	return nil
+}
+
+type QueryVectorsRequest struct {
+	state         protoimpl.MessageState
+	sizeCache     protoimpl.SizeCache
+	unknownFields protoimpl.UnknownFields
+
+	Vectors           []*Vector `protobuf:"bytes,1,rep,name=vectors,proto3" json:"vectors,omitempty"`
+	K                 int32     `protobuf:"varint,2,opt,name=k,proto3" json:"k,omitempty"`
+	AllowedIds        []string  `protobuf:"bytes,3,rep,name=allowed_ids,json=allowedIds,proto3" json:"allowed_ids,omitempty"`
+	IncludeEmbeddings bool      `protobuf:"varint,4,opt,name=include_embeddings,json=includeEmbeddings,proto3" json:"include_embeddings,omitempty"`
+	SegmentId         string    `protobuf:"bytes,5,opt,name=segment_id,json=segmentId,proto3" json:"segment_id,omitempty"`
+	// Texts to embed server side when no vectors are provided.
+	QueryTexts []string `protobuf:"bytes,6,rep,name=query_texts,json=queryTexts,proto3" json:"query_texts,omitempty"`
+	// Restricts the results to the records of the metadata segment matching
+	// where and where_document.
+	MetadataSegmentId string         `protobuf:"bytes,7,opt,name=metadata_segment_id,json=metadataSegmentId,proto3" json:"metadata_segment_id,omitempty"`
+	Where             *Where         `protobuf:"bytes,8,opt,name=where,proto3" json:"where,omitempty"`
+	WhereDocument     *WhereDocument `protobuf:"bytes,9,opt,name=where_document,json=whereDocument,proto3" json:"where_document,omitempty"` // TODO: options as in types.py, its currently unused so can add later
+}
+
+func (x *QueryVectorsRequest) Reset() {
+	*x = QueryVectorsRequest{}
+	if protoimpl.UnsafeEnabled {
+		mi := &file_chromadb_proto_chroma_proto_msgTypes[28]
+		ms := protoimpl.X.MessageStateOf(protoimpl.Pointer(x))
+		ms.StoreMessageInfo(mi)
+	}
+}
+
+func (x *QueryVectorsRequest) String() string {
+	return protoimpl.X.MessageStringOf(x)
+}
+
+func (*QueryVectorsRequest) ProtoMessage() {}
+
+func (x *QueryVectorsRequest) ProtoReflect() protoreflect.Message {
+	mi := &file_chromadb_proto_chroma_proto_msgTypes[28]
+	if protoimpl.UnsafeEnabled && x != nil {
+		ms := protoimpl.X.MessageStateOf(protoimpl.Pointer(x))
+		if ms.LoadMessageInfo() == nil {
+			ms.StoreMessageInfo(mi)
+		}
+		return ms
+	}
+	return mi.MessageOf(x)
+}
+
+// Deprecated: Use QueryVectorsRequest.ProtoReflect.Descriptor instead.
+func (*QueryVectorsRequest) Descriptor() ([]byte, []int) {
+	return file_chromadb_proto_chroma_proto_rawDescGZIP(), []int{28}
+}
+
+func (x *QueryVectorsRequest) GetVectors() []*Vector {
+	if x != nil {
+		return x.Vectors
+	}
+	return nil
+}
+
+func (x *QueryVectorsRequest) GetK() int32 {
+	if x != nil {
+		return x.K
+	}
+	return 0
+}
+
+func (x *QueryVectorsRequest) GetAllowedIds() []string {
+	if x != nil {
+		return x.AllowedIds
+	}
+	return nil
+}
+
+func (x *QueryVectorsRequest) GetIncludeEmbeddings() bool {
+	if x != nil {
+		return x.IncludeEmbeddings
+	}
+	return false
+}
+
+func (x *QueryVectorsRequest) GetSegmentId() string {
+	if x != nil {
+		return x.SegmentId
+	}
+	return ""
+}
+
+func (x *QueryVectorsRequest) GetQueryTexts() []string {
+	if x != nil {
+		return x.QueryTexts
+	}
+	return nil
+}
+
+func (x *QueryVectorsRequest) GetMetadataSegmentId() string {
+	if x != nil {
+		return x.MetadataSegmentId
+	}
+	return ""
+}
+
+func (x *QueryVectorsRequest) GetWhere() *Where {
+	if x != nil {
+		return x.Where
+	}
+	return nil
+}
+
+func (x *QueryVectorsRequest) GetWhereDocument() *WhereDocument {
+	if x != nil {
+		return x.WhereDocument
+	}
+	return nil
+}
+
+type QueryVectorsResponse struct {
+	state         protoimpl.MessageState
+	sizeCache     protoimpl.SizeCache
+	unknownFields protoimpl.UnknownFields
+
+	Results []*VectorQueryResults `protobuf:"bytes,1,rep,name=results,proto3" json:"results,omitempty"`
+}
+
+func (x *QueryVectorsResponse) Reset() {
+	*x = QueryVectorsResponse{}
+	if protoimpl.UnsafeEnabled {
+		mi := &file_chromadb_proto_chroma_proto_msgTypes[29]
+		ms := protoimpl.X.MessageStateOf(protoimpl.Pointer(x))
+		ms.StoreMessageInfo(mi)
+	}
+}
+
+func (x *QueryVectorsResponse) String() string {
+	return protoimpl.X.MessageStringOf(x)
+}
+
+func (*QueryVectorsResponse) ProtoMessage() {}
+
+func (x *QueryVectorsResponse) ProtoReflect() protoreflect.Message {
+	mi := &file_chromadb_proto_chroma_proto_msgTypes[29]
+	if protoimpl.UnsafeEnabled && x != nil {
+		ms := protoimpl.X.MessageStateOf(protoimpl.Pointer(x))
+		if ms.LoadMessageInfo() == nil {
+			ms.StoreMessageInfo(mi)
+		}
+		return ms
+	}
+	return mi.MessageOf(x)
+}
+
+// Deprecated: Use QueryVectorsResponse.ProtoReflect.Descriptor instead.
+func (*QueryVectorsResponse) Descriptor() ([]byte, []int) {
+	return file_chromadb_proto_chroma_proto_rawDescGZIP(), []int{29}
+}
+
+func (x *QueryVectorsResponse) GetResults() []*VectorQueryResults {
+	if x != nil {
+		return x.Results
+	}
+	return nil
+}
+
+var File_chromadb_proto_chroma_proto protoreflect.FileDescriptor
+
+var file_chromadb_proto_chroma_proto_rawDesc = []byte{
+	0x0a, 0x1b, 0x63, 0x68, 0x72, 0x6f, 0x6d, 0x61, 0x64, 0x62, 0x2f, 0x70, 0x72, 0x6f, 0x74, 0x6f,
+	0x2f, 0x63, 0x68, 0x72, 0x6f, 0x6d, 0x61, 0x2e, 0x70, 0x72, 0x6f, 0x74, 0x6f, 0x12, 0x06, 0x63,
+	0x68, 0x72, 0x6f, 0x6d, 0x61, 0x22, 0x34, 0x0a, 0x06, 0x53, 0x74, 0x61, 0x74, 0x75, 0x73, 0x12,
+	0x16, 0x0a, 0x06, 0x72, 0x65, 0x61, 0x73, 0x6f, 0x6e, 0x18, 0x01, 0x20, 0x01, 0x28, 0x09, 0x52,
+	0x06, 0x72, 0x65, 0x61, 0x73, 0x6f, 0x6e, 0x12, 0x12, 0x0a, 0x04, 0x63, 0x6f, 0x64, 0x65, 0x18,
+	0x02, 0x20, 0x01, 0x28, 0x05, 0x52, 0x04, 0x63, 0x6f, 0x64, 0x65, 0x22, 0x72, 0x0a, 0x06, 0x56,
+	0x65, 0x63, 0x74, 0x6f, 0x72, 0x12, 0x1c, 0x0a, 0x09, 0x64, 0x69, 0x6d, 0x65, 0x6e, 0x73, 0x69,
+	0x6f, 0x6e, 0x18, 0x01, 0x20, 0x01, 0x28, 0x05, 0x52, 0x09, 0x64, 0x69, 0x6d, 0x65, 0x6e, 0x73,
+	0x69, 0x6f, 0x6e, 0x12, 0x16, 0x0a, 0x06, 0x76, 0x65, 0x63, 0x74, 0x6f, 0x72, 0x18, 0x02, 0x20,
+	0x01, 0x28, 0x0c, 0x52, 0x06, 0x76, 0x65, 0x63, 0x74, 0x6f, 0x72, 0x12, 0x32, 0x0a, 0x08, 0x65,
+	0x6e, 0x63, 0x6f, 0x64, 0x69, 0x6e, 0x67, 0x18, 0x03, 0x20, 0x01, 0x28, 0x0e, 0x32, 0x16, 0x2e,
+	0x63, 0x68, 0x72, 0x6f, 0x6d, 0x61, 0x2e, 0x53, 0x63, 0x61, 0x6c, 0x61, 0x72, 0x45, 0x6e, 0x63,
+	0x6f, 0x64, 0x69, 0x6e, 0x67, 0x52, 0x08, 0x65, 0x6e, 0x63, 0x6f, 0x64, 0x69, 0x6e, 0x67, 0x22,
+	0xf8, 0x01, 0x0a, 0x07, 0x53, 0x65, 0x67, 0x6d, 0x65, 0x6e, 0x74, 0x12, 0x0e, 0x0a, 0x02, 0x69,
+	0x64, 0x18, 0x01, 0x20, 0x01, 0x28, 0x09, 0x52, 0x02, 0x69, 0x64, 0x12, 0x12, 0x0a, 0x04, 0x74,
+	0x79, 0x70, 0x65, 0x18, 0x02, 0x20, 0x01, 0x28, 0x09, 0x52, 0x04, 0x74, 0x79, 0x70, 0x65, 0x12,
+	0x2a, 0x0a, 0x05, 0x73, 0x63, 0x6f, 0x70, 0x65, 0x18, 0x03, 0x20, 0x01, 0x28, 0x0e, 0x32, 0x14,
+	0x2e, 0x63, 0x68, 0x72, 0x6f, 0x6d, 0x61, 0x2e, 0x53, 0x65, 0x67, 0x6d, 0x65, 0x6e, 0x74, 0x53,
+	0x63, 0x6f, 0x70, 0x65, 0x52, 0x05, 0x73, 0x63, 0x6f, 0x70, 0x65, 0x12, 0x19, 0x0a, 0x05, 0x74,
 	0x6f, 0x70, 0x69, 0x63, 0x18, 0x04, 0x20, 0x01, 0x28, 0x09, 0x48, 0x00, 0x52, 0x05, 0x74, 0x6f,
 	0x70, 0x69, 0x63, 0x88, 0x01, 0x01, 0x12, 0x23, 0x0a, 0x0a, 0x63, 0x6f, 0x6c, 0x6c, 0x65, 0x63,
 	0x74, 0x69, 0x6f, 0x6e, 0x18, 0x05, 0x20, 0x01, 0x28, 0x09, 0x48, 0x01, 0x52, 0x0a, 0x63, 0x6f,
@@ -1314,57 +2608,234 @@ var file_chromadb_proto_chroma_proto_rawDesc = []byte{
 	0x79, 0x52, 0x65, 0x73, 0x75, 0x6c, 0x74, 0x73, 0x12, 0x33, 0x0a, 0x07, 0x72, 0x65, 0x73, 0x75,
 	0x6c, 0x74, 0x73, 0x18, 0x01, 0x20, 0x03, 0x28, 0x0b, 0x32, 0x19, 0x2e, 0x63, 0x68, 0x72, 0x6f,
 	0x6d, 0x61, 0x2e, 0x56, 0x65, 0x63, 0x74, 0x6f, 0x72, 0x51, 0x75, 0x65, 0x72, 0x79, 0x52, 0x65,
-	0x73, 0x75, 0x6c, 0x74, 0x52, 0x07, 0x72, 0x65, 0x73, 0x75, 0x6c, 0x74, 0x73, 0x22, 0x44, 0x0a,
-	0x11, 0x47, 0x65, 0x74, 0x56, 0x65, 0x63, 0x74, 0x6f, 0x72, 0x73, 0x52, 0x65, 0x71, 0x75, 0x65,
-	0x73, 0x74, 0x12, 0x10, 0x0a, 0x03, 0x69, 0x64, 0x73, 0x18, 0x01, 0x20, 0x03, 0x28, 0x09, 0x52,
-	0x03, 0x69, 0x64, 0x73, 0x12, 0x1d, 0x0a, 0x0a, 0x73, 0x65, 0x67, 0x6d, 0x65, 0x6e, 0x74, 0x5f,
-	0x69, 0x64, 0x18, 0x02, 0x20, 0x01, 0x28, 0x09, 0x52, 0x09, 0x73, 0x65, 0x67, 0x6d, 0x65, 0x6e,
-	0x74, 0x49, 0x64, 0x22, 0x4d, 0x0a, 0x12, 0x47, 0x65, 0x74, 0x56, 0x65, 0x63, 0x74, 0x6f, 0x72,
-	0x73, 0x52, 0x65, 0x73, 0x70, 0x6f, 0x6e, 0x73, 0x65, 0x12, 0x37, 0x0a, 0x07, 0x72, 0x65, 0x63,
-	0x6f, 0x72, 0x64, 0x73, 0x18, 0x01, 0x20, 0x03, 0x28, 0x0b, 0x32, 0x1d, 0x2e, 0x63, 0x68, 0x72,
-	0x6f, 0x6d, 0x61, 0x2e, 0x56, 0x65, 0x63, 0x74, 0x6f, 0x72, 0x45, 0x6d, 0x62, 0x65, 0x64, 0x64,
-	0x69, 0x6e, 0x67, 0x52, 0x65, 0x63, 0x6f, 0x72, 0x64, 0x52, 0x07, 0x72, 0x65, 0x63, 0x6f, 0x72,
-	0x64, 0x73, 0x22, 0xbc, 0x01, 0x0a, 0x13, 0x51, 0x75, 0x65, 0x72, 0x79, 0x56, 0x65, 0x63, 0x74,
-	0x6f, 0x72, 0x73, 0x52, 0x65, 0x71, 0x75, 0x65, 0x73, 0x74, 0x12, 0x28, 0x0a, 0x07, 0x76, 0x65,
-	0x63, 0x74, 0x6f, 0x72, 0x73, 0x18, 0x01, 0x20, 0x03, 0x28, 0x0b, 0x32, 0x0e, 0x2e, 0x63, 0x68,
-	0x72, 0x6f, 0x6d, 0x61, 0x2e, 0x56, 0x65, 0x63, 0x74, 0x6f, 0x72, 0x52, 0x07, 0x76, 0x65, 0x63,
-	0x74, 0x6f, 0x72, 0x73, 0x12, 0x0c, 0x0a, 0x01, 0x6b, 0x18, 0x02, 0x20, 0x01, 0x28, 0x05, 0x52,
-	0x01, 0x6b, 0x12, 0x1f, 0x0a, 0x0b, 0x61, 0x6c, 0x6c, 0x6f, 0x77, 0x65, 0x64, 0x5f, 0x69, 0x64,
-	0x73, 0x18, 0x03, 0x20, 0x03, 0x28, 0x09, 0x52, 0x0a, 0x61, 0x6c, 0x6c, 0x6f, 0x77, 0x65, 0x64,
-	0x49, 0x64, 0x73, 0x12, 0x2d, 0x0a, 0x12, 0x69, 0x6e, 0x63, 0x6c, 0x75, 0x64, 0x65, 0x5f, 0x65,
-	0x6d, 0x62, 0x65, 0x64, 0x64, 0x69, 0x6e, 0x67, 0x73, 0x18, 0x04, 0x20, 0x01, 0x28, 0x08, 0x52,
-	0x11, 0x69, 0x6e, 0x63, 0x6c, 0x75, 0x64, 0x65, 0x45, 0x6d, 0x62, 0x65, 0x64, 0x64, 0x69, 0x6e,
-	0x67, 0x73, 0x12, 0x1d, 0x0a, 0x0a, 0x73, 0x65, 0x67, 0x6d, 0x65, 0x6e, 0x74, 0x5f, 0x69, 0x64,
-	0x18, 0x05, 0x20, 0x01, 0x28, 0x09, 0x52, 0x09, 0x73, 0x65, 0x67, 0x6d, 0x65, 0x6e, 0x74, 0x49,
-	0x64, 0x22, 0x4c, 0x0a, 0x14, 0x51, 0x75, 0x65, 0x72, 0x79, 0x56, 0x65, 0x63, 0x74, 0x6f, 0x72,
-	0x73, 0x52, 0x65, 0x73, 0x70, 0x6f, 0x6e, 0x73, 0x65, 0x12, 0x34, 0x0a, 0x07, 0x72, 0x65, 0x73,
-	0x75, 0x6c, 0x74, 0x73, 0x18, 0x01, 0x20, 0x03, 0x28, 0x0b, 0x32, 0x1a, 0x2e, 0x63, 0x68, 0x72,
-	0x6f, 0x6d, 0x61, 0x2e, 0x56, 0x65, 0x63, 0x74, 0x6f, 0x72, 0x51, 0x75, 0x65, 0x72, 0x79, 0x52,
-	0x65, 0x73, 0x75, 0x6c, 0x74, 0x73, 0x52, 0x07, 0x72, 0x65, 0x73, 0x75, 0x6c, 0x74, 0x73, 0x2a,
-	0x38, 0x0a, 0x09, 0x4f, 0x70, 0x65, 0x72, 0x61, 0x74, 0x69, 0x6f, 0x6e, 0x12, 0x07, 0x0a, 0x03,
-	0x41, 0x44, 0x44, 0x10, 0x00, 0x12, 0x0a, 0x0a, 0x06, 0x55, 0x50, 0x44, 0x41, 0x54, 0x45, 0x10,
-	0x01, 0x12, 0x0a, 0x0a, 0x06, 0x55, 0x50, 0x53, 0x45, 0x52, 0x54, 0x10, 0x02, 0x12, 0x0a, 0x0a,
-	0x06, 0x44, 0x45, 0x4c, 0x45, 0x54, 0x45, 0x10, 0x03, 0x2a, 0x28, 0x0a, 0x0e, 0x53, 0x63, 0x61,
-	0x6c, 0x61, 0x72, 0x45, 0x6e, 0x63, 0x6f, 0x64, 0x69, 0x6e, 0x67, 0x12, 0x0b, 0x0a, 0x07, 0x46,
-	0x4c, 0x4f, 0x41, 0x54, 0x33, 0x32, 0x10, 0x00, 0x12, 0x09, 0x0a, 0x05, 0x49, 0x4e, 0x54, 0x33,
-	0x32, 0x10, 0x01, 0x2a, 0x28, 0x0a, 0x0c, 0x53, 0x65, 0x67, 0x6d, 0x65, 0x6e, 0x74, 0x53, 0x63,
-	0x6f, 0x70, 0x65, 0x12, 0x0a, 0x0a, 0x06, 0x56, 0x45, 0x43, 0x54, 0x4f, 0x52, 0x10, 0x00, 0x12,
-	0x0c, 0x0a, 0x08, 0x4d, 0x45, 0x54, 0x41, 0x44, 0x41, 0x54, 0x41, 0x10, 0x01, 0x32, 0xa2, 0x01,
-	0x0a, 0x0c, 0x56, 0x65, 0x63, 0x74, 0x6f, 0x72, 0x52, 0x65, 0x61, 0x64, 0x65, 0x72, 0x12, 0x45,
-	0x0a, 0x0a, 0x47, 0x65, 0x74, 0x56, 0x65, 0x63, 0x74, 0x6f, 0x72, 0x73, 0x12, 0x19, 0x2e, 0x63,
-	0x68, 0x72, 0x6f, 0x6d, 0x61, 0x2e, 0x47, 0x65, 0x74, 0x56, 0x65, 0x63, 0x74, 0x6f, 0x72, 0x73,
-	0x52, 0x65, 0x71, 0x75, 0x65, 0x73, 0x74, 0x1a, 0x1a, 0x2e, 0x63, 0x68, 0x72, 0x6f, 0x6d, 0x61,
-	0x2e, 0x47, 0x65, 0x74, 0x56, 0x65, 0x63, 0x74, 0x6f, 0x72, 0x73, 0x52, 0x65, 0x73, 0x70, 0x6f,
-	0x6e, 0x73, 0x65, 0x22, 0x00, 0x12, 0x4b, 0x0a, 0x0c, 0x51, 0x75, 0x65, 0x72, 0x79, 0x56, 0x65,
-	0x63, 0x74, 0x6f, 0x72, 0x73, 0x12, 0x1b, 0x2e, 0x63, 0x68, 0x72, 0x6f, 0x6d, 0x61, 0x2e, 0x51,
-	0x75, 0x65, 0x72, 0x79, 0x56, 0x65, 0x63, 0x74, 0x6f, 0x72, 0x73, 0x52, 0x65, 0x71, 0x75, 0x65,
-	0x73, 0x74, 0x1a, 0x1c, 0x2e, 0x63, 0x68, 0x72, 0x6f, 0x6d, 0x61, 0x2e, 0x51, 0x75, 0x65, 0x72,
-	0x79, 0x56, 0x65, 0x63, 0x74, 0x6f, 0x72, 0x73, 0x52, 0x65, 0x73, 0x70, 0x6f, 0x6e, 0x73, 0x65,
-	0x22, 0x00, 0x42, 0x3a, 0x5a, 0x38, 0x67, 0x69, 0x74, 0x68, 0x75, 0x62, 0x2e, 0x63, 0x6f, 0x6d,
-	0x2f, 0x63, 0x68, 0x72, 0x6f, 0x6d, 0x61, 0x2d, 0x63, 0x6f, 0x72, 0x65, 0x2f, 0x63, 0x68, 0x72,
-	0x6f, 0x6d, 0x61, 0x2f, 0x67, 0x6f, 0x2f, 0x70, 0x6b, 0x67, 0x2f, 0x70, 0x72, 0x6f, 0x74, 0x6f,
-	0x2f, 0x63, 0x6f, 0x6f, 0x72, 0x64, 0x69, 0x6e, 0x61, 0x74, 0x6f, 0x72, 0x70, 0x62, 0x62, 0x06,
-	0x70, 0x72, 0x6f, 0x74, 0x6f, 0x33,
+	0x73, 0x75, 0x6c, 0x74, 0x52, 0x07, 0x72, 0x65, 0x73, 0x75, 0x6c, 0x74, 0x73, 0x22, 0x69, 0x0a,
+	0x16, 0x53, 0x69, 0x6e, 0x67, 0x6c, 0x65, 0x53, 0x74, 0x72, 0x69, 0x6e, 0x67, 0x43, 0x6f, 0x6d,
+	0x70, 0x61, 0x72, 0x69, 0x73, 0x6f, 0x6e, 0x12, 0x14, 0x0a, 0x05, 0x76, 0x61, 0x6c, 0x75, 0x65,
+	0x18, 0x01, 0x20, 0x01, 0x28, 0x09, 0x52, 0x05, 0x76, 0x61, 0x6c, 0x75, 0x65, 0x12, 0x39, 0x0a,
+	0x0a, 0x63, 0x6f, 0x6d, 0x70, 0x61, 0x72, 0x61, 0x74, 0x6f, 0x72, 0x18, 0x02, 0x20, 0x01, 0x28,
+	0x0e, 0x32, 0x19, 0x2e, 0x63, 0x68, 0x72, 0x6f, 0x6d, 0x61, 0x2e, 0x47, 0x65, 0x6e, 0x65, 0x72,
+	0x69, 0x63, 0x43, 0x6f, 0x6d, 0x70, 0x61, 0x72, 0x61, 0x74, 0x6f, 0x72, 0x52, 0x0a, 0x63, 0x6f,
+	0x6d, 0x70, 0x61, 0x72, 0x61, 0x74, 0x6f, 0x72, 0x22, 0xce, 0x01, 0x0a, 0x13, 0x53, 0x69, 0x6e,
+	0x67, 0x6c, 0x65, 0x49, 0x6e, 0x74, 0x43, 0x6f, 0x6d, 0x70, 0x61, 0x72, 0x69, 0x73, 0x6f, 0x6e,
+	0x12, 0x14, 0x0a, 0x05, 0x76, 0x61, 0x6c, 0x75, 0x65, 0x18, 0x01, 0x20, 0x01, 0x28, 0x03, 0x52,
+	0x05, 0x76, 0x61, 0x6c, 0x75, 0x65, 0x12, 0x4a, 0x0a, 0x12, 0x67, 0x65, 0x6e, 0x65, 0x72, 0x69,
+	0x63, 0x5f, 0x63, 0x6f, 0x6d, 0x70, 0x61, 0x72, 0x61, 0x74, 0x6f, 0x72, 0x18, 0x02, 0x20, 0x01,
+	0x28, 0x0e, 0x32, 0x19, 0x2e, 0x63, 0x68, 0x72, 0x6f, 0x6d, 0x61, 0x2e, 0x47, 0x65, 0x6e, 0x65,
+	0x72, 0x69, 0x63, 0x43, 0x6f, 0x6d, 0x70, 0x61, 0x72, 0x61, 0x74, 0x6f, 0x72, 0x48, 0x00, 0x52,
+	0x11, 0x67, 0x65, 0x6e, 0x65, 0x72, 0x69, 0x63, 0x43, 0x6f, 0x6d, 0x70, 0x61, 0x72, 0x61, 0x74,
+	0x6f, 0x72, 0x12, 0x47, 0x0a, 0x11, 0x6e, 0x75, 0x6d, 0x62, 0x65, 0x72, 0x5f, 0x63, 0x6f, 0x6d,
+	0x70, 0x61, 0x72, 0x61, 0x74, 0x6f, 0x72, 0x18, 0x03, 0x20, 0x01, 0x28, 0x0e, 0x32, 0x18, 0x2e,
+	0x63, 0x68, 0x72, 0x6f, 0x6d, 0x61, 0x2e, 0x4e, 0x75, 0x6d, 0x62, 0x65, 0x72, 0x43, 0x6f, 0x6d,
+	0x70, 0x61, 0x72, 0x61, 0x74, 0x6f, 0x72, 0x48, 0x00, 0x52, 0x10, 0x6e, 0x75, 0x6d, 0x62, 0x65,
+	0x72, 0x43, 0x6f, 0x6d, 0x70, 0x61, 0x72, 0x61, 0x74, 0x6f, 0x72, 0x42, 0x0c, 0x0a, 0x0a, 0x63,
+	0x6f, 0x6d, 0x70, 0x61, 0x72, 0x61, 0x74, 0x6f, 0x72, 0x22, 0xd1, 0x01, 0x0a, 0x16, 0x53, 0x69,
+	0x6e, 0x67, 0x6c, 0x65, 0x44, 0x6f, 0x75, 0x62, 0x6c, 0x65, 0x43, 0x6f, 0x6d, 0x70, 0x61, 0x72,
+	0x69, 0x73, 0x6f, 0x6e, 0x12, 0x14, 0x0a, 0x05, 0x76, 0x61, 0x6c, 0x75, 0x65, 0x18, 0x01, 0x20,
+	0x01, 0x28, 0x01, 0x52, 0x05, 0x76, 0x61, 0x6c, 0x75, 0x65, 0x12, 0x4a, 0x0a, 0x12, 0x67, 0x65,
+	0x6e, 0x65, 0x72, 0x69, 0x63, 0x5f, 0x63, 0x6f, 0x6d, 0x70, 0x61, 0x72, 0x61, 0x74, 0x6f, 0x72,
+	0x18, 0x02, 0x20, 0x01, 0x28, 0x0e, 0x32, 0x19, 0x2e, 0x63, 0x68, 0x72, 0x6f, 0x6d, 0x61, 0x2e,
+	0x47, 0x65, 0x6e, 0x65, 0x72, 0x69, 0x63, 0x43, 0x6f, 0x6d, 0x70, 0x61, 0x72, 0x61, 0x74, 0x6f,
+	0x72, 0x48, 0x00, 0x52, 0x11, 0x67, 0x65, 0x6e, 0x65, 0x72, 0x69, 0x63, 0x43, 0x6f, 0x6d, 0x70,
+	0x61, 0x72, 0x61, 0x74, 0x6f, 0x72, 0x12, 0x47, 0x0a, 0x11, 0x6e, 0x75, 0x6d, 0x62, 0x65, 0x72,
+	0x5f, 0x63, 0x6f, 0x6d, 0x70, 0x61, 0x72, 0x61, 0x74, 0x6f, 0x72, 0x18, 0x03, 0x20, 0x01, 0x28,
+	0x0e, 0x32, 0x18, 0x2e, 0x63, 0x68, 0x72, 0x6f, 0x6d, 0x61, 0x2e, 0x4e, 0x75, 0x6d, 0x62, 0x65,
+	0x72, 0x43, 0x6f, 0x6d, 0x70, 0x61, 0x72, 0x61, 0x74, 0x6f, 0x72, 0x48, 0x00, 0x52, 0x10, 0x6e,
+	0x75, 0x6d, 0x62, 0x65, 0x72, 0x43, 0x6f, 0x6d, 0x70, 0x61, 0x72, 0x61, 0x74, 0x6f, 0x72, 0x42,
+	0x0c, 0x0a, 0x0a, 0x63, 0x6f, 0x6d, 0x70, 0x61, 0x72, 0x61, 0x74, 0x6f, 0x72, 0x22, 0x67, 0x0a,
+	0x14, 0x53, 0x69, 0x6e, 0x67, 0x6c, 0x65, 0x42, 0x6f, 0x6f, 0x6c, 0x43, 0x6f, 0x6d, 0x70, 0x61,
+	0x72, 0x69, 0x73, 0x6f, 0x6e, 0x12, 0x14, 0x0a, 0x05, 0x76, 0x61, 0x6c, 0x75, 0x65, 0x18, 0x01,
+	0x20, 0x01, 0x28, 0x08, 0x52, 0x05, 0x76, 0x61, 0x6c, 0x75, 0x65, 0x12, 0x39, 0x0a, 0x0a, 0x63,
+	0x6f, 0x6d, 0x70, 0x61, 0x72, 0x61, 0x74, 0x6f, 0x72, 0x18, 0x02, 0x20, 0x01, 0x28, 0x0e, 0x32,
+	0x19, 0x2e, 0x63, 0x68, 0x72, 0x6f, 0x6d, 0x61, 0x2e, 0x47, 0x65, 0x6e, 0x65, 0x72, 0x69, 0x63,
+	0x43, 0x6f, 0x6d, 0x70, 0x61, 0x72, 0x61, 0x74, 0x6f, 0x72, 0x52, 0x0a, 0x63, 0x6f, 0x6d, 0x70,
+	0x61, 0x72, 0x61, 0x74, 0x6f, 0x72, 0x22, 0x69, 0x0a, 0x14, 0x53, 0x74, 0x72, 0x69, 0x6e, 0x67,
+	0x4c, 0x69, 0x73, 0x74, 0x43, 0x6f, 0x6d, 0x70, 0x61, 0x72, 0x69, 0x73, 0x6f, 0x6e, 0x12, 0x16,
+	0x0a, 0x06, 0x76, 0x61, 0x6c, 0x75, 0x65, 0x73, 0x18, 0x01, 0x20, 0x03, 0x28, 0x09, 0x52, 0x06,
+	0x76, 0x61, 0x6c, 0x75, 0x65, 0x73, 0x12, 0x39, 0x0a, 0x0d, 0x6c, 0x69, 0x73, 0x74, 0x5f, 0x6f,
+	0x70, 0x65, 0x72, 0x61, 0x74, 0x6f, 0x72, 0x18, 0x02, 0x20, 0x01, 0x28, 0x0e, 0x32, 0x14, 0x2e,
+	0x63, 0x68, 0x72, 0x6f, 0x6d, 0x61, 0x2e, 0x4c, 0x69, 0x73, 0x74, 0x4f, 0x70, 0x65, 0x72, 0x61,
+	0x74, 0x6f, 0x72, 0x52, 0x0c, 0x6c, 0x69, 0x73, 0x74, 0x4f, 0x70, 0x65, 0x72, 0x61, 0x74, 0x6f,
+	0x72, 0x22, 0x66, 0x0a, 0x11, 0x49, 0x6e, 0x74, 0x4c, 0x69, 0x73, 0x74, 0x43, 0x6f, 0x6d, 0x70,
+	0x61, 0x72, 0x69, 0x73, 0x6f, 0x6e, 0x12, 0x16, 0x0a, 0x06, 0x76, 0x61, 0x6c, 0x75, 0x65, 0x73,
+	0x18, 0x01, 0x20, 0x03, 0x28, 0x03, 0x52, 0x06, 0x76, 0x61, 0x6c, 0x75, 0x65, 0x73, 0x12, 0x39,
+	0x0a, 0x0d, 0x6c, 0x69, 0x73, 0x74, 0x5f, 0x6f, 0x70, 0x65, 0x72, 0x61, 0x74, 0x6f, 0x72, 0x18,
+	0x02, 0x20, 0x01, 0x28, 0x0e, 0x32, 0x14, 0x2e, 0x63, 0x68, 0x72, 0x6f, 0x6d, 0x61, 0x2e, 0x4c,
+	0x69, 0x73, 0x74, 0x4f, 0x70, 0x65, 0x72, 0x61, 0x74, 0x6f, 0x72, 0x52, 0x0c, 0x6c, 0x69, 0x73,
+	0x74, 0x4f, 0x70, 0x65, 0x72, 0x61, 0x74, 0x6f, 0x72, 0x22, 0x69, 0x0a, 0x14, 0x44, 0x6f, 0x75,
+	0x62, 0x6c, 0x65, 0x4c, 0x69, 0x73, 0x74, 0x43, 0x6f, 0x6d, 0x70, 0x61, 0x72, 0x69, 0x73, 0x6f,
+	0x6e, 0x12, 0x16, 0x0a, 0x06, 0x76, 0x61, 0x6c, 0x75, 0x65, 0x73, 0x18, 0x01, 0x20, 0x03, 0x28,
+	0x01, 0x52, 0x06, 0x76, 0x61, 0x6c, 0x75, 0x65, 0x73, 0x12, 0x39, 0x0a, 0x0d, 0x6c, 0x69, 0x73,
+	0x74, 0x5f, 0x6f, 0x70, 0x65, 0x72, 0x61, 0x74, 0x6f, 0x72, 0x18, 0x02, 0x20, 0x01, 0x28, 0x0e,
+	0x32, 0x14, 0x2e, 0x63, 0x68, 0x72, 0x6f, 0x6d, 0x61, 0x2e, 0x4c, 0x69, 0x73, 0x74, 0x4f, 0x70,
+	0x65, 0x72, 0x61, 0x74, 0x6f, 0x72, 0x52, 0x0c, 0x6c, 0x69, 0x73, 0x74, 0x4f, 0x70, 0x65, 0x72,
+	0x61, 0x74, 0x6f, 0x72, 0x22, 0x67, 0x0a, 0x12, 0x42, 0x6f, 0x6f, 0x6c, 0x4c, 0x69, 0x73, 0x74,
+	0x43, 0x6f, 0x6d, 0x70, 0x61, 0x72, 0x69, 0x73, 0x6f, 0x6e, 0x12, 0x16, 0x0a, 0x06, 0x76, 0x61,
+	0x6c, 0x75, 0x65, 0x73, 0x18, 0x01, 0x20, 0x03, 0x28, 0x08, 0x52, 0x06, 0x76, 0x61, 0x6c, 0x75,
+	0x65, 0x73, 0x12, 0x39, 0x0a, 0x0d, 0x6c, 0x69, 0x73, 0x74, 0x5f, 0x6f, 0x70, 0x65, 0x72, 0x61,
+	0x74, 0x6f, 0x72, 0x18, 0x02, 0x20, 0x01, 0x28, 0x0e, 0x32, 0x14, 0x2e, 0x63, 0x68, 0x72, 0x6f,
+	0x6d, 0x61, 0x2e, 0x4c, 0x69, 0x73, 0x74, 0x4f, 0x70, 0x65, 0x72, 0x61, 0x74, 0x6f, 0x72, 0x52,
+	0x0c, 0x6c, 0x69, 0x73, 0x74, 0x4f, 0x70, 0x65, 0x72, 0x61, 0x74, 0x6f, 0x72, 0x22, 0xac, 0x05,
+	0x0a, 0x10, 0x44, 0x69, 0x72, 0x65, 0x63, 0x74, 0x43, 0x6f, 0x6d, 0x70, 0x61, 0x72, 0x69, 0x73,
+	0x6f, 0x6e, 0x12, 0x10, 0x0a, 0x03, 0x6b, 0x65, 0x79, 0x18, 0x01, 0x20, 0x01, 0x28, 0x09, 0x52,
+	0x03, 0x6b, 0x65, 0x79, 0x12, 0x54, 0x0a, 0x15, 0x73, 0x69, 0x6e, 0x67, 0x6c, 0x65, 0x5f, 0x73,
+	0x74, 0x72, 0x69, 0x6e, 0x67, 0x5f, 0x6f, 0x70, 0x65, 0x72, 0x61, 0x6e, 0x64, 0x18, 0x02, 0x20,
+	0x01, 0x28, 0x0b, 0x32, 0x1e, 0x2e, 0x63, 0x68, 0x72, 0x6f, 0x6d, 0x61, 0x2e, 0x53, 0x69, 0x6e,
+	0x67, 0x6c, 0x65, 0x53, 0x74, 0x72, 0x69, 0x6e, 0x67, 0x43, 0x6f, 0x6d, 0x70, 0x61, 0x72, 0x69,
+	0x73, 0x6f, 0x6e, 0x48, 0x00, 0x52, 0x13, 0x73, 0x69, 0x6e, 0x67, 0x6c, 0x65, 0x53, 0x74, 0x72,
+	0x69, 0x6e, 0x67, 0x4f, 0x70, 0x65, 0x72, 0x61, 0x6e, 0x64, 0x12, 0x4e, 0x0a, 0x13, 0x73, 0x74,
+	0x72, 0x69, 0x6e, 0x67, 0x5f, 0x6c, 0x69, 0x73, 0x74, 0x5f, 0x6f, 0x70, 0x65, 0x72, 0x61, 0x6e,
+	0x64, 0x18, 0x03, 0x20, 0x01, 0x28, 0x0b, 0x32, 0x1c, 0x2e, 0x63, 0x68, 0x72, 0x6f, 0x6d, 0x61,
+	0x2e, 0x53, 0x74, 0x72, 0x69, 0x6e, 0x67, 0x4c, 0x69, 0x73, 0x74, 0x43, 0x6f, 0x6d, 0x70, 0x61,
+	0x72, 0x69, 0x73, 0x6f, 0x6e, 0x48, 0x00, 0x52, 0x11, 0x73, 0x74, 0x72, 0x69, 0x6e, 0x67, 0x4c,
+	0x69, 0x73, 0x74, 0x4f, 0x70, 0x65, 0x72, 0x61, 0x6e, 0x64, 0x12, 0x4b, 0x0a, 0x12, 0x73, 0x69,
+	0x6e, 0x67, 0x6c, 0x65, 0x5f, 0x69, 0x6e, 0x74, 0x5f, 0x6f, 0x70, 0x65, 0x72, 0x61, 0x6e, 0x64,
+	0x18, 0x04, 0x20, 0x01, 0x28, 0x0b, 0x32, 0x1b, 0x2e, 0x63, 0x68, 0x72, 0x6f, 0x6d, 0x61, 0x2e,
+	0x53, 0x69, 0x6e, 0x67, 0x6c, 0x65, 0x49, 0x6e, 0x74, 0x43, 0x6f, 0x6d, 0x70, 0x61, 0x72, 0x69,
+	0x73, 0x6f, 0x6e, 0x48, 0x00, 0x52, 0x10, 0x73, 0x69, 0x6e, 0x67, 0x6c, 0x65, 0x49, 0x6e, 0x74,
+	0x4f, 0x70, 0x65, 0x72, 0x61, 0x6e, 0x64, 0x12, 0x45, 0x0a, 0x10, 0x69, 0x6e, 0x74, 0x5f, 0x6c,
+	0x69, 0x73, 0x74, 0x5f, 0x6f, 0x70, 0x65, 0x72, 0x61, 0x6e, 0x64, 0x18, 0x05, 0x20, 0x01, 0x28,
+	0x0b, 0x32, 0x19, 0x2e, 0x63, 0x68, 0x72, 0x6f, 0x6d, 0x61, 0x2e, 0x49, 0x6e, 0x74, 0x4c, 0x69,
+	0x73, 0x74, 0x43, 0x6f, 0x6d, 0x70, 0x61, 0x72, 0x69, 0x73, 0x6f, 0x6e, 0x48, 0x00, 0x52, 0x0e,
+	0x69, 0x6e, 0x74, 0x4c, 0x69, 0x73, 0x74, 0x4f, 0x70, 0x65, 0x72, 0x61, 0x6e, 0x64, 0x12, 0x54,
+	0x0a, 0x15, 0x73, 0x69, 0x6e, 0x67, 0x6c, 0x65, 0x5f, 0x64, 0x6f, 0x75, 0x62, 0x6c, 0x65, 0x5f,
+	0x6f, 0x70, 0x65, 0x72, 0x61, 0x6e, 0x64, 0x18, 0x06, 0x20, 0x01, 0x28, 0x0b, 0x32, 0x1e, 0x2e,
+	0x63, 0x68, 0x72, 0x6f, 0x6d, 0x61, 0x2e, 0x53, 0x69, 0x6e, 0x67, 0x6c, 0x65, 0x44, 0x6f, 0x75,
+	0x62, 0x6c, 0x65, 0x43, 0x6f, 0x6d, 0x70, 0x61, 0x72, 0x69, 0x73, 0x6f, 0x6e, 0x48, 0x00, 0x52,
+	0x13, 0x73, 0x69, 0x6e, 0x67, 0x6c, 0x65, 0x44, 0x6f, 0x75, 0x62, 0x6c, 0x65, 0x4f, 0x70, 0x65,
+	0x72, 0x61, 0x6e, 0x64, 0x12, 0x4e, 0x0a, 0x13, 0x64, 0x6f, 0x75, 0x62, 0x6c, 0x65, 0x5f, 0x6c,
+	0x69, 0x73, 0x74, 0x5f, 0x6f, 0x70, 0x65, 0x72, 0x61, 0x6e, 0x64, 0x18, 0x07, 0x20, 0x01, 0x28,
+	0x0b, 0x32, 0x1c, 0x2e, 0x63, 0x68, 0x72, 0x6f, 0x6d, 0x61, 0x2e, 0x44, 0x6f, 0x75, 0x62, 0x6c,
+	0x65, 0x4c, 0x69, 0x73, 0x74, 0x43, 0x6f, 0x6d, 0x70, 0x61, 0x72, 0x69, 0x73, 0x6f, 0x6e, 0x48,
+	0x00, 0x52, 0x11, 0x64, 0x6f, 0x75, 0x62, 0x6c, 0x65, 0x4c, 0x69, 0x73, 0x74, 0x4f, 0x70, 0x65,
+	0x72, 0x61, 0x6e, 0x64, 0x12, 0x4e, 0x0a, 0x13, 0x73, 0x69, 0x6e, 0x67, 0x6c, 0x65, 0x5f, 0x62,
+	0x6f, 0x6f, 0x6c, 0x5f, 0x6f, 0x70, 0x65, 0x72, 0x61, 0x6e, 0x64, 0x18, 0x08, 0x20, 0x01, 0x28,
+	0x0b, 0x32, 0x1c, 0x2e, 0x63, 0x68, 0x72, 0x6f, 0x6d, 0x61, 0x2e, 0x53, 0x69, 0x6e, 0x67, 0x6c,
+	0x65, 0x42, 0x6f, 0x6f, 0x6c, 0x43, 0x6f, 0x6d, 0x70, 0x61, 0x72, 0x69, 0x73, 0x6f, 0x6e, 0x48,
+	0x00, 0x52, 0x11, 0x73, 0x69, 0x6e, 0x67, 0x6c, 0x65, 0x42, 0x6f, 0x6f, 0x6c, 0x4f, 0x70, 0x65,
+	0x72, 0x61, 0x6e, 0x64, 0x12, 0x48, 0x0a, 0x11, 0x62, 0x6f, 0x6f, 0x6c, 0x5f, 0x6c, 0x69, 0x73,
+	0x74, 0x5f, 0x6f, 0x70, 0x65, 0x72, 0x61, 0x6e, 0x64, 0x18, 0x09, 0x20, 0x01, 0x28, 0x0b, 0x32,
+	0x1a, 0x2e, 0x63, 0x68, 0x72, 0x6f, 0x6d, 0x61, 0x2e, 0x42, 0x6f, 0x6f, 0x6c, 0x4c, 0x69, 0x73,
+	0x74, 0x43, 0x6f, 0x6d, 0x70, 0x61, 0x72, 0x69, 0x73, 0x6f, 0x6e, 0x48, 0x00, 0x52, 0x0f, 0x62,
+	0x6f, 0x6f, 0x6c, 0x4c, 0x69, 0x73, 0x74, 0x4f, 0x70, 0x65, 0x72, 0x61, 0x6e, 0x64, 0x42, 0x0c,
+	0x0a, 0x0a, 0x63, 0x6f, 0x6d, 0x70, 0x61, 0x72, 0x69, 0x73, 0x6f, 0x6e, 0x22, 0x6f, 0x0a, 0x0d,
+	0x57, 0x68, 0x65, 0x72, 0x65, 0x43, 0x68, 0x69, 0x6c, 0x64, 0x72, 0x65, 0x6e, 0x12, 0x29, 0x0a,
+	0x08, 0x63, 0x68, 0x69, 0x6c, 0x64, 0x72, 0x65, 0x6e, 0x18, 0x01, 0x20, 0x03, 0x28, 0x0b, 0x32,
+	0x0d, 0x2e, 0x63, 0x68, 0x72, 0x6f, 0x6d, 0x61, 0x2e, 0x57, 0x68, 0x65, 0x72, 0x65, 0x52, 0x08,
+	0x63, 0x68, 0x69, 0x6c, 0x64, 0x72, 0x65, 0x6e, 0x12, 0x33, 0x0a, 0x08, 0x6f, 0x70, 0x65, 0x72,
+	0x61, 0x74, 0x6f, 0x72, 0x18, 0x02, 0x20, 0x01, 0x28, 0x0e, 0x32, 0x17, 0x2e, 0x63, 0x68, 0x72,
+	0x6f, 0x6d, 0x61, 0x2e, 0x42, 0x6f, 0x6f, 0x6c, 0x65, 0x61, 0x6e, 0x4f, 0x70, 0x65, 0x72, 0x61,
+	0x74, 0x6f, 0x72, 0x52, 0x08, 0x6f, 0x70, 0x65, 0x72, 0x61, 0x74, 0x6f, 0x72, 0x22, 0xbf, 0x01,
+	0x0a, 0x05, 0x57, 0x68, 0x65, 0x72, 0x65, 0x12, 0x47, 0x0a, 0x11, 0x64, 0x69, 0x72, 0x65, 0x63,
+	0x74, 0x5f, 0x63, 0x6f, 0x6d, 0x70, 0x61, 0x72, 0x69, 0x73, 0x6f, 0x6e, 0x18, 0x01, 0x20, 0x01,
+	0x28, 0x0b, 0x32, 0x18, 0x2e, 0x63, 0x68, 0x72, 0x6f, 0x6d, 0x61, 0x2e, 0x44, 0x69, 0x72, 0x65,
+	0x63, 0x74, 0x43, 0x6f, 0x6d, 0x70, 0x61, 0x72, 0x69, 0x73, 0x6f, 0x6e, 0x48, 0x00, 0x52, 0x10,
+	0x64, 0x69, 0x72, 0x65, 0x63, 0x74, 0x43, 0x6f, 0x6d, 0x70, 0x61, 0x72, 0x69, 0x73, 0x6f, 0x6e,
+	0x12, 0x33, 0x0a, 0x08, 0x63, 0x68, 0x69, 0x6c, 0x64, 0x72, 0x65, 0x6e, 0x18, 0x02, 0x20, 0x01,
+	0x28, 0x0b, 0x32, 0x15, 0x2e, 0x63, 0x68, 0x72, 0x6f, 0x6d, 0x61, 0x2e, 0x57, 0x68, 0x65, 0x72,
+	0x65, 0x43, 0x68, 0x69, 0x6c, 0x64, 0x72, 0x65, 0x6e, 0x48, 0x00, 0x52, 0x08, 0x63, 0x68, 0x69,
+	0x6c, 0x64, 0x72, 0x65, 0x6e, 0x12, 0x2e, 0x0a, 0x0a, 0x6e, 0x6f, 0x74, 0x5f, 0x63, 0x6c, 0x61,
+	0x75, 0x73, 0x65, 0x18, 0x03, 0x20, 0x01, 0x28, 0x0b, 0x32, 0x0d, 0x2e, 0x63, 0x68, 0x72, 0x6f,
+	0x6d, 0x61, 0x2e, 0x57, 0x68, 0x65, 0x72, 0x65, 0x48, 0x00, 0x52, 0x09, 0x6e, 0x6f, 0x74, 0x43,
+	0x6c, 0x61, 0x75, 0x73, 0x65, 0x42, 0x08, 0x0a, 0x06, 0x63, 0x6c, 0x61, 0x75, 0x73, 0x65, 0x22,
+	0x6c, 0x0a, 0x13, 0x44, 0x69, 0x72, 0x65, 0x63, 0x74, 0x57, 0x68, 0x65, 0x72, 0x65, 0x44, 0x6f,
+	0x63, 0x75, 0x6d, 0x65, 0x6e, 0x74, 0x12, 0x1a, 0x0a, 0x08, 0x64, 0x6f, 0x63, 0x75, 0x6d, 0x65,
+	0x6e, 0x74, 0x18, 0x01, 0x20, 0x01, 0x28, 0x09, 0x52, 0x08, 0x64, 0x6f, 0x63, 0x75, 0x6d, 0x65,
+	0x6e, 0x74, 0x12, 0x39, 0x0a, 0x08, 0x6f, 0x70, 0x65, 0x72, 0x61, 0x74, 0x6f, 0x72, 0x18, 0x02,
+	0x20, 0x01, 0x28, 0x0e, 0x32, 0x1d, 0x2e, 0x63, 0x68, 0x72, 0x6f, 0x6d, 0x61, 0x2e, 0x57, 0x68,
+	0x65, 0x72, 0x65, 0x44, 0x6f, 0x63, 0x75, 0x6d, 0x65, 0x6e, 0x74, 0x4f, 0x70, 0x65, 0x72, 0x61,
+	0x74, 0x6f, 0x72, 0x52, 0x08, 0x6f, 0x70, 0x65, 0x72, 0x61, 0x74, 0x6f, 0x72, 0x22, 0x7f, 0x0a,
+	0x15, 0x57, 0x68, 0x65, 0x72, 0x65, 0x44, 0x6f, 0x63, 0x75, 0x6d, 0x65, 0x6e, 0x74, 0x43, 0x68,
+	0x69, 0x6c, 0x64, 0x72, 0x65, 0x6e, 0x12, 0x31, 0x0a, 0x08, 0x63, 0x68, 0x69, 0x6c, 0x64, 0x72,
+	0x65, 0x6e, 0x18, 0x01, 0x20, 0x03, 0x28, 0x0b, 0x32, 0x15, 0x2e, 0x63, 0x68, 0x72, 0x6f, 0x6d,
+	0x61, 0x2e, 0x57, 0x68, 0x65, 0x72, 0x65, 0x44, 0x6f, 0x63, 0x75, 0x6d, 0x65, 0x6e, 0x74, 0x52,
+	0x08, 0x63, 0x68, 0x69, 0x6c, 0x64, 0x72, 0x65, 0x6e, 0x12, 0x33, 0x0a, 0x08, 0x6f, 0x70, 0x65,
+	0x72, 0x61, 0x74, 0x6f, 0x72, 0x18, 0x02, 0x20, 0x01, 0x28, 0x0e, 0x32, 0x17, 0x2e, 0x63, 0x68,
+	0x72, 0x6f, 0x6d, 0x61, 0x2e, 0x42, 0x6f, 0x6f, 0x6c, 0x65, 0x61, 0x6e, 0x4f, 0x70, 0x65, 0x72,
+	0x61, 0x74, 0x6f, 0x72, 0x52, 0x08, 0x6f, 0x70, 0x65, 0x72, 0x61, 0x74, 0x6f, 0x72, 0x22, 0x8d,
+	0x01, 0x0a, 0x0d, 0x57, 0x68, 0x65, 0x72, 0x65, 0x44, 0x6f, 0x63, 0x75, 0x6d, 0x65, 0x6e, 0x74,
+	0x12, 0x35, 0x0a, 0x06, 0x64, 0x69, 0x72, 0x65, 0x63, 0x74, 0x18, 0x01, 0x20, 0x01, 0x28, 0x0b,
+	0x32, 0x1b, 0x2e, 0x63, 0x68, 0x72, 0x6f, 0x6d, 0x61, 0x2e, 0x44, 0x69, 0x72, 0x65, 0x63, 0x74,
+	0x57, 0x68, 0x65, 0x72, 0x65, 0x44, 0x6f, 0x63, 0x75, 0x6d, 0x65, 0x6e, 0x74, 0x48, 0x00, 0x52,
+	0x06, 0x64, 0x69, 0x72, 0x65, 0x63, 0x74, 0x12, 0x3b, 0x0a, 0x08, 0x63, 0x68, 0x69, 0x6c, 0x64,
+	0x72, 0x65, 0x6e, 0x18, 0x02, 0x20, 0x01, 0x28, 0x0b, 0x32, 0x1d, 0x2e, 0x63, 0x68, 0x72, 0x6f,
+	0x6d, 0x61, 0x2e, 0x57, 0x68, 0x65, 0x72, 0x65, 0x44, 0x6f, 0x63, 0x75, 0x6d, 0x65, 0x6e, 0x74,
+	0x43, 0x68, 0x69, 0x6c, 0x64, 0x72, 0x65, 0x6e, 0x48, 0x00, 0x52, 0x08, 0x63, 0x68, 0x69, 0x6c,
+	0x64, 0x72, 0x65, 0x6e, 0x42, 0x08, 0x0a, 0x06, 0x63, 0x6c, 0x61, 0x75, 0x73, 0x65, 0x22, 0x44,
+	0x0a, 0x11, 0x47, 0x65, 0x74, 0x56, 0x65, 0x63, 0x74, 0x6f, 0x72, 0x73, 0x52, 0x65, 0x71, 0x75,
+	0x65, 0x73, 0x74, 0x12, 0x10, 0x0a, 0x03, 0x69, 0x64, 0x73, 0x18, 0x01, 0x20, 0x03, 0x28, 0x09,
+	0x52, 0x03, 0x69, 0x64, 0x73, 0x12, 0x1d, 0x0a, 0x0a, 0x73, 0x65, 0x67, 0x6d, 0x65, 0x6e, 0x74,
+	0x5f, 0x69, 0x64, 0x18, 0x02, 0x20, 0x01, 0x28, 0x09, 0x52, 0x09, 0x73, 0x65, 0x67, 0x6d, 0x65,
+	0x6e, 0x74, 0x49, 0x64, 0x22, 0x4d, 0x0a, 0x12, 0x47, 0x65, 0x74, 0x56, 0x65, 0x63, 0x74, 0x6f,
+	0x72, 0x73, 0x52, 0x65, 0x73, 0x70, 0x6f, 0x6e, 0x73, 0x65, 0x12, 0x37, 0x0a, 0x07, 0x72, 0x65,
+	0x63, 0x6f, 0x72, 0x64, 0x73, 0x18, 0x01, 0x20, 0x03, 0x28, 0x0b, 0x32, 0x1d, 0x2e, 0x63, 0x68,
+	0x72, 0x6f, 0x6d, 0x61, 0x2e, 0x56, 0x65, 0x63, 0x74, 0x6f, 0x72, 0x45, 0x6d, 0x62, 0x65, 0x64,
+	0x64, 0x69, 0x6e, 0x67, 0x52, 0x65, 0x63, 0x6f, 0x72, 0x64, 0x52, 0x07, 0x72, 0x65, 0x63, 0x6f,
+	0x72, 0x64, 0x73, 0x22, 0xf0, 0x02, 0x0a, 0x13, 0x51, 0x75, 0x65, 0x72, 0x79, 0x56, 0x65, 0x63,
+	0x74, 0x6f, 0x72, 0x73, 0x52, 0x65, 0x71, 0x75, 0x65, 0x73, 0x74, 0x12, 0x28, 0x0a, 0x07, 0x76,
+	0x65, 0x63, 0x74, 0x6f, 0x72, 0x73, 0x18, 0x01, 0x20, 0x03, 0x28, 0x0b, 0x32, 0x0e, 0x2e, 0x63,
+	0x68, 0x72, 0x6f, 0x6d, 0x61, 0x2e, 0x56, 0x65, 0x63, 0x74, 0x6f, 0x72, 0x52, 0x07, 0x76, 0x65,
+	0x63, 0x74, 0x6f, 0x72, 0x73, 0x12, 0x0c, 0x0a, 0x01, 0x6b, 0x18, 0x02, 0x20, 0x01, 0x28, 0x05,
+	0x52, 0x01, 0x6b, 0x12, 0x1f, 0x0a, 0x0b, 0x61, 0x6c, 0x6c, 0x6f, 0x77, 0x65, 0x64, 0x5f, 0x69,
+	0x64, 0x73, 0x18, 0x03, 0x20, 0x03, 0x28, 0x09, 0x52, 0x0a, 0x61, 0x6c, 0x6c, 0x6f, 0x77, 0x65,
+	0x64, 0x49, 0x64, 0x73, 0x12, 0x2d, 0x0a, 0x12, 0x69, 0x6e, 0x63, 0x6c, 0x75, 0x64, 0x65, 0x5f,
+	0x65, 0x6d, 0x62, 0x65, 0x64, 0x64, 0x69, 0x6e, 0x67, 0x73, 0x18, 0x04, 0x20, 0x01, 0x28, 0x08,
+	0x52, 0x11, 0x69, 0x6e, 0x63, 0x6c, 0x75, 0x64, 0x65, 0x45, 0x6d, 0x62, 0x65, 0x64, 0x64, 0x69,
+	0x6e, 0x67, 0x73, 0x12, 0x1d, 0x0a, 0x0a, 0x73, 0x65, 0x67, 0x6d, 0x65, 0x6e, 0x74, 0x5f, 0x69,
+	0x64, 0x18, 0x05, 0x20, 0x01, 0x28, 0x09, 0x52, 0x09, 0x73, 0x65, 0x67, 0x6d, 0x65, 0x6e, 0x74,
+	0x49, 0x64, 0x12, 0x1f, 0x0a, 0x0b, 0x71, 0x75, 0x65, 0x72, 0x79, 0x5f, 0x74, 0x65, 0x78, 0x74,
+	0x73, 0x18, 0x06, 0x20, 0x03, 0x28, 0x09, 0x52, 0x0a, 0x71, 0x75, 0x65, 0x72, 0x79, 0x54, 0x65,
+	0x78, 0x74, 0x73, 0x12, 0x2e, 0x0a, 0x13, 0x6d, 0x65, 0x74, 0x61, 0x64, 0x61, 0x74, 0x61, 0x5f,
+	0x73, 0x65, 0x67, 0x6d, 0x65, 0x6e, 0x74, 0x5f, 0x69, 0x64, 0x18, 0x07, 0x20, 0x01, 0x28, 0x09,
+	0x52, 0x11, 0x6d, 0x65, 0x74, 0x61, 0x64, 0x61, 0x74, 0x61, 0x53, 0x65, 0x67, 0x6d, 0x65, 0x6e,
+	0x74, 0x49, 0x64, 0x12, 0x23, 0x0a, 0x05, 0x77, 0x68, 0x65, 0x72, 0x65, 0x18, 0x08, 0x20, 0x01,
+	0x28, 0x0b, 0x32, 0x0d, 0x2e, 0x63, 0x68, 0x72, 0x6f, 0x6d, 0x61, 0x2e, 0x57, 0x68, 0x65, 0x72,
+	0x65, 0x52, 0x05, 0x77, 0x68, 0x65, 0x72, 0x65, 0x12, 0x3c, 0x0a, 0x0e, 0x77, 0x68, 0x65, 0x72,
+	0x65, 0x5f, 0x64, 0x6f, 0x63, 0x75, 0x6d, 0x65, 0x6e, 0x74, 0x18, 0x09, 0x20, 0x01, 0x28, 0x0b,
+	0x32, 0x15, 0x2e, 0x63, 0x68, 0x72, 0x6f, 0x6d, 0x61, 0x2e, 0x57, 0x68, 0x65, 0x72, 0x65, 0x44,
+	0x6f, 0x63, 0x75, 0x6d, 0x65, 0x6e, 0x74, 0x52, 0x0d, 0x77, 0x68, 0x65, 0x72, 0x65, 0x44, 0x6f,
+	0x63, 0x75, 0x6d, 0x65, 0x6e, 0x74, 0x22, 0x4c, 0x0a, 0x14, 0x51, 0x75, 0x65, 0x72, 0x79, 0x56,
+	0x65, 0x63, 0x74, 0x6f, 0x72, 0x73, 0x52, 0x65, 0x73, 0x70, 0x6f, 0x6e, 0x73, 0x65, 0x12, 0x34,
+	0x0a, 0x07, 0x72, 0x65, 0x73, 0x75, 0x6c, 0x74, 0x73, 0x18, 0x01, 0x20, 0x03, 0x28, 0x0b, 0x32,
+	0x1a, 0x2e, 0x63, 0x68, 0x72, 0x6f, 0x6d, 0x61, 0x2e, 0x56, 0x65, 0x63, 0x74, 0x6f, 0x72, 0x51,
+	0x75, 0x65, 0x72, 0x79, 0x52, 0x65, 0x73, 0x75, 0x6c, 0x74, 0x73, 0x52, 0x07, 0x72, 0x65, 0x73,
+	0x75, 0x6c, 0x74, 0x73, 0x2a, 0x38, 0x0a, 0x09, 0x4f, 0x70, 0x65, 0x72, 0x61, 0x74, 0x69, 0x6f,
+	0x6e, 0x12, 0x07, 0x0a, 0x03, 0x41, 0x44, 0x44, 0x10, 0x00, 0x12, 0x0a, 0x0a, 0x06, 0x55, 0x50,
+	0x44, 0x41, 0x54, 0x45, 0x10, 0x01, 0x12, 0x0a, 0x0a, 0x06, 0x55, 0x50, 0x53, 0x45, 0x52, 0x54,
+	0x10, 0x02, 0x12, 0x0a, 0x0a, 0x06, 0x44, 0x45, 0x4c, 0x45, 0x54, 0x45, 0x10, 0x03, 0x2a, 0x28,
+	0x0a, 0x0e, 0x53, 0x63, 0x61, 0x6c, 0x61, 0x72, 0x45, 0x6e, 0x63, 0x6f, 0x64, 0x69, 0x6e, 0x67,
+	0x12, 0x0b, 0x0a, 0x07, 0x46, 0x4c, 0x4f, 0x41, 0x54, 0x33, 0x32, 0x10, 0x00, 0x12, 0x09, 0x0a,
+	0x05, 0x49, 0x4e, 0x54, 0x33, 0x32, 0x10, 0x01, 0x2a, 0x28, 0x0a, 0x0c, 0x53, 0x65, 0x67, 0x6d,
+	0x65, 0x6e, 0x74, 0x53, 0x63, 0x6f, 0x70, 0x65, 0x12, 0x0a, 0x0a, 0x06, 0x56, 0x45, 0x43, 0x54,
+	0x4f, 0x52, 0x10, 0x00, 0x12, 0x0c, 0x0a, 0x08, 0x4d, 0x45, 0x54, 0x41, 0x44, 0x41, 0x54, 0x41,
+	0x10, 0x01, 0x2a, 0x22, 0x0a, 0x0f, 0x42, 0x6f, 0x6f, 0x6c, 0x65, 0x61, 0x6e, 0x4f, 0x70, 0x65,
+	0x72, 0x61, 0x74, 0x6f, 0x72, 0x12, 0x07, 0x0a, 0x03, 0x41, 0x4e, 0x44, 0x10, 0x00, 0x12, 0x06,
+	0x0a, 0x02, 0x4f, 0x52, 0x10, 0x01, 0x2a, 0x23, 0x0a, 0x11, 0x47, 0x65, 0x6e, 0x65, 0x72, 0x69,
+	0x63, 0x43, 0x6f, 0x6d, 0x70, 0x61, 0x72, 0x61, 0x74, 0x6f, 0x72, 0x12, 0x06, 0x0a, 0x02, 0x45,
+	0x51, 0x10, 0x00, 0x12, 0x06, 0x0a, 0x02, 0x4e, 0x45, 0x10, 0x01, 0x2a, 0x34, 0x0a, 0x10, 0x4e,
+	0x75, 0x6d, 0x62, 0x65, 0x72, 0x43, 0x6f, 0x6d, 0x70, 0x61, 0x72, 0x61, 0x74, 0x6f, 0x72, 0x12,
+	0x06, 0x0a, 0x02, 0x47, 0x54, 0x10, 0x00, 0x12, 0x07, 0x0a, 0x03, 0x47, 0x54, 0x45, 0x10, 0x01,
+	0x12, 0x06, 0x0a, 0x02, 0x4c, 0x54, 0x10, 0x02, 0x12, 0x07, 0x0a, 0x03, 0x4c, 0x54, 0x45, 0x10,
+	0x03, 0x2a, 0x1f, 0x0a, 0x0c, 0x4c, 0x69, 0x73, 0x74, 0x4f, 0x70, 0x65, 0x72, 0x61, 0x74, 0x6f,
+	0x72, 0x12, 0x06, 0x0a, 0x02, 0x49, 0x4e, 0x10, 0x00, 0x12, 0x07, 0x0a, 0x03, 0x4e, 0x49, 0x4e,
+	0x10, 0x01, 0x2a, 0x37, 0x0a, 0x15, 0x57, 0x68, 0x65, 0x72, 0x65, 0x44, 0x6f, 0x63, 0x75, 0x6d,
+	0x65, 0x6e, 0x74, 0x4f, 0x70, 0x65, 0x72, 0x61, 0x74, 0x6f, 0x72, 0x12, 0x0c, 0x0a, 0x08, 0x43,
+	0x4f, 0x4e, 0x54, 0x41, 0x49, 0x4e, 0x53, 0x10, 0x00, 0x12, 0x10, 0x0a, 0x0c, 0x4e, 0x4f, 0x54,
+	0x5f, 0x43, 0x4f, 0x4e, 0x54, 0x41, 0x49, 0x4e, 0x53, 0x10, 0x01, 0x32, 0xa2, 0x01, 0x0a, 0x0c,
+	0x56, 0x65, 0x63, 0x74, 0x6f, 0x72, 0x52, 0x65, 0x61, 0x64, 0x65, 0x72, 0x12, 0x45, 0x0a, 0x0a,
+	0x47, 0x65, 0x74, 0x56, 0x65, 0x63, 0x74, 0x6f, 0x72, 0x73, 0x12, 0x19, 0x2e, 0x63, 0x68, 0x72,
+	0x6f, 0x6d, 0x61, 0x2e, 0x47, 0x65, 0x74, 0x56, 0x65, 0x63, 0x74, 0x6f, 0x72, 0x73, 0x52, 0x65,
+	0x71, 0x75, 0x65, 0x73, 0x74, 0x1a, 0x1a, 0x2e, 0x63, 0x68, 0x72, 0x6f, 0x6d, 0x61, 0x2e, 0x47,
+	0x65, 0x74, 0x56, 0x65, 0x63, 0x74, 0x6f, 0x72, 0x73, 0x52, 0x65, 0x73, 0x70, 0x6f, 0x6e, 0x73,
+	0x65, 0x22, 0x00, 0x12, 0x4b, 0x0a, 0x0c, 0x51, 0x75, 0x65, 0x72, 0x79, 0x56, 0x65, 0x63, 0x74,
+	0x6f, 0x72, 0x73, 0x12, 0x1b, 0x2e, 0x63, 0x68, 0x72, 0x6f, 0x6d, 0x61, 0x2e, 0x51, 0x75, 0x65,
+	0x72, 0x79, 0x56, 0x65, 0x63, 0x74, 0x6f, 0x72, 0x73, 0x52, 0x65, 0x71, 0x75, 0x65, 0x73, 0x74,
+	0x1a, 0x1c, 0x2e, 0x63, 0x68, 0x72, 0x6f, 0x6d, 0x61, 0x2e, 0x51, 0x75, 0x65, 0x72, 0x79, 0x56,
+	0x65, 0x63, 0x74, 0x6f, 0x72, 0x73, 0x52, 0x65, 0x73, 0x70, 0x6f, 0x6e, 0x73, 0x65, 0x22, 0x00,
+	0x42, 0x3a, 0x5a, 0x38, 0x67, 0x69, 0x74, 0x68, 0x75, 0x62, 0x2e, 0x63, 0x6f, 0x6d, 0x2f, 0x63,
+	0x68, 0x72, 0x6f, 0x6d, 0x61, 0x2d, 0x63, 0x6f, 0x72, 0x65, 0x2f, 0x63, 0x68, 0x72, 0x6f, 0x6d,
+	0x61, 0x2f, 0x67, 0x6f, 0x2f, 0x70, 0x6b, 0x67, 0x2f, 0x70, 0x72, 0x6f, 0x74, 0x6f, 0x2f, 0x63,
+	0x6f, 0x6f, 0x72, 0x64, 0x69, 0x6e, 0x61, 0x74, 0x6f, 0x72, 0x70, 0x62, 0x62, 0x06, 0x70, 0x72,
+	0x6f, 0x74, 0x6f, 0x33,
 }
 
 var (
@@ -1379,55 +2850,104 @@ func file_chromadb_proto_chroma_proto_rawDescGZIP() []byte {
 	return file_chromadb_proto_chroma_proto_rawDescData
 }
 
-var file_chromadb_proto_chroma_proto_enumTypes = make([]protoimpl.EnumInfo, 3)
-var file_chromadb_proto_chroma_proto_msgTypes = make([]protoimpl.MessageInfo, 17)
+var file_chromadb_proto_chroma_proto_enumTypes = make([]protoimpl.EnumInfo, 8)
+var file_chromadb_proto_chroma_proto_msgTypes = make([]protoimpl.MessageInfo, 31)
 var file_chromadb_proto_chroma_proto_goTypes = []interface{}{
-	(Operation)(0),                // 0: chroma.Operation
-	(ScalarEncoding)(0),           // 1: chroma.ScalarEncoding
-	(SegmentScope)(0),             // 2: chroma.SegmentScope
-	(*Status)(nil),                // 3: chroma.Status
-	(*Vector)(nil),                // 4: chroma.Vector
-	(*Segment)(nil),               // 5: chroma.Segment
-	(*Collection)(nil),            // 6: chroma.Collection
-	(*Database)(nil),              // 7: chroma.Database
-	(*Tenant)(nil),                // 8: chroma.Tenant
-	(*UpdateMetadataValue)(nil),   // 9: chroma.UpdateMetadataValue
-	(*UpdateMetadata)(nil),        // 10: chroma.UpdateMetadata
-	(*SubmitEmbeddingRecord)(nil), // 11: chroma.SubmitEmbeddingRecord
-	(*VectorEmbeddingRecord)(nil), // 12: chroma.VectorEmbeddingRecord
-	(*VectorQueryResult)(nil),     // 13: chroma.VectorQueryResult
-	(*VectorQueryResults)(nil),    // 14: chroma.VectorQueryResults
-	(*GetVectorsRequest)(nil),     // 15: chroma.GetVectorsRequest
-	(*GetVectorsResponse)(nil),    // 16: chroma.GetVectorsResponse
-	(*QueryVectorsRequest)(nil),   // 17: chroma.QueryVectorsRequest
-	(*QueryVectorsResponse)(nil),  // 18: chroma.QueryVectorsResponse
-	nil,                           // 19: chroma.UpdateMetadata.MetadataEntry
+	(Operation)(0),                 // 0: chroma.Operation
+	(ScalarEncoding)(0),            // 1: chroma.ScalarEncoding
+	(SegmentScope)(0),              // 2: chroma.SegmentScope
+	(BooleanOperator)(0),           // 3: chroma.BooleanOperator
+	(GenericComparator)(0),         // 4: chroma.GenericComparator
+	(NumberComparator)(0),          // 5: chroma.NumberComparator
+	(ListOperator)(0),              // 6: chroma.ListOperator
+	(WhereDocumentOperator)(0),     // 7: chroma.WhereDocumentOperator
+	(*Status)(nil),                 // 8: chroma.Status
+	(*Vector)(nil),                 // 9: chroma.Vector
+	(*Segment)(nil),                // 10: chroma.Segment
+	(*Collection)(nil),             // 11: chroma.Collection
+	(*Database)(nil),               // 12: chroma.Database
+	(*Tenant)(nil),                 // 13: chroma.Tenant
+	(*UpdateMetadataValue)(nil),    // 14: chroma.UpdateMetadataValue
+	(*UpdateMetadata)(nil),         // 15: chroma.UpdateMetadata
+	(*SubmitEmbeddingRecord)(nil),  // 16: chroma.SubmitEmbeddingRecord
+	(*VectorEmbeddingRecord)(nil),  // 17: chroma.VectorEmbeddingRecord
+	(*VectorQueryResult)(nil),      // 18: chroma.VectorQueryResult
+	(*VectorQueryResults)(nil),     // 19: chroma.VectorQueryResults
+	(*SingleStringComparison)(nil), // 20: chroma.SingleStringComparison
+	(*SingleIntComparison)(nil),    // 21: chroma.SingleIntComparison
+	(*SingleDoubleComparison)(nil), // 22: chroma.SingleDoubleComparison
+	(*SingleBoolComparison)(nil),   // 23: chroma.SingleBoolComparison
+	(*StringListComparison)(nil),   // 24: chroma.StringListComparison
+	(*IntListComparison)(nil),      // 25: chroma.IntListComparison
+	(*DoubleListComparison)(nil),   // 26: chroma.DoubleListComparison
+	(*BoolListComparison)(nil),     // 27: chroma.BoolListComparison
+	(*DirectComparison)(nil),       // 28: chroma.DirectComparison
+	(*WhereChildren)(nil),          // 29: chroma.WhereChildren
+	(*Where)(nil),                  // 30: chroma.Where
+	(*DirectWhereDocument)(nil),    // 31: chroma.DirectWhereDocument
+	(*WhereDocumentChildren)(nil),  // 32: chroma.WhereDocumentChildren
+	(*WhereDocument)(nil),          // 33: chroma.WhereDocument
+	(*GetVectorsRequest)(nil),      // 34: chroma.GetVectorsRequest
+	(*GetVectorsResponse)(nil),     // 35: chroma.GetVectorsResponse
+	(*QueryVectorsRequest)(nil),    // 36: chroma.QueryVectorsRequest
+	(*QueryVectorsResponse)(nil),   // 37: chroma.QueryVectorsResponse
+	nil,                            // 38: chroma.UpdateMetadata.MetadataEntry
 }
 var file_chromadb_proto_chroma_proto_depIdxs = []int32{
 	1,  // 0: chroma.Vector.encoding:type_name -> chroma.ScalarEncoding
 	2,  // 1: chroma.Segment.scope:type_name -> chroma.SegmentScope
-	10, // 2: chroma.Segment.metadata:type_name -> chroma.UpdateMetadata
-	10, // 3: chroma.Collection.metadata:type_name -> chroma.UpdateMetadata
-	19, // 4: chroma.UpdateMetadata.metadata:type_name -> chroma.UpdateMetadata.MetadataEntry
-	4,  // 5: chroma.SubmitEmbeddingRecord.vector:type_name -> chroma.Vector
-	10, // 6: chroma.SubmitEmbeddingRecord.metadata:type_name -> chroma.UpdateMetadata
+	15, // 2: chroma.Segment.metadata:type_name -> chroma.UpdateMetadata
+	15, // 3: chroma.Collection.metadata:type_name -> chroma.UpdateMetadata
+	38, // 4: chroma.UpdateMetadata.metadata:type_name -> chroma.UpdateMetadata.MetadataEntry
+	9,  // 5: chroma.SubmitEmbeddingRecord.vector:type_name -> chroma.Vector
+	15, // 6: chroma.SubmitEmbeddingRecord.metadata:type_name -> chroma.UpdateMetadata
 	0,  // 7: chroma.SubmitEmbeddingRecord.operation:type_name -> chroma.Operation
-	4,  // 8: chroma.VectorEmbeddingRecord.vector:type_name -> chroma.Vector
-	4,  // 9: chroma.VectorQueryResult.vector:type_name -> chroma.Vector
-	13, // 10: chroma.VectorQueryResults.results:type_name -> chroma.VectorQueryResult
-	12, // 11: chroma.GetVectorsResponse.records:type_name -> chroma.VectorEmbeddingRecord
-	4,  // 12: chroma.QueryVectorsRequest.vectors:type_name -> chroma.Vector
-	14, // 13: chroma.QueryVectorsResponse.results:type_name -> chroma.VectorQueryResults
-	9,  // 14: chroma.UpdateMetadata.MetadataEntry.value:type_name -> chroma.UpdateMetadataValue
-	15, // 15: chroma.VectorReader.GetVectors:input_type -> chroma.GetVectorsRequest
-	17, // 16: chroma.VectorReader.QueryVectors:input_type -> chroma.QueryVectorsRequest
-	16, // 17: chroma.VectorReader.GetVectors:output_type -> chroma.GetVectorsResponse
-	18, // 18: chroma.VectorReader.QueryVectors:output_type -> chroma.QueryVectorsResponse
-	17, // [17:19] is the sub-list for method output_type
-	15, // [15:17] is the sub-list for method input_type
-	15, // [15:15] is the sub-list for extension type_name
-	15, // [15:15] is the sub-list for extension extendee
-	0,  // [0:15] is the sub-list for field type_name
+	9,  // 8: chroma.VectorEmbeddingRecord.vector:type_name -> chroma.Vector
+	9,  // 9: chroma.VectorQueryResult.vector:type_name -> chroma.Vector
+	18, // 10: chroma.VectorQueryResults.results:type_name -> chroma.VectorQueryResult
+	4,  // 11: chroma.SingleStringComparison.comparator:type_name -> chroma.GenericComparator
+	4,  // 12: chroma.SingleIntComparison.generic_comparator:type_name -> chroma.GenericComparator
+	5,  // 13: chroma.SingleIntComparison.number_comparator:type_name -> chroma.NumberComparator
+	4,  // 14: chroma.SingleDoubleComparison.generic_comparator:type_name -> chroma.GenericComparator
+	5,  // 15: chroma.SingleDoubleComparison.number_comparator:type_name -> chroma.NumberComparator
+	4,  // 16: chroma.SingleBoolComparison.comparator:type_name -> chroma.GenericComparator
+	6,  // 17: chroma.StringListComparison.list_operator:type_name -> chroma.ListOperator
+	6,  // 18: chroma.IntListComparison.list_operator:type_name -> chroma.ListOperator
+	6,  // 19: chroma.DoubleListComparison.list_operator:type_name -> chroma.ListOperator
+	6,  // 20: chroma.BoolListComparison.list_operator:type_name -> chroma.ListOperator
+	20, // 21: chroma.DirectComparison.single_string_operand:type_name -> chroma.SingleStringComparison
+	24, // 22: chroma.DirectComparison.string_list_operand:type_name -> chroma.StringListComparison
+	21, // 23: chroma.DirectComparison.single_int_operand:type_name -> chroma.SingleIntComparison
+	25, // 24: chroma.DirectComparison.int_list_operand:type_name -> chroma.IntListComparison
+	22, // 25: chroma.DirectComparison.single_double_operand:type_name -> chroma.SingleDoubleComparison
+	26, // 26: chroma.DirectComparison.double_list_operand:type_name -> chroma.DoubleListComparison
+	23, // 27: chroma.DirectComparison.single_bool_operand:type_name -> chroma.SingleBoolComparison
+	27, // 28: chroma.DirectComparison.bool_list_operand:type_name -> chroma.BoolListComparison
+	30, // 29: chroma.WhereChildren.children:type_name -> chroma.Where
+	3,  // 30: chroma.WhereChildren.operator:type_name -> chroma.BooleanOperator
+	28, // 31: chroma.Where.direct_comparison:type_name -> chroma.DirectComparison
+	29, // 32: chroma.Where.children:type_name -> chroma.WhereChildren
+	30, // 33: chroma.Where.not_clause:type_name -> chroma.Where
+	7,  // 34: chroma.DirectWhereDocument.operator:type_name -> chroma.WhereDocumentOperator
+	33, // 35: chroma.WhereDocumentChildren.children:type_name -> chroma.WhereDocument
+	3,  // 36: chroma.WhereDocumentChildren.operator:type_name -> chroma.BooleanOperator
+	31, // 37: chroma.WhereDocument.direct:type_name -> chroma.DirectWhereDocument
+	32, // 38: chroma.WhereDocument.children:type_name -> chroma.WhereDocumentChildren
+	17, // 39: chroma.GetVectorsResponse.records:type_name -> chroma.VectorEmbeddingRecord
+	9,  // 40: chroma.QueryVectorsRequest.vectors:type_name -> chroma.Vector
+	30, // 41: chroma.QueryVectorsRequest.where:type_name -> chroma.Where
+	33, // 42: chroma.QueryVectorsRequest.where_document:type_name -> chroma.WhereDocument
+	19, // 43: chroma.QueryVectorsResponse.results:type_name -> chroma.VectorQueryResults
+	14, // 44: chroma.UpdateMetadata.MetadataEntry.value:type_name -> chroma.UpdateMetadataValue
+	34, // 45: chroma.VectorReader.GetVectors:input_type -> chroma.GetVectorsRequest
+	36, // 46: chroma.VectorReader.QueryVectors:input_type -> chroma.QueryVectorsRequest
+	35, // 47: chroma.VectorReader.GetVectors:output_type -> chroma.GetVectorsResponse
+	37, // 48: chroma.VectorReader.QueryVectors:output_type -> chroma.QueryVectorsResponse
+	47, // [47:49] is the sub-list for method output_type
+	45, // [45:47] is the sub-list for method input_type
+	45, // [45:45] is the sub-list for extension type_name
+	45, // [45:45] is the sub-list for extension extendee
+	0,  // [0:45] is the sub-list for field type_name
 }
 
 func init() { file_chromadb_proto_chroma_proto_init() }
@@ -1581,7 +3101,7 @@ func file_chromadb_proto_chroma_proto_init() {
 			}
 		}
 		file_chromadb_proto_chroma_proto_msgTypes[12].Exporter = func(v interface{}, i int) interface{} {
-			switch v := v.(*GetVectorsRequest); i {
+			switch v := v.(*SingleStringComparison); i {
 			case 0:
 				return &v.state
 			case 1:
@@ -1593,7 +3113,7 @@ func file_chromadb_proto_chroma_proto_init() {
 			}
 		}
 		file_chromadb_proto_chroma_proto_msgTypes[13].Exporter = func(v interface{}, i int) interface{} {
-			switch v := v.(*GetVectorsResponse); i {
+			switch v := v.(*SingleIntComparison); i {
 			case 0:
 				return &v.state
 			case 1:
@@ -1605,7 +3125,7 @@ func file_chromadb_proto_chroma_proto_init() {
 			}
 		}
 		file_chromadb_proto_chroma_proto_msgTypes[14].Exporter = func(v interface{}, i int) interface{} {
-			switch v := v.(*QueryVectorsRequest); i {
+			switch v := v.(*SingleDoubleComparison); i {
 			case 0:
 				return &v.state
 			case 1:
@@ -1617,6 +3137,174 @@ func file_chromadb_proto_chroma_proto_init() {
 			}
 		}
 		file_chromadb_proto_chroma_proto_msgTypes[15].Exporter = func(v interface{}, i int) interface{} {
+			switch v := v.(*SingleBoolComparison); i {
+			case 0:
+				return &v.state
+			case 1:
+				return &v.sizeCache
+			case 2:
+				return &v.unknownFields
+			default:
+				return nil
+			}
+		}
+		file_chromadb_proto_chroma_proto_msgTypes[16].Exporter = func(v interface{}, i int) interface{} {
+			switch v := v.(*StringListComparison); i {
+			case 0:
+				return &v.state
+			case 1:
+				return &v.sizeCache
+			case 2:
+				return &v.unknownFields
+			default:
+				return nil
+			}
+		}
+		file_chromadb_proto_chroma_proto_msgTypes[17].Exporter = func(v interface{}, i int) interface{} {
+			switch v := v.(*IntListComparison); i {
+			case 0:
+				return &v.state
+			case 1:
+				return &v.sizeCache
+			case 2:
+				return &v.unknownFields
+			default:
+				return nil
+			}
+		}
+		file_chromadb_proto_chroma_proto_msgTypes[18].Exporter = func(v interface{}, i int) interface{} {
+			switch v := v.(*DoubleListComparison); i {
+			case 0:
+				return &v.state
+			case 1:
+				return &v.sizeCache
+			case 2:
+				return &v.unknownFields
+			default:
+				return nil
+			}
+		}
+		file_chromadb_proto_chroma_proto_msgTypes[19].Exporter = func(v interface{}, i int) interface{} {
+			switch v := v.(*BoolListComparison); i {
+			case 0:
+				return &v.state
+			case 1:
+				return &v.sizeCache
+			case 2:
+				return &v.unknownFields
+			default:
+				return nil
+			}
+		}
+		file_chromadb_proto_chroma_proto_msgTypes[20].Exporter = func(v interface{}, i int) interface{} {
+			switch v := v.(*DirectComparison); i {
+			case 0:
+				return &v.state
+			case 1:
+				return &v.sizeCache
+			case 2:
+				return &v.unknownFields
+			default:
+				return nil
+			}
+		}
+		file_chromadb_proto_chroma_proto_msgTypes[21].Exporter = func(v interface{}, i int) interface{} {
+			switch v := v.(*WhereChildren); i {
+			case 0:
+				return &v.state
+			case 1:
+				return &v.sizeCache
+			case 2:
+				return &v.unknownFields
+			default:
+				return nil
+			}
+		}
+		file_chromadb_proto_chroma_proto_msgTypes[22].Exporter = func(v interface{}, i int) interface{} {
+			switch v := v.(*Where); i {
+			case 0:
+				return &v.state
+			case 1:
+				return &v.sizeCache
+			case 2:
+				return &v.unknownFields
+			default:
+				return nil
+			}
+		}
+		file_chromadb_proto_chroma_proto_msgTypes[23].Exporter = func(v interface{}, i int) interface{} {
+			switch v := v.(*DirectWhereDocument); i {
+			case 0:
+				return &v.state
+			case 1:
+				return &v.sizeCache
+			case 2:
+				return &v.unknownFields
+			default:
+				return nil
+			}
+		}
+		file_chromadb_proto_chroma_proto_msgTypes[24].Exporter = func(v interface{}, i int) interface{} {
+			switch v := v.(*WhereDocumentChildren); i {
+			case 0:
+				return &v.state
+			case 1:
+				return &v.sizeCache
+			case 2:
+				return &v.unknownFields
+			default:
+				return nil
+			}
+		}
+		file_chromadb_proto_chroma_proto_msgTypes[25].Exporter = func(v interface{}, i int) interface{} {
+			switch v := v.(*WhereDocument); i {
+			case 0:
+				return &v.state
+			case 1:
+				return &v.sizeCache
+			case 2:
+				return &v.unknownFields
+			default:
+				return nil
+			}
+		}
+		file_chromadb_proto_chroma_proto_msgTypes[26].Exporter = func(v interface{}, i int) interface{} {
+			switch v := v.(*GetVectorsRequest); i {
+			case 0:
+				return &v.state
+			case 1:
+				return &v.sizeCache
+			case 2:
+				return &v.unknownFields
+			default:
+				return nil
+			}
+		}
+		file_chromadb_proto_chroma_proto_msgTypes[27].Exporter = func(v interface{}, i int) interface{} {
+			switch v := v.(*GetVectorsResponse); i {
+			case 0:
+				return &v.state
+			case 1:
+				return &v.sizeCache
+			case 2:
+				return &v.unknownFields
+			default:
+				return nil
+			}
+		}
+		file_chromadb_proto_chroma_proto_msgTypes[28].Exporter = func(v interface{}, i int) interface{} {
+			switch v := v.(*QueryVectorsRequest); i {
+			case 0:
+				return &v.state
+			case 1:
+				return &v.sizeCache
+			case 2:
+				return &v.unknownFields
+			default:
+				return nil
+			}
+		}
+		file_chromadb_proto_chroma_proto_msgTypes[29].Exporter = func(v interface{}, i int) interface{} {
 			switch v := v.(*QueryVectorsResponse); i {
 			case 0:
 				return &v.state
@@ -1638,13 +3326,40 @@ func file_chromadb_proto_chroma_proto_init() {
 	}
 	file_chromadb_proto_chroma_proto_msgTypes[8].OneofWrappers = []interface{}{}
 	file_chromadb_proto_chroma_proto_msgTypes[10].OneofWrappers = []interface{}{}
+	file_chromadb_proto_chroma_proto_msgTypes[13].OneofWrappers = []interface{}{
+		(*SingleIntComparison_GenericComparator)(nil),
+		(*SingleIntComparison_NumberComparator)(nil),
+	}
+	file_chromadb_proto_chroma_proto_msgTypes[14].OneofWrappers = []interface{}{
+		(*SingleDoubleComparison_GenericComparator)(nil),
+		(*SingleDoubleComparison_NumberComparator)(nil),
+	}
+	file_chromadb_proto_chroma_proto_msgTypes[20].OneofWrappers = []interface{}{
+		(*DirectComparison_SingleStringOperand)(nil),
+		(*DirectComparison_StringListOperand)(nil),
+		(*DirectComparison_SingleIntOperand)(nil),
+		(*DirectComparison_IntListOperand)(nil),
+		(*DirectComparison_SingleDoubleOperand)(nil),
+		(*DirectComparison_DoubleListOperand)(nil),
+		(*DirectComparison_SingleBoolOperand)(nil),
+		(*DirectComparison_BoolListOperand)(nil),
+	}
+	file_chromadb_proto_chroma_proto_msgTypes[22].OneofWrappers = []interface{}{
+		(*Where_DirectComparison)(nil),
+		(*Where_Children)(nil),
+		(*Where_NotClause)(nil),
+	}
+	file_chromadb_proto_chroma_proto_msgTypes[25].OneofWrappers = []interface{}{
+		(*WhereDocument_Direct)(nil),
+		(*WhereDocument_Children)(nil),
+	}
 	type x struct{}
 	out := protoimpl.TypeBuilder{
 		File: protoimpl.DescBuilder{
 			GoPackagePath: reflect.TypeOf(x{}).PkgPath(),
 			RawDescriptor: file_chromadb_proto_chroma_proto_rawDesc,
-			NumEnums:      3,
-			NumMessages:   17,
+			NumEnums:      8,
+			NumMessages:   31,
 			NumExtensions: 0,
 			NumServices:   1,
 		},
@@ -1,6 +1,6 @@
 use super::{Block, BlockBuilderOptions, BlockData, BlockDataBuilder};
 use crate::blockstore::{
-    arrow_blockfile::{blockfile::MAX_BLOCK_SIZE, provider::ArrowBlockProvider},
+    arrow_blockfile::provider::ArrowBlockProvider,
     types::{BlockfileKey, KeyType, Value, ValueType},
 };
 use arrow::util::bit_util;
@@ -19,7 +19,7 @@ use std::{collections::BTreeMap, sync::Arc};
 /// - delete: deletes a key from the block delta.
 /// - get_min_key: gets the minimum key in the block delta.
 /// - get_size: gets the size of the block delta.
-/// - split: splits the block delta into two block deltas.
+/// - split_to_fit: splits the block delta into block deltas that each fit the max block size.
 #[derive(Clone)]
 pub struct BlockDelta {
     pub source_block: Arc<Block>,
@@ -28,10 +28,10 @@ pub struct BlockDelta {
 
 impl BlockDelta {
     /// Checks if a key value pair can be added to the block delta and still be within the
-    /// max block size.
+    /// max block size of the source block.
     pub fn can_add(&self, key: &BlockfileKey, value: &Value) -> bool {
         let inner = self.inner.read();
-        inner.can_add(key, value)
+        inner.can_add(key, value, self.source_block.get_max_size())
     }
 
     /// Adds a key value pair to the block delta.
@@ -66,34 +66,33 @@ impl BlockDelta {
         )
     }
 
-    /// Splits the block delta into two block deltas. The split point is the last key
-    /// that pushes the block over the half size.
+    /// Splits the block delta into block deltas that each fit the max block size of the
+    /// source block. This block delta keeps the first keys, every other block delta gets
+    /// a new block from the provider.
     /// # Arguments
-    /// - provider: the arrow block provider to create the new block.
+    /// - provider: the arrow block provider to create the new blocks.
     /// # Returns
-    /// A tuple containing the the key of the split point and the new block delta.
-    /// The new block delta contains all the key value pairs after, but not including the
-    /// split point.
-    /// # Panics
-    /// This function will panic if their is no split point found. This should never happen
-    /// as we should only call this function if can_add returns false.
-    pub fn split(&self, provider: &ArrowBlockProvider) -> (BlockfileKey, BlockDelta) {
-        let new_block = provider.create_block(
-            self.source_block.get_key_type(),
-            self.source_block.get_value_type(),
-        );
+    /// The new block deltas in key order, each with its first key. The result is empty if
+    /// the block delta already fits.
+    /// # Notes
+    /// A single key value pair larger than the max block size gets a block of its own.
+    pub fn split_to_fit(&self, provider: &ArrowBlockProvider) -> Vec<(BlockfileKey, BlockDelta)> {
+        let key_type = self.source_block.get_key_type();
+        let value_type = self.source_block.get_value_type();
         let mut inner = self.inner.write();
-        let (split_key, new_adds) = inner.split(
-            self.source_block.get_key_type(),
-            self.source_block.get_value_type(),
-        );
-        (
-            split_key,
-            BlockDelta {
-                source_block: new_block,
-                inner: Arc::new(RwLock::new(BlockDeltaInner { new_data: new_adds })),
-            },
-        )
+        inner
+            .split_to_fit(key_type, value_type, self.source_block.get_max_size())
+            .into_iter()
+            .map(|(split_key, new_adds)| {
+                (
+                    split_key,
+                    BlockDelta {
+                        source_block: provider.create_block(key_type, value_type),
+                        inner: Arc::new(RwLock::new(BlockDeltaInner { new_data: new_adds })),
+                    },
+                )
+            })
+            .collect()
     }
 
     fn get_prefix_size(&self) -> usize {
@@ -116,7 +115,7 @@ impl BlockDelta {
         inner.get_value_count()
     }
 
-    fn len(&self) -> usize {
+    pub fn len(&self) -> usize {
         let inner = self.inner.read();
         inner.new_data.len()
     }
@@ -201,11 +200,12 @@ impl BlockDeltaInner {
         self.new_data.iter().fold(0, |acc, (_, value)| match value {
             Value::Int32ArrayValue(arr) => acc + arr.len(),
             Value::StringValue(s) => acc + s.len(),
+            Value::RoaringBitmapValue(_) | Value::Int32Value(_) => acc + 1,
             _ => unimplemented!("Value type not implemented"),
         })
     }
 
-    fn can_add(&self, key: &BlockfileKey, value: &Value) -> bool {
+    fn can_add(&self, key: &BlockfileKey, value: &Value, max_block_size: usize) -> bool {
         let additional_prefix_size = key.get_prefix_size();
         let additional_key_size = key.key.get_size();
         let additional_value_size = value.get_size();
@@ -214,9 +214,11 @@ impl BlockDeltaInner {
         let key_data_size = self.get_key_size() + additional_key_size;
         let value_data_size = self.get_value_size() + additional_value_size;
 
-        let prefix_offset_size = bit_util::round_upto_multiple_of_64((self.new_data.len() + 1) * 4);
-        let key_offset_size = self.offset_size_for_key_type(self.new_data.len(), key.into());
-        let value_offset_size = self.offset_size_for_value_type(self.new_data.len(), value.into());
+        // The offsets are sized for the item count after the add
+        let future_item_count = self.new_data.len() + 1;
+        let prefix_offset_size = bit_util::round_upto_multiple_of_64((future_item_count + 1) * 4);
+        let key_offset_size = self.offset_size_for_key_type(future_item_count, key.into());
+        let value_offset_size = self.offset_size_for_value_type(future_item_count, value.into());
 
         let prefix_total_bytes =
             bit_util::round_upto_multiple_of_64(prefix_data_size) + prefix_offset_size;
@@ -225,14 +227,15 @@ impl BlockDeltaInner {
             bit_util::round_upto_multiple_of_64(value_data_size) + value_offset_size;
         let total_future_size = prefix_total_bytes + key_total_bytes + value_total_bytes;
 
-        total_future_size <= MAX_BLOCK_SIZE
+        total_future_size <= max_block_size
     }
 
     fn offset_size_for_value_type(&self, item_count: usize, value_type: ValueType) -> usize {
         match value_type {
-            ValueType::Int32Array | ValueType::String => {
+            ValueType::Int32Array | ValueType::String | ValueType::RoaringBitmap => {
                 bit_util::round_upto_multiple_of_64((item_count + 1) * 4)
             }
+            ValueType::Int32 => 0,
             _ => unimplemented!("Value type not implemented"),
         }
     }
@@ -240,60 +243,62 @@ impl BlockDeltaInner {
     fn offset_size_for_key_type(&self, item_count: usize, key_type: KeyType) -> usize {
         match key_type {
             KeyType::String => bit_util::round_upto_multiple_of_64((item_count + 1) * 4),
-            KeyType::Float => 0,
-            _ => unimplemented!("Key type not implemented"),
+            // Bools are bit packed and have no offsets, like the fixed width keys
+            KeyType::Float | KeyType::Bool | KeyType::Int => 0,
         }
     }
 
-    /// Splits the block delta into two block deltas. The split point is the last key
-    /// that pushes the block over the half size.
+    /// Splits off the key value pairs that do not fit in a block of max_block_size, in
+    /// chunks that each fill a block as far as possible.
     /// # Arguments
     /// - key_type: the key type of the block.
     /// - value_type: the value type of the block.
+    /// - max_block_size: the max size of the block.
     /// # Returns
-    ///
-    fn split(
+    /// The chunks that were split off in key order, each with its first key.
+    fn split_to_fit(
         &mut self,
         key_type: KeyType,
         value_type: ValueType,
-    ) -> (BlockfileKey, BTreeMap<BlockfileKey, Value>) {
-        let half_size = MAX_BLOCK_SIZE / 2;
+        max_block_size: usize,
+    ) -> Vec<(BlockfileKey, BTreeMap<BlockfileKey, Value>)> {
         let mut running_prefix_size = 0;
         let mut running_key_size = 0;
         let mut running_value_size = 0;
         let mut running_count = 0;
-        let mut split_key = None;
-        // The split key will be the last key that pushes the block over the half size. Not the first key that pushes it over
+        let mut split_keys = Vec::new();
+        // A chunk ends before the key that would push it over the max size. A chunk always
+        // takes at least one key, so that no chunk is empty.
         for (key, value) in self.new_data.iter() {
-            running_prefix_size += key.get_prefix_size();
-            running_key_size += key.key.get_size();
-            running_value_size += value.get_size();
-            running_count += 1;
-            let current_size = self.get_block_size(
-                running_count,
-                running_prefix_size,
-                running_key_size,
-                running_value_size,
+            let size_with_key = self.get_block_size(
+                running_count + 1,
+                running_prefix_size + key.get_prefix_size(),
+                running_key_size + key.key.get_size(),
+                running_value_size + value.get_size(),
                 key_type,
                 value_type,
             );
-            if half_size < current_size {
-                break;
+            if running_count > 0 && size_with_key > max_block_size {
+                split_keys.push(key.clone());
+                running_prefix_size = 0;
+                running_key_size = 0;
+                running_value_size = 0;
+                running_count = 0;
             }
-            split_key = Some(key.clone());
+            running_prefix_size += key.get_prefix_size();
+            running_key_size += key.key.get_size();
+            running_value_size += value.get_size();
+            running_count += 1;
         }
 
-        match &split_key {
-            // Note: Consider returning a Result instead of panicking
-            // This should never happen as we should only call this
-            // function if can_add returns false. But it may be worth making
-            // this compile time safe.
-            None => panic!("No split point found"),
-            Some(split_key) => {
-                let split_after = self.new_data.split_off(split_key);
-                return (split_key.clone(), split_after);
-            }
+        // Split off from the back, so that every split_off only moves a single chunk
+        let mut chunks = Vec::with_capacity(split_keys.len());
+        for split_key in split_keys.into_iter().rev() {
+            let chunk = self.new_data.split_off(&split_key);
+            chunks.push((split_key, chunk));
         }
+        chunks.reverse();
+        chunks
     }
 }
 
@@ -339,6 +344,7 @@ impl From<Arc<Block>> for BlockDelta {
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::blockstore::arrow_blockfile::blockfile::BlockSizeConfig;
     use crate::blockstore::types::{Key, KeyType, ValueType};
     use arrow::array::Int32Array;
     use rand::{random, Rng};
@@ -390,7 +396,25 @@ mod test {
 
         let n = 2000;
         for i in 0..n {
-            let key = BlockfileKey::new("prefix".to_string(), Key::Float(i as f32));
+            let key = BlockfileKey::new("prefix".to_string(), Key::Float(i as f64));
+            let value = Value::StringValue(format!("value{}", i));
+            delta.add(key, value);
+        }
+
+        let size = delta.get_size();
+        let block_data = BlockData::try_from(&delta).unwrap();
+        assert_eq!(size, block_data.get_size());
+    }
+
+    #[test]
+    fn test_sizing_i32_key() {
+        let block_provider = ArrowBlockProvider::new();
+        let block = block_provider.create_block(KeyType::Int, ValueType::String);
+        let delta = BlockDelta::from(block.clone());
+
+        let n = 2000;
+        for i in 0..n {
+            let key = BlockfileKey::new("prefix".to_string(), Key::Int(i - n / 2));
             let value = Value::StringValue(format!("value{}", i));
             delta.add(key, value);
         }
@@ -399,4 +423,110 @@ mod test {
         let block_data = BlockData::try_from(&delta).unwrap();
         assert_eq!(size, block_data.get_size());
     }
+
+    #[test]
+    fn test_max_size_per_value_type() {
+        let block_provider = ArrowBlockProvider::with_block_sizes(BlockSizeConfig {
+            string: 1024,
+            ..Default::default()
+        });
+        let string_block = block_provider.create_block(KeyType::String, ValueType::String);
+        let int_arr_block = block_provider.create_block(KeyType::String, ValueType::Int32Array);
+        assert_eq!(string_block.get_max_size(), 1024);
+        assert_eq!(
+            int_arr_block.get_max_size(),
+            BlockSizeConfig::default().int32_array
+        );
+
+        let delta = BlockDelta::from(string_block.clone());
+        let mut i = 0;
+        loop {
+            let key = BlockfileKey::new("prefix".to_string(), Key::String(format!("key{}", i)));
+            let value = Value::StringValue(format!("value{}", i));
+            if !delta.can_add(&key, &value) {
+                break;
+            }
+            delta.add(key, value);
+            i += 1;
+        }
+        assert!(delta.get_size() <= 1024);
+        assert!(delta.split_to_fit(&block_provider).is_empty());
+    }
+
+    #[test]
+    fn test_split_to_fit() {
+        let block_provider = ArrowBlockProvider::with_block_sizes(BlockSizeConfig {
+            string: 1024,
+            ..Default::default()
+        });
+        let block = block_provider.create_block(KeyType::String, ValueType::String);
+        let delta = BlockDelta::from(block.clone());
+
+        let n = 1000;
+        for i in 0..n {
+            let key = BlockfileKey::new("prefix".to_string(), Key::String(format!("key{:04}", i)));
+            delta.add(key, Value::StringValue(format!("value{}", i)));
+        }
+        // A value larger than a block gets a block of its own
+        delta.add(
+            BlockfileKey::new("prefix".to_string(), Key::String("key9999".to_string())),
+            Value::StringValue("v".repeat(2048)),
+        );
+
+        let new_deltas = delta.split_to_fit(&block_provider);
+        assert!(new_deltas.len() > 1);
+        assert!(delta.get_size() <= 1024);
+        let mut total = delta.len();
+        let mut previous_max = delta.get_min_key().unwrap();
+        for (split_key, new_delta) in new_deltas.iter() {
+            assert_eq!(new_delta.source_block.get_max_size(), 1024);
+            assert_ne!(new_delta.source_block.get_id(), block.get_id());
+            assert_eq!(&new_delta.get_min_key().unwrap(), split_key);
+            assert!(split_key > &previous_max);
+            previous_max = split_key.clone();
+            total += new_delta.len();
+        }
+        assert_eq!(total, n + 1);
+        let (_, last) = new_deltas.last().unwrap();
+        assert_eq!(last.len(), 1);
+        for (_, new_delta) in new_deltas[..new_deltas.len() - 1].iter() {
+            assert!(new_delta.get_size() <= 1024);
+        }
+    }
+
+    #[test]
+    fn test_sizing_roaring_bitmap_val() {
+        let block_provider = ArrowBlockProvider::new();
+        let block = block_provider.create_block(KeyType::String, ValueType::RoaringBitmap);
+        let delta = BlockDelta::from(block.clone());
+
+        let n = 2000;
+        for i in 0..n {
+            let key = BlockfileKey::new("prefix".to_string(), Key::String(format!("key{}", i)));
+            let mut bitmap = roaring::RoaringBitmap::new();
+            bitmap.insert_range(0..(i % 100));
+            delta.add(key, Value::RoaringBitmapValue(bitmap));
+        }
+
+        let size = delta.get_size();
+        let block_data = BlockData::try_from(&delta).unwrap();
+        assert_eq!(size, block_data.get_size());
+    }
+
+    #[test]
+    fn test_sizing_int32_val() {
+        let block_provider = ArrowBlockProvider::new();
+        let block = block_provider.create_block(KeyType::String, ValueType::Int32);
+        let delta = BlockDelta::from(block.clone());
+
+        let n = 2000;
+        for i in 0..n {
+            let key = BlockfileKey::new("prefix".to_string(), Key::String(format!("key{}", i)));
+            delta.add(key, Value::Int32Value(i));
+        }
+
+        let size = delta.get_size();
+        let block_data = BlockData::try_from(&delta).unwrap();
+        assert_eq!(size, block_data.get_size());
+    }
 }
@@ -1,11 +1,14 @@
 use super::types::Block;
 use crate::blockstore::types::{BlockfileKey, Key, KeyType, Value, ValueType};
-use arrow::array::{Array, BooleanArray, Int32Array, ListArray, StringArray};
+use arrow::array::{
+    Array, BinaryArray, BooleanArray, Float64Array, Int32Array, Int64Array, ListArray, StringArray,
+};
+use roaring::RoaringBitmap;
 
 /// An iterator over the contents of a block.
 /// This is a simple wrapper around the Arrow array data that is stored in the block.
 /// For now, it clones the data in the Block, since it is only used to populate BlockDeltas.
-pub(super) struct BlockIterator {
+pub(in crate::blockstore::arrow_blockfile) struct BlockIterator {
     block: Block,
     index: usize,
     key_type: KeyType,
@@ -46,7 +49,12 @@ impl Iterator for BlockIterator {
             .as_any()
             .downcast_ref::<StringArray>()
         {
-            Some(prefix) => prefix.value(self.index).to_owned(),
+            Some(prefix) => {
+                if self.index >= prefix.len() {
+                    return None;
+                }
+                prefix.value(self.index).to_owned()
+            }
             None => return None,
         };
 
@@ -60,23 +68,23 @@ impl Iterator for BlockIterator {
             None => return None,
         };
 
-        if self.index >= prefix.len() {
-            return None;
-        }
-
         let key = match self.key_type {
             KeyType::String => match key.as_any().downcast_ref::<StringArray>() {
                 Some(key) => Key::String(key.value(self.index).to_string()),
                 None => return None,
             },
-            KeyType::Float => match key.as_any().downcast_ref::<Int32Array>() {
-                Some(key) => Key::Float(key.value(self.index) as f32),
+            KeyType::Float => match key.as_any().downcast_ref::<Float64Array>() {
+                Some(key) => Key::Float(key.value(self.index)),
                 None => return None,
             },
             KeyType::Bool => match key.as_any().downcast_ref::<BooleanArray>() {
                 Some(key) => Key::Bool(key.value(self.index)),
                 None => return None,
             },
+            KeyType::Int => match key.as_any().downcast_ref::<Int64Array>() {
+                Some(key) => Key::Int(key.value(self.index)),
+                None => return None,
+            },
         };
 
         let value = match self.value_type {
@@ -110,8 +118,23 @@ impl Iterator for BlockIterator {
                 }
                 None => return None,
             },
-            // TODO: Implement the rest of the value types
-            _ => unimplemented!(),
+            ValueType::String => match value.as_any().downcast_ref::<StringArray>() {
+                Some(value) => Value::StringValue(value.value(self.index).to_string()),
+                None => return None,
+            },
+            ValueType::RoaringBitmap => match value.as_any().downcast_ref::<BinaryArray>() {
+                Some(value) => match RoaringBitmap::deserialize_from(value.value(self.index)) {
+                    Ok(bitmap) => Value::RoaringBitmapValue(bitmap),
+                    Err(_) => return None,
+                },
+                None => return None,
+            },
+            ValueType::Int32 => match value.as_any().downcast_ref::<Int32Array>() {
+                Some(value) => Value::Int32Value(value.value(self.index)),
+                None => return None,
+            },
+            // TODO: Implement positional posting lists
+            ValueType::PositionalPostingList => unimplemented!(),
         };
         self.index += 1;
         Some((BlockfileKey::new(prefix, key), value))
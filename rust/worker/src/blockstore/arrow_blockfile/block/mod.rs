@@ -3,4 +3,6 @@ mod iterator;
 mod types;
 
 // Re-export types at the arrow_blockfile module level
+pub(in crate::blockstore::arrow_blockfile) use delta::BlockDelta;
+pub(in crate::blockstore::arrow_blockfile) use iterator::BlockIterator;
 pub(in crate::blockstore::arrow_blockfile) use types::*;
@@ -1,12 +1,18 @@
 use crate::blockstore::types::{BlockfileKey, Key, KeyType, Value, ValueType};
 use crate::errors::{ChromaError, ErrorCodes};
-use arrow::array::{BooleanArray, BooleanBuilder, Float32Array, Float32Builder};
+use arrow::array::{
+    BinaryArray, BinaryBuilder, BooleanArray, BooleanBuilder, Float64Array, Float64Builder,
+};
 use arrow::{
-    array::{Array, Int32Array, Int32Builder, ListArray, ListBuilder, StringArray, StringBuilder},
+    array::{
+        Array, Int32Array, Int32Builder, Int64Array, Int64Builder, ListArray, ListBuilder,
+        StringArray, StringBuilder,
+    },
     datatypes::{DataType, Field},
     record_batch::RecordBatch,
 };
 use parking_lot::RwLock;
+use roaring::RoaringBitmap;
 use std::sync::Arc;
 use thiserror::Error;
 use uuid::Uuid;
@@ -37,6 +43,7 @@ pub(super) struct Inner {
     pub(super) state: BlockState,
     pub(super) key_type: KeyType,
     pub(super) value_type: ValueType,
+    pub(super) max_size: usize,
 }
 
 /// A block in a blockfile. A block is a sorted collection of data that is immutable once it has been committed.
@@ -73,7 +80,7 @@ impl ChromaError for BlockError {
 }
 
 impl Block {
-    pub fn new(id: Uuid, key_type: KeyType, value_type: ValueType) -> Self {
+    pub fn new(id: Uuid, key_type: KeyType, value_type: ValueType, max_size: usize) -> Self {
         Self {
             inner: Arc::new(RwLock::new(Inner {
                 id,
@@ -81,6 +88,27 @@ impl Block {
                 state: BlockState::Uninitialized,
                 key_type,
                 value_type,
+                max_size,
+            })),
+        }
+    }
+
+    /// Creates a registered block from data that was previously flushed.
+    pub fn from_record_batch(
+        id: Uuid,
+        record_batch: RecordBatch,
+        key_type: KeyType,
+        value_type: ValueType,
+        max_size: usize,
+    ) -> Self {
+        Self {
+            inner: Arc::new(RwLock::new(Inner {
+                id,
+                data: Some(BlockData::new(record_batch)),
+                state: BlockState::Registered,
+                key_type,
+                value_type,
+                max_size,
             })),
         }
     }
@@ -105,13 +133,15 @@ impl Block {
                                 inner_key
                                     == key.as_any().downcast_ref::<StringArray>().unwrap().value(i)
                             }
+                            // Compared by bits to agree with the total order of keys.
                             Key::Float(inner_key) => {
-                                *inner_key
+                                inner_key.to_bits()
                                     == key
                                         .as_any()
-                                        .downcast_ref::<Float32Array>()
+                                        .downcast_ref::<Float64Array>()
                                         .unwrap()
                                         .value(i)
+                                        .to_bits()
                             }
                             Key::Bool(inner_key) => {
                                 *inner_key
@@ -121,6 +151,10 @@ impl Block {
                                         .unwrap()
                                         .value(i)
                             }
+                            Key::Int(inner_key) => {
+                                *inner_key
+                                    == key.as_any().downcast_ref::<Int64Array>().unwrap().value(i)
+                            }
                         };
                         if key_matches {
                             match self.get_value_type() {
@@ -147,8 +181,29 @@ impl Block {
                                             .to_string(),
                                     ))
                                 }
-                                // TODO: Add support for other types
-                                _ => unimplemented!(),
+                                ValueType::RoaringBitmap => {
+                                    let bytes = value
+                                        .as_any()
+                                        .downcast_ref::<BinaryArray>()
+                                        .unwrap()
+                                        .value(i);
+                                    return match RoaringBitmap::deserialize_from(bytes) {
+                                        Ok(bitmap) => Some(Value::RoaringBitmapValue(bitmap)),
+                                        // TODO: Log error
+                                        Err(_) => None,
+                                    };
+                                }
+                                ValueType::Int32 => {
+                                    return Some(Value::Int32Value(
+                                        value
+                                            .as_any()
+                                            .downcast_ref::<Int32Array>()
+                                            .unwrap()
+                                            .value(i),
+                                    ))
+                                }
+                                // TODO: Add support for positional posting lists
+                                ValueType::PositionalPostingList => unimplemented!(),
                             }
                         }
                     }
@@ -187,6 +242,11 @@ impl Block {
         self.inner.read().value_type
     }
 
+    /// Returns the size in bytes the block may grow to before it has to be split
+    pub fn get_max_size(&self) -> usize {
+        self.inner.read().max_size
+    }
+
     pub fn get_state(&self) -> BlockState {
         self.inner.read().state
     }
@@ -206,6 +266,29 @@ impl Block {
         }
     }
 
+    /// Marks a commited block as registered, once it has been flushed.
+    pub fn register(&self) -> Result<(), Box<BlockError>> {
+        let mut inner = self.inner.write();
+        match inner.state {
+            BlockState::Commited => {
+                inner.state = BlockState::Registered;
+                Ok(())
+            }
+            BlockState::Uninitialized | BlockState::Initialized | BlockState::Registered => {
+                Err(Box::new(BlockError::InvalidStateTransition))
+            }
+        }
+    }
+
+    /// Returns the record batch holding the data of the block, if it has any.
+    pub fn get_record_batch(&self) -> Option<RecordBatch> {
+        self.inner
+            .read()
+            .data
+            .as_ref()
+            .map(|data| data.data.clone())
+    }
+
     pub fn apply_delta(&self, delta: &BlockDelta) -> Result<(), Box<BlockError>> {
         let data = match BlockData::try_from(delta) {
             Ok(data) => data,
@@ -229,7 +312,7 @@ impl Block {
         }
     }
 
-    pub(super) fn iter(&self) -> BlockIterator {
+    pub(in crate::blockstore::arrow_blockfile) fn iter(&self) -> BlockIterator {
         BlockIterator::new(
             self.clone(),
             self.inner.read().key_type,
@@ -264,13 +347,17 @@ impl BlockData {
 
 enum KeyBuilder {
     StringBuilder(StringBuilder),
-    FloatBuilder(Float32Builder),
+    FloatBuilder(Float64Builder),
     BoolBuilder(BooleanBuilder),
+    IntBuilder(Int64Builder),
 }
 
 enum ValueBuilder {
     Int32ArrayValueBuilder(ListBuilder<Int32Builder>),
     StringValueBuilder(StringBuilder),
+    // Roaring bitmaps are stored in their portable serialization.
+    RoaringBitmap(BinaryBuilder),
+    Int32(Int32Builder),
 }
 
 /// BlockDataBuilder is used to build a block. It is used to add data to a block and then build the BlockData once all data has been added.
@@ -342,26 +429,33 @@ impl BlockDataBuilder {
                 options.key_data_capacity,
             )),
             KeyType::Float => {
-                KeyBuilder::FloatBuilder(Float32Builder::with_capacity(options.item_count))
+                KeyBuilder::FloatBuilder(Float64Builder::with_capacity(options.item_count))
             }
             KeyType::Bool => {
                 KeyBuilder::BoolBuilder(BooleanBuilder::with_capacity(options.item_count))
             }
+            KeyType::Int => KeyBuilder::IntBuilder(Int64Builder::with_capacity(options.item_count)),
         };
-        let value_builder = match value_type {
-            ValueType::Int32Array => {
-                ValueBuilder::Int32ArrayValueBuilder(ListBuilder::with_capacity(
-                    Int32Builder::with_capacity(options.total_value_count),
-                    options.item_count,
-                ))
-            }
-            ValueType::String => ValueBuilder::StringValueBuilder(StringBuilder::with_capacity(
-                options.item_count,
-                options.total_value_capacity,
-            )),
-            // TODO: Implement the other value types
-            _ => unimplemented!(),
-        };
+        let value_builder =
+            match value_type {
+                ValueType::Int32Array => {
+                    ValueBuilder::Int32ArrayValueBuilder(ListBuilder::with_capacity(
+                        Int32Builder::with_capacity(options.total_value_count),
+                        options.item_count,
+                    ))
+                }
+                ValueType::String => ValueBuilder::StringValueBuilder(
+                    StringBuilder::with_capacity(options.item_count, options.total_value_capacity),
+                ),
+                ValueType::RoaringBitmap => ValueBuilder::RoaringBitmap(
+                    BinaryBuilder::with_capacity(options.item_count, options.total_value_capacity),
+                ),
+                ValueType::Int32 => {
+                    ValueBuilder::Int32(Int32Builder::with_capacity(options.item_count))
+                }
+                // TODO: Implement positional posting lists
+                ValueType::PositionalPostingList => unimplemented!(),
+            };
         Self {
             prefix_builder,
             key_builder,
@@ -405,6 +499,12 @@ impl BlockDataBuilder {
                 }
                 _ => unreachable!("Invalid key type for block"),
             },
+            KeyBuilder::IntBuilder(ref mut builder) => match key.key {
+                Key::Int(key) => {
+                    builder.append_value(key);
+                }
+                _ => unreachable!("Invalid key type for block"),
+            },
         }
 
         match self.value_builder {
@@ -420,6 +520,21 @@ impl BlockDataBuilder {
                 }
                 _ => unreachable!("Invalid value type for block"),
             },
+            ValueBuilder::RoaringBitmap(ref mut builder) => match value {
+                Value::RoaringBitmapValue(bitmap) => {
+                    let mut bytes = Vec::with_capacity(bitmap.serialized_size());
+                    // Serializing into a Vec cannot fail.
+                    bitmap.serialize_into(&mut bytes).unwrap();
+                    builder.append_value(bytes);
+                }
+                _ => unreachable!("Invalid value type for block"),
+            },
+            ValueBuilder::Int32(ref mut builder) => match value {
+                Value::Int32Value(int) => {
+                    builder.append_value(int);
+                }
+                _ => unreachable!("Invalid value type for block"),
+            },
         }
 
         Ok(())
@@ -437,7 +552,7 @@ impl BlockDataBuilder {
                 (&arr as &dyn Array).slice(0, arr.len())
             }
             KeyBuilder::FloatBuilder(ref mut builder) => {
-                key_field = Field::new("key", DataType::Float32, true);
+                key_field = Field::new("key", DataType::Float64, true);
                 let arr = builder.finish();
                 (&arr as &dyn Array).slice(0, arr.len())
             }
@@ -446,6 +561,11 @@ impl BlockDataBuilder {
                 let arr = builder.finish();
                 (&arr as &dyn Array).slice(0, arr.len())
             }
+            KeyBuilder::IntBuilder(ref mut builder) => {
+                key_field = Field::new("key", DataType::Int64, true);
+                let arr = builder.finish();
+                (&arr as &dyn Array).slice(0, arr.len())
+            }
         };
 
         let value_field;
@@ -464,6 +584,16 @@ impl BlockDataBuilder {
                 let arr = builder.finish();
                 (&arr as &dyn Array).slice(0, arr.len())
             }
+            ValueBuilder::RoaringBitmap(ref mut builder) => {
+                value_field = Field::new("value", DataType::Binary, true);
+                let arr = builder.finish();
+                (&arr as &dyn Array).slice(0, arr.len())
+            }
+            ValueBuilder::Int32(ref mut builder) => {
+                value_field = Field::new("value", DataType::Int32, true);
+                let arr = builder.finish();
+                (&arr as &dyn Array).slice(0, arr.len())
+            }
         };
 
         let schema = Arc::new(arrow::datatypes::Schema::new(vec![
@@ -1 +1,1326 @@
-pub(super) const MAX_BLOCK_SIZE: usize = 16384;
+use super::block::{Block, BlockDelta, BlockState};
+use super::cursor::{ArrowBlockfileCursor, ArrowBlockfileStream};
+use super::provider::ArrowBlockProvider;
+use super::sparse_index::{PersistedSparseIndex, SparseIndex};
+use crate::blockstore::types::{
+    min_key, Blockfile, BlockfileCursor, BlockfileError, BlockfileIterator, BlockfileKey, Key,
+    KeyType, Value, ValueType,
+};
+use crate::errors::{ChromaError, ErrorCodes};
+use parking_lot::{Mutex, RwLock};
+use serde::{Deserialize, Serialize};
+use std::ops::{Bound, RangeBounds};
+use std::sync::Arc;
+use thiserror::Error;
+use uuid::Uuid;
+
+/// The target size in bytes of the blocks of a blockfile, per value type.
+/// # Fields
+/// - int32_array: The target size of blocks holding Int32Array values. Defaults to 16KiB.
+/// - positional_posting_list: The target size of blocks holding positional posting lists. Defaults to 64KiB.
+/// - roaring_bitmap: The target size of blocks holding roaring bitmaps. Defaults to 64KiB.
+/// - string: The target size of blocks holding string values. Defaults to 8KiB.
+/// - int32: The target size of blocks holding Int32 values. Defaults to 8KiB.
+/// # Notes
+/// Large values such as bitmaps would only fit a handful of entries in a small block, while
+/// small values such as strings would make a large block read far more data than a lookup
+/// needs. Each value type therefore gets its own target size.
+/// Blocks that grow past their target size in a transaction are split when the transaction
+/// is committed.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default)]
+pub(crate) struct BlockSizeConfig {
+    pub(crate) int32_array: usize,
+    pub(crate) positional_posting_list: usize,
+    pub(crate) roaring_bitmap: usize,
+    pub(crate) string: usize,
+    pub(crate) int32: usize,
+}
+
+impl Default for BlockSizeConfig {
+    fn default() -> Self {
+        BlockSizeConfig {
+            int32_array: 16384,
+            positional_posting_list: 65536,
+            roaring_bitmap: 65536,
+            string: 8192,
+            int32: 8192,
+        }
+    }
+}
+
+impl BlockSizeConfig {
+    pub(super) fn max_block_size(&self, value_type: ValueType) -> usize {
+        match value_type {
+            ValueType::Int32Array => self.int32_array,
+            ValueType::PositionalPostingList => self.positional_posting_list,
+            ValueType::RoaringBitmap => self.roaring_bitmap,
+            ValueType::String => self.string,
+            ValueType::Int32 => self.int32,
+        }
+    }
+}
+
+/// The persisted form of an arrow blockfile. The blocks themselves are persisted
+/// separately by the block provider.
+#[derive(Serialize, Deserialize)]
+pub(super) struct PersistedBlockfile {
+    key_type: KeyType,
+    value_type: ValueType,
+    sparse_index: PersistedSparseIndex,
+}
+
+impl PersistedBlockfile {
+    pub(super) fn block_ids(self) -> Result<Vec<Uuid>, uuid::Error> {
+        Ok(SparseIndex::try_from(self.sparse_index)?.block_ids())
+    }
+}
+
+/// A blockfile backed by arrow blocks. The keys are split into contiguous ranges, each
+/// stored sorted in one block, and a sparse index maps the start key of every range to
+/// its block.
+/// # Notes
+/// Blocks are immutable once committed. A transaction collects its writes in block deltas
+/// and on commit turns every delta into a new block, which replaces the block the delta
+/// was read from in a new sparse index. A delta that outgrew the max block size is first
+/// split into several blocks, each added to the new sparse index at its first key. Reads
+/// only see the committed sparse index, so all
+/// clones of a blockfile observe a commit at once. A clone does not carry the transaction
+/// of the blockfile it was cloned from.
+/// Clones of a blockfile may run transactions concurrently, but commits run one at a time
+/// and a transaction can only be committed if no other transaction was committed since it
+/// began. Otherwise the commit fails with TransactionConflict and the transaction has to be
+/// retried, so that no commit silently drops the writes of another.
+/// The blocks a commit replaced are deleted once the new sparse index is in place. Cursors
+/// read their blocks lazily, so a cursor created before a commit fails with SnapshotExpired
+/// when it reaches a block the commit replaced.
+pub(crate) struct ArrowBlockfile {
+    name: String,
+    key_type: KeyType,
+    value_type: ValueType,
+    block_provider: ArrowBlockProvider,
+    sparse_index: Arc<RwLock<SparseIndex>>,
+    // The number of commits of the blockfile. Commits hold the lock while they run.
+    generation: Arc<Mutex<u64>>,
+    transaction_state: Option<TransactionState>,
+}
+
+struct TransactionState {
+    block_delta_pool: Vec<BlockDelta>,
+    sparse_index: SparseIndex,
+    // The generation of the sparse index the transaction started from
+    generation: u64,
+}
+
+impl TransactionState {
+    fn get_delta_for_block(&self, block_id: &Uuid) -> Option<BlockDelta> {
+        self.block_delta_pool
+            .iter()
+            .find(|delta| delta.source_block.get_id() == *block_id)
+            .cloned()
+    }
+}
+
+impl Clone for ArrowBlockfile {
+    fn clone(&self) -> Self {
+        Self {
+            name: self.name.clone(),
+            key_type: self.key_type,
+            value_type: self.value_type,
+            block_provider: self.block_provider.clone(),
+            sparse_index: self.sparse_index.clone(),
+            generation: self.generation.clone(),
+            transaction_state: None,
+        }
+    }
+}
+
+impl ArrowBlockfile {
+    pub(super) fn new(
+        name: &str,
+        key_type: KeyType,
+        value_type: ValueType,
+        block_provider: ArrowBlockProvider,
+    ) -> Self {
+        let initial_block = block_provider.create_block(key_type, value_type);
+        Self {
+            name: name.to_string(),
+            key_type,
+            value_type,
+            block_provider,
+            sparse_index: Arc::new(RwLock::new(SparseIndex::new(initial_block.get_id()))),
+            generation: Arc::new(Mutex::new(0)),
+            transaction_state: None,
+        }
+    }
+
+    pub(super) fn from_persisted(
+        name: &str,
+        persisted: PersistedBlockfile,
+        block_provider: ArrowBlockProvider,
+    ) -> Result<Self, uuid::Error> {
+        Ok(Self {
+            name: name.to_string(),
+            key_type: persisted.key_type,
+            value_type: persisted.value_type,
+            block_provider,
+            sparse_index: Arc::new(RwLock::new(SparseIndex::try_from(persisted.sparse_index)?)),
+            generation: Arc::new(Mutex::new(0)),
+            transaction_state: None,
+        })
+    }
+
+    /// Returns a stream over the committed entries in key order, holding at most max_blocks
+    /// blocks at a time. See ArrowBlockfileStream.
+    pub(crate) fn stream(&self, max_blocks: usize) -> ArrowBlockfileStream {
+        let block_ids = self.sparse_index.read().block_ids();
+        ArrowBlockfileStream::new(
+            self.name.clone(),
+            self.block_provider.clone(),
+            self.sparse_index.clone(),
+            block_ids,
+            max_blocks,
+        )
+    }
+
+    fn get_block(&self, block_id: &Uuid) -> Result<Option<Arc<Block>>, Box<dyn ChromaError>> {
+        match self.block_provider.get_block(&self.name, block_id) {
+            Ok(block) => Ok(block),
+            Err(e) => Err(Box::new(e)),
+        }
+    }
+
+    // Applies the delta to the block, then commits and flushes the block.
+    fn flush_delta(&self, block: &Block, delta: &BlockDelta) -> Result<(), Box<dyn ChromaError>> {
+        if let Err(e) = block.apply_delta(delta) {
+            return Err(e as Box<dyn ChromaError>);
+        }
+        if let Err(e) = block.commit() {
+            return Err(e as Box<dyn ChromaError>);
+        }
+        match self.block_provider.flush_block(&self.name, block) {
+            Ok(_) => Ok(()),
+            Err(e) => Err(Box::new(e)),
+        }
+    }
+
+    fn check_key_type(&self, key: &BlockfileKey) -> Result<(), Box<dyn ChromaError>> {
+        if KeyType::from(key) != self.key_type {
+            return Err(Box::new(ArrowBlockfileError::InvalidKeyType));
+        }
+        Ok(())
+    }
+}
+
+impl Blockfile for ArrowBlockfile {
+    fn begin_transaction(&mut self) -> Result<(), Box<dyn ChromaError>> {
+        if self.transaction_state.is_some() {
+            return Err(Box::new(ArrowBlockfileError::TransactionInProgress));
+        }
+        // Commits update the sparse index while holding the generation, so both are read
+        // from the same commit
+        let generation = self.generation.lock();
+        self.transaction_state = Some(TransactionState {
+            block_delta_pool: Vec::new(),
+            sparse_index: self.sparse_index.read().clone(),
+            generation: *generation,
+        });
+        Ok(())
+    }
+
+    fn commit_transaction(&mut self) -> Result<(), Box<dyn ChromaError>> {
+        let transaction_state = match self.transaction_state.take() {
+            Some(transaction_state) => transaction_state,
+            None => return Err(Box::new(ArrowBlockfileError::TransactionNotInProgress)),
+        };
+        let mut generation = self.generation.lock();
+        if *generation != transaction_state.generation {
+            return Err(Box::new(ArrowBlockfileError::TransactionConflict));
+        }
+        let mut sparse_index = transaction_state.sparse_index;
+        let mut replaced_block_ids = Vec::new();
+
+        for delta in transaction_state.block_delta_pool {
+            for (start_key, new_delta) in delta.split_to_fit(&self.block_provider) {
+                sparse_index.add_block(start_key, new_delta.source_block.get_id());
+                self.flush_delta(&new_delta.source_block, &new_delta)?;
+            }
+
+            // Blocks are immutable once commited, so the delta of a commited block is
+            // written to a new block that takes its place in the sparse index.
+            let block = match delta.source_block.get_state() {
+                BlockState::Uninitialized | BlockState::Initialized => delta.source_block.clone(),
+                BlockState::Commited | BlockState::Registered => {
+                    let new_block = self
+                        .block_provider
+                        .create_block(self.key_type, self.value_type);
+                    sparse_index.replace_block(delta.source_block.get_id(), new_block.get_id());
+                    replaced_block_ids.push(delta.source_block.get_id());
+                    new_block
+                }
+            };
+            self.flush_delta(&block, &delta)?;
+        }
+
+        // A blockfile that was never written to still has an empty initial block, which
+        // is flushed so that the blockfile can be opened again.
+        if sparse_index.len() == 1 {
+            let block_id = sparse_index.block_ids()[0];
+            if let Some(block) = self.get_block(&block_id)? {
+                if let BlockState::Uninitialized = block.get_state() {
+                    self.flush_delta(&block, &BlockDelta::from(block.clone()))?;
+                }
+            }
+        }
+
+        let persisted = PersistedBlockfile {
+            key_type: self.key_type,
+            value_type: self.value_type,
+            sparse_index: PersistedSparseIndex::from(&sparse_index),
+        };
+        if let Err(e) = self.block_provider.write_blockfile(&self.name, &persisted) {
+            return Err(Box::new(e));
+        }
+        *self.sparse_index.write() = sparse_index;
+        *generation += 1;
+        drop(generation);
+
+        // Reads hold the sparse index while they read its blocks, so no read can reach
+        // the replaced blocks anymore. The commit is durable at this point, a block that
+        // cannot be deleted is only wasted space.
+        for block_id in replaced_block_ids {
+            if let Err(e) = self.block_provider.delete_block(&self.name, &block_id) {
+                tracing::error!("Failed to delete replaced block {}: {}", block_id, e);
+            }
+        }
+        Ok(())
+    }
+
+    fn get(&self, key: BlockfileKey) -> Result<Value, Box<dyn ChromaError>> {
+        self.check_key_type(&key)?;
+        let sparse_index = self.sparse_index.read();
+        let block_id = sparse_index.get_target_block_id(&key);
+        match self.get_block(&block_id)? {
+            Some(block) => match block.get(&key) {
+                Some(value) => Ok(value),
+                None => Err(Box::new(BlockfileError::NotFoundError)),
+            },
+            None => Err(Box::new(BlockfileError::NotFoundError)),
+        }
+    }
+
+    fn get_many(&self, keys: &[BlockfileKey]) -> Result<Vec<Option<Value>>, Box<dyn ChromaError>> {
+        for key in keys {
+            self.check_key_type(key)?;
+        }
+        // Visit the keys in sorted order, so that the keys of a block are looked up one
+        // after another and every block is loaded once.
+        let mut order = (0..keys.len()).collect::<Vec<_>>();
+        order.sort_by(|a, b| keys[*a].cmp(&keys[*b]));
+
+        let sparse_index = self.sparse_index.read();
+        let mut values = vec![None; keys.len()];
+        let mut current_block: Option<Arc<Block>> = None;
+        for i in order {
+            let block_id = sparse_index.get_target_block_id(&keys[i]);
+            let block = match &current_block {
+                Some(block) if block.get_id() == block_id => block.clone(),
+                _ => match self.get_block(&block_id)? {
+                    Some(block) => {
+                        current_block = Some(block.clone());
+                        block
+                    }
+                    None => return Err(Box::new(ArrowBlockfileError::BlockNotFound)),
+                },
+            };
+            values[i] = block.get(&keys[i]);
+        }
+        Ok(values)
+    }
+
+    fn get_len(&self, key: BlockfileKey) -> Result<u64, Box<dyn ChromaError>> {
+        match self.get(key)? {
+            Value::RoaringBitmapValue(rbm) => Ok(rbm.len()),
+            Value::Int32ArrayValue(array) => Ok(array.len() as u64),
+            _ => Err(Box::new(BlockfileError::NotACollectionError)),
+        }
+    }
+
+    fn set(&mut self, key: BlockfileKey, value: Value) -> Result<(), Box<dyn ChromaError>> {
+        self.check_key_type(&key)?;
+        if ValueType::from(&value) != self.value_type {
+            return Err(Box::new(ArrowBlockfileError::InvalidValueType));
+        }
+        let transaction_state = match &mut self.transaction_state {
+            Some(transaction_state) => transaction_state,
+            None => return Err(Box::new(ArrowBlockfileError::TransactionNotInProgress)),
+        };
+
+        // Deltas may grow past the max block size, they are split on commit
+        let target_block_id = transaction_state.sparse_index.get_target_block_id(&key);
+        let delta = match transaction_state.get_delta_for_block(&target_block_id) {
+            Some(delta) => delta,
+            None => {
+                let block = match self.block_provider.get_block(&self.name, &target_block_id) {
+                    Ok(Some(block)) => block,
+                    Ok(None) => return Err(Box::new(ArrowBlockfileError::BlockNotFound)),
+                    Err(e) => return Err(Box::new(e)),
+                };
+                let delta = BlockDelta::from(block);
+                transaction_state.block_delta_pool.push(delta.clone());
+                delta
+            }
+        };
+        delta.add(key, value);
+        Ok(())
+    }
+
+    fn value_type(&self) -> Option<ValueType> {
+        Some(self.value_type)
+    }
+
+    fn get_range(
+        &self,
+        prefix: String,
+        start: Bound<Key>,
+        end: Bound<Key>,
+    ) -> Result<BlockfileIterator, Box<dyn ChromaError>> {
+        for bound in [&start, &end] {
+            if let Bound::Included(key) | Bound::Excluded(key) = bound {
+                if KeyType::from(key) != self.key_type {
+                    return Err(Box::new(ArrowBlockfileError::InvalidKeyType));
+                }
+            }
+        }
+        let start_key = match &start {
+            Bound::Included(key) | Bound::Excluded(key) => key.clone(),
+            Bound::Unbounded => min_key(self.key_type),
+        };
+        let sparse_index = self.sparse_index.read();
+        let block_ids = sparse_index.block_ids_from(&BlockfileKey::new(prefix.clone(), start_key));
+
+        // Blocks hold contiguous, sorted key ranges, so the scan starts at the block that
+        // may hold the start key and stops at the first key past the end of the range.
+        let range = (start, end);
+        let mut result = Vec::new();
+        'blocks: for block_id in block_ids {
+            let block = match self.get_block(&block_id)? {
+                Some(block) => block,
+                None => return Err(Box::new(ArrowBlockfileError::BlockNotFound)),
+            };
+            for (key, value) in block.iter() {
+                if key.prefix > prefix || (key.prefix == prefix && past_end(&key.key, &range.1)) {
+                    break 'blocks;
+                }
+                if key.prefix == prefix && range.contains(&key.key) {
+                    result.push((key, value));
+                }
+            }
+        }
+        Ok(Box::new(result.into_iter()))
+    }
+
+    fn iter(&self) -> Result<BlockfileCursor, Box<dyn ChromaError>> {
+        let block_ids = self.sparse_index.read().block_ids();
+        Ok(Box::new(ArrowBlockfileCursor::new(
+            self.name.clone(),
+            self.block_provider.clone(),
+            self.sparse_index.clone(),
+            block_ids,
+            None,
+        )))
+    }
+
+    fn iter_from(&self, key: BlockfileKey) -> Result<BlockfileCursor, Box<dyn ChromaError>> {
+        self.check_key_type(&key)?;
+        let block_ids = self.sparse_index.read().block_ids_from(&key);
+        Ok(Box::new(ArrowBlockfileCursor::new(
+            self.name.clone(),
+            self.block_provider.clone(),
+            self.sparse_index.clone(),
+            block_ids,
+            Some(key),
+        )))
+    }
+
+    fn verify(&self) -> Result<(), Box<dyn ChromaError>> {
+        let sparse_index = self.sparse_index.read();
+        for block_id in sparse_index.block_ids() {
+            match self.block_provider.verify_block(&block_id) {
+                Ok(true) => {}
+                Ok(false) => return Err(Box::new(ArrowBlockfileError::BlockNotFound)),
+                Err(e) => return Err(Box::new(e)),
+            }
+        }
+        Ok(())
+    }
+}
+
+fn past_end(key: &Key, end: &Bound<Key>) -> bool {
+    match end {
+        Bound::Included(end) => key > end,
+        Bound::Excluded(end) => key >= end,
+        Bound::Unbounded => false,
+    }
+}
+
+#[derive(Error, Debug)]
+pub(crate) enum ArrowBlockfileError {
+    #[error("Transaction already in progress")]
+    TransactionInProgress,
+    #[error("Transaction not in progress")]
+    TransactionNotInProgress,
+    #[error("Key type does not match the blockfile")]
+    InvalidKeyType,
+    #[error("Value type does not match the blockfile")]
+    InvalidValueType,
+    #[error("Block not found")]
+    BlockNotFound,
+    #[error("Another transaction was committed since this transaction began")]
+    TransactionConflict,
+    #[error("Block was replaced by a later commit")]
+    SnapshotExpired,
+}
+
+impl ChromaError for ArrowBlockfileError {
+    fn code(&self) -> ErrorCodes {
+        match self {
+            ArrowBlockfileError::TransactionInProgress => ErrorCodes::FailedPrecondition,
+            ArrowBlockfileError::TransactionNotInProgress => ErrorCodes::FailedPrecondition,
+            ArrowBlockfileError::InvalidKeyType => ErrorCodes::InvalidArgument,
+            ArrowBlockfileError::InvalidValueType => ErrorCodes::InvalidArgument,
+            ArrowBlockfileError::BlockNotFound => ErrorCodes::DataLoss,
+            ArrowBlockfileError::TransactionConflict => ErrorCodes::Aborted,
+            ArrowBlockfileError::SnapshotExpired => ErrorCodes::Aborted,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blockstore::arrow_blockfile::cache::BlockCache;
+    use crate::blockstore::arrow_blockfile::format::BlockCompression;
+    use crate::blockstore::arrow_blockfile::provider::ArrowBlockfileProvider;
+    use crate::blockstore::provider::BlockfileProvider;
+    use arrow::array::Int32Array;
+    use roaring::RoaringBitmap;
+
+    fn string_key(key: &str) -> BlockfileKey {
+        BlockfileKey::new("prefix".to_string(), Key::String(key.to_string()))
+    }
+
+    #[test]
+    fn test_set_get_across_transactions() {
+        let mut provider = ArrowBlockfileProvider::new();
+        let mut blockfile = provider
+            .create("test", KeyType::String, ValueType::Int32Array)
+            .unwrap();
+
+        blockfile.begin_transaction().unwrap();
+        blockfile
+            .set(
+                string_key("a"),
+                Value::Int32ArrayValue(Int32Array::from(vec![1, 2, 3])),
+            )
+            .unwrap();
+        // Uncommitted writes are not visible
+        assert!(blockfile.get(string_key("a")).is_err());
+        blockfile.commit_transaction().unwrap();
+
+        blockfile.begin_transaction().unwrap();
+        blockfile
+            .set(
+                string_key("b"),
+                Value::Int32ArrayValue(Int32Array::from(vec![4])),
+            )
+            .unwrap();
+        blockfile.commit_transaction().unwrap();
+
+        let reader = provider.open("test").unwrap();
+        match reader.get(string_key("a")).unwrap() {
+            Value::Int32ArrayValue(arr) => assert_eq!(arr, Int32Array::from(vec![1, 2, 3])),
+            _ => panic!("Unexpected value type"),
+        }
+        assert_eq!(reader.get_len(string_key("b")).unwrap(), 1);
+        assert!(reader.get(string_key("c")).is_err());
+    }
+
+    #[test]
+    fn test_set_requires_transaction_and_types() {
+        let mut provider = ArrowBlockfileProvider::new();
+        let mut blockfile = provider
+            .create("test", KeyType::String, ValueType::String)
+            .unwrap();
+        let result = blockfile.set(string_key("a"), Value::StringValue("a".to_string()));
+        assert_eq!(result.unwrap_err().code(), ErrorCodes::FailedPrecondition);
+
+        blockfile.begin_transaction().unwrap();
+        let result = blockfile.set(string_key("a"), Value::Int32Value(1));
+        assert_eq!(result.unwrap_err().code(), ErrorCodes::InvalidArgument);
+        let result = blockfile.set(
+            BlockfileKey::new("prefix".to_string(), Key::Int(1)),
+            Value::StringValue("a".to_string()),
+        );
+        assert_eq!(result.unwrap_err().code(), ErrorCodes::InvalidArgument);
+    }
+
+    #[test]
+    fn test_blocks_split() {
+        let block_provider = ArrowBlockProvider::with_block_sizes(BlockSizeConfig {
+            string: 1024,
+            ..Default::default()
+        });
+        let mut blockfile =
+            ArrowBlockfile::new("test", KeyType::String, ValueType::String, block_provider);
+
+        let n = 500;
+        blockfile.begin_transaction().unwrap();
+        for i in 0..n {
+            blockfile
+                .set(
+                    string_key(&format!("{:04}", i)),
+                    Value::StringValue(format!("value{}", i)),
+                )
+                .unwrap();
+        }
+        blockfile.commit_transaction().unwrap();
+        assert!(blockfile.sparse_index.read().len() > 1);
+
+        // Overwrite a committed key and add new ones in a second transaction
+        blockfile.begin_transaction().unwrap();
+        blockfile
+            .set(string_key("0000"), Value::StringValue("new".to_string()))
+            .unwrap();
+        blockfile
+            .set(string_key("9999"), Value::StringValue("last".to_string()))
+            .unwrap();
+        blockfile.commit_transaction().unwrap();
+
+        for i in 1..n {
+            match blockfile.get(string_key(&format!("{:04}", i))).unwrap() {
+                Value::StringValue(s) => assert_eq!(s, format!("value{}", i)),
+                _ => panic!("Unexpected value type"),
+            }
+        }
+        match blockfile.get(string_key("0000")).unwrap() {
+            Value::StringValue(s) => assert_eq!(s, "new"),
+            _ => panic!("Unexpected value type"),
+        }
+
+        let values = blockfile
+            .get_by_prefix("prefix")
+            .unwrap()
+            .collect::<Vec<_>>();
+        assert_eq!(values.len(), n + 1);
+        let keys = values.iter().map(|(k, _)| k.clone()).collect::<Vec<_>>();
+        let mut sorted_keys = keys.clone();
+        sorted_keys.sort();
+        assert_eq!(keys, sorted_keys);
+
+        let greater = blockfile
+            .get_gt("prefix".to_string(), Key::String("0490".to_string()))
+            .unwrap()
+            .collect::<Vec<_>>();
+        assert_eq!(greater.len(), 10);
+    }
+
+    #[test]
+    fn test_commit_splits_blocks_to_budget() {
+        let block_provider = ArrowBlockProvider::with_block_sizes(BlockSizeConfig {
+            int32: 2048,
+            ..Default::default()
+        });
+        let mut blockfile = ArrowBlockfile::new(
+            "test",
+            KeyType::Int,
+            ValueType::Int32,
+            block_provider.clone(),
+        );
+
+        blockfile.begin_transaction().unwrap();
+        for i in 0..10 {
+            blockfile
+                .set(
+                    BlockfileKey::new("prefix".to_string(), Key::Int(i)),
+                    Value::Int32Value(i as i32),
+                )
+                .unwrap();
+        }
+        blockfile.commit_transaction().unwrap();
+        assert_eq!(blockfile.sparse_index.read().len(), 1);
+
+        // Grow the single committed block far past the budget in one transaction
+        blockfile.begin_transaction().unwrap();
+        for i in 10..5000 {
+            blockfile
+                .set(
+                    BlockfileKey::new("prefix".to_string(), Key::Int(i)),
+                    Value::Int32Value(i as i32),
+                )
+                .unwrap();
+        }
+        blockfile.commit_transaction().unwrap();
+
+        let block_ids = blockfile.sparse_index.read().block_ids();
+        assert!(block_ids.len() > 1);
+        let mut total = 0;
+        for block_id in block_ids {
+            let block = block_provider
+                .get_block("test", &block_id)
+                .unwrap()
+                .unwrap();
+            assert!(block.get_size() <= 2048);
+            total += block.len();
+        }
+        assert_eq!(total, 5000);
+        for i in 0..5000 {
+            match blockfile
+                .get(BlockfileKey::new("prefix".to_string(), Key::Int(i)))
+                .unwrap()
+            {
+                Value::Int32Value(v) => assert_eq!(v, i as i32),
+                _ => panic!("Unexpected value type"),
+            }
+        }
+    }
+
+    #[test]
+    fn test_range_scans_across_blocks() {
+        let block_provider = ArrowBlockProvider::with_block_sizes(BlockSizeConfig {
+            int32: 1024,
+            ..Default::default()
+        });
+        let mut blockfile =
+            ArrowBlockfile::new("test", KeyType::Int, ValueType::Int32, block_provider);
+        blockfile.begin_transaction().unwrap();
+        for prefix in ["a", "b", "c"] {
+            for i in (0..1000).rev() {
+                blockfile
+                    .set(
+                        BlockfileKey::new(prefix.to_string(), Key::Int(i)),
+                        Value::Int32Value(i as i32),
+                    )
+                    .unwrap();
+            }
+        }
+        blockfile.commit_transaction().unwrap();
+        assert!(blockfile.sparse_index.read().len() > 3);
+
+        let keys = |range: BlockfileIterator| {
+            range
+                .map(|(k, _)| {
+                    assert_eq!(k.prefix, "b");
+                    match k.key {
+                        Key::Int(i) => i,
+                        _ => panic!("Key is not an int"),
+                    }
+                })
+                .collect::<Vec<_>>()
+        };
+        let range = blockfile
+            .get_range(
+                "b".to_string(),
+                Bound::Excluded(Key::Int(100)),
+                Bound::Included(Key::Int(900)),
+            )
+            .unwrap();
+        assert_eq!(keys(range), (101..=900).collect::<Vec<_>>());
+        let range = blockfile.get_lt("b".to_string(), Key::Int(10)).unwrap();
+        assert_eq!(keys(range), (0..10).collect::<Vec<_>>());
+        let range = blockfile.get_gte("b".to_string(), Key::Int(990)).unwrap();
+        assert_eq!(keys(range), (990..1000).collect::<Vec<_>>());
+        let range = blockfile
+            .get_range("b".to_string(), Bound::Unbounded, Bound::Unbounded)
+            .unwrap();
+        assert_eq!(keys(range), (0..1000).collect::<Vec<_>>());
+        let range = blockfile.get_by_prefix("b").unwrap();
+        assert_eq!(keys(range), (0..1000).collect::<Vec<_>>());
+        assert_eq!(blockfile.get_by_prefix("ab").unwrap().count(), 0);
+
+        let result = blockfile.get_gt("b".to_string(), Key::String("1".to_string()));
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_get_many_across_blocks() {
+        let block_provider = ArrowBlockProvider::with_block_sizes(BlockSizeConfig {
+            string: 1024,
+            ..Default::default()
+        });
+        let mut blockfile =
+            ArrowBlockfile::new("test", KeyType::String, ValueType::String, block_provider);
+        blockfile.begin_transaction().unwrap();
+        for i in 0..500 {
+            blockfile
+                .set(
+                    string_key(&format!("{:04}", i)),
+                    Value::StringValue(format!("value{}", i)),
+                )
+                .unwrap();
+        }
+        blockfile.commit_transaction().unwrap();
+        assert!(blockfile.sparse_index.read().len() > 1);
+
+        let queried = ["0499", "0000", "missing", "0250", "0000", "0001"];
+        let keys = queried.iter().map(|k| string_key(k)).collect::<Vec<_>>();
+        let values = blockfile.get_many(&keys).unwrap();
+        assert_eq!(values.len(), queried.len());
+        for (queried, value) in queried.iter().zip(values) {
+            match (queried.parse::<usize>(), value) {
+                (Ok(i), Some(Value::StringValue(s))) => assert_eq!(s, format!("value{}", i)),
+                (Err(_), None) => {}
+                _ => panic!("Unexpected value for {}", queried),
+            }
+        }
+    }
+
+    #[test]
+    fn test_cursor_across_blocks() {
+        let tmp_dir = tempfile::tempdir().unwrap();
+        let storage_path = tmp_dir.path().to_path_buf();
+        let block_sizes = BlockSizeConfig {
+            int32: 1024,
+            ..Default::default()
+        };
+        let block_cache = BlockCache::new(1024 * 1024);
+
+        {
+            let mut provider = ArrowBlockfileProvider::with_config(
+                block_sizes.clone(),
+                Some(storage_path.clone()),
+                BlockCompression::None,
+                block_cache.clone(),
+            );
+            let mut blockfile = provider
+                .create("test", KeyType::Int, ValueType::Int32)
+                .unwrap();
+            blockfile.begin_transaction().unwrap();
+            for prefix in ["a", "b"] {
+                for i in 0..1000 {
+                    blockfile
+                        .set(
+                            BlockfileKey::new(prefix.to_string(), Key::Int(i)),
+                            Value::Int32Value(i as i32),
+                        )
+                        .unwrap();
+                }
+            }
+            blockfile.commit_transaction().unwrap();
+        }
+
+        let provider = ArrowBlockfileProvider::with_config(
+            block_sizes,
+            Some(storage_path.clone()),
+            BlockCompression::None,
+            block_cache,
+        );
+        let blockfile = provider.open("test").unwrap();
+        let entries = blockfile
+            .iter()
+            .unwrap()
+            .map(|entry| entry.unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(entries.len(), 2000);
+        let keys = entries.iter().map(|(k, _)| k.clone()).collect::<Vec<_>>();
+        let mut sorted_keys = keys.clone();
+        sorted_keys.sort();
+        assert_eq!(keys, sorted_keys);
+
+        let mut cursor = blockfile
+            .iter_from(BlockfileKey::new("b".to_string(), Key::Int(500)))
+            .unwrap();
+        let (first_key, _) = cursor.next().unwrap().unwrap();
+        assert_eq!(first_key, BlockfileKey::new("b".to_string(), Key::Int(500)));
+        assert_eq!(cursor.count(), 499);
+
+        // A block that went missing surfaces as an error instead of ending the cursor early.
+        // The provider has an empty cache, so that it reads every block from storage.
+        let provider = ArrowBlockfileProvider::with_config(
+            BlockSizeConfig::default(),
+            Some(storage_path.clone()),
+            BlockCompression::None,
+            BlockCache::new(0),
+        );
+        let blockfile = provider.open("test").unwrap();
+        let mut block_files = std::fs::read_dir(storage_path.join("blocks"))
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .collect::<Vec<_>>();
+        block_files.sort();
+        std::fs::remove_file(&block_files[0]).unwrap();
+        let results = blockfile.iter().unwrap().collect::<Vec<_>>();
+        assert!(results.len() < 2000);
+        match results.last().unwrap() {
+            Err(e) => assert_eq!(e.code(), ErrorCodes::DataLoss),
+            Ok(_) => panic!("Expected an error"),
+        }
+    }
+
+    #[test]
+    fn test_reopen_from_storage() {
+        let tmp_dir = tempfile::tempdir().unwrap();
+        let storage_path = tmp_dir.path().to_path_buf();
+
+        {
+            let mut provider = ArrowBlockfileProvider::with_config(
+                BlockSizeConfig::default(),
+                Some(storage_path.clone()),
+                BlockCompression::None,
+                BlockCache::new(1024 * 1024),
+            );
+            let mut blockfile = provider
+                .create("segment/bitmaps", KeyType::Float, ValueType::RoaringBitmap)
+                .unwrap();
+            blockfile.begin_transaction().unwrap();
+            for i in 0..100 {
+                let mut bitmap = RoaringBitmap::new();
+                bitmap.insert_range(0..i);
+                blockfile
+                    .set(
+                        BlockfileKey::new("prefix".to_string(), Key::Float(i as f64)),
+                        Value::RoaringBitmapValue(bitmap),
+                    )
+                    .unwrap();
+            }
+            blockfile.commit_transaction().unwrap();
+
+            let mut empty = provider
+                .create("segment/empty", KeyType::String, ValueType::Int32)
+                .unwrap();
+            empty.begin_transaction().unwrap();
+            empty.commit_transaction().unwrap();
+        }
+
+        let mut provider = ArrowBlockfileProvider::with_config(
+            BlockSizeConfig::default(),
+            Some(storage_path),
+            BlockCompression::None,
+            BlockCache::new(1024 * 1024),
+        );
+        assert!(provider
+            .create("segment/bitmaps", KeyType::Float, ValueType::RoaringBitmap)
+            .is_err());
+        let blockfile = provider.open("segment/bitmaps").unwrap();
+        for i in 0..100 {
+            let key = BlockfileKey::new("prefix".to_string(), Key::Float(i as f64));
+            assert_eq!(blockfile.get_len(key).unwrap(), i);
+        }
+        let empty = provider.open("segment/empty").unwrap();
+        assert!(empty.get(string_key("a")).is_err());
+        assert!(provider.open("segment/missing").is_err());
+    }
+
+    #[test]
+    fn test_compressed_blocks() {
+        let tmp_dir = tempfile::tempdir().unwrap();
+        let storage_path = tmp_dir.path().to_path_buf();
+        let value = |i: usize| Value::StringValue(format!("value{}", i).repeat(10));
+
+        {
+            let mut provider = ArrowBlockfileProvider::with_config(
+                BlockSizeConfig::default(),
+                Some(storage_path.clone()),
+                BlockCompression::Zstd { level: 3 },
+                BlockCache::new(1024 * 1024),
+            );
+            let mut blockfile = provider
+                .create("test", KeyType::String, ValueType::String)
+                .unwrap();
+            blockfile.begin_transaction().unwrap();
+            for i in 0..100 {
+                blockfile
+                    .set(string_key(&format!("{:04}", i)), value(i))
+                    .unwrap();
+            }
+            blockfile.commit_transaction().unwrap();
+        }
+
+        // Blocks record their codec, so a provider without compression reads them as well
+        let provider = ArrowBlockfileProvider::with_config(
+            BlockSizeConfig::default(),
+            Some(storage_path),
+            BlockCompression::None,
+            BlockCache::new(1024 * 1024),
+        );
+        let blockfile = provider.open("test").unwrap();
+        for i in 0..100 {
+            match (
+                blockfile.get(string_key(&format!("{:04}", i))).unwrap(),
+                value(i),
+            ) {
+                (Value::StringValue(s), Value::StringValue(expected)) => assert_eq!(s, expected),
+                _ => panic!("Unexpected value type"),
+            }
+        }
+    }
+
+    #[test]
+    fn test_verify_detects_corruption() {
+        let tmp_dir = tempfile::tempdir().unwrap();
+        let storage_path = tmp_dir.path().to_path_buf();
+        let mut provider = ArrowBlockfileProvider::with_config(
+            BlockSizeConfig::default(),
+            Some(storage_path.clone()),
+            BlockCompression::None,
+            BlockCache::new(1024 * 1024),
+        );
+        let mut blockfile = provider
+            .create("test", KeyType::String, ValueType::String)
+            .unwrap();
+        blockfile.verify().unwrap();
+        blockfile.begin_transaction().unwrap();
+        for i in 0..10 {
+            blockfile
+                .set(
+                    string_key(&format!("{:04}", i)),
+                    Value::StringValue(format!("value{}", i)),
+                )
+                .unwrap();
+        }
+        blockfile.commit_transaction().unwrap();
+        blockfile.verify().unwrap();
+
+        let block_path = std::fs::read_dir(storage_path.join("blocks"))
+            .unwrap()
+            .next()
+            .unwrap()
+            .unwrap()
+            .path();
+        let mut bytes = std::fs::read(&block_path).unwrap();
+        let last = bytes.len() - 1;
+        bytes[last] ^= 0xff;
+        std::fs::write(&block_path, bytes).unwrap();
+
+        // The block is still cached, but verification reads it from storage
+        assert!(blockfile.get(string_key("0000")).is_ok());
+        assert_eq!(blockfile.verify().unwrap_err().code(), ErrorCodes::DataLoss);
+
+        let provider = ArrowBlockfileProvider::with_config(
+            BlockSizeConfig::default(),
+            Some(storage_path.clone()),
+            BlockCompression::None,
+            BlockCache::new(1024 * 1024),
+        );
+        let reopened = provider.open("test").unwrap();
+        assert_eq!(
+            reopened.get(string_key("0000")).unwrap_err().code(),
+            ErrorCodes::DataLoss
+        );
+
+        std::fs::remove_file(&block_path).unwrap();
+        assert_eq!(blockfile.verify().unwrap_err().code(), ErrorCodes::DataLoss);
+    }
+
+    #[test]
+    fn test_invalid_blockfile_names() {
+        let tmp_dir = tempfile::tempdir().unwrap();
+        let storage_path = tmp_dir.path().join("storage");
+        let mut provider = ArrowBlockfileProvider::with_config(
+            BlockSizeConfig::default(),
+            Some(storage_path.clone()),
+            BlockCompression::None,
+            BlockCache::new(1024 * 1024),
+        );
+        for name in [
+            "",
+            "../escape",
+            "/absolute",
+            "a/../../b",
+            "a//b",
+            "a/./b",
+            "a\\b",
+        ] {
+            let err = provider
+                .create(name, KeyType::String, ValueType::String)
+                .err()
+                .unwrap();
+            assert_eq!(err.code(), ErrorCodes::InvalidArgument);
+            let err = provider.open(name).err().unwrap();
+            assert_eq!(err.code(), ErrorCodes::InvalidArgument);
+        }
+
+        let mut blockfile = provider
+            .create("a/b", KeyType::String, ValueType::String)
+            .unwrap();
+        blockfile.begin_transaction().unwrap();
+        blockfile.commit_transaction().unwrap();
+        assert!(storage_path.join("blockfiles/a/b.json").exists());
+    }
+
+    #[test]
+    fn test_commit_deletes_replaced_blocks() {
+        let tmp_dir = tempfile::tempdir().unwrap();
+        let storage_path = tmp_dir.path().to_path_buf();
+        let block_cache = BlockCache::new(1024 * 1024);
+        let mut provider = ArrowBlockfileProvider::with_config(
+            BlockSizeConfig::default(),
+            Some(storage_path.clone()),
+            BlockCompression::None,
+            block_cache.clone(),
+        );
+        let mut blockfile = provider
+            .create("test", KeyType::String, ValueType::String)
+            .unwrap();
+        let block_ids = || {
+            std::fs::read_dir(storage_path.join("blocks"))
+                .unwrap()
+                .map(|entry| {
+                    let path = entry.unwrap().path();
+                    let stem = path.file_stem().unwrap().to_str().unwrap().to_string();
+                    Uuid::parse_str(&stem).unwrap()
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let mut previous_block_ids: Vec<Uuid> = Vec::new();
+        for i in 0..5 {
+            blockfile.begin_transaction().unwrap();
+            blockfile
+                .set(
+                    string_key(&format!("{:04}", i)),
+                    Value::StringValue(format!("value{}", i)),
+                )
+                .unwrap();
+            blockfile.commit_transaction().unwrap();
+
+            // Each commit replaces the only block, the replaced block is gone from storage
+            // and from the cache
+            let block_ids = block_ids();
+            assert_eq!(block_ids.len(), 1);
+            for block_id in previous_block_ids {
+                assert!(!block_ids.contains(&block_id));
+                assert!(block_cache.get("test", &block_id).is_none());
+            }
+            previous_block_ids = block_ids;
+        }
+
+        for i in 0..5 {
+            match blockfile.get(string_key(&format!("{:04}", i))).unwrap() {
+                Value::StringValue(value) => assert_eq!(value, format!("value{}", i)),
+                _ => panic!("Unexpected value type"),
+            }
+        }
+    }
+
+    #[test]
+    fn test_concurrent_commits_conflict() {
+        let mut provider = ArrowBlockfileProvider::new();
+        let mut first = provider
+            .create("test", KeyType::String, ValueType::String)
+            .unwrap();
+        let mut second = provider.open("test").unwrap();
+
+        first.begin_transaction().unwrap();
+        second.begin_transaction().unwrap();
+        first
+            .set(string_key("a"), Value::StringValue("a".to_string()))
+            .unwrap();
+        second
+            .set(string_key("b"), Value::StringValue("b".to_string()))
+            .unwrap();
+        first.commit_transaction().unwrap();
+        let result = second.commit_transaction();
+        assert_eq!(result.unwrap_err().code(), ErrorCodes::Aborted);
+        assert!(first.get(string_key("a")).is_ok());
+        assert!(first.get(string_key("b")).is_err());
+
+        // A retried transaction starts from the latest commit
+        second.begin_transaction().unwrap();
+        second
+            .set(string_key("b"), Value::StringValue("b".to_string()))
+            .unwrap();
+        second.commit_transaction().unwrap();
+        assert!(first.get(string_key("a")).is_ok());
+        assert!(first.get(string_key("b")).is_ok());
+    }
+
+    #[test]
+    fn test_cursor_snapshot_expired() {
+        let mut provider = ArrowBlockfileProvider::new();
+        let mut blockfile = provider
+            .create("test", KeyType::String, ValueType::String)
+            .unwrap();
+        blockfile.begin_transaction().unwrap();
+        blockfile
+            .set(string_key("a"), Value::StringValue("a".to_string()))
+            .unwrap();
+        blockfile.commit_transaction().unwrap();
+
+        // The commit replaces the only block before the cursor loads it
+        let mut cursor = blockfile.iter().unwrap();
+        blockfile.begin_transaction().unwrap();
+        blockfile
+            .set(string_key("b"), Value::StringValue("b".to_string()))
+            .unwrap();
+        blockfile.commit_transaction().unwrap();
+        match cursor.next() {
+            Some(Err(e)) => assert_eq!(e.code(), ErrorCodes::Aborted),
+            _ => panic!("Expected an error"),
+        }
+        assert!(cursor.next().is_none());
+        assert_eq!(blockfile.iter().unwrap().count(), 2);
+    }
+
+    #[test]
+    fn test_create_over_unreadable_blockfile() {
+        let tmp_dir = tempfile::tempdir().unwrap();
+        let storage_path = tmp_dir.path().to_path_buf();
+        std::fs::create_dir_all(storage_path.join("blockfiles")).unwrap();
+        std::fs::write(storage_path.join("blockfiles/test.json"), "not json").unwrap();
+        let mut provider = ArrowBlockfileProvider::with_config(
+            BlockSizeConfig::default(),
+            Some(storage_path),
+            BlockCompression::None,
+            BlockCache::new(1024 * 1024),
+        );
+        let result = provider.create("test", KeyType::String, ValueType::String);
+        assert_eq!(result.err().unwrap().code(), ErrorCodes::DataLoss);
+        let result = provider.create("other", KeyType::String, ValueType::String);
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_stream() {
+        let tmp_dir = tempfile::tempdir().unwrap();
+        let mut provider = ArrowBlockfileProvider::with_config(
+            BlockSizeConfig {
+                string: 1024,
+                ..Default::default()
+            },
+            Some(tmp_dir.path().to_path_buf()),
+            BlockCompression::None,
+            // Every block is read back from storage
+            BlockCache::new(0),
+        );
+        let mut blockfile = provider
+            .create("test", KeyType::String, ValueType::String)
+            .unwrap();
+        blockfile.begin_transaction().unwrap();
+        for i in 0..500 {
+            blockfile
+                .set(
+                    string_key(&format!("{:04}", i)),
+                    Value::StringValue(format!("value {}", i)),
+                )
+                .unwrap();
+        }
+        blockfile.commit_transaction().unwrap();
+        let expected = blockfile
+            .iter()
+            .unwrap()
+            .map(|entry| entry.unwrap())
+            .collect::<Vec<_>>();
+        assert!(
+            std::fs::read_dir(tmp_dir.path().join("blocks"))
+                .unwrap()
+                .count()
+                > 2
+        );
+
+        for max_blocks in [0, 2, 8] {
+            let mut stream = provider.stream("test", max_blocks).unwrap();
+            let mut entries = Vec::new();
+            while let Some(entry) = stream.next().await {
+                entries.push(entry.unwrap());
+            }
+            assert_eq!(entries.len(), expected.len());
+            assert!(entries.iter().zip(expected.iter()).all(|(a, b)| a.0 == b.0));
+        }
+
+        // A block that cannot be read ends the stream with an error
+        std::fs::remove_dir_all(tmp_dir.path().join("blocks")).unwrap();
+        let mut stream = provider.stream("test", 2).unwrap();
+        match stream.next().await {
+            Some(Err(e)) => assert_eq!(e.code(), ErrorCodes::DataLoss),
+            _ => panic!("Expected an error"),
+        }
+        assert!(stream.next().await.is_none());
+    }
+
+    #[test]
+    fn test_warm_block_cache_from_hot_set() {
+        let tmp_dir = tempfile::tempdir().unwrap();
+        let storage_path = tmp_dir.path().to_path_buf();
+        let new_provider = |block_cache: &BlockCache| {
+            ArrowBlockfileProvider::with_config(
+                BlockSizeConfig::default(),
+                Some(storage_path.clone()),
+                BlockCompression::None,
+                block_cache.clone(),
+            )
+        };
+
+        let block_cache = BlockCache::new(1024 * 1024);
+        let mut provider = new_provider(&block_cache);
+        let mut blockfile = provider
+            .create("test", KeyType::String, ValueType::String)
+            .unwrap();
+        blockfile.begin_transaction().unwrap();
+        blockfile
+            .set(string_key("a"), Value::StringValue("a".to_string()))
+            .unwrap();
+        blockfile.commit_transaction().unwrap();
+        provider.save_block_cache_hot_set(16).unwrap();
+
+        // A restarted worker loads the hot set before the blockfile is read
+        let block_cache = BlockCache::new(1024 * 1024);
+        let provider = new_provider(&block_cache);
+        assert_eq!(provider.warm_block_cache(16).unwrap(), 1);
+        let size = block_cache.size();
+        assert!(size > 0);
+        let misses = block_cache.misses();
+        let reopened = provider.open("test").unwrap();
+        assert!(reopened.get(string_key("a")).is_ok());
+        assert_eq!(block_cache.misses(), misses);
+
+        // The hot set is bounded
+        let block_cache = BlockCache::new(1024 * 1024);
+        assert_eq!(new_provider(&block_cache).warm_block_cache(0).unwrap(), 0);
+        assert_eq!(block_cache.size(), 0);
+    }
+
+    #[test]
+    fn test_shared_block_cache() {
+        let tmp_dir = tempfile::tempdir().unwrap();
+        let storage_path = tmp_dir.path().to_path_buf();
+        let block_cache = BlockCache::new(1024 * 1024);
+        let new_provider = || {
+            ArrowBlockfileProvider::with_config(
+                BlockSizeConfig::default(),
+                Some(storage_path.clone()),
+                BlockCompression::None,
+                block_cache.clone(),
+            )
+        };
+
+        let mut provider = new_provider();
+        let mut blockfile = provider
+            .create("test", KeyType::String, ValueType::String)
+            .unwrap();
+        blockfile.begin_transaction().unwrap();
+        blockfile
+            .set(string_key("a"), Value::StringValue("a".to_string()))
+            .unwrap();
+        blockfile.commit_transaction().unwrap();
+        assert!(block_cache.size() > 0);
+
+        // Another provider sharing the cache serves the block without reading storage
+        std::fs::remove_dir_all(storage_path.join("blocks")).unwrap();
+        let reopened = new_provider().open("test").unwrap();
+        let misses = block_cache.misses();
+        let hits = block_cache.hits();
+        assert!(reopened.get(string_key("a")).is_ok());
+        assert_eq!(block_cache.hits(), hits + 1);
+        assert_eq!(block_cache.misses(), misses);
+
+        let uncached = ArrowBlockfileProvider::with_config(
+            BlockSizeConfig::default(),
+            Some(storage_path.clone()),
+            BlockCompression::None,
+            BlockCache::new(1024 * 1024),
+        );
+        let reopened = uncached.open("test").unwrap();
+        assert!(reopened.get(string_key("a")).is_err());
+    }
+}
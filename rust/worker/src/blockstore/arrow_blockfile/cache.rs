@@ -0,0 +1,368 @@
+use super::block::Block;
+use crate::config::{Configurable, WorkerConfig};
+use crate::errors::ChromaError;
+use async_trait::async_trait;
+use lru::LruCache;
+use parking_lot::{Condvar, Mutex};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use uuid::Uuid;
+
+// The default capacity of the process-wide block cache, 256MiB.
+pub(crate) const DEFAULT_BLOCK_CACHE_CAPACITY: usize = 256 * 1024 * 1024;
+// The default number of blocks whose keys are saved on shutdown to warm the cache on startup.
+pub(crate) const DEFAULT_HOT_SET_SIZE: usize = 1024;
+// How often the block cache logs its hit and miss counters.
+const STATS_INTERVAL: Duration = Duration::from_secs(60);
+
+struct CachedBlocks {
+    blocks: LruCache<(String, Uuid), Arc<Block>>,
+    // The total size in bytes of the cached blocks
+    size: usize,
+}
+
+// A load of a block that other lookups of the same block wait for
+#[derive(Default)]
+struct Loading {
+    // Set once the load finished, to the loaded block if it succeeded
+    done: Mutex<Option<Option<Arc<Block>>>>,
+    finished: Condvar,
+}
+
+struct BlockCacheInner {
+    cached: Mutex<CachedBlocks>,
+    loading: Mutex<HashMap<(String, Uuid), Arc<Loading>>>,
+    capacity: usize,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+/// A cache of persisted blocks, keyed by the name of the blockfile and the id of the
+/// block. Every provider given the same cache shares it, so the memory spent on blocks
+/// is bounded across all of their blockfiles rather than per blockfile.
+/// # Notes
+/// The capacity bounds the total size of the cached blocks in bytes. Once it is exceeded,
+/// the least recently used blocks are evicted. A block larger than the capacity is not
+/// cached at all.
+/// Blocks are immutable once persisted, so a cached block never has to be invalidated. It
+/// is only removed once a commit replaced it and the block is deleted.
+/// Concurrent misses of the same block share a single load, the first lookup reads the
+/// block and the others wait for it rather than reading it again.
+/// # Methods
+/// - new: Creates a cache holding at most capacity bytes of blocks.
+/// - try_from_config: Creates the cache of the worker with the configured capacity. It is built
+/// once at startup and passed to every blockfile provider.
+/// - get_or_load: Returns the cached block or loads it, sharing the load with concurrent
+/// lookups of the same block.
+/// - hot_set: The keys of the most recently used blocks, most recent first.
+/// - hits, misses: The number of lookups that did and did not find their block.
+/// - size: The total size in bytes of the cached blocks.
+/// - report_stats: Logs the hit and miss counters periodically.
+#[derive(Clone)]
+pub(crate) struct BlockCache {
+    inner: Arc<BlockCacheInner>,
+}
+
+impl BlockCache {
+    pub(crate) fn new(capacity: usize) -> Self {
+        Self {
+            inner: Arc::new(BlockCacheInner {
+                cached: Mutex::new(CachedBlocks {
+                    blocks: LruCache::unbounded(),
+                    size: 0,
+                }),
+                loading: Mutex::new(HashMap::new()),
+                capacity,
+                hits: AtomicU64::new(0),
+                misses: AtomicU64::new(0),
+            }),
+        }
+    }
+
+    pub(super) fn get(&self, blockfile: &str, block_id: &Uuid) -> Option<Arc<Block>> {
+        let block = self
+            .inner
+            .cached
+            .lock()
+            .blocks
+            .get(&(blockfile.to_string(), *block_id))
+            .cloned();
+        match block {
+            Some(_) => self.inner.hits.fetch_add(1, Ordering::Relaxed),
+            None => self.inner.misses.fetch_add(1, Ordering::Relaxed),
+        };
+        block
+    }
+
+    pub(super) fn get_or_load<E>(
+        &self,
+        blockfile: &str,
+        block_id: &Uuid,
+        load: impl FnOnce() -> Result<Option<Arc<Block>>, E>,
+    ) -> Result<Option<Arc<Block>>, E> {
+        if let Some(block) = self.get(blockfile, block_id) {
+            return Ok(Some(block));
+        }
+        let key = (blockfile.to_string(), *block_id);
+        let (loading, leader) = {
+            let mut in_flight = self.inner.loading.lock();
+            match in_flight.get(&key) {
+                Some(loading) => (loading.clone(), false),
+                None => {
+                    let loading = Arc::new(Loading::default());
+                    in_flight.insert(key.clone(), loading.clone());
+                    (loading, true)
+                }
+            }
+        };
+
+        if !leader {
+            let mut done = loading.done.lock();
+            while done.is_none() {
+                loading.finished.wait(&mut done);
+            }
+            if let Some(Some(block)) = done.as_ref() {
+                return Ok(Some(block.clone()));
+            }
+            drop(done);
+            // The load failed or found no block, load it again so that this lookup
+            // reports its own result
+            return load();
+        }
+
+        // Wakes the waiting lookups even if the load panics
+        let mut finish = LoadingGuard {
+            cache: self,
+            key,
+            loading,
+            block: None,
+        };
+        let result = load();
+        if let Ok(Some(block)) = &result {
+            self.insert(blockfile, block.clone());
+        }
+        finish.block = result.as_ref().ok().and_then(|block| block.clone());
+        drop(finish);
+        result
+    }
+
+    pub(super) fn insert(&self, blockfile: &str, block: Arc<Block>) {
+        let block_size = block.get_size();
+        if block_size > self.inner.capacity {
+            return;
+        }
+        let mut cached = self.inner.cached.lock();
+        if let Some(replaced) = cached
+            .blocks
+            .put((blockfile.to_string(), block.get_id()), block)
+        {
+            cached.size -= replaced.get_size();
+        }
+        cached.size += block_size;
+        while cached.size > self.inner.capacity {
+            match cached.blocks.pop_lru() {
+                Some((_, evicted)) => cached.size -= evicted.get_size(),
+                None => break,
+            }
+        }
+    }
+
+    pub(super) fn remove(&self, blockfile: &str, block_id: &Uuid) {
+        let mut cached = self.inner.cached.lock();
+        if let Some(removed) = cached.blocks.pop(&(blockfile.to_string(), *block_id)) {
+            cached.size -= removed.get_size();
+        }
+    }
+
+    pub(super) fn hot_set(&self, limit: usize) -> Vec<(String, Uuid)> {
+        self.inner
+            .cached
+            .lock()
+            .blocks
+            .iter()
+            .take(limit)
+            .map(|(key, _)| key.clone())
+            .collect()
+    }
+
+    pub(crate) fn hits(&self) -> u64 {
+        self.inner.hits.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn misses(&self) -> u64 {
+        self.inner.misses.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn size(&self) -> usize {
+        self.inner.cached.lock().size
+    }
+
+    pub(crate) async fn report_stats(self) {
+        loop {
+            tokio::time::sleep(STATS_INTERVAL).await;
+            tracing::info!(
+                "Block cache: {} hits, {} misses, {} of {} bytes used",
+                self.hits(),
+                self.misses(),
+                self.size(),
+                self.inner.capacity
+            );
+        }
+    }
+}
+
+struct LoadingGuard<'a> {
+    cache: &'a BlockCache,
+    key: (String, Uuid),
+    loading: Arc<Loading>,
+    block: Option<Arc<Block>>,
+}
+
+impl Drop for LoadingGuard<'_> {
+    fn drop(&mut self) {
+        self.cache.inner.loading.lock().remove(&self.key);
+        *self.loading.done.lock() = Some(self.block.take());
+        self.loading.finished.notify_all();
+    }
+}
+
+#[async_trait]
+impl Configurable for BlockCache {
+    async fn try_from_config(worker_config: &WorkerConfig) -> Result<Self, Box<dyn ChromaError>> {
+        Ok(BlockCache::new(worker_config.blockfile.block_cache_bytes))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blockstore::arrow_blockfile::block::BlockDelta;
+    use crate::blockstore::arrow_blockfile::provider::ArrowBlockProvider;
+    use crate::blockstore::types::{BlockfileKey, Key, KeyType, Value, ValueType};
+
+    fn block_of_size(block_provider: &ArrowBlockProvider, n: i64) -> Arc<Block> {
+        let block = block_provider.create_block(KeyType::Int, ValueType::Int32);
+        let delta = BlockDelta::from(block.clone());
+        for i in 0..n {
+            delta.add(
+                BlockfileKey::new("prefix".to_string(), Key::Int(i)),
+                Value::Int32Value(i as i32),
+            );
+        }
+        block.apply_delta(&delta).unwrap();
+        block
+    }
+
+    #[test]
+    fn test_hits_and_misses() {
+        let block_provider = ArrowBlockProvider::new();
+        let cache = BlockCache::new(1024 * 1024);
+        let block = block_of_size(&block_provider, 10);
+        let block_id = block.get_id();
+
+        assert!(cache.get("a", &block_id).is_none());
+        cache.insert("a", block.clone());
+        assert_eq!(cache.size(), block.get_size());
+        assert_eq!(cache.get("a", &block_id).unwrap().get_id(), block_id);
+        // Blocks are cached per blockfile
+        assert!(cache.get("b", &block_id).is_none());
+        assert_eq!(cache.hits(), 1);
+        assert_eq!(cache.misses(), 2);
+
+        // Inserting a block again does not count it twice
+        cache.insert("a", block.clone());
+        assert_eq!(cache.size(), block.get_size());
+    }
+
+    #[test]
+    fn test_evicts_least_recently_used() {
+        let block_provider = ArrowBlockProvider::new();
+        let blocks = (0..3)
+            .map(|_| block_of_size(&block_provider, 100))
+            .collect::<Vec<_>>();
+        let block_size = blocks[0].get_size();
+        let cache = BlockCache::new(2 * block_size);
+
+        cache.insert("a", blocks[0].clone());
+        cache.insert("a", blocks[1].clone());
+        assert!(cache.get("a", &blocks[0].get_id()).is_some());
+        cache.insert("a", blocks[2].clone());
+        assert_eq!(cache.size(), 2 * block_size);
+        assert!(cache.get("a", &blocks[0].get_id()).is_some());
+        assert!(cache.get("a", &blocks[1].get_id()).is_none());
+        assert!(cache.get("a", &blocks[2].get_id()).is_some());
+
+        let oversized = block_of_size(&block_provider, 1000);
+        cache.insert("a", oversized.clone());
+        assert!(cache.get("a", &oversized.get_id()).is_none());
+        assert_eq!(cache.size(), 2 * block_size);
+    }
+
+    #[test]
+    fn test_hot_set_is_most_recent_first() {
+        let block_provider = ArrowBlockProvider::new();
+        let cache = BlockCache::new(1024 * 1024);
+        let blocks = (0..3)
+            .map(|_| block_of_size(&block_provider, 10))
+            .collect::<Vec<_>>();
+        for block in blocks.iter() {
+            cache.insert("a", block.clone());
+        }
+        cache.get("a", &blocks[0].get_id());
+
+        assert_eq!(
+            cache.hot_set(2),
+            vec![
+                ("a".to_string(), blocks[0].get_id()),
+                ("a".to_string(), blocks[2].get_id())
+            ]
+        );
+        assert_eq!(cache.hot_set(10).len(), 3);
+    }
+
+    #[test]
+    fn test_concurrent_misses_share_a_load() {
+        let block_provider = ArrowBlockProvider::new();
+        let cache = BlockCache::new(1024 * 1024);
+        let block = block_of_size(&block_provider, 10);
+        let block_id = block.get_id();
+        let loads = Arc::new(AtomicU64::new(0));
+
+        let handles = (0..8)
+            .map(|_| {
+                let cache = cache.clone();
+                let block = block.clone();
+                let loads = loads.clone();
+                std::thread::spawn(move || {
+                    cache
+                        .get_or_load("a", &block_id, || {
+                            loads.fetch_add(1, Ordering::SeqCst);
+                            std::thread::sleep(Duration::from_millis(50));
+                            Ok::<_, ()>(Some(block))
+                        })
+                        .unwrap()
+                        .unwrap()
+                        .get_id()
+                })
+            })
+            .collect::<Vec<_>>();
+        for handle in handles {
+            assert_eq!(handle.join().unwrap(), block_id);
+        }
+        assert_eq!(loads.load(Ordering::SeqCst), 1);
+        assert!(cache.inner.loading.lock().is_empty());
+    }
+
+    #[test]
+    fn test_failed_load_is_not_shared() {
+        let cache = BlockCache::new(1024 * 1024);
+        let block_id = Uuid::new_v4();
+        let result = cache.get_or_load("a", &block_id, || Err("unreadable"));
+        assert!(matches!(result, Err("unreadable")));
+        let result = cache.get_or_load("a", &block_id, || Ok::<_, ()>(None));
+        assert!(result.unwrap().is_none());
+        assert_eq!(cache.size(), 0);
+    }
+}
@@ -0,0 +1,186 @@
+use super::block::{Block, BlockIterator};
+use super::blockfile::ArrowBlockfileError;
+use super::provider::{ArrowBlockProvider, BlockStorageError};
+use super::sparse_index::SparseIndex;
+use crate::blockstore::types::{BlockfileKey, Value};
+use crate::errors::ChromaError;
+use parking_lot::RwLock;
+use std::collections::VecDeque;
+use std::sync::Arc;
+use tokio::sync::mpsc;
+use uuid::Uuid;
+
+/// A cursor over the entries of an arrow blockfile in key order. The cursor walks the
+/// blocks of a sparse index and only loads a block once the entries before it have been
+/// consumed, so the blockfile is never materialized as a whole.
+/// # Notes
+/// The cursor iterates over the blocks the blockfile had when the cursor was created. A
+/// block that cannot be loaded yields an error and ends the iteration. A block that a later
+/// commit replaced, and that is therefore no longer in the current sparse index, yields
+/// SnapshotExpired, which is retriable with a new cursor.
+pub(super) struct ArrowBlockfileCursor {
+    blockfile: String,
+    block_provider: ArrowBlockProvider,
+    // The current sparse index of the blockfile, not the one the cursor was created from
+    sparse_index: Arc<RwLock<SparseIndex>>,
+    remaining_block_ids: VecDeque<Uuid>,
+    current_block: Option<BlockIterator>,
+    // Keys before the start key are skipped. Only the first block can hold such keys.
+    start_key: Option<BlockfileKey>,
+}
+
+impl ArrowBlockfileCursor {
+    pub(super) fn new(
+        blockfile: String,
+        block_provider: ArrowBlockProvider,
+        sparse_index: Arc<RwLock<SparseIndex>>,
+        block_ids: Vec<Uuid>,
+        start_key: Option<BlockfileKey>,
+    ) -> Self {
+        Self {
+            blockfile,
+            block_provider,
+            sparse_index,
+            remaining_block_ids: block_ids.into(),
+            current_block: None,
+            start_key,
+        }
+    }
+}
+
+impl Iterator for ArrowBlockfileCursor {
+    type Item = Result<(BlockfileKey, Value), Box<dyn ChromaError>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(current_block) = &mut self.current_block {
+                for (key, value) in current_block.by_ref() {
+                    if let Some(start_key) = &self.start_key {
+                        if key < *start_key {
+                            continue;
+                        }
+                        self.start_key = None;
+                    }
+                    return Some(Ok((key, value)));
+                }
+                self.current_block = None;
+            }
+
+            let block_id = self.remaining_block_ids.pop_front()?;
+            let result = self.block_provider.get_block(&self.blockfile, &block_id);
+            match loaded_block(&self.sparse_index, &block_id, result) {
+                Ok(block) => self.current_block = Some(block.iter()),
+                Err(e) => {
+                    self.remaining_block_ids.clear();
+                    return Some(Err(e));
+                }
+            }
+        }
+    }
+}
+
+// A block loaded ahead by a stream, or the error loading it.
+type BlockLoad = (Uuid, Result<Option<Arc<Block>>, BlockStorageError>);
+
+/// An asynchronous cursor over the entries of an arrow blockfile in key order, for scans
+/// over blockfiles that do not fit in memory. It yields the same entries and errors as
+/// ArrowBlockfileCursor, but loads the blocks ahead of the one being read in the
+/// background.
+/// # Notes
+/// At most max_blocks blocks are held at a time, the block being read and the blocks
+/// loaded or being loaded ahead of it, max_blocks is at least 2 so that the next block is
+/// always prefetched. A block is only loaded once there is room for it, so a slow reader
+/// holds back the loads rather than the blocks piling up.
+/// The blocks are loaded by a task on the tokio runtime, which stops once the stream is
+/// dropped, so the stream has to be created on the runtime.
+pub(crate) struct ArrowBlockfileStream {
+    sparse_index: Arc<RwLock<SparseIndex>>,
+    blocks: mpsc::Receiver<BlockLoad>,
+    current_block: Option<BlockIterator>,
+    failed: bool,
+}
+
+impl ArrowBlockfileStream {
+    pub(super) fn new(
+        blockfile: String,
+        block_provider: ArrowBlockProvider,
+        sparse_index: Arc<RwLock<SparseIndex>>,
+        block_ids: Vec<Uuid>,
+        max_blocks: usize,
+    ) -> Self {
+        // The block being read is held outside of the channel
+        let (sender, blocks) = mpsc::channel(max_blocks.max(2) - 1);
+        tokio::spawn(async move {
+            for block_id in block_ids {
+                // Reserving the room first also bounds the block being loaded
+                let permit = match sender.reserve().await {
+                    Ok(permit) => permit,
+                    Err(_) => return,
+                };
+                let block_provider = block_provider.clone();
+                let blockfile = blockfile.clone();
+                let result = tokio::task::spawn_blocking(move || {
+                    block_provider.get_block(&blockfile, &block_id)
+                })
+                .await
+                .unwrap_or_else(|e| Err(std::io::Error::other(e.to_string()).into()));
+                let loaded = matches!(result, Ok(Some(_)));
+                permit.send((block_id, result));
+                if !loaded {
+                    return;
+                }
+            }
+        });
+        Self {
+            sparse_index,
+            blocks,
+            current_block: None,
+            failed: false,
+        }
+    }
+
+    pub(crate) async fn next(
+        &mut self,
+    ) -> Option<Result<(BlockfileKey, Value), Box<dyn ChromaError>>> {
+        loop {
+            if let Some(current_block) = &mut self.current_block {
+                if let Some(entry) = current_block.next() {
+                    return Some(Ok(entry));
+                }
+                self.current_block = None;
+            }
+            if self.failed {
+                return None;
+            }
+
+            let (block_id, result) = self.blocks.recv().await?;
+            match loaded_block(&self.sparse_index, &block_id, result) {
+                Ok(block) => self.current_block = Some(block.iter()),
+                Err(e) => {
+                    self.failed = true;
+                    return Some(Err(e));
+                }
+            }
+        }
+    }
+}
+
+// Turns the result of loading a block into the block, or into the error that ends the
+// iteration.
+fn loaded_block(
+    sparse_index: &RwLock<SparseIndex>,
+    block_id: &Uuid,
+    result: Result<Option<Arc<Block>>, BlockStorageError>,
+) -> Result<Arc<Block>, Box<dyn ChromaError>> {
+    if let Ok(Some(block)) = result {
+        return Ok(block);
+    }
+    // A commit deletes the blocks it replaced, possibly while they are being read
+    if !sparse_index.read().contains_block(block_id) {
+        return Err(Box::new(ArrowBlockfileError::SnapshotExpired));
+    }
+    match result {
+        Err(e) => Err(Box::new(e)),
+        _ => Err(Box::new(ArrowBlockfileError::BlockNotFound)),
+    }
+}
@@ -0,0 +1,155 @@
+use super::provider::BlockStorageError;
+use serde::Deserialize;
+
+// Every persisted block starts with a fixed size header, followed by the Arrow IPC file of
+// the block encoded with the codec named in the header. The checksum is the CRC32C of the
+// payload as stored. Integers are little endian.
+// | magic (4) | version (1) | codec (1) | checksum (4) | payload length (8) | payload |
+const BLOCK_MAGIC: &[u8; 4] = b"CBLK";
+const BLOCK_FORMAT_VERSION: u8 = 1;
+const BLOCK_HEADER_LEN: usize = 18;
+
+/// How blocks are compressed when they are persisted. Every block records the codec it
+/// was written with, so changing the compression only affects blocks written afterwards
+/// and blocks of either kind can be read back.
+/// # Variants
+/// - None: Blocks are written as plain Arrow IPC files.
+/// - Zstd: Blocks are compressed with zstd at the given level. Levels range from 1 to 22,
+/// 0 selects the zstd default.
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq)]
+pub(crate) enum BlockCompression {
+    #[default]
+    None,
+    Zstd {
+        level: i32,
+    },
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum BlockCodec {
+    None = 0,
+    Zstd = 1,
+}
+
+impl TryFrom<u8> for BlockCodec {
+    type Error = BlockStorageError;
+
+    fn try_from(codec: u8) -> Result<Self, Self::Error> {
+        match codec {
+            0 => Ok(BlockCodec::None),
+            1 => Ok(BlockCodec::Zstd),
+            _ => Err(BlockStorageError::InvalidBlock),
+        }
+    }
+}
+
+/// Prepends the block header to the Arrow IPC file of a block, compressing it first if
+/// the compression asks for it.
+pub(super) fn encode_block(
+    ipc: Vec<u8>,
+    compression: BlockCompression,
+) -> Result<Vec<u8>, BlockStorageError> {
+    let (codec, payload) = match compression {
+        BlockCompression::None => (BlockCodec::None, ipc),
+        BlockCompression::Zstd { level } => match zstd::bulk::compress(&ipc, level) {
+            Ok(compressed) => (BlockCodec::Zstd, compressed),
+            Err(e) => return Err(BlockStorageError::CompressionError(e)),
+        },
+    };
+    let mut bytes = Vec::with_capacity(BLOCK_HEADER_LEN + payload.len());
+    bytes.extend_from_slice(BLOCK_MAGIC);
+    bytes.push(BLOCK_FORMAT_VERSION);
+    bytes.push(codec as u8);
+    bytes.extend_from_slice(&crc32c::crc32c(&payload).to_le_bytes());
+    bytes.extend_from_slice(&(payload.len() as u64).to_le_bytes());
+    bytes.extend_from_slice(&payload);
+    Ok(bytes)
+}
+
+/// Checks the header and the checksum of a persisted block and returns the Arrow IPC file
+/// it holds, decompressed if it was written compressed.
+pub(super) fn decode_block(bytes: &[u8]) -> Result<Vec<u8>, BlockStorageError> {
+    if bytes.len() < BLOCK_HEADER_LEN
+        || &bytes[0..4] != BLOCK_MAGIC
+        || bytes[4] != BLOCK_FORMAT_VERSION
+    {
+        return Err(BlockStorageError::InvalidBlock);
+    }
+    let codec = BlockCodec::try_from(bytes[5])?;
+    let expected = read_u32(&bytes[6..10]);
+    let payload_len = read_u64(&bytes[10..18]);
+    let payload = &bytes[BLOCK_HEADER_LEN..];
+    // A payload of a different length is a truncated or partially overwritten block
+    if payload_len != payload.len() as u64 {
+        return Err(BlockStorageError::InvalidBlock);
+    }
+    let actual = crc32c::crc32c(payload);
+    if actual != expected {
+        return Err(BlockStorageError::ChecksumMismatch { expected, actual });
+    }
+    match codec {
+        BlockCodec::None => Ok(payload.to_vec()),
+        BlockCodec::Zstd => match zstd::stream::decode_all(payload) {
+            Ok(decompressed) => Ok(decompressed),
+            Err(e) => Err(BlockStorageError::DecompressionError(e)),
+        },
+    }
+}
+
+fn read_u32(bytes: &[u8]) -> u32 {
+    let mut buf = [0; 4];
+    buf.copy_from_slice(bytes);
+    u32::from_le_bytes(buf)
+}
+
+fn read_u64(bytes: &[u8]) -> u64 {
+    let mut buf = [0; 8];
+    buf.copy_from_slice(bytes);
+    u64::from_le_bytes(buf)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::errors::{ChromaError, ErrorCodes};
+
+    #[test]
+    fn test_round_trip() {
+        let ipc = "value".repeat(1000).into_bytes();
+        for compression in [BlockCompression::None, BlockCompression::Zstd { level: 3 }] {
+            let encoded = encode_block(ipc.clone(), compression).unwrap();
+            assert_eq!(&encoded[0..4], BLOCK_MAGIC);
+            assert_eq!(decode_block(&encoded).unwrap(), ipc);
+        }
+
+        let uncompressed = encode_block(ipc.clone(), BlockCompression::None).unwrap();
+        let compressed = encode_block(ipc, BlockCompression::Zstd { level: 3 }).unwrap();
+        assert!(compressed.len() < uncompressed.len());
+    }
+
+    #[test]
+    fn test_invalid_blocks() {
+        let encoded = encode_block(vec![1, 2, 3], BlockCompression::None).unwrap();
+
+        let truncated = &encoded[..encoded.len() - 1];
+        assert!(matches!(
+            decode_block(truncated),
+            Err(BlockStorageError::InvalidBlock)
+        ));
+
+        let mut unknown_codec = encoded.clone();
+        unknown_codec[5] = 42;
+        assert!(matches!(
+            decode_block(&unknown_codec),
+            Err(BlockStorageError::InvalidBlock)
+        ));
+
+        let mut corrupted = encoded.clone();
+        corrupted[BLOCK_HEADER_LEN] ^= 1;
+        let err = decode_block(&corrupted).unwrap_err();
+        assert!(matches!(err, BlockStorageError::ChecksumMismatch { .. }));
+        assert_eq!(err.code(), ErrorCodes::DataLoss);
+
+        assert!(decode_block(b"ARROW1").is_err());
+    }
+}
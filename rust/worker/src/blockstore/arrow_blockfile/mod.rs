@@ -1,3 +1,8 @@
 mod block;
-mod blockfile;
-mod provider;
+pub(crate) mod blockfile;
+pub(crate) mod cache;
+pub(crate) mod cursor;
+pub(crate) mod format;
+pub(crate) mod provider;
+pub(crate) mod scrubber;
+mod sparse_index;
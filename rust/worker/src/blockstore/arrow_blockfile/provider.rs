@@ -1,13 +1,42 @@
-use super::block::Block;
-use crate::blockstore::{KeyType, ValueType};
+use super::block::{Block, BlockState};
+use super::blockfile::{ArrowBlockfile, BlockSizeConfig, PersistedBlockfile};
+use super::cache::BlockCache;
+use super::cursor::ArrowBlockfileStream;
+use super::format::{decode_block, encode_block, BlockCompression};
+use super::scrubber::BlockScrubber;
+use crate::blockstore::config::BlockfileConfig;
+use crate::blockstore::provider::{BlockfileProvider, CreateError, OpenError};
+use crate::blockstore::{Blockfile, KeyType, ValueType};
+use crate::errors::{ChromaError, ErrorCodes};
+use arrow::datatypes::DataType;
+use arrow::ipc::reader::FileReader;
+use arrow::ipc::writer::FileWriter;
 use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File};
+use std::io::{BufReader, BufWriter, Cursor, ErrorKind, Write};
+use std::path::{Path, PathBuf};
 use std::{collections::HashMap, sync::Arc};
+use thiserror::Error;
 use uuid::Uuid;
 
 struct ArrowBlockProviderInner {
+    // The blocks that only exist in memory
     blocks: HashMap<Uuid, Arc<Block>>,
+    block_sizes: BlockSizeConfig,
+    storage_path: Option<PathBuf>,
+    compression: BlockCompression,
+    block_cache: Option<BlockCache>,
 }
 
+/// Creates blocks and keeps track of them by id. When the provider has a storage path,
+/// blocks are written there as Arrow IPC files when they are flushed and are read back
+/// on demand, so that they outlive the process. Flushed blocks are compressed with the
+/// configured compression.
+/// # Notes
+/// Without a storage path, the provider holds every block in memory. With one, it only
+/// holds the blocks that were not flushed yet, flushed blocks are kept in the block cache
+/// and read from storage again once they were evicted from it.
 #[derive(Clone)]
 pub(super) struct ArrowBlockProvider {
     inner: Arc<RwLock<ArrowBlockProviderInner>>,
@@ -15,23 +44,552 @@ pub(super) struct ArrowBlockProvider {
 
 impl ArrowBlockProvider {
     pub(super) fn new() -> Self {
+        Self::with_block_sizes(BlockSizeConfig::default())
+    }
+
+    pub(super) fn with_block_sizes(block_sizes: BlockSizeConfig) -> Self {
         Self {
             inner: Arc::new(RwLock::new(ArrowBlockProviderInner {
                 blocks: HashMap::new(),
+                block_sizes,
+                storage_path: None,
+                compression: BlockCompression::None,
+                block_cache: None,
             })),
         }
     }
 
+    pub(super) fn with_storage(
+        block_sizes: BlockSizeConfig,
+        storage_path: PathBuf,
+        compression: BlockCompression,
+        block_cache: BlockCache,
+    ) -> Self {
+        let provider = Self::with_block_sizes(block_sizes);
+        {
+            let mut inner = provider.inner.write();
+            inner.storage_path = Some(storage_path);
+            inner.compression = compression;
+            inner.block_cache = Some(block_cache);
+        }
+        provider
+    }
+
+    /// Creates a new block. The maximum size of the block is chosen from the
+    /// configured block sizes based on its value type.
     pub(super) fn create_block(&self, key_type: KeyType, value_type: ValueType) -> Arc<Block> {
-        let block = Arc::new(Block::new(Uuid::new_v4(), key_type, value_type));
-        self.inner
-            .write()
-            .blocks
-            .insert(block.get_id(), block.clone());
+        let mut inner = self.inner.write();
+        let max_size = inner.block_sizes.max_block_size(value_type);
+        let block = Arc::new(Block::new(Uuid::new_v4(), key_type, value_type, max_size));
+        inner.blocks.insert(block.get_id(), block.clone());
         block
     }
 
-    pub(super) fn get_block(&self, id: &Uuid) -> Option<Arc<Block>> {
-        self.inner.read().blocks.get(id).cloned()
+    /// Returns the block with the given id of the given blockfile, looking it up in memory,
+    /// then in the block cache and finally reading it from storage. Returns None if the
+    /// block is unknown.
+    pub(super) fn get_block(
+        &self,
+        blockfile: &str,
+        id: &Uuid,
+    ) -> Result<Option<Arc<Block>>, BlockStorageError> {
+        let (storage_path, block_cache) = {
+            let inner = self.inner.read();
+            if let Some(block) = inner.blocks.get(id) {
+                return Ok(Some(block.clone()));
+            }
+            match (&inner.storage_path, &inner.block_cache) {
+                (Some(storage_path), Some(block_cache)) => {
+                    (storage_path.clone(), block_cache.clone())
+                }
+                _ => return Ok(None),
+            }
+        };
+        // Concurrent misses of the same block share a single read from storage
+        block_cache.get_or_load(blockfile, id, || {
+            let block_path = Self::block_path(&storage_path, id);
+            if !block_path.exists() {
+                return Ok(None);
+            }
+            Ok(Some(Arc::new(self.read_block(id, block_path)?)))
+        })
+    }
+
+    /// Checks that the block with the given id can be read back intact. Flushed blocks are
+    /// read from storage even if they are in memory, so that corruption on disk is caught
+    /// before the block would be loaded again. Returns false if the block is unknown.
+    pub(super) fn verify_block(&self, id: &Uuid) -> Result<bool, BlockStorageError> {
+        let storage_path = {
+            let inner = self.inner.read();
+            let block = inner.blocks.get(id);
+            // Blocks that were never flushed only exist in memory
+            if let Some(BlockState::Uninitialized | BlockState::Initialized) =
+                block.map(|block| block.get_state())
+            {
+                return Ok(true);
+            }
+            match &inner.storage_path {
+                Some(storage_path) => storage_path.clone(),
+                None => return Ok(block.is_some()),
+            }
+        };
+
+        let block_path = Self::block_path(&storage_path, id);
+        if !block_path.exists() {
+            return Ok(false);
+        }
+        self.read_block(id, block_path)?;
+        Ok(true)
+    }
+
+    /// Writes a commited block of the given blockfile to storage, if the provider has a
+    /// storage path, and marks it as registered. A written block moves from memory to the
+    /// block cache. The block file is synced before the block is registered, its directory
+    /// entry is synced when the sparse index is written.
+    pub(super) fn flush_block(
+        &self,
+        blockfile: &str,
+        block: &Block,
+    ) -> Result<(), BlockStorageError> {
+        let (storage_path, compression) = {
+            let inner = self.inner.read();
+            (inner.storage_path.clone(), inner.compression)
+        };
+        if let Some(storage_path) = storage_path {
+            let record_batch = match block.get_record_batch() {
+                Some(record_batch) => record_batch,
+                None => return Err(BlockStorageError::EmptyBlock),
+            };
+            let mut writer = FileWriter::try_new(Vec::new(), &record_batch.schema())?;
+            writer.write(&record_batch)?;
+            writer.finish()?;
+            let bytes = encode_block(writer.into_inner()?, compression)?;
+            fs::create_dir_all(storage_path.join("blocks"))?;
+            let mut file = File::create(Self::block_path(&storage_path, &block.get_id()))?;
+            file.write_all(&bytes)?;
+            file.sync_all()?;
+        }
+        if block.register().is_err() {
+            return Err(BlockStorageError::NotCommited);
+        }
+        let mut inner = self.inner.write();
+        if let Some(block_cache) = inner.block_cache.clone() {
+            if let Some(block) = inner.blocks.remove(&block.get_id()) {
+                block_cache.insert(blockfile, block);
+            }
+        }
+        Ok(())
+    }
+
+    /// Deletes a block of the given blockfile that its sparse index no longer refers to,
+    /// from memory, from the block cache and from storage.
+    pub(super) fn delete_block(&self, blockfile: &str, id: &Uuid) -> Result<(), BlockStorageError> {
+        let (storage_path, block_cache) = {
+            let mut inner = self.inner.write();
+            inner.blocks.remove(id);
+            (inner.storage_path.clone(), inner.block_cache.clone())
+        };
+        if let Some(block_cache) = block_cache {
+            block_cache.remove(blockfile, id);
+        }
+        if let Some(storage_path) = storage_path {
+            match fs::remove_file(Self::block_path(&storage_path, id)) {
+                Ok(_) => {}
+                Err(e) if e.kind() == ErrorKind::NotFound => {}
+                Err(e) => return Err(e.into()),
+            }
+        }
+        Ok(())
+    }
+
+    /// Persists the sparse index of the blockfile with the given name. Writing the sparse
+    /// index is what makes a commit durable, so it is written to a temporary file first
+    /// and then renamed over the previous one. The blocks directory is synced first, so
+    /// that the sparse index never refers to blocks that could be lost on a crash, and the
+    /// blockfile directory is synced after the rename.
+    pub(super) fn write_blockfile(
+        &self,
+        name: &str,
+        blockfile: &PersistedBlockfile,
+    ) -> Result<(), BlockStorageError> {
+        let storage_path = match self.inner.read().storage_path.clone() {
+            Some(storage_path) => storage_path,
+            None => return Ok(()),
+        };
+        let blockfile_path = Self::blockfile_path(&storage_path, name);
+        if let Some(parent) = blockfile_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let blocks_path = storage_path.join("blocks");
+        if blocks_path.exists() {
+            sync_dir(&blocks_path)?;
+        }
+        let temporary_path = blockfile_path.with_extension("json.tmp");
+        let mut writer = BufWriter::new(File::create(&temporary_path)?);
+        serde_json::to_writer(&mut writer, blockfile)?;
+        let file = writer.into_inner().map_err(|e| e.into_error())?;
+        file.sync_all()?;
+        fs::rename(temporary_path, &blockfile_path)?;
+        if let Some(parent) = blockfile_path.parent() {
+            sync_dir(parent)?;
+        }
+        Ok(())
+    }
+
+    /// Reads the sparse index of the blockfile with the given name. Returns None if the
+    /// blockfile was never persisted.
+    pub(super) fn read_blockfile(
+        &self,
+        name: &str,
+    ) -> Result<Option<PersistedBlockfile>, BlockStorageError> {
+        let storage_path = match self.inner.read().storage_path.clone() {
+            Some(storage_path) => storage_path,
+            None => return Ok(None),
+        };
+        let blockfile_path = Self::blockfile_path(&storage_path, name);
+        if !blockfile_path.exists() {
+            return Ok(None);
+        }
+        let file = File::open(blockfile_path)?;
+        Ok(Some(serde_json::from_reader(BufReader::new(file))?))
+    }
+
+    /// Lists the names of the blockfiles persisted under the storage path.
+    pub(super) fn list_blockfiles(&self) -> Result<Vec<String>, BlockStorageError> {
+        let storage_path = match self.inner.read().storage_path.clone() {
+            Some(storage_path) => storage_path,
+            None => return Ok(Vec::new()),
+        };
+        let blockfiles_path = storage_path.join("blockfiles");
+        let mut names = Vec::new();
+        if blockfiles_path.exists() {
+            list_blockfiles_under(&blockfiles_path, "", &mut names)?;
+        }
+        names.sort();
+        Ok(names)
+    }
+
+    /// Saves the keys of the most recently used blocks in the block cache, at most limit of
+    /// them, so that the cache can be warmed with them after a restart.
+    pub(super) fn save_hot_set(&self, limit: usize) -> Result<(), BlockStorageError> {
+        let (storage_path, block_cache) = {
+            let inner = self.inner.read();
+            match (&inner.storage_path, &inner.block_cache) {
+                (Some(storage_path), Some(block_cache)) => {
+                    (storage_path.clone(), block_cache.clone())
+                }
+                _ => return Ok(()),
+            }
+        };
+        let hot_set = block_cache
+            .hot_set(limit)
+            .into_iter()
+            .map(|(blockfile, block_id)| HotBlock {
+                blockfile,
+                block_id: block_id.to_string(),
+            })
+            .collect::<Vec<_>>();
+        fs::create_dir_all(&storage_path)?;
+        let hot_set_path = storage_path.join(HOT_SET_FILE);
+        let temporary_path = hot_set_path.with_extension("json.tmp");
+        let mut writer = BufWriter::new(File::create(&temporary_path)?);
+        serde_json::to_writer(&mut writer, &hot_set)?;
+        let file = writer.into_inner().map_err(|e| e.into_error())?;
+        file.sync_all()?;
+        fs::rename(temporary_path, hot_set_path)?;
+        Ok(())
+    }
+
+    /// Loads the blocks of the saved hot set into the block cache, at most limit of them.
+    /// The least recently used blocks are loaded first, so that the cache ends up in the
+    /// order it was saved in. Blocks that were deleted since are skipped. Returns the
+    /// number of blocks loaded.
+    pub(super) fn warm_cache(&self, limit: usize) -> Result<usize, BlockStorageError> {
+        let storage_path = match self.inner.read().storage_path.clone() {
+            Some(storage_path) => storage_path,
+            None => return Ok(0),
+        };
+        let hot_set_path = storage_path.join(HOT_SET_FILE);
+        if !hot_set_path.exists() {
+            return Ok(0);
+        }
+        let file = File::open(hot_set_path)?;
+        let hot_set: Vec<HotBlock> = serde_json::from_reader(BufReader::new(file))?;
+        let mut loaded = 0;
+        for hot_block in hot_set.into_iter().take(limit).rev() {
+            let block_id = match Uuid::parse_str(&hot_block.block_id) {
+                Ok(block_id) => block_id,
+                Err(_) => continue,
+            };
+            match self.get_block(&hot_block.blockfile, &block_id) {
+                Ok(Some(_)) => loaded += 1,
+                Ok(None) => {}
+                Err(e) => {
+                    tracing::warn!("Failed to warm block {}: {}", block_id, e);
+                }
+            }
+        }
+        Ok(loaded)
+    }
+
+    fn read_block(&self, id: &Uuid, block_path: PathBuf) -> Result<Block, BlockStorageError> {
+        let ipc = decode_block(&fs::read(block_path)?)?;
+        let mut reader = FileReader::try_new(Cursor::new(ipc), None)?;
+        let record_batch = match reader.next() {
+            Some(record_batch) => record_batch?,
+            None => return Err(BlockStorageError::InvalidBlock),
+        };
+        let schema = record_batch.schema();
+        if schema.fields().len() != 3 {
+            return Err(BlockStorageError::InvalidBlock);
+        }
+        let key_type = match schema.field(1).data_type() {
+            DataType::Utf8 => KeyType::String,
+            DataType::Float64 => KeyType::Float,
+            DataType::Boolean => KeyType::Bool,
+            DataType::Int64 => KeyType::Int,
+            _ => return Err(BlockStorageError::InvalidBlock),
+        };
+        let value_type = match schema.field(2).data_type() {
+            DataType::List(_) => ValueType::Int32Array,
+            DataType::Utf8 => ValueType::String,
+            DataType::Binary => ValueType::RoaringBitmap,
+            DataType::Int32 => ValueType::Int32,
+            _ => return Err(BlockStorageError::InvalidBlock),
+        };
+        let max_size = self.inner.read().block_sizes.max_block_size(value_type);
+        Ok(Block::from_record_batch(
+            *id,
+            record_batch,
+            key_type,
+            value_type,
+            max_size,
+        ))
+    }
+
+    fn block_path(storage_path: &Path, id: &Uuid) -> PathBuf {
+        storage_path.join("blocks").join(format!("{}.arrow", id))
+    }
+
+    fn blockfile_path(storage_path: &Path, name: &str) -> PathBuf {
+        storage_path
+            .join("blockfiles")
+            .join(format!("{}.json", name))
+    }
+}
+
+// The file under the storage path that the hot set of the block cache is saved to.
+const HOT_SET_FILE: &str = "block_cache_hot_set.json";
+
+// The key of a block in the saved hot set of the block cache.
+#[derive(Serialize, Deserialize)]
+struct HotBlock {
+    blockfile: String,
+    block_id: String,
+}
+
+// Collects the names of the blockfiles in a directory under the blockfiles directory,
+// prefixing them with the path of the directory relative to it.
+fn list_blockfiles_under(
+    path: &Path,
+    prefix: &str,
+    names: &mut Vec<String>,
+) -> Result<(), BlockStorageError> {
+    for entry in fs::read_dir(path)? {
+        let entry = entry?;
+        let file_name = entry.file_name().to_string_lossy().to_string();
+        if entry.file_type()?.is_dir() {
+            list_blockfiles_under(&entry.path(), &format!("{}{}/", prefix, file_name), names)?;
+        } else if let Some(name) = file_name.strip_suffix(".json") {
+            names.push(format!("{}{}", prefix, name));
+        }
+    }
+    Ok(())
+}
+
+// Syncs the entries of a directory, so that files created or renamed in it survive a crash.
+fn sync_dir(path: &Path) -> std::io::Result<()> {
+    File::open(path)?.sync_all()
+}
+
+// Blockfile names become paths under the storage path. A name made of normal, '/'
+// separated components cannot point outside of it.
+fn is_valid_blockfile_name(name: &str) -> bool {
+    !name.contains('\\')
+        && !name.contains('\0')
+        && name
+            .split('/')
+            .all(|component| !component.is_empty() && component != "." && component != "..")
+}
+
+/// A BlockfileProvider that creates ArrowBlockfiles. Without a storage path the
+/// blockfiles only live in memory, with one they are persisted under it and can be
+/// opened again after a restart.
+/// # Note
+/// The provider bookkeeps the blockfiles it opened, so that all handles to a blockfile
+/// share its committed state.
+#[derive(Clone)]
+pub(crate) struct ArrowBlockfileProvider {
+    block_provider: ArrowBlockProvider,
+    files: Arc<RwLock<HashMap<String, ArrowBlockfile>>>,
+}
+
+impl ArrowBlockfileProvider {
+    /// Creates a provider whose blockfiles split their blocks at the given sizes. The
+    /// blockfiles are persisted under the storage path, if one is given, with their
+    /// blocks compressed as configured and cached in the given block cache.
+    pub(crate) fn with_config(
+        block_sizes: BlockSizeConfig,
+        storage_path: Option<PathBuf>,
+        compression: BlockCompression,
+        block_cache: BlockCache,
+    ) -> Self {
+        let block_provider = match storage_path {
+            Some(storage_path) => ArrowBlockProvider::with_storage(
+                block_sizes,
+                storage_path,
+                compression,
+                block_cache,
+            ),
+            None => ArrowBlockProvider::with_block_sizes(block_sizes),
+        };
+        Self {
+            block_provider,
+            files: Arc::new(RwLock::new(HashMap::new())),
+        }
+    }
+
+    /// Creates a provider from the blockfile config of the worker. The block cache is
+    /// passed in rather than created here, so that all providers share the one cache
+    /// built at startup.
+    pub(crate) fn from_config(config: &BlockfileConfig, block_cache: BlockCache) -> Self {
+        Self::with_config(
+            config.block_sizes.clone(),
+            config.storage_path.clone(),
+            config.compression,
+            block_cache,
+        )
+    }
+
+    /// Saves the keys of the hottest blocks in the block cache, see ArrowBlockProvider::save_hot_set.
+    pub(crate) fn save_block_cache_hot_set(&self, limit: usize) -> Result<(), BlockStorageError> {
+        self.block_provider.save_hot_set(limit)
+    }
+
+    /// Warms the block cache with the saved hot set, see ArrowBlockProvider::warm_cache.
+    pub(crate) fn warm_block_cache(&self, limit: usize) -> Result<usize, BlockStorageError> {
+        self.block_provider.warm_cache(limit)
+    }
+
+    /// Opens the blockfile at path and returns a stream over its entries, holding at most
+    /// max_blocks blocks at a time. See ArrowBlockfileStream.
+    pub(crate) fn stream(
+        &self,
+        path: &str,
+        max_blocks: usize,
+    ) -> Result<ArrowBlockfileStream, Box<OpenError>> {
+        Ok(self.open_arrow(path)?.stream(max_blocks))
+    }
+
+    fn open_arrow(&self, path: &str) -> Result<ArrowBlockfile, Box<OpenError>> {
+        if !is_valid_blockfile_name(path) {
+            return Err(Box::new(OpenError::InvalidName(path.to_string())));
+        }
+        if let Some(file) = self.files.read().get(path) {
+            return Ok(file.clone());
+        }
+        let persisted = match self.block_provider.read_blockfile(path) {
+            Ok(Some(persisted)) => persisted,
+            Ok(None) => return Err(Box::new(OpenError::NotFound)),
+            Err(e) => return Err(Box::new(OpenError::Unreadable(e.to_string()))),
+        };
+        let blockfile =
+            match ArrowBlockfile::from_persisted(path, persisted, self.block_provider.clone()) {
+                Ok(blockfile) => blockfile,
+                Err(e) => return Err(Box::new(OpenError::Unreadable(e.to_string()))),
+            };
+        let mut files = self.files.write();
+        let file = files.entry(path.to_string()).or_insert(blockfile);
+        Ok(file.clone())
+    }
+
+    /// Creates a scrubber of the blocks persisted by this provider.
+    pub(crate) fn scrubber(&self, blocks_per_round: usize) -> BlockScrubber {
+        BlockScrubber::new(self.block_provider.clone(), blocks_per_round)
+    }
+}
+
+impl BlockfileProvider for ArrowBlockfileProvider {
+    fn new() -> Self {
+        Self {
+            block_provider: ArrowBlockProvider::new(),
+            files: Arc::new(RwLock::new(HashMap::new())),
+        }
+    }
+
+    fn open(&self, path: &str) -> Result<Box<dyn Blockfile>, Box<OpenError>> {
+        Ok(Box::new(self.open_arrow(path)?))
+    }
+
+    fn create(
+        &mut self,
+        path: &str,
+        key_type: KeyType,
+        value_type: ValueType,
+    ) -> Result<Box<dyn Blockfile>, Box<CreateError>> {
+        if !is_valid_blockfile_name(path) {
+            return Err(Box::new(CreateError::InvalidName(path.to_string())));
+        }
+        let mut files = self.files.write();
+        if files.contains_key(path) {
+            return Err(Box::new(CreateError::AlreadyExists));
+        }
+        match self.block_provider.read_blockfile(path) {
+            Ok(None) => {}
+            Ok(Some(_)) => return Err(Box::new(CreateError::AlreadyExists)),
+            Err(e) => return Err(Box::new(CreateError::Unreadable(e.to_string()))),
+        }
+        let blockfile =
+            ArrowBlockfile::new(path, key_type, value_type, self.block_provider.clone());
+        files.insert(path.to_string(), blockfile.clone());
+        Ok(Box::new(blockfile))
+    }
+}
+
+#[derive(Error, Debug)]
+pub(crate) enum BlockStorageError {
+    #[error("IO error: {0}")]
+    IoError(#[from] std::io::Error),
+    #[error("Arrow error: {0}")]
+    ArrowError(#[from] arrow::error::ArrowError),
+    #[error("Serialization error: {0}")]
+    SerializationError(#[from] serde_json::Error),
+    #[error("Block file is not a valid block")]
+    InvalidBlock,
+    #[error("Failed to compress block: {0}")]
+    CompressionError(std::io::Error),
+    #[error("Failed to decompress block: {0}")]
+    DecompressionError(std::io::Error),
+    #[error("Block checksum mismatch, expected {expected:#010x} but got {actual:#010x}")]
+    ChecksumMismatch { expected: u32, actual: u32 },
+    #[error("Cannot flush a block without data")]
+    EmptyBlock,
+    #[error("Only commited blocks can be flushed")]
+    NotCommited,
+}
+
+impl ChromaError for BlockStorageError {
+    fn code(&self) -> ErrorCodes {
+        match self {
+            BlockStorageError::IoError(_) => ErrorCodes::Unavailable,
+            BlockStorageError::ArrowError(_) => ErrorCodes::DataLoss,
+            BlockStorageError::SerializationError(_) => ErrorCodes::DataLoss,
+            BlockStorageError::InvalidBlock => ErrorCodes::DataLoss,
+            BlockStorageError::CompressionError(_) => ErrorCodes::Internal,
+            BlockStorageError::DecompressionError(_) => ErrorCodes::DataLoss,
+            BlockStorageError::ChecksumMismatch { .. } => ErrorCodes::DataLoss,
+            BlockStorageError::EmptyBlock => ErrorCodes::Internal,
+            BlockStorageError::NotCommited => ErrorCodes::Internal,
+        }
     }
 }
@@ -0,0 +1,256 @@
+use super::provider::{ArrowBlockProvider, BlockStorageError};
+use parking_lot::Mutex;
+use rand::seq::SliceRandom;
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use uuid::Uuid;
+
+/// A persisted block that failed verification.
+/// # Fields
+/// - blockfile: The name of the blockfile that refers to the block.
+/// - block_id: The id of the block.
+/// - error: Why the block failed verification.
+#[derive(Clone, Debug)]
+pub(crate) struct CorruptBlock {
+    pub(crate) blockfile: String,
+    pub(crate) block_id: Uuid,
+    pub(crate) error: String,
+}
+
+struct BlockScrubberInner {
+    blocks_scrubbed: AtomicU64,
+    corrupt_blocks: Mutex<BTreeMap<Uuid, CorruptBlock>>,
+}
+
+/// Verifies persisted blocks in the background, so that a corrupt block is flagged before
+/// a query reads it. Every round samples blocks of the persisted blockfiles and checks
+/// that they can be read back, which covers their checksum and their Arrow encoding.
+/// # Notes
+/// Every round verifies at most blocks_per_round blocks, split evenly between tenants, so
+/// that a tenant with many blockfiles does not starve the others. The tenant of a blockfile
+/// is the first component of its name, blockfiles without a '/' in their name share one
+/// tenant.
+/// Rounds run on the blocking thread pool, one at a time, with a pause in between. After
+/// every round, the number of verified and corrupt blocks is logged, and the corrupt blocks
+/// are listed whenever a new one was found.
+/// # Methods
+/// - scrub_round: Verifies a sample of blocks and returns the number of newly found corrupt blocks.
+/// - run: Scrubs rounds forever, pausing interval between them.
+/// - blocks_scrubbed: The number of blocks verified so far.
+/// - corrupt_blocks: The corrupt blocks found so far, ordered by id.
+#[derive(Clone)]
+pub(crate) struct BlockScrubber {
+    block_provider: ArrowBlockProvider,
+    blocks_per_round: usize,
+    inner: Arc<BlockScrubberInner>,
+}
+
+impl BlockScrubber {
+    pub(super) fn new(block_provider: ArrowBlockProvider, blocks_per_round: usize) -> Self {
+        Self {
+            block_provider,
+            blocks_per_round,
+            inner: Arc::new(BlockScrubberInner {
+                blocks_scrubbed: AtomicU64::new(0),
+                corrupt_blocks: Mutex::new(BTreeMap::new()),
+            }),
+        }
+    }
+
+    pub(crate) fn scrub_round(&self) -> Result<usize, BlockStorageError> {
+        let mut tenants: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        for blockfile in self.block_provider.list_blockfiles()? {
+            let tenant = match blockfile.split_once('/') {
+                Some((tenant, _)) => tenant.to_string(),
+                None => String::new(),
+            };
+            tenants.entry(tenant).or_default().push(blockfile);
+        }
+        if tenants.is_empty() {
+            return Ok(0);
+        }
+
+        let blocks_per_tenant = (self.blocks_per_round + tenants.len() - 1) / tenants.len();
+        let mut rng = rand::thread_rng();
+        let mut found = 0;
+        for blockfiles in tenants.values() {
+            let mut blocks = Vec::new();
+            for blockfile in blockfiles {
+                for block_id in self.block_ids(blockfile) {
+                    blocks.push((blockfile, block_id));
+                }
+            }
+            for (blockfile, block_id) in blocks.choose_multiple(&mut rng, blocks_per_tenant) {
+                if self.scrub_block(blockfile, block_id) {
+                    found += 1;
+                }
+            }
+        }
+        Ok(found)
+    }
+
+    pub(crate) async fn run(self, interval: Duration) {
+        loop {
+            tokio::time::sleep(interval).await;
+            let scrubber = self.clone();
+            match tokio::task::spawn_blocking(move || scrubber.scrub_round()).await {
+                Ok(Ok(found)) => {
+                    let corrupt_blocks = self.corrupt_blocks();
+                    tracing::info!(
+                        "Block scrubber: {} blocks verified, {} corrupt",
+                        self.blocks_scrubbed(),
+                        corrupt_blocks.len()
+                    );
+                    // Lists every corrupt block again whenever a new one is found
+                    if found > 0 {
+                        for block in corrupt_blocks {
+                            tracing::error!(
+                                "Corrupt block {} of blockfile {}: {}",
+                                block.block_id,
+                                block.blockfile,
+                                block.error
+                            );
+                        }
+                    }
+                }
+                Ok(Err(e)) => tracing::warn!("Block scrubber round failed: {}", e),
+                Err(e) => tracing::warn!("Block scrubber round panicked: {}", e),
+            }
+        }
+    }
+
+    pub(crate) fn blocks_scrubbed(&self) -> u64 {
+        self.inner.blocks_scrubbed.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn corrupt_blocks(&self) -> Vec<CorruptBlock> {
+        self.inner.corrupt_blocks.lock().values().cloned().collect()
+    }
+
+    // The ids of the blocks the blockfile refers to. A blockfile that cannot be read is
+    // skipped, opening it reports the error.
+    fn block_ids(&self, blockfile: &str) -> Vec<Uuid> {
+        match self.block_provider.read_blockfile(blockfile) {
+            Ok(Some(persisted)) => persisted.block_ids().unwrap_or_default(),
+            Ok(None) => Vec::new(),
+            Err(e) => {
+                tracing::warn!("Block scrubber cannot read blockfile {}: {}", blockfile, e);
+                Vec::new()
+            }
+        }
+    }
+
+    // Verifies a block and records it if it is corrupt. Returns whether it is newly found
+    // to be corrupt.
+    fn scrub_block(&self, blockfile: &str, block_id: &Uuid) -> bool {
+        self.inner.blocks_scrubbed.fetch_add(1, Ordering::Relaxed);
+        let error = match self.block_provider.verify_block(block_id) {
+            Ok(true) => return false,
+            // A commit may have replaced the block since the blockfile was read
+            Ok(false) if !self.block_ids(blockfile).contains(block_id) => return false,
+            Ok(false) => "Block not found".to_string(),
+            Err(e) => e.to_string(),
+        };
+        self.inner
+            .corrupt_blocks
+            .lock()
+            .insert(
+                *block_id,
+                CorruptBlock {
+                    blockfile: blockfile.to_string(),
+                    block_id: *block_id,
+                    error,
+                },
+            )
+            .is_none()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blockstore::arrow_blockfile::blockfile::BlockSizeConfig;
+    use crate::blockstore::arrow_blockfile::cache::BlockCache;
+    use crate::blockstore::arrow_blockfile::format::BlockCompression;
+    use crate::blockstore::arrow_blockfile::provider::ArrowBlockfileProvider;
+    use crate::blockstore::provider::BlockfileProvider;
+    use crate::blockstore::types::{BlockfileKey, Key, KeyType, Value, ValueType};
+    use std::path::Path;
+
+    fn create_blockfile(provider: &mut ArrowBlockfileProvider, name: &str) {
+        let mut blockfile = provider
+            .create(name, KeyType::String, ValueType::String)
+            .unwrap();
+        blockfile.begin_transaction().unwrap();
+        blockfile
+            .set(
+                BlockfileKey::new("prefix".to_string(), Key::String("a".to_string())),
+                Value::StringValue("a".to_string()),
+            )
+            .unwrap();
+        blockfile.commit_transaction().unwrap();
+    }
+
+    fn provider(storage_path: &Path) -> ArrowBlockfileProvider {
+        ArrowBlockfileProvider::with_config(
+            BlockSizeConfig::default(),
+            Some(storage_path.to_path_buf()),
+            BlockCompression::None,
+            // Blocks are always read back from storage
+            BlockCache::new(0),
+        )
+    }
+
+    #[test]
+    fn test_scrub_flags_corrupt_blocks() {
+        let tmp_dir = tempfile::tempdir().unwrap();
+        let mut provider = provider(tmp_dir.path());
+        create_blockfile(&mut provider, "tenant/good");
+        create_blockfile(&mut provider, "tenant/bad");
+        let scrubber = provider.scrubber(16);
+        assert_eq!(scrubber.scrub_round().unwrap(), 0);
+        assert_eq!(scrubber.blocks_scrubbed(), 2);
+
+        let block_id = scrubber.block_ids("tenant/bad")[0];
+        let block_path = tmp_dir.path().join(format!("blocks/{}.arrow", block_id));
+        let mut bytes = std::fs::read(&block_path).unwrap();
+        let last = bytes.len() - 1;
+        bytes[last] ^= 0xff;
+        std::fs::write(&block_path, bytes).unwrap();
+
+        assert_eq!(scrubber.scrub_round().unwrap(), 1);
+        // A corrupt block is only reported once
+        assert_eq!(scrubber.scrub_round().unwrap(), 0);
+        let corrupt_blocks = scrubber.corrupt_blocks();
+        assert_eq!(corrupt_blocks.len(), 1);
+        assert_eq!(corrupt_blocks[0].blockfile, "tenant/bad");
+        assert_eq!(corrupt_blocks[0].block_id, block_id);
+
+        // A missing block is corrupt as well
+        std::fs::remove_file(&block_path).unwrap();
+        let block_id = scrubber.block_ids("tenant/good")[0];
+        std::fs::remove_file(tmp_dir.path().join(format!("blocks/{}.arrow", block_id))).unwrap();
+        assert_eq!(scrubber.scrub_round().unwrap(), 1);
+        assert_eq!(scrubber.corrupt_blocks().len(), 2);
+    }
+
+    #[test]
+    fn test_scrub_is_fair_between_tenants() {
+        let tmp_dir = tempfile::tempdir().unwrap();
+        let mut provider = provider(tmp_dir.path());
+        for i in 0..8 {
+            create_blockfile(&mut provider, &format!("large/{}", i));
+        }
+        create_blockfile(&mut provider, "small/0");
+        let scrubber = provider.scrubber(2);
+        let block_id = scrubber.block_ids("small/0")[0];
+        std::fs::remove_file(tmp_dir.path().join(format!("blocks/{}.arrow", block_id))).unwrap();
+
+        // Every round verifies one block of each tenant
+        assert_eq!(scrubber.scrub_round().unwrap(), 1);
+        assert_eq!(scrubber.blocks_scrubbed(), 2);
+        assert_eq!(scrubber.corrupt_blocks()[0].block_id, block_id);
+    }
+}
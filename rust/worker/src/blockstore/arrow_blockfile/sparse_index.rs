@@ -0,0 +1,264 @@
+use crate::blockstore::types::{BlockfileKey, Key};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Debug;
+use uuid::Uuid;
+
+/// A delimiter in the sparse index. Every block starts at a delimiter and holds the keys up
+/// to, but not including, the delimiter of the next block.
+/// # Variants
+/// - Start: The delimiter of the first block. It sorts before every key.
+/// - Key: The delimiter of any other block, the smallest key that block may contain.
+#[derive(Clone, Debug)]
+pub(super) enum SparseIndexDelimiter {
+    Start,
+    Key(BlockfileKey),
+}
+
+impl PartialEq for SparseIndexDelimiter {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (SparseIndexDelimiter::Start, SparseIndexDelimiter::Start) => true,
+            (SparseIndexDelimiter::Key(k1), SparseIndexDelimiter::Key(k2)) => k1 == k2,
+            _ => false,
+        }
+    }
+}
+
+impl Eq for SparseIndexDelimiter {}
+
+impl PartialOrd for SparseIndexDelimiter {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for SparseIndexDelimiter {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        match (self, other) {
+            (SparseIndexDelimiter::Start, SparseIndexDelimiter::Start) => std::cmp::Ordering::Equal,
+            (SparseIndexDelimiter::Start, SparseIndexDelimiter::Key(_)) => std::cmp::Ordering::Less,
+            (SparseIndexDelimiter::Key(_), SparseIndexDelimiter::Start) => {
+                std::cmp::Ordering::Greater
+            }
+            (SparseIndexDelimiter::Key(k1), SparseIndexDelimiter::Key(k2)) => k1.cmp(k2),
+        }
+    }
+}
+
+/// A sparse index maps the start key of every block of a blockfile to the id of the block.
+/// Blocks cover contiguous, non-overlapping key ranges, so the block that may contain a key
+/// is the one with the greatest delimiter less than or equal to the key.
+/// # Methods
+/// - new: Creates a sparse index with a single block covering every key.
+/// - get_target_block_id: Returns the id of the block that may contain a key.
+/// - add_block: Adds a block starting at the given key.
+/// - replace_block: Replaces a block with a new block covering the same key range.
+/// - block_ids: Returns the ids of the blocks in key order.
+/// - block_ids_from: Returns the ids of the blocks that may contain a key or any greater key.
+/// - contains_block: Returns whether a block is part of the sparse index.
+#[derive(Clone)]
+pub(super) struct SparseIndex {
+    forward: BTreeMap<SparseIndexDelimiter, Uuid>,
+    reverse: HashMap<Uuid, SparseIndexDelimiter>,
+}
+
+impl SparseIndex {
+    pub(super) fn new(initial_block_id: Uuid) -> Self {
+        let mut forward = BTreeMap::new();
+        forward.insert(SparseIndexDelimiter::Start, initial_block_id);
+        let mut reverse = HashMap::new();
+        reverse.insert(initial_block_id, SparseIndexDelimiter::Start);
+        Self { forward, reverse }
+    }
+
+    pub(super) fn get_target_block_id(&self, search_key: &BlockfileKey) -> Uuid {
+        let search_key = SparseIndexDelimiter::Key(search_key.clone());
+        // The start delimiter sorts before every key, so there is always a candidate
+        match self.forward.range(..=search_key).next_back() {
+            Some((_, block_id)) => *block_id,
+            None => unreachable!("Sparse index has no start delimiter"),
+        }
+    }
+
+    pub(super) fn add_block(&mut self, start_key: BlockfileKey, block_id: Uuid) {
+        let delimiter = SparseIndexDelimiter::Key(start_key);
+        self.forward.insert(delimiter.clone(), block_id);
+        self.reverse.insert(block_id, delimiter);
+    }
+
+    /// Replaces the block with id old_block_id by the block with id new_block_id. The new
+    /// block keeps the delimiter of the old block.
+    pub(super) fn replace_block(&mut self, old_block_id: Uuid, new_block_id: Uuid) {
+        if let Some(delimiter) = self.reverse.remove(&old_block_id) {
+            self.forward.insert(delimiter.clone(), new_block_id);
+            self.reverse.insert(new_block_id, delimiter);
+        }
+    }
+
+    pub(super) fn block_ids(&self) -> Vec<Uuid> {
+        self.forward.values().cloned().collect()
+    }
+
+    pub(super) fn block_ids_from(&self, search_key: &BlockfileKey) -> Vec<Uuid> {
+        let first_block_id = self.get_target_block_id(search_key);
+        let first_delimiter = &self.reverse[&first_block_id];
+        self.forward
+            .range(first_delimiter..)
+            .map(|(_, block_id)| *block_id)
+            .collect()
+    }
+
+    pub(super) fn contains_block(&self, block_id: &Uuid) -> bool {
+        self.reverse.contains_key(block_id)
+    }
+
+    pub(super) fn len(&self) -> usize {
+        self.forward.len()
+    }
+}
+
+impl Debug for SparseIndex {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "SparseIndex {{ ")?;
+        for (delimiter, block_id) in self.forward.iter() {
+            match delimiter {
+                SparseIndexDelimiter::Start => write!(f, "Start -> {}, ", block_id)?,
+                SparseIndexDelimiter::Key(key) => write!(f, "{:?} -> {}, ", key, block_id)?,
+            }
+        }
+        write!(f, "}}")
+    }
+}
+
+// ===== Persistence =====
+
+// Floats are persisted by their bits, since JSON cannot represent NaN or infinite keys.
+#[derive(Serialize, Deserialize)]
+enum PersistedKey {
+    String(String),
+    Float(u64),
+    Bool(bool),
+    Int(i64),
+}
+
+#[derive(Serialize, Deserialize)]
+struct PersistedDelimiter {
+    // None for the start delimiter
+    start_key: Option<(String, PersistedKey)>,
+    block_id: String,
+}
+
+/// The on-disk form of a sparse index, the delimiters of the blocks in key order.
+#[derive(Serialize, Deserialize)]
+pub(super) struct PersistedSparseIndex {
+    delimiters: Vec<PersistedDelimiter>,
+}
+
+impl From<&SparseIndex> for PersistedSparseIndex {
+    fn from(sparse_index: &SparseIndex) -> Self {
+        let delimiters = sparse_index
+            .forward
+            .iter()
+            .map(|(delimiter, block_id)| PersistedDelimiter {
+                start_key: match delimiter {
+                    SparseIndexDelimiter::Start => None,
+                    SparseIndexDelimiter::Key(key) => Some((
+                        key.prefix.clone(),
+                        match &key.key {
+                            Key::String(s) => PersistedKey::String(s.clone()),
+                            Key::Float(f) => PersistedKey::Float(f.to_bits()),
+                            Key::Bool(b) => PersistedKey::Bool(*b),
+                            Key::Int(i) => PersistedKey::Int(*i),
+                        },
+                    )),
+                },
+                block_id: block_id.to_string(),
+            })
+            .collect();
+        PersistedSparseIndex { delimiters }
+    }
+}
+
+impl TryFrom<PersistedSparseIndex> for SparseIndex {
+    type Error = uuid::Error;
+
+    fn try_from(persisted: PersistedSparseIndex) -> Result<Self, Self::Error> {
+        let mut forward = BTreeMap::new();
+        let mut reverse = HashMap::new();
+        for delimiter in persisted.delimiters {
+            let block_id = Uuid::parse_str(&delimiter.block_id)?;
+            let delimiter = match delimiter.start_key {
+                None => SparseIndexDelimiter::Start,
+                Some((prefix, key)) => SparseIndexDelimiter::Key(BlockfileKey::new(
+                    prefix,
+                    match key {
+                        PersistedKey::String(s) => Key::String(s),
+                        PersistedKey::Float(bits) => Key::Float(f64::from_bits(bits)),
+                        PersistedKey::Bool(b) => Key::Bool(b),
+                        PersistedKey::Int(i) => Key::Int(i),
+                    },
+                )),
+            };
+            forward.insert(delimiter.clone(), block_id);
+            reverse.insert(block_id, delimiter);
+        }
+        Ok(SparseIndex { forward, reverse })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(k: &str) -> BlockfileKey {
+        BlockfileKey::new("prefix".to_string(), Key::String(k.to_string()))
+    }
+
+    #[test]
+    fn test_target_block() {
+        let first = Uuid::new_v4();
+        let second = Uuid::new_v4();
+        let mut sparse_index = SparseIndex::new(first);
+        assert_eq!(sparse_index.get_target_block_id(&key("a")), first);
+
+        sparse_index.add_block(key("m"), second);
+        assert_eq!(sparse_index.get_target_block_id(&key("a")), first);
+        assert_eq!(sparse_index.get_target_block_id(&key("m")), second);
+        assert_eq!(sparse_index.get_target_block_id(&key("z")), second);
+
+        let replacement = Uuid::new_v4();
+        sparse_index.replace_block(first, replacement);
+        assert_eq!(sparse_index.get_target_block_id(&key("a")), replacement);
+        assert_eq!(sparse_index.block_ids(), vec![replacement, second]);
+        assert_eq!(
+            sparse_index.block_ids_from(&key("a")),
+            vec![replacement, second]
+        );
+        assert_eq!(sparse_index.block_ids_from(&key("n")), vec![second]);
+    }
+
+    #[test]
+    fn test_persisted_round_trip() {
+        let first = Uuid::new_v4();
+        let second = Uuid::new_v4();
+        let mut sparse_index = SparseIndex::new(first);
+        sparse_index.add_block(
+            BlockfileKey::new("prefix".to_string(), Key::Float(f64::NAN)),
+            second,
+        );
+
+        let persisted = PersistedSparseIndex::from(&sparse_index);
+        let json = serde_json::to_string(&persisted).unwrap();
+        let persisted: PersistedSparseIndex = serde_json::from_str(&json).unwrap();
+        let restored = SparseIndex::try_from(persisted).unwrap();
+        assert_eq!(restored.block_ids(), vec![first, second]);
+        assert_eq!(
+            restored.get_target_block_id(&BlockfileKey::new(
+                "prefix".to_string(),
+                Key::Float(f64::NAN)
+            )),
+            second
+        );
+    }
+}
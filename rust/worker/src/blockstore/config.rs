@@ -0,0 +1,46 @@
+pub(crate) use super::arrow_blockfile::blockfile::BlockSizeConfig;
+use super::arrow_blockfile::cache::{DEFAULT_BLOCK_CACHE_CAPACITY, DEFAULT_HOT_SET_SIZE};
+pub(crate) use super::arrow_blockfile::format::BlockCompression;
+use serde::Deserialize;
+use std::path::PathBuf;
+
+const DEFAULT_SCRUB_INTERVAL_SECS: u64 = 300;
+const DEFAULT_SCRUB_BLOCKS_PER_ROUND: usize = 64;
+
+/// The configuration for the blockfiles of the worker.
+/// # Fields
+/// - block_sizes: The target size of blocks per value type. If not provided, the defaults of BlockSizeConfig are used.
+/// - storage_path: The directory blockfiles are persisted under. If not provided, blockfiles only live in memory.
+/// - compression: How persisted blocks are compressed. Defaults to no compression.
+/// - block_cache_bytes: The capacity in bytes of the block cache shared by all blockfiles. Defaults to 256MiB.
+/// - block_cache_hot_set_size: The number of most recently used blocks whose keys are saved under the storage
+/// path on shutdown and loaded back into the block cache on startup. Defaults to 1024, 0 disables it.
+/// - scrub_interval_secs: The pause in seconds between rounds of the block scrubber. Defaults to 300, 0 disables it.
+/// - scrub_blocks_per_round: The number of persisted blocks the block scrubber verifies per round. Defaults to 64.
+/// # Notes
+/// The whole section is optional, omitting it yields the defaults above.
+#[derive(Deserialize)]
+#[serde(default)]
+pub(crate) struct BlockfileConfig {
+    pub(crate) block_sizes: BlockSizeConfig,
+    pub(crate) storage_path: Option<PathBuf>,
+    pub(crate) compression: BlockCompression,
+    pub(crate) block_cache_bytes: usize,
+    pub(crate) block_cache_hot_set_size: usize,
+    pub(crate) scrub_interval_secs: u64,
+    pub(crate) scrub_blocks_per_round: usize,
+}
+
+impl Default for BlockfileConfig {
+    fn default() -> Self {
+        BlockfileConfig {
+            block_sizes: BlockSizeConfig::default(),
+            storage_path: None,
+            compression: BlockCompression::default(),
+            block_cache_bytes: DEFAULT_BLOCK_CACHE_CAPACITY,
+            block_cache_hot_set_size: DEFAULT_HOT_SET_SIZE,
+            scrub_interval_secs: DEFAULT_SCRUB_INTERVAL_SECS,
+            scrub_blocks_per_round: DEFAULT_SCRUB_BLOCKS_PER_ROUND,
+        }
+    }
+}
@@ -0,0 +1,425 @@
+use super::provider::{BlockfileProvider, CreateError, OpenError};
+use super::types::{
+    min_key, Blockfile, BlockfileCursor, BlockfileIterator, BlockfileKey, Key, KeyType, Value,
+    ValueType,
+};
+use crate::errors::ChromaError;
+use std::ops::{Bound, RangeBounds};
+
+// The key types of the partitions.
+const KEY_TYPES: [KeyType; 4] = [KeyType::String, KeyType::Float, KeyType::Bool, KeyType::Int];
+
+fn partition_index(key_type: KeyType) -> usize {
+    match key_type {
+        KeyType::String => 0,
+        KeyType::Float => 1,
+        KeyType::Bool => 2,
+        KeyType::Int => 3,
+    }
+}
+
+fn partition_path(path: &str, key_type: KeyType) -> String {
+    let name = match key_type {
+        KeyType::String => "string",
+        KeyType::Float => "float",
+        KeyType::Bool => "bool",
+        KeyType::Int => "int",
+    };
+    format!("{}/{}", path, name)
+}
+
+/// A blockfile holding keys of every type, backed by one blockfile per key type. Blockfiles
+/// such as ArrowBlockfile only hold keys of the type they were created with, this lets them
+/// back indexes whose keys are of several types.
+/// # Notes
+/// Every operation is routed to the partitions of the key types it covers, and scans
+/// merge the partitions so that entries come in the same order as from a single blockfile.
+/// Transactions span every partition, but the partitions are committed one after another,
+/// so a failed commit may leave some of them committed.
+/// The partitions of the blockfile at path are created at path/string, path/float,
+/// path/bool and path/int.
+#[derive(Clone)]
+pub(crate) struct KeyPartitionedBlockfile {
+    // One blockfile per key type, in the order of KEY_TYPES
+    partitions: Vec<Box<dyn Blockfile>>,
+}
+
+impl KeyPartitionedBlockfile {
+    pub(crate) fn create<P: BlockfileProvider>(
+        provider: &mut P,
+        path: &str,
+        value_type: ValueType,
+    ) -> Result<Self, Box<CreateError>> {
+        let mut partitions = Vec::with_capacity(KEY_TYPES.len());
+        for key_type in KEY_TYPES {
+            partitions.push(provider.create(
+                &partition_path(path, key_type),
+                key_type,
+                value_type,
+            )?);
+        }
+        Ok(Self { partitions })
+    }
+
+    pub(crate) fn open<P: BlockfileProvider>(
+        provider: &P,
+        path: &str,
+    ) -> Result<Self, Box<OpenError>> {
+        let mut partitions = Vec::with_capacity(KEY_TYPES.len());
+        for key_type in KEY_TYPES {
+            partitions.push(provider.open(&partition_path(path, key_type))?);
+        }
+        Ok(Self { partitions })
+    }
+
+    fn partition(&self, key: &BlockfileKey) -> &dyn Blockfile {
+        self.partitions[partition_index(KeyType::from(key))].as_ref()
+    }
+}
+
+impl Blockfile for KeyPartitionedBlockfile {
+    fn begin_transaction(&mut self) -> Result<(), Box<dyn ChromaError>> {
+        for partition in self.partitions.iter_mut() {
+            partition.begin_transaction()?;
+        }
+        Ok(())
+    }
+
+    fn commit_transaction(&mut self) -> Result<(), Box<dyn ChromaError>> {
+        for partition in self.partitions.iter_mut() {
+            partition.commit_transaction()?;
+        }
+        Ok(())
+    }
+
+    fn get(&self, key: BlockfileKey) -> Result<Value, Box<dyn ChromaError>> {
+        self.partition(&key).get(key)
+    }
+
+    fn get_len(&self, key: BlockfileKey) -> Result<u64, Box<dyn ChromaError>> {
+        self.partition(&key).get_len(key)
+    }
+
+    fn get_many(&self, keys: &[BlockfileKey]) -> Result<Vec<Option<Value>>, Box<dyn ChromaError>> {
+        let mut values = vec![None; keys.len()];
+        for (i, partition) in self.partitions.iter().enumerate() {
+            let (positions, partition_keys): (Vec<usize>, Vec<BlockfileKey>) = keys
+                .iter()
+                .enumerate()
+                .filter(|(_, key)| partition_index(KeyType::from(*key)) == i)
+                .map(|(position, key)| (position, key.clone()))
+                .unzip();
+            if partition_keys.is_empty() {
+                continue;
+            }
+            for (position, value) in positions
+                .into_iter()
+                .zip(partition.get_many(&partition_keys)?)
+            {
+                values[position] = value;
+            }
+        }
+        Ok(values)
+    }
+
+    fn set(&mut self, key: BlockfileKey, value: Value) -> Result<(), Box<dyn ChromaError>> {
+        self.partitions[partition_index(KeyType::from(&key))].set(key, value)
+    }
+
+    fn value_type(&self) -> Option<ValueType> {
+        // Every partition is created with the same value type
+        self.partitions[0].value_type()
+    }
+
+    fn get_range(
+        &self,
+        prefix: String,
+        start: Bound<Key>,
+        end: Bound<Key>,
+    ) -> Result<BlockfileIterator, Box<dyn ChromaError>> {
+        // Keys of different type groups are ordered by group, so the range covers the
+        // partitions from the group of start to the group of end. A bound applies to the
+        // partition of its own type, and to the other numeric partition once converted,
+        // since ints and floats are ordered by value among each other.
+        let group = |bound: &Bound<Key>| match bound {
+            Bound::Included(key) | Bound::Excluded(key) => Some(KeyType::from(key).order()),
+            Bound::Unbounded => None,
+        };
+        let (first, last) = (group(&start), group(&end));
+        let mut entries = Vec::new();
+        for key_type in KEY_TYPES {
+            let order = key_type.order();
+            if first.map_or(false, |first| order < first) || last.map_or(false, |last| order > last)
+            {
+                continue;
+            }
+            let partition_start = partition_bound(&start, key_type, true);
+            let partition_end = partition_bound(&end, key_type, false);
+            entries.extend(self.partitions[partition_index(key_type)].get_range(
+                prefix.clone(),
+                partition_start,
+                partition_end,
+            )?);
+        }
+        let range = (start, end);
+        entries.retain(|(key, _)| range.contains(&key.key));
+        // The partitions are sorted, the numeric ones are interleaved by the sort
+        entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+        Ok(Box::new(entries.into_iter()))
+    }
+
+    fn iter(&self) -> Result<BlockfileCursor, Box<dyn ChromaError>> {
+        let cursors = self
+            .partitions
+            .iter()
+            .map(|partition| partition.iter())
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Box::new(MergedCursor::new(cursors)))
+    }
+
+    fn iter_from(&self, key: BlockfileKey) -> Result<BlockfileCursor, Box<dyn ChromaError>> {
+        let key_type = KeyType::from(&key);
+        let mut cursors = Vec::with_capacity(KEY_TYPES.len());
+        for (partition_key_type, partition) in KEY_TYPES.iter().zip(self.partitions.iter()) {
+            if *partition_key_type == key_type {
+                cursors.push(partition.iter_from(key.clone())?);
+                continue;
+            }
+            // The other partitions start at the prefix of key, skipping the entries of
+            // the prefix whose type sorts before the type of key.
+            let start_key = key.clone();
+            let cursor = partition.iter_from(BlockfileKey::new(
+                key.prefix.clone(),
+                min_key(*partition_key_type),
+            ))?;
+            cursors.push(Box::new(cursor.filter(move |entry| match entry {
+                Ok((entry_key, _)) => *entry_key >= start_key,
+                Err(_) => true,
+            })));
+        }
+        Ok(Box::new(MergedCursor::new(cursors)))
+    }
+
+    fn verify(&self) -> Result<(), Box<dyn ChromaError>> {
+        for partition in self.partitions.iter() {
+            partition.verify()?;
+        }
+        Ok(())
+    }
+}
+
+// Converts a bound of a range to a bound for the partition of key_type that covers at
+// least the keys of that partition in the range. Bounds of other type groups do not
+// restrict the partition, and bounds of the other numeric type are rounded outwards.
+fn partition_bound(bound: &Bound<Key>, key_type: KeyType, lower: bool) -> Bound<Key> {
+    let key = match bound {
+        Bound::Included(key) | Bound::Excluded(key) => key,
+        Bound::Unbounded => return Bound::Unbounded,
+    };
+    match (key, key_type) {
+        (key, key_type) if KeyType::from(key) == key_type => bound.clone(),
+        // The nearest float to an int leaves no other float between the two
+        (Key::Int(i), KeyType::Float) => Bound::Included(Key::Float(*i as f64)),
+        (Key::Float(f), KeyType::Int) if !f.is_nan() => {
+            // Casts saturate, so floats outside the range of i64 bound at its ends
+            let rounded = if lower { f.ceil() } else { f.floor() };
+            Bound::Included(Key::Int(rounded as i64))
+        }
+        _ => Bound::Unbounded,
+    }
+}
+
+// Merges the cursors of the partitions into a single cursor in key order. Every cursor
+// is only read once the entries before its next entry have been returned.
+struct MergedCursor {
+    // A cursor is dropped once it is exhausted
+    cursors: Vec<Option<BlockfileCursor>>,
+    heads: Vec<Option<(BlockfileKey, Value)>>,
+}
+
+impl MergedCursor {
+    fn new(cursors: Vec<BlockfileCursor>) -> Self {
+        let heads = cursors.iter().map(|_| None).collect();
+        Self {
+            cursors: cursors.into_iter().map(Some).collect(),
+            heads,
+        }
+    }
+}
+
+impl Iterator for MergedCursor {
+    type Item = Result<(BlockfileKey, Value), Box<dyn ChromaError>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        for (cursor, head) in self.cursors.iter_mut().zip(self.heads.iter_mut()) {
+            if head.is_some() {
+                continue;
+            }
+            if let Some(entries) = cursor {
+                match entries.next() {
+                    Some(Ok(entry)) => *head = Some(entry),
+                    Some(Err(e)) => return Some(Err(e)),
+                    None => *cursor = None,
+                }
+            }
+        }
+        let mut smallest: Option<&BlockfileKey> = None;
+        let mut smallest_index = None;
+        for (i, head) in self.heads.iter().enumerate() {
+            if let Some((key, _)) = head {
+                if smallest.map_or(true, |smallest| key < smallest) {
+                    smallest = Some(key);
+                    smallest_index = Some(i);
+                }
+            }
+        }
+        smallest_index.and_then(|i| self.heads[i].take().map(Ok))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blockstore::arrow_blockfile::cache::BlockCache;
+    use crate::blockstore::arrow_blockfile::provider::ArrowBlockfileProvider;
+    use crate::blockstore::config::{BlockCompression, BlockSizeConfig};
+    use crate::blockstore::provider::HashMapBlockfileProvider;
+
+    fn entries() -> Vec<BlockfileKey> {
+        let mut keys = Vec::new();
+        for prefix in ["a", "b"] {
+            for i in 0..20 {
+                keys.push(BlockfileKey::new(
+                    prefix.to_string(),
+                    Key::String(format!("{:02}", i)),
+                ));
+                keys.push(BlockfileKey::new(prefix.to_string(), Key::Float(i as f64)));
+                keys.push(BlockfileKey::new(prefix.to_string(), Key::Int(i)));
+            }
+            keys.push(BlockfileKey::new(prefix.to_string(), Key::Bool(true)));
+        }
+        keys
+    }
+
+    // Writes the same entries to a key partitioned blockfile backed by arrow blockfiles
+    // and to a hash map blockfile, which holds keys of every type.
+    fn blockfiles() -> (KeyPartitionedBlockfile, Box<dyn Blockfile>) {
+        let mut arrow_provider = ArrowBlockfileProvider::new();
+        let mut partitioned =
+            KeyPartitionedBlockfile::create(&mut arrow_provider, "test", ValueType::Int32).unwrap();
+        let mut reference = HashMapBlockfileProvider::new()
+            .create("test", KeyType::String, ValueType::Int32)
+            .unwrap();
+        partitioned.begin_transaction().unwrap();
+        reference.begin_transaction().unwrap();
+        for (i, key) in entries().into_iter().enumerate() {
+            partitioned
+                .set(key.clone(), Value::Int32Value(i as i32))
+                .unwrap();
+            reference.set(key, Value::Int32Value(i as i32)).unwrap();
+        }
+        partitioned.commit_transaction().unwrap();
+        reference.commit_transaction().unwrap();
+        (partitioned, reference)
+    }
+
+    fn keys_of(entries: impl Iterator<Item = (BlockfileKey, Value)>) -> Vec<BlockfileKey> {
+        entries.map(|(key, _)| key).collect()
+    }
+
+    #[test]
+    fn test_get_across_key_types() {
+        let (blockfile, _) = blockfiles();
+        for (i, key) in entries().into_iter().enumerate() {
+            match blockfile.get(key).unwrap() {
+                Value::Int32Value(value) => assert_eq!(value, i as i32),
+                _ => panic!("Unexpected value type"),
+            }
+        }
+        let keys = vec![
+            BlockfileKey::new("a".to_string(), Key::Int(3)),
+            BlockfileKey::new("b".to_string(), Key::String("04".to_string())),
+            BlockfileKey::new("a".to_string(), Key::Bool(false)),
+            BlockfileKey::new("b".to_string(), Key::Float(5.0)),
+        ];
+        let values = blockfile.get_many(&keys).unwrap();
+        assert_eq!(values.len(), 4);
+        assert!(values[0].is_some());
+        assert!(values[1].is_some());
+        assert!(values[2].is_none());
+        assert!(values[3].is_some());
+    }
+
+    #[test]
+    fn test_scans_match_single_blockfile() {
+        let (blockfile, reference) = blockfiles();
+        let ranges = [
+            (Bound::Unbounded, Bound::Unbounded),
+            (Bound::Excluded(Key::Float(15.0)), Bound::Unbounded),
+            (Bound::Unbounded, Bound::Included(Key::Float(3.0))),
+            (
+                Bound::Included(Key::String("18".to_string())),
+                Bound::Excluded(Key::Int(2)),
+            ),
+            (Bound::Included(Key::Int(5)), Bound::Included(Key::Int(8))),
+            (
+                Bound::Excluded(Key::Int(15)),
+                Bound::Included(Key::Float(17.5)),
+            ),
+            (
+                Bound::Included(Key::Float(2.5)),
+                Bound::Excluded(Key::Int(5)),
+            ),
+            (Bound::Excluded(Key::Float(f64::NAN)), Bound::Unbounded),
+        ];
+        for (start, end) in ranges {
+            let expected = keys_of(
+                reference
+                    .get_range("a".to_string(), start.clone(), end.clone())
+                    .unwrap(),
+            );
+            let actual = keys_of(blockfile.get_range("a".to_string(), start, end).unwrap());
+            assert!(!actual.is_empty());
+            assert_eq!(actual, expected);
+        }
+
+        let collect = |cursor: BlockfileCursor| -> Vec<BlockfileKey> {
+            cursor.map(|entry| entry.unwrap().0).collect()
+        };
+        assert_eq!(
+            collect(blockfile.iter().unwrap()),
+            collect(reference.iter().unwrap())
+        );
+        let start = BlockfileKey::new("a".to_string(), Key::Float(12.0));
+        let from = collect(blockfile.iter_from(start.clone()).unwrap());
+        assert_eq!(from, collect(reference.iter_from(start.clone()).unwrap()));
+        assert_eq!(from[0], start);
+    }
+
+    #[test]
+    fn test_reopen_partitions() {
+        let tmp_dir = tempfile::tempdir().unwrap();
+        let new_provider = || {
+            ArrowBlockfileProvider::with_config(
+                BlockSizeConfig::default(),
+                Some(tmp_dir.path().to_path_buf()),
+                BlockCompression::None,
+                BlockCache::new(1024 * 1024),
+            )
+        };
+        let mut provider = new_provider();
+        let mut blockfile =
+            KeyPartitionedBlockfile::create(&mut provider, "test", ValueType::Int32).unwrap();
+        blockfile.begin_transaction().unwrap();
+        for (i, key) in entries().into_iter().enumerate() {
+            blockfile.set(key, Value::Int32Value(i as i32)).unwrap();
+        }
+        blockfile.commit_transaction().unwrap();
+        assert!(KeyPartitionedBlockfile::create(&mut provider, "test", ValueType::Int32).is_err());
+
+        let reopened = KeyPartitionedBlockfile::open(&new_provider(), "test").unwrap();
+        reopened.verify().unwrap();
+        assert_eq!(reopened.iter().unwrap().count(), entries().len());
+        assert!(KeyPartitionedBlockfile::open(&new_provider(), "missing").is_err());
+    }
+}
@@ -1,8 +1,11 @@
-mod arrow_blockfile;
+mod key_partitioned_blockfile;
 mod positional_posting_list_value;
 mod types;
 
+pub(crate) mod arrow_blockfile;
+pub(crate) mod config;
 pub(crate) mod provider;
 
+pub(crate) use key_partitioned_blockfile::*;
 pub(crate) use positional_posting_list_value::*;
 pub(crate) use types::*;
@@ -11,7 +11,7 @@ use thiserror::Error;
 /// A trait for opening and creating blockfiles
 /// # Methods
 /// - new: Create a new instance of the blockfile provider. A blockfile provider returns a Box<dyn Blockfile> of a given type.
-/// Currently, we support HashMap and Arrow-backed blockfiles.
+/// Currently, we support HashMap and Arrow-backed blockfiles, see arrow_blockfile::provider::ArrowBlockfileProvider for the latter.
 /// - open: Open a blockfile at the given path, returning a Box<dyn Blockfile> and error if it does not exist
 /// - create: Create a new blockfile at the given path, returning a Box<dyn Blockfile> and error if it already exists
 /// # Example
@@ -77,11 +77,19 @@ impl BlockfileProvider for HashMapBlockfileProvider {
 pub(crate) enum OpenError {
     #[error("Blockfile not found")]
     NotFound,
+    #[error("Blockfile could not be read: {0}")]
+    Unreadable(String),
+    #[error("Invalid blockfile name: {0}")]
+    InvalidName(String),
 }
 
 impl ChromaError for OpenError {
     fn code(&self) -> crate::errors::ErrorCodes {
-        crate::errors::ErrorCodes::NotFound
+        match self {
+            OpenError::NotFound => crate::errors::ErrorCodes::NotFound,
+            OpenError::Unreadable(_) => crate::errors::ErrorCodes::DataLoss,
+            OpenError::InvalidName(_) => crate::errors::ErrorCodes::InvalidArgument,
+        }
     }
 }
 
@@ -89,10 +97,18 @@ impl ChromaError for OpenError {
 pub(crate) enum CreateError {
     #[error("Blockfile already exists")]
     AlreadyExists,
+    #[error("Existing blockfile could not be read: {0}")]
+    Unreadable(String),
+    #[error("Invalid blockfile name: {0}")]
+    InvalidName(String),
 }
 
 impl ChromaError for CreateError {
     fn code(&self) -> crate::errors::ErrorCodes {
-        crate::errors::ErrorCodes::AlreadyExists
+        match self {
+            CreateError::AlreadyExists => crate::errors::ErrorCodes::AlreadyExists,
+            CreateError::Unreadable(_) => crate::errors::ErrorCodes::DataLoss,
+            CreateError::InvalidName(_) => crate::errors::ErrorCodes::InvalidArgument,
+        }
     }
 }
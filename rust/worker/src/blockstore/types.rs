@@ -3,9 +3,12 @@ use crate::errors::{ChromaError, ErrorCodes};
 use arrow::array::{Array, Int32Array};
 use parking_lot::RwLock;
 use roaring::RoaringBitmap;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
 use std::collections::HashMap;
 use std::fmt::{Debug, Display};
 use std::hash::{Hash, Hasher};
+use std::ops::{Bound, RangeBounds};
 use std::sync::Arc;
 use thiserror::Error;
 
@@ -13,12 +16,15 @@ use thiserror::Error;
 pub(crate) enum BlockfileError {
     #[error("Key not found")]
     NotFoundError,
+    #[error("Value is not a collection")]
+    NotACollectionError,
 }
 
 impl ChromaError for BlockfileError {
     fn code(&self) -> ErrorCodes {
         match self {
             BlockfileError::NotFoundError => ErrorCodes::InvalidArgument,
+            BlockfileError::NotACollectionError => ErrorCodes::InvalidArgument,
         }
     }
 }
@@ -34,8 +40,9 @@ impl Key {
     pub(crate) fn get_size(&self) -> usize {
         match self {
             Key::String(s) => s.len(),
-            Key::Float(_) => 4,
+            Key::Float(_) => 8,
             Key::Bool(_) => 1,
+            Key::Int(_) => 8,
         }
     }
 }
@@ -52,26 +59,134 @@ impl BlockfileKey {
 
 impl From<&BlockfileKey> for KeyType {
     fn from(key: &BlockfileKey) -> Self {
-        match key.key {
+        KeyType::from(&key.key)
+    }
+}
+
+impl From<&Key> for KeyType {
+    fn from(key: &Key) -> Self {
+        match key {
             Key::String(_) => KeyType::String,
             Key::Float(_) => KeyType::Float,
             Key::Bool(_) => KeyType::Bool,
+            Key::Int(_) => KeyType::Int,
         }
     }
 }
 
-#[derive(Clone, PartialEq, PartialOrd, Debug)]
+#[derive(Clone, Debug)]
 pub(crate) enum Key {
     String(String),
-    Float(f32),
+    Float(f64),
     Bool(bool),
+    Int(i64),
+}
+
+// Compares an int to a float by numeric value. Positive NaNs are greater than every
+// int and negative NaNs smaller, like in the total order of floats. An int and a float
+// of the same value are ordered float first, so that they remain distinct keys.
+fn cmp_int_float(i: i64, f: f64) -> Ordering {
+    // 2^63 is exactly representable, so every float in [-2^63, 2^63) truncates to an i64
+    const LIMIT: f64 = 9_223_372_036_854_775_808.0;
+    if f.is_nan() {
+        return if f.is_sign_negative() {
+            Ordering::Greater
+        } else {
+            Ordering::Less
+        };
+    }
+    if f >= LIMIT {
+        return Ordering::Less;
+    }
+    if f < -LIMIT {
+        return Ordering::Greater;
+    }
+    let whole = f.trunc();
+    match i.cmp(&(whole as i64)) {
+        Ordering::Equal if f > whole => Ordering::Less,
+        Ordering::Equal => Ordering::Greater,
+        ordering => ordering,
+    }
+}
+
+// Keys are ordered by type group first, see KeyType::order. Floats are ordered with
+// f64::total_cmp so that every key has a deterministic place in a range scan: -0.0 sorts
+// before 0.0, negative NaNs before -inf and positive NaNs after inf. Ints and floats are
+// ordered by value among each other, see cmp_int_float. Equality follows the same order,
+// so a NaN key is equal to a NaN with the same bits and Int(1) is not equal to Float(1.0).
+impl Ord for Key {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (Key::String(s1), Key::String(s2)) => s1.cmp(s2),
+            (Key::Float(f1), Key::Float(f2)) => f1.total_cmp(f2),
+            (Key::Bool(b1), Key::Bool(b2)) => b1.cmp(b2),
+            (Key::Int(i1), Key::Int(i2)) => i1.cmp(i2),
+            (Key::Int(i), Key::Float(f)) => cmp_int_float(*i, *f),
+            (Key::Float(f), Key::Int(i)) => cmp_int_float(*i, *f).reverse(),
+            _ => KeyType::from(self)
+                .order()
+                .cmp(&KeyType::from(other).order()),
+        }
+    }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+impl PartialOrd for Key {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for Key {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Key {}
+
+// Keys of different types are never equal, and floats are equal exactly when their bits are,
+// so hashing the type and the bits of the value agrees with Eq.
+impl Hash for Key {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        std::mem::discriminant(self).hash(state);
+        match self {
+            Key::String(s) => s.hash(state),
+            Key::Float(f) => f.to_bits().hash(state),
+            Key::Bool(b) => b.hash(state),
+            Key::Int(i) => i.hash(state),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub(crate) enum KeyType {
     String,
     Float,
     Bool,
+    Int,
+}
+
+impl KeyType {
+    // Keys of different types are ordered by this group: strings, then numbers, then
+    // bools. Ints and floats are both numbers and are ordered by value among each other.
+    pub(crate) fn order(&self) -> u8 {
+        match self {
+            KeyType::String => 0,
+            KeyType::Float | KeyType::Int => 1,
+            KeyType::Bool => 2,
+        }
+    }
+}
+
+// Returns the smallest key of the given type.
+pub(crate) fn min_key(key_type: KeyType) -> Key {
+    match key_type {
+        KeyType::String => Key::String(String::new()),
+        // The negative NaN with all bits set sorts first in the total order of floats
+        KeyType::Float => Key::Float(f64::from_bits(u64::MAX)),
+        KeyType::Bool => Key::Bool(false),
+        KeyType::Int => Key::Int(i64::MIN),
+    }
 }
 
 impl Display for Key {
@@ -80,6 +195,7 @@ impl Display for Key {
             Key::String(s) => write!(f, "{}", s),
             Key::Float(fl) => write!(f, "{}", fl),
             Key::Bool(b) => write!(f, "{}", b),
+            Key::Int(i) => write!(f, "{}", i),
         }
     }
 }
@@ -101,11 +217,9 @@ impl Debug for BlockfileKey {
 }
 
 impl Hash for BlockfileKey {
-    // Hash is only used for the HashMap implementation, which is a test/reference implementation
-    // Therefore this hash implementation is not used in production and allowed to be
-    // hacky
     fn hash<H: Hasher>(&self, state: &mut H) {
         self.prefix.hash(state);
+        self.key.hash(state);
     }
 }
 
@@ -116,37 +230,18 @@ impl PartialEq for BlockfileKey {
 }
 
 impl PartialOrd for BlockfileKey {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        if self.prefix == other.prefix {
-            self.key.partial_cmp(&other.key)
-        } else {
-            self.prefix.partial_cmp(&other.prefix)
-        }
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
     }
 }
 
 impl Eq for BlockfileKey {}
 
 impl Ord for BlockfileKey {
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        if self.prefix == other.prefix {
-            match self.key {
-                Key::String(ref s1) => match &other.key {
-                    Key::String(s2) => s1.cmp(s2),
-                    _ => panic!("Cannot compare string to float or bool"),
-                },
-                Key::Float(f1) => match &other.key {
-                    Key::Float(f2) => f1.partial_cmp(f2).unwrap(),
-                    _ => panic!("Cannot compare float to string or bool"),
-                },
-                Key::Bool(b1) => match &other.key {
-                    Key::Bool(b2) => b1.cmp(b2),
-                    _ => panic!("Cannot compare bool to string or float"),
-                },
-            }
-        } else {
-            self.prefix.cmp(&other.prefix)
-        }
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.prefix
+            .cmp(&other.prefix)
+            .then_with(|| self.key.cmp(&other.key))
     }
 }
 
@@ -199,7 +294,7 @@ impl Value {
                 unimplemented!("Size of positional posting list")
             }
             Value::StringValue(s) => s.len(),
-            Value::RoaringBitmapValue(bitmap) => unimplemented!("Size of roaring bitmap"),
+            Value::RoaringBitmapValue(bitmap) => bitmap.serialized_size(),
             Value::Int32Value(_) => 4,
         }
     }
@@ -217,7 +312,7 @@ impl From<&Value> for ValueType {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub(crate) enum ValueType {
     Int32Array,
     PositionalPostingList,
@@ -226,7 +321,7 @@ pub(crate) enum ValueType {
     Int32,
 }
 
-pub(crate) trait Blockfile: BlockfileClone {
+pub(crate) trait Blockfile: BlockfileClone + Send + Sync {
     // ===== Transaction methods =====
     fn begin_transaction(&mut self) -> Result<(), Box<dyn ChromaError>>;
 
@@ -234,38 +329,70 @@ pub(crate) trait Blockfile: BlockfileClone {
 
     // ===== Data methods =====
     fn get(&self, key: BlockfileKey) -> Result<Value, Box<dyn ChromaError>>;
-    fn get_by_prefix(
-        &self,
-        prefix: String,
-    ) -> Result<Vec<(BlockfileKey, Value)>, Box<dyn ChromaError>>;
+    // Returns the number of items in the array or bitmap stored at key without
+    // copying the value out of the blockfile.
+    fn get_len(&self, key: BlockfileKey) -> Result<u64, Box<dyn ChromaError>>;
+    // Returns the value of every key in the order of keys, None for the keys that are
+    // not in the blockfile. Implementations read the storage holding several of the keys
+    // only once.
+    fn get_many(&self, keys: &[BlockfileKey]) -> Result<Vec<Option<Value>>, Box<dyn ChromaError>>;
+    // Returns all entries under prefix in ascending key order.
+    fn get_by_prefix(&self, prefix: &str) -> Result<BlockfileIterator, Box<dyn ChromaError>> {
+        self.get_range(prefix.to_string(), Bound::Unbounded, Bound::Unbounded)
+    }
 
     fn set(&mut self, key: BlockfileKey, value: Value) -> Result<(), Box<dyn ChromaError>>;
 
-    fn get_gt(
-        &self,
-        prefix: String,
-        key: Key,
-    ) -> Result<Vec<(BlockfileKey, Value)>, Box<dyn ChromaError>>;
+    // Returns the type of value set accepts, None if the blockfile holds values of any type.
+    fn value_type(&self) -> Option<ValueType>;
 
-    fn get_lt(
+    // Range scans return the entries under prefix whose key lies between start and end,
+    // in ascending key order. Keys of other types than the bounds are ordered by type.
+    fn get_range(
         &self,
         prefix: String,
-        key: Key,
-    ) -> Result<Vec<(BlockfileKey, Value)>, Box<dyn ChromaError>>;
+        start: Bound<Key>,
+        end: Bound<Key>,
+    ) -> Result<BlockfileIterator, Box<dyn ChromaError>>;
 
-    fn get_gte(
-        &self,
-        prefix: String,
-        key: Key,
-    ) -> Result<Vec<(BlockfileKey, Value)>, Box<dyn ChromaError>>;
+    fn get_gt(&self, prefix: String, key: Key) -> Result<BlockfileIterator, Box<dyn ChromaError>> {
+        self.get_range(prefix, Bound::Excluded(key), Bound::Unbounded)
+    }
 
-    fn get_lte(
-        &self,
-        prefix: String,
-        key: Key,
-    ) -> Result<Vec<(BlockfileKey, Value)>, Box<dyn ChromaError>>;
+    fn get_lt(&self, prefix: String, key: Key) -> Result<BlockfileIterator, Box<dyn ChromaError>> {
+        self.get_range(prefix, Bound::Unbounded, Bound::Excluded(key))
+    }
+
+    fn get_gte(&self, prefix: String, key: Key) -> Result<BlockfileIterator, Box<dyn ChromaError>> {
+        self.get_range(prefix, Bound::Included(key), Bound::Unbounded)
+    }
+
+    fn get_lte(&self, prefix: String, key: Key) -> Result<BlockfileIterator, Box<dyn ChromaError>> {
+        self.get_range(prefix, Bound::Unbounded, Bound::Included(key))
+    }
+
+    // ===== Cursor methods =====
+    // Cursors walk the entries of the blockfile in ascending order of prefix and key,
+    // reading the underlying storage lazily.
+    fn iter(&self) -> Result<BlockfileCursor, Box<dyn ChromaError>>;
+
+    // Returns a cursor over the entries from key onwards.
+    fn iter_from(&self, key: BlockfileKey) -> Result<BlockfileCursor, Box<dyn ChromaError>>;
+
+    // Checks that the committed contents of the blockfile can be read back intact from its
+    // storage, for offline integrity checks. Returns a DataLoss error for the first part
+    // of the blockfile that is missing or corrupted.
+    fn verify(&self) -> Result<(), Box<dyn ChromaError>>;
 }
 
+/// An iterator over the entries of a blockfile, as returned by range scans.
+pub(crate) type BlockfileIterator = Box<dyn Iterator<Item = (BlockfileKey, Value)> + Send>;
+
+/// A lazy iterator over the entries of a blockfile. Since it reads the blockfile as it
+/// goes, every entry may instead be an error.
+pub(crate) type BlockfileCursor =
+    Box<dyn Iterator<Item = Result<(BlockfileKey, Value), Box<dyn ChromaError>>>>;
+
 pub(crate) trait BlockfileClone {
     fn clone_box(&self) -> Box<dyn Blockfile>;
 }
@@ -285,106 +412,135 @@ impl Clone for Box<dyn Blockfile> {
     }
 }
 
-#[derive(Clone)]
+/// An in-memory blockfile.
+/// # Notes
+/// Readers share an immutable snapshot of the map. The sets of a transaction are
+/// buffered in the handle and published all at once on commit by swapping in a new
+/// snapshot, so readers never observe a partially applied transaction. Sets outside
+/// of a transaction are applied immediately.
 pub(crate) struct HashMapBlockfile {
-    map: Arc<RwLock<HashMap<BlockfileKey, Value>>>,
+    map: Arc<RwLock<Arc<HashMap<BlockfileKey, Value>>>>,
+    uncommitted: Option<HashMap<BlockfileKey, Value>>,
 }
 
 impl HashMapBlockfile {
     pub(super) fn new() -> Self {
         Self {
-            map: Arc::new(RwLock::new(HashMap::new())),
+            map: Arc::new(RwLock::new(Arc::new(HashMap::new()))),
+            uncommitted: None,
+        }
+    }
+
+    fn snapshot(&self) -> Arc<HashMap<BlockfileKey, Value>> {
+        self.map.read().clone()
+    }
+}
+
+// A clone shares the committed state, but not the open transaction of the original.
+impl Clone for HashMapBlockfile {
+    fn clone(&self) -> Self {
+        Self {
+            map: self.map.clone(),
+            uncommitted: None,
         }
     }
 }
 
 impl Blockfile for HashMapBlockfile {
     fn get(&self, key: BlockfileKey) -> Result<Value, Box<dyn ChromaError>> {
-        match self.map.read().get(&key) {
+        match self.snapshot().get(&key) {
             Some(value) => Ok(value.clone()),
             None => Err(Box::new(BlockfileError::NotFoundError)),
         }
     }
 
-    fn get_by_prefix(
-        &self,
-        prefix: String,
-    ) -> Result<Vec<(BlockfileKey, Value)>, Box<dyn ChromaError>> {
-        let mut result = Vec::new();
-        for (key, value) in self.map.read().iter() {
-            if key.prefix == prefix {
-                result.push((key.clone(), value.clone()));
-            }
+    fn get_len(&self, key: BlockfileKey) -> Result<u64, Box<dyn ChromaError>> {
+        match self.snapshot().get(&key) {
+            Some(Value::RoaringBitmapValue(rbm)) => Ok(rbm.len()),
+            Some(Value::Int32ArrayValue(array)) => Ok(array.len() as u64),
+            Some(_) => Err(Box::new(BlockfileError::NotACollectionError)),
+            None => Err(Box::new(BlockfileError::NotFoundError)),
         }
-        Ok(result)
     }
 
-    fn set(&mut self, key: BlockfileKey, value: Value) -> Result<(), Box<dyn ChromaError>> {
-        self.map.write().insert(key, value);
-        Ok(())
+    fn get_many(&self, keys: &[BlockfileKey]) -> Result<Vec<Option<Value>>, Box<dyn ChromaError>> {
+        let map = self.snapshot();
+        Ok(keys.iter().map(|key| map.get(key).cloned()).collect())
     }
 
-    fn get_gt(
-        &self,
-        prefix: String,
-        key: Key,
-    ) -> Result<Vec<(BlockfileKey, Value)>, Box<dyn ChromaError>> {
-        let mut result = Vec::new();
-        for (k, v) in self.map.read().iter() {
-            if k.prefix == prefix && k.key > key {
-                result.push((k.clone(), v.clone()));
+    fn set(&mut self, key: BlockfileKey, value: Value) -> Result<(), Box<dyn ChromaError>> {
+        match &mut self.uncommitted {
+            Some(uncommitted) => {
+                uncommitted.insert(key, value);
+            }
+            None => {
+                Arc::make_mut(&mut self.map.write()).insert(key, value);
             }
         }
-        Ok(result)
+        Ok(())
     }
 
-    fn get_gte(
-        &self,
-        prefix: String,
-        key: Key,
-    ) -> Result<Vec<(BlockfileKey, Value)>, Box<dyn ChromaError>> {
-        let mut result = Vec::new();
-        for (k, v) in self.map.read().iter() {
-            if k.prefix == prefix && k.key >= key {
-                result.push((k.clone(), v.clone()));
-            }
-        }
-        Ok(result)
+    fn value_type(&self) -> Option<ValueType> {
+        None
     }
 
-    fn get_lt(
+    fn get_range(
         &self,
         prefix: String,
-        key: Key,
-    ) -> Result<Vec<(BlockfileKey, Value)>, Box<dyn ChromaError>> {
+        start: Bound<Key>,
+        end: Bound<Key>,
+    ) -> Result<BlockfileIterator, Box<dyn ChromaError>> {
+        let range = (start, end);
         let mut result = Vec::new();
-        for (k, v) in self.map.read().iter() {
-            if k.prefix == prefix && k.key < key {
+        for (k, v) in self.snapshot().iter() {
+            if k.prefix == prefix && range.contains(&k.key) {
                 result.push((k.clone(), v.clone()));
             }
         }
-        Ok(result)
+        result.sort_by(|(k1, _), (k2, _)| {
+            k1.key
+                .partial_cmp(&k2.key)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        Ok(Box::new(result.into_iter()))
     }
 
-    fn get_lte(
-        &self,
-        prefix: String,
-        key: Key,
-    ) -> Result<Vec<(BlockfileKey, Value)>, Box<dyn ChromaError>> {
-        let mut result = Vec::new();
-        for (k, v) in self.map.read().iter() {
-            if k.prefix == prefix && k.key <= key {
-                result.push((k.clone(), v.clone()));
-            }
-        }
-        Ok(result)
+    fn iter(&self) -> Result<BlockfileCursor, Box<dyn ChromaError>> {
+        let mut result = self
+            .snapshot()
+            .iter()
+            .map(|(k, v)| (k.clone(), v.clone()))
+            .collect::<Vec<_>>();
+        result.sort_by(|(k1, _), (k2, _)| k1.partial_cmp(k2).unwrap_or(std::cmp::Ordering::Equal));
+        Ok(Box::new(result.into_iter().map(Ok)))
+    }
+
+    fn iter_from(&self, key: BlockfileKey) -> Result<BlockfileCursor, Box<dyn ChromaError>> {
+        Ok(Box::new(self.iter()?.filter(move |entry| match entry {
+            Ok((k, _)) => *k >= key,
+            Err(_) => true,
+        })))
+    }
+
+    fn verify(&self) -> Result<(), Box<dyn ChromaError>> {
+        // The map is not persisted, so there is nothing that could be corrupted
+        Ok(())
     }
 
     fn begin_transaction(&mut self) -> Result<(), Box<dyn ChromaError>> {
+        self.uncommitted = Some(HashMap::new());
         Ok(())
     }
 
     fn commit_transaction(&mut self) -> Result<(), Box<dyn ChromaError>> {
+        let uncommitted = match self.uncommitted.take() {
+            Some(uncommitted) => uncommitted,
+            None => return Ok(()),
+        };
+        let mut map = self.map.write();
+        let mut next = HashMap::clone(&map);
+        next.extend(uncommitted);
+        *map = Arc::new(next);
         Ok(())
     }
 }
@@ -394,6 +550,24 @@ mod tests {
     use super::*;
     use crate::blockstore::positional_posting_list_value::PositionalPostingListBuilder;
     use arrow::array::Array;
+    use std::collections::hash_map::DefaultHasher;
+
+    #[test]
+    fn test_blockfile_key_hash() {
+        let hash = |key: Key| {
+            let mut hasher = DefaultHasher::new();
+            BlockfileKey::new("prefix".to_string(), key).hash(&mut hasher);
+            hasher.finish()
+        };
+        assert_eq!(hash(Key::Float(1.5)), hash(Key::Float(1.5)));
+        assert_ne!(hash(Key::Int(1)), hash(Key::Int(2)));
+        assert_ne!(hash(Key::Int(1)), hash(Key::Float(1.0)));
+        assert_ne!(hash(Key::Float(0.0)), hash(Key::Float(-0.0)));
+        assert_ne!(
+            hash(Key::String("a".to_string())),
+            hash(Key::String("b".to_string()))
+        );
+    }
 
     #[test]
     fn test_blockfile_set_get() {
@@ -439,19 +613,29 @@ mod tests {
                 Value::Int32ArrayValue(Int32Array::from(vec![4, 5, 6])),
             )
             .unwrap();
-        let values = blockfile.get_by_prefix("text_prefix".to_string()).unwrap();
+        let _res = blockfile
+            .set(
+                BlockfileKey::new(
+                    "text_prefix_other".to_string(),
+                    Key::String("key0".to_string()),
+                ),
+                Value::Int32ArrayValue(Int32Array::from(vec![7])),
+            )
+            .unwrap();
+        let values = blockfile
+            .get_by_prefix("text_prefix")
+            .unwrap()
+            .collect::<Vec<_>>();
         assert_eq!(values.len(), 2);
-        // May return values in any order
+        // Values are returned in key order
+        assert_eq!(values[0].0, key1);
         match &values[0].1 {
-            Value::Int32ArrayValue(arr) => assert!(
-                arr == &Int32Array::from(vec![1, 2, 3]) || arr == &Int32Array::from(vec![4, 5, 6])
-            ),
+            Value::Int32ArrayValue(arr) => assert_eq!(arr, &Int32Array::from(vec![1, 2, 3])),
             _ => panic!("Value is not a string"),
         }
+        assert_eq!(values[1].0, key2);
         match &values[1].1 {
-            Value::Int32ArrayValue(arr) => assert!(
-                arr == &Int32Array::from(vec![1, 2, 3]) || arr == &Int32Array::from(vec![4, 5, 6])
-            ),
+            Value::Int32ArrayValue(arr) => assert_eq!(arr, &Int32Array::from(vec![4, 5, 6])),
             _ => panic!("Value is not a string"),
         }
     }
@@ -519,18 +703,170 @@ mod tests {
         );
         let values = blockfile
             .get_gt("text_prefix".to_string(), Key::String("key1".to_string()))
-            .unwrap();
+            .unwrap()
+            .collect::<Vec<_>>();
         assert_eq!(values.len(), 2);
+        // Range scans are sorted by key
         match &values[0].0.key {
-            Key::String(s) => assert!(s == "key2" || s == "key3"),
+            Key::String(s) => assert_eq!(s, "key2"),
             _ => panic!("Key is not a string"),
         }
         match &values[1].0.key {
-            Key::String(s) => assert!(s == "key2" || s == "key3"),
+            Key::String(s) => assert_eq!(s, "key3"),
             _ => panic!("Key is not a string"),
         }
     }
 
+    #[test]
+    fn test_blockfile_get_range() {
+        let mut blockfile = HashMapBlockfile::new();
+        for i in (0..10).rev() {
+            let key = BlockfileKey::new("prefix".to_string(), Key::Int(i));
+            blockfile.set(key, Value::Int32Value(i as i32)).unwrap();
+        }
+        blockfile
+            .set(
+                BlockfileKey::new("other".to_string(), Key::Int(5)),
+                Value::Int32Value(-1),
+            )
+            .unwrap();
+
+        let keys = |range: BlockfileIterator| {
+            range
+                .map(|(k, _)| match k.key {
+                    Key::Int(i) => i,
+                    _ => panic!("Key is not an int"),
+                })
+                .collect::<Vec<_>>()
+        };
+        let range = blockfile
+            .get_range(
+                "prefix".to_string(),
+                Bound::Included(Key::Int(3)),
+                Bound::Excluded(Key::Int(7)),
+            )
+            .unwrap();
+        assert_eq!(keys(range), vec![3, 4, 5, 6]);
+        let range = blockfile
+            .get_lte("prefix".to_string(), Key::Int(2))
+            .unwrap();
+        assert_eq!(keys(range), vec![0, 1, 2]);
+        let range = blockfile
+            .get_range("prefix".to_string(), Bound::Unbounded, Bound::Unbounded)
+            .unwrap();
+        assert_eq!(keys(range), (0..10).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_key_order_across_types() {
+        let ordered = vec![
+            Key::String("b".to_string()),
+            Key::Float(f64::from_bits(u64::MAX)),
+            Key::Float(f64::NEG_INFINITY),
+            Key::Int(i64::MIN),
+            Key::Float(-2.5),
+            Key::Int(-2),
+            Key::Float(0.0),
+            Key::Int(0),
+            Key::Float(1.5),
+            Key::Int(2),
+            Key::Float(2.5),
+            Key::Int(i64::MAX),
+            Key::Float(9_223_372_036_854_775_808.0),
+            Key::Float(f64::INFINITY),
+            Key::Float(f64::NAN),
+            Key::Bool(false),
+            Key::Bool(true),
+        ];
+        for (i, a) in ordered.iter().enumerate() {
+            for (j, b) in ordered.iter().enumerate() {
+                assert_eq!(a.cmp(b), i.cmp(&j), "{:?} vs {:?}", a, b);
+            }
+        }
+        assert_ne!(Key::Int(1), Key::Float(1.0));
+
+        let a = BlockfileKey::new("a".to_string(), Key::Int(3));
+        let b = BlockfileKey::new("a".to_string(), Key::Float(2.5));
+        let c = BlockfileKey::new("b".to_string(), Key::String("x".to_string()));
+        let mut keys = vec![c.clone(), a.clone(), b.clone()];
+        keys.sort();
+        assert_eq!(keys, vec![b, a, c]);
+    }
+
+    #[test]
+    fn test_blockfile_get_many() {
+        let mut blockfile = HashMapBlockfile::new();
+        for i in 0..3 {
+            let key = BlockfileKey::new("prefix".to_string(), Key::Int(i));
+            blockfile.set(key, Value::Int32Value(i as i32)).unwrap();
+        }
+        let keys = [2, 5, 0]
+            .iter()
+            .map(|i| BlockfileKey::new("prefix".to_string(), Key::Int(*i)))
+            .collect::<Vec<_>>();
+        let values = blockfile.get_many(&keys).unwrap();
+        assert_eq!(values.len(), 3);
+        assert!(matches!(values[0], Some(Value::Int32Value(2))));
+        assert!(values[1].is_none());
+        assert!(matches!(values[2], Some(Value::Int32Value(0))));
+    }
+
+    #[test]
+    fn test_blockfile_transaction_is_atomic() {
+        let mut blockfile = HashMapBlockfile::new();
+        let reader = blockfile.clone();
+        let key = |i| BlockfileKey::new("prefix".to_string(), Key::Int(i));
+        blockfile.set(key(0), Value::Int32Value(0)).unwrap();
+
+        blockfile.begin_transaction().unwrap();
+        blockfile.set(key(0), Value::Int32Value(10)).unwrap();
+        blockfile.set(key(1), Value::Int32Value(11)).unwrap();
+        // Readers keep seeing the committed state until the whole transaction is published
+        assert!(matches!(reader.get(key(0)).unwrap(), Value::Int32Value(0)));
+        assert!(reader.get(key(1)).is_err());
+        assert_eq!(reader.iter().unwrap().count(), 1);
+
+        blockfile.commit_transaction().unwrap();
+        assert!(matches!(reader.get(key(0)).unwrap(), Value::Int32Value(10)));
+        assert!(matches!(reader.get(key(1)).unwrap(), Value::Int32Value(11)));
+    }
+
+    #[test]
+    fn test_blockfile_iter() {
+        let mut blockfile = HashMapBlockfile::new();
+        for prefix in ["b", "a"] {
+            for i in [2, 0, 1] {
+                let key = BlockfileKey::new(prefix.to_string(), Key::Int(i));
+                blockfile.set(key, Value::Int32Value(i as i32)).unwrap();
+            }
+        }
+
+        let keys = |cursor: BlockfileCursor| {
+            cursor
+                .map(|entry| {
+                    let (k, _) = entry.unwrap();
+                    match k.key {
+                        Key::Int(i) => (k.prefix, i),
+                        _ => panic!("Key is not an int"),
+                    }
+                })
+                .collect::<Vec<_>>()
+        };
+        let expected = vec![
+            ("a".to_string(), 0),
+            ("a".to_string(), 1),
+            ("a".to_string(), 2),
+            ("b".to_string(), 0),
+            ("b".to_string(), 1),
+            ("b".to_string(), 2),
+        ];
+        assert_eq!(keys(blockfile.iter().unwrap()), expected);
+        let cursor = blockfile
+            .iter_from(BlockfileKey::new("a".to_string(), Key::Int(2)))
+            .unwrap();
+        assert_eq!(keys(cursor), expected[2..].to_vec());
+    }
+
     #[test]
     fn test_learning_arrow_struct() {
         let mut builder = PositionalPostingListBuilder::new();
@@ -90,6 +90,8 @@ impl RootConfig {
 /// - pulsar_tenant: The pulsar tenant to use. Must be provided.
 /// - pulsar_namespace: The pulsar namespace to use. Must be provided.
 /// - assignment_policy: The assignment policy to use. Must be provided.
+/// - server: The gRPC server limits. If not provided, no IP allowlist and the default limits are used.
+/// - blockfile: The block sizes, storage and compression of blockfiles. If not provided, in-memory blockfiles with the default block sizes are used.
 /// # Notes
 /// In order to set the enviroment variables, you must prefix them with CHROMA_WORKER__<FIELD_NAME>.
 /// For example, to set my_ip, you would set CHROMA_WORKER__MY_IP.
@@ -110,6 +112,10 @@ pub(crate) struct WorkerConfig {
     pub(crate) segment_manager: crate::segment::config::SegmentManagerConfig,
     pub(crate) storage: crate::storage::config::StorageConfig,
     pub(crate) log: crate::log::config::LogConfig,
+    #[serde(default)]
+    pub(crate) server: crate::server::config::ServerConfig,
+    #[serde(default)]
+    pub(crate) blockfile: crate::blockstore::config::BlockfileConfig,
 }
 
 /// # Description
@@ -127,6 +133,7 @@ pub(crate) trait Configurable {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::blockstore::config::BlockCompression;
     use figment::Jail;
 
     #[test]
@@ -173,6 +180,16 @@ mod tests {
             assert_eq!(config.worker.pulsar_tenant, "public");
             assert_eq!(config.worker.pulsar_namespace, "default");
             assert_eq!(config.worker.kube_namespace, "chroma");
+            assert_eq!(
+                config.worker.segment_manager.hnsw_build_threads,
+                num_cpus::get()
+            );
+            assert!(config.worker.server.allowed_ips.is_none());
+            assert_eq!(config.worker.server.max_request_size_bytes, 4 * 1024 * 1024);
+            assert!(config.worker.server.max_embeddings_per_request.is_none());
+            assert_eq!(config.worker.blockfile.block_sizes.roaring_bitmap, 65536);
+            assert!(config.worker.blockfile.storage_path.is_none());
+            assert_eq!(config.worker.blockfile.block_cache_bytes, 256 * 1024 * 1024);
             Ok(())
         });
     }
@@ -206,6 +223,7 @@ mod tests {
                             port: 50051
                     segment_manager:
                         storage_path: "/tmp"
+                        hnsw_build_threads: 2
                     storage:
                         S3:
                             bucket: "chroma"
@@ -213,6 +231,21 @@ mod tests {
                         Grpc:
                             host: "localhost"
                             port: 50052
+                    server:
+                        allowed_ips: ["10.0.0.1", "::1"]
+                        max_request_size_bytes: 1048576
+                        max_embeddings_per_request: 100
+                        embedding:
+                            url: "http://localhost:8080/embed"
+                            batch_size: 16
+                    blockfile:
+                        block_sizes:
+                            roaring_bitmap: 131072
+                        storage_path: "/tmp/blockfiles"
+                        compression:
+                            Zstd:
+                                level: 3
+                        block_cache_bytes: 1048576
 
                 "#,
             );
@@ -222,6 +255,28 @@ mod tests {
             assert_eq!(config.worker.pulsar_tenant, "public");
             assert_eq!(config.worker.pulsar_namespace, "default");
             assert_eq!(config.worker.kube_namespace, "chroma");
+            assert_eq!(config.worker.segment_manager.hnsw_build_threads, 2);
+            assert_eq!(config.worker.server.allowed_ips.unwrap().len(), 2);
+            assert_eq!(config.worker.server.max_request_size_bytes, 1048576);
+            assert_eq!(config.worker.server.max_embeddings_per_request, Some(100));
+            #[cfg(feature = "query-embedding")]
+            {
+                let embedding = config.worker.server.embedding.unwrap();
+                assert_eq!(embedding.url, "http://localhost:8080/embed");
+                assert_eq!(embedding.batch_size, 16);
+                assert_eq!(embedding.cache_size, 1024);
+            }
+            assert_eq!(config.worker.blockfile.block_sizes.roaring_bitmap, 131072);
+            assert_eq!(config.worker.blockfile.block_sizes.string, 8192);
+            assert_eq!(
+                config.worker.blockfile.storage_path,
+                Some("/tmp/blockfiles".into())
+            );
+            assert_eq!(
+                config.worker.blockfile.compression,
+                BlockCompression::Zstd { level: 3 }
+            );
+            assert_eq!(config.worker.blockfile.block_cache_bytes, 1048576);
             Ok(())
         });
     }
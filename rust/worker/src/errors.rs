@@ -42,6 +42,74 @@ pub(crate) enum ErrorCodes {
     DataLoss = 15,
 }
 
+/// The broad category of an error, independent of the component that raised it.
+/// # Variants
+/// - User: The caller sent something invalid or unsupported, retrying the same request will fail again.
+/// - Retriable: A transient infrastructure failure, the same request may succeed if retried later.
+/// - DataCorruption: Stored data is lost or unreadable.
+/// - Bug: An internal invariant was violated.
+/// # Notes
+/// The category decides how an error is surfaced, e.g. whether its details are returned
+/// to the client and whether it is logged. Details of DataCorruption and Bug errors are
+/// not returned to clients.
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub(crate) enum ErrorCategory {
+    User,
+    Retriable,
+    DataCorruption,
+    Bug,
+}
+
+impl ErrorCodes {
+    pub(crate) fn category(&self) -> ErrorCategory {
+        match self {
+            ErrorCodes::Cancelled
+            | ErrorCodes::InvalidArgument
+            | ErrorCodes::NotFound
+            | ErrorCodes::AlreadyExists
+            | ErrorCodes::PermissionDenied
+            | ErrorCodes::UNAUTHENTICATED
+            | ErrorCodes::FailedPrecondition
+            | ErrorCodes::OutOfRange
+            | ErrorCodes::Unimplemented => ErrorCategory::User,
+            ErrorCodes::DeadlineExceeded
+            | ErrorCodes::ResourceExhausted
+            | ErrorCodes::Aborted
+            | ErrorCodes::Unavailable => ErrorCategory::Retriable,
+            ErrorCodes::DataLoss => ErrorCategory::DataCorruption,
+            ErrorCodes::Success | ErrorCodes::UNKNOWN | ErrorCodes::Internal => ErrorCategory::Bug,
+        }
+    }
+}
+
+/// The error trait implemented by every error in the worker.
+/// # Methods
+/// - code: The gRPC-like code of the error
+/// - category: The category of the error, derived from the code unless overridden
 pub(crate) trait ChromaError: Error {
     fn code(&self) -> ErrorCodes;
+    fn category(&self) -> ErrorCategory {
+        self.code().category()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_code_categories() {
+        assert_eq!(ErrorCodes::InvalidArgument.category(), ErrorCategory::User);
+        assert_eq!(ErrorCodes::NotFound.category(), ErrorCategory::User);
+        assert_eq!(ErrorCodes::Unavailable.category(), ErrorCategory::Retriable);
+        assert_eq!(
+            ErrorCodes::ResourceExhausted.category(),
+            ErrorCategory::Retriable
+        );
+        assert_eq!(
+            ErrorCodes::DataLoss.category(),
+            ErrorCategory::DataCorruption
+        );
+        assert_eq!(ErrorCodes::Internal.category(), ErrorCategory::Bug);
+    }
 }
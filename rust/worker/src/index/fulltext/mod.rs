@@ -1,4 +1,5 @@
 pub mod tokenizer;
+mod trigram;
 mod types;
 
 pub use types::*;
@@ -1,6 +1,11 @@
 use crate::errors::{ChromaError, ErrorCodes};
+use crate::types::{MetadataValue, Segment};
+use thiserror::Error;
 
-use tantivy::tokenizer::{NgramTokenizer, Token, Tokenizer, TokenStream};
+use tantivy::tokenizer::{
+    Language, LowerCaser, NgramTokenizer, SimpleTokenizer, Stemmer, StopWordFilter,
+    TextAnalyzer, TextAnalyzerBuilder, Token, TokenStream, WhitespaceTokenizer,
+};
 
 pub(crate) trait ChromaTokenStream {
     fn process(&mut self, sink: &mut dyn FnMut(&Token));
@@ -31,22 +36,225 @@ impl ChromaTokenStream for TantivyChromaTokenStream {
     }
 }
 
-pub(crate) trait ChromaTokenizer {
+pub(crate) trait ChromaTokenizer: Send + Sync {
     fn encode(&mut self, text: &str) -> Box<dyn ChromaTokenStream>;
 }
 
 pub(crate) struct TantivyChromaTokenizer {
-    tokenizer: Box<NgramTokenizer>
+    tokenizer: TextAnalyzer
 }
 
 impl TantivyChromaTokenizer {
-    pub fn new(tokenizer: Box<NgramTokenizer>) -> Self {
+    pub fn new(tokenizer: NgramTokenizer) -> Self {
+        TantivyChromaTokenizer {
+            tokenizer: TextAnalyzer::from(tokenizer),
+        }
+    }
+
+    // Wraps a tokenizer followed by token filters.
+    pub fn from_analyzer(tokenizer: TextAnalyzer) -> Self {
         TantivyChromaTokenizer {
             tokenizer,
         }
     }
 }
 
+#[derive(Error, Debug)]
+pub(crate) enum TokenizerConfigError {
+    #[error("Unknown tokenizer `{0}`")]
+    UnknownTokenizer(String),
+    #[error("Invalid n-gram range {0}..={1}")]
+    InvalidNgramRange(usize, usize),
+    #[error("Unknown language `{0}`")]
+    UnknownLanguage(String),
+    #[error("No stopwords for language {0:?}")]
+    NoStopwords(Language),
+}
+
+impl ChromaError for TokenizerConfigError {
+    fn code(&self) -> ErrorCodes {
+        match self {
+            TokenizerConfigError::UnknownTokenizer(_) => ErrorCodes::InvalidArgument,
+            TokenizerConfigError::InvalidNgramRange(_, _) => ErrorCodes::InvalidArgument,
+            TokenizerConfigError::UnknownLanguage(_) => ErrorCodes::InvalidArgument,
+            TokenizerConfigError::NoStopwords(_) => ErrorCodes::InvalidArgument,
+        }
+    }
+}
+
+/// The tokenizer of a full-text index.
+/// # Variants
+/// - Whitespace: Splits on whitespace and keeps punctuation and case.
+/// - Unicode: Splits on anything that is not a unicode alphanumeric character and lowercases.
+/// - Ngram: Every run of min_gram to max_gram characters. Scripts without word separators,
+///   such as CJK, need n-grams for $contains to match within words.
+/// # Notes
+/// Positions are byte offsets into the document, so a phrase only matches when the text
+/// between its tokens is the same as in the query. Queries that produce no tokens, such as
+/// queries shorter than min_gram, match no documents.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum TokenizerConfig {
+    Whitespace,
+    Unicode,
+    Ngram { min_gram: usize, max_gram: usize },
+}
+
+impl Default for TokenizerConfig {
+    fn default() -> Self {
+        TokenizerConfig::Ngram {
+            min_gram: 1,
+            max_gram: 3,
+        }
+    }
+}
+
+impl TokenizerConfig {
+    // Reads the tokenizer from the `fulltext:tokenizer` key of the segment metadata,
+    // and the n-gram range from `fulltext:ngram_min` and `fulltext:ngram_max`. Missing
+    // keys fall back to the default. Writers and readers of a segment must agree on the
+    // tokenizer, so it should not be changed once the segment holds documents.
+    pub(crate) fn from_segment(segment: &Segment) -> Result<Self, Box<dyn ChromaError>> {
+        let metadata = match &segment.metadata {
+            Some(metadata) => metadata,
+            None => return Ok(TokenizerConfig::default()),
+        };
+        let get_usize = |key: &str, default: usize| match metadata.get(key) {
+            Some(MetadataValue::Int(value)) => *value as usize,
+            _ => default,
+        };
+        let config = match metadata.get("fulltext:tokenizer") {
+            Some(MetadataValue::Str(tokenizer)) => match tokenizer.as_str() {
+                "whitespace" => TokenizerConfig::Whitespace,
+                "unicode" => TokenizerConfig::Unicode,
+                "ngram" => TokenizerConfig::Ngram {
+                    min_gram: get_usize("fulltext:ngram_min", 1),
+                    max_gram: get_usize("fulltext:ngram_max", 3),
+                },
+                _ => {
+                    return Err(Box::new(TokenizerConfigError::UnknownTokenizer(
+                        tokenizer.clone(),
+                    )))
+                }
+            },
+            _ => TokenizerConfig::default(),
+        };
+        Ok(config)
+    }
+
+    pub(crate) fn build(&self) -> Result<Box<dyn ChromaTokenizer>, Box<dyn ChromaError>> {
+        let analyzer = self.analyzer_builder()?.build();
+        Ok(Box::new(TantivyChromaTokenizer::from_analyzer(analyzer)))
+    }
+
+    fn analyzer_builder(&self) -> Result<TextAnalyzerBuilder, Box<dyn ChromaError>> {
+        let builder = match self {
+            TokenizerConfig::Whitespace => {
+                TextAnalyzer::builder(WhitespaceTokenizer::default()).dynamic()
+            }
+            TokenizerConfig::Unicode => {
+                TextAnalyzer::builder(SimpleTokenizer::default()).filter_dynamic(LowerCaser)
+            }
+            TokenizerConfig::Ngram { min_gram, max_gram } => {
+                match NgramTokenizer::new(*min_gram, *max_gram, false) {
+                    Ok(tokenizer) => TextAnalyzer::builder(tokenizer).dynamic(),
+                    Err(_) => {
+                        return Err(Box::new(TokenizerConfigError::InvalidNgramRange(
+                            *min_gram, *max_gram,
+                        )))
+                    }
+                }
+            }
+        };
+        Ok(builder)
+    }
+}
+
+/// The analysis chain of a full-text index, applied to documents and queries alike.
+/// # Fields
+/// - tokenizer: Splits the text into tokens.
+/// - stopwords: Removes the stopwords of the language. Stopword lists are lowercase, so
+///   this is meant to follow a lowercasing tokenizer.
+/// - stemmer: Reduces tokens to their Snowball stem in the language, after stopword removal.
+/// # Notes
+/// Removed tokens leave gaps in positions, so a query is matched as if its stopwords
+/// were absent, and stemmed phrases only match when the text between tokens is the same.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub(crate) struct AnalyzerConfig {
+    pub(crate) tokenizer: TokenizerConfig,
+    pub(crate) stopwords: Option<Language>,
+    pub(crate) stemmer: Option<Language>,
+}
+
+impl AnalyzerConfig {
+    // Reads the tokenizer as TokenizerConfig does, and the languages of the stopwords
+    // and the stemmer from `fulltext:stopwords` and `fulltext:stemmer`.
+    pub(crate) fn from_segment(segment: &Segment) -> Result<Self, Box<dyn ChromaError>> {
+        let tokenizer = TokenizerConfig::from_segment(segment)?;
+        let get_language = |key: &str| match &segment.metadata {
+            Some(metadata) => match metadata.get(key) {
+                Some(MetadataValue::Str(language)) => match language_from_str(language) {
+                    Some(language) => Ok(Some(language)),
+                    None => Err(TokenizerConfigError::UnknownLanguage(language.clone())),
+                },
+                _ => Ok(None),
+            },
+            None => Ok(None),
+        };
+        let stopwords = match get_language("fulltext:stopwords") {
+            Ok(stopwords) => stopwords,
+            Err(e) => return Err(Box::new(e)),
+        };
+        let stemmer = match get_language("fulltext:stemmer") {
+            Ok(stemmer) => stemmer,
+            Err(e) => return Err(Box::new(e)),
+        };
+        Ok(AnalyzerConfig {
+            tokenizer,
+            stopwords,
+            stemmer,
+        })
+    }
+
+    pub(crate) fn build(&self) -> Result<Box<dyn ChromaTokenizer>, Box<dyn ChromaError>> {
+        let mut builder = self.tokenizer.analyzer_builder()?;
+        if let Some(language) = self.stopwords {
+            match StopWordFilter::new(language) {
+                Some(filter) => builder = builder.filter_dynamic(filter),
+                None => return Err(Box::new(TokenizerConfigError::NoStopwords(language))),
+            }
+        }
+        if let Some(language) = self.stemmer {
+            builder = builder.filter_dynamic(Stemmer::new(language));
+        }
+        Ok(Box::new(TantivyChromaTokenizer::from_analyzer(builder.build())))
+    }
+}
+
+fn language_from_str(language: &str) -> Option<Language> {
+    let language = match language.to_lowercase().as_str() {
+        "arabic" => Language::Arabic,
+        "danish" => Language::Danish,
+        "dutch" => Language::Dutch,
+        "english" => Language::English,
+        "finnish" => Language::Finnish,
+        "french" => Language::French,
+        "german" => Language::German,
+        "greek" => Language::Greek,
+        "hungarian" => Language::Hungarian,
+        "italian" => Language::Italian,
+        "norwegian" => Language::Norwegian,
+        "portuguese" => Language::Portuguese,
+        "romanian" => Language::Romanian,
+        "russian" => Language::Russian,
+        "spanish" => Language::Spanish,
+        "swedish" => Language::Swedish,
+        "tamil" => Language::Tamil,
+        "turkish" => Language::Turkish,
+        _ => return None,
+    };
+    Some(language)
+}
+
 impl ChromaTokenizer for TantivyChromaTokenizer {
     fn encode(&mut self, text: &str) -> Box<dyn ChromaTokenStream> {
         let mut token_stream = self.tokenizer.token_stream(text);
@@ -58,12 +266,13 @@ impl ChromaTokenizer for TantivyChromaTokenizer {
     }
 }
 
+#[cfg(test)]
 mod test {
     use super::*;
 
     #[test]
     fn test_chroma_tokenizer() {
-        let tokenizer: Box<NgramTokenizer> = Box::new(NgramTokenizer::new(1, 1, false).unwrap());
+        let tokenizer = NgramTokenizer::new(1, 1, false).unwrap();
         let mut chroma_tokenizer = TantivyChromaTokenizer::new(tokenizer);
         let mut token_stream = chroma_tokenizer.encode("hello world");
         let mut tokens = Vec::new();
@@ -77,7 +286,7 @@ mod test {
 
     #[test]
     fn test_get_tokens() {
-        let tokenizer: Box<NgramTokenizer> = Box::new(NgramTokenizer::new(1, 1, false).unwrap());
+        let tokenizer = NgramTokenizer::new(1, 1, false).unwrap();
         let mut chroma_tokenizer = TantivyChromaTokenizer::new(tokenizer);
         let token_stream = chroma_tokenizer.encode("hello world");
         let tokens = token_stream.get_tokens();
@@ -85,4 +294,149 @@ mod test {
         assert_eq!(tokens[0].text, "h");
         assert_eq!(tokens[1].text, "e");
     }
-}
\ No newline at end of file
+
+    fn token_texts(config: TokenizerConfig, text: &str) -> Vec<String> {
+        let mut tokenizer = config.build().unwrap();
+        let token_stream = tokenizer.encode(text);
+        token_stream
+            .get_tokens()
+            .iter()
+            .map(|token| token.text.clone())
+            .collect()
+    }
+
+    #[test]
+    fn test_tokenizer_configs() {
+        assert_eq!(
+            token_texts(TokenizerConfig::Whitespace, "Hello, World"),
+            vec!["Hello,", "World"]
+        );
+        assert_eq!(
+            token_texts(TokenizerConfig::Unicode, "Hello, Wörld"),
+            vec!["hello", "wörld"]
+        );
+        assert_eq!(
+            token_texts(
+                TokenizerConfig::Ngram {
+                    min_gram: 2,
+                    max_gram: 2
+                },
+                "東京都"
+            ),
+            vec!["東京", "京都"]
+        );
+        let config = TokenizerConfig::Ngram {
+            min_gram: 3,
+            max_gram: 2,
+        };
+        assert_eq!(
+            config.build().err().unwrap().code(),
+            ErrorCodes::InvalidArgument
+        );
+    }
+
+    #[test]
+    fn test_tokenizer_config_from_segment() {
+        let mut segment = Segment {
+            id: uuid::Uuid::new_v4(),
+            r#type: crate::types::SegmentType::HnswDistributed,
+            scope: crate::types::SegmentScope::METADATA,
+            topic: None,
+            collection: None,
+            metadata: None,
+        };
+        assert_eq!(
+            TokenizerConfig::from_segment(&segment).unwrap(),
+            TokenizerConfig::default()
+        );
+
+        let mut metadata = crate::types::Metadata::new();
+        metadata.insert(
+            "fulltext:tokenizer".to_string(),
+            MetadataValue::Str("ngram".to_string()),
+        );
+        metadata.insert("fulltext:ngram_min".to_string(), MetadataValue::Int(2));
+        metadata.insert("fulltext:ngram_max".to_string(), MetadataValue::Int(2));
+        segment.metadata = Some(metadata.clone());
+        assert_eq!(
+            TokenizerConfig::from_segment(&segment).unwrap(),
+            TokenizerConfig::Ngram {
+                min_gram: 2,
+                max_gram: 2
+            }
+        );
+
+        metadata.insert(
+            "fulltext:tokenizer".to_string(),
+            MetadataValue::Str("letters".to_string()),
+        );
+        segment.metadata = Some(metadata);
+        let result = TokenizerConfig::from_segment(&segment);
+        assert_eq!(result.err().unwrap().code(), ErrorCodes::InvalidArgument);
+    }
+
+    #[test]
+    fn test_analyzer_config() {
+        let mut tokenizer = AnalyzerConfig {
+            tokenizer: TokenizerConfig::Unicode,
+            stopwords: Some(Language::English),
+            stemmer: Some(Language::English),
+        }
+        .build()
+        .unwrap();
+        let token_stream = tokenizer.encode("The foxes are running");
+        let tokens = token_stream
+            .get_tokens()
+            .iter()
+            .map(|token| token.text.as_str())
+            .collect::<Vec<&str>>();
+        assert_eq!(tokens, vec!["fox", "run"]);
+
+        let config = AnalyzerConfig {
+            tokenizer: TokenizerConfig::Unicode,
+            stopwords: Some(Language::Tamil),
+            stemmer: None,
+        };
+        assert_eq!(
+            config.build().err().unwrap().code(),
+            ErrorCodes::InvalidArgument
+        );
+    }
+
+    #[test]
+    fn test_analyzer_config_from_segment() {
+        let mut metadata = crate::types::Metadata::new();
+        metadata.insert(
+            "fulltext:tokenizer".to_string(),
+            MetadataValue::Str("unicode".to_string()),
+        );
+        metadata.insert(
+            "fulltext:stemmer".to_string(),
+            MetadataValue::Str("German".to_string()),
+        );
+        let mut segment = Segment {
+            id: uuid::Uuid::new_v4(),
+            r#type: crate::types::SegmentType::HnswDistributed,
+            scope: crate::types::SegmentScope::METADATA,
+            topic: None,
+            collection: None,
+            metadata: Some(metadata.clone()),
+        };
+        assert_eq!(
+            AnalyzerConfig::from_segment(&segment).unwrap(),
+            AnalyzerConfig {
+                tokenizer: TokenizerConfig::Unicode,
+                stopwords: None,
+                stemmer: Some(Language::German),
+            }
+        );
+
+        metadata.insert(
+            "fulltext:stopwords".to_string(),
+            MetadataValue::Str("klingon".to_string()),
+        );
+        segment.metadata = Some(metadata);
+        let result = AnalyzerConfig::from_segment(&segment);
+        assert_eq!(result.err().unwrap().code(), ErrorCodes::InvalidArgument);
+    }
+}
@@ -0,0 +1,96 @@
+use regex_syntax::hir::{Hir, HirKind, Literal};
+use std::collections::HashSet;
+
+// Every run of three characters of text.
+pub(crate) fn trigrams(text: &str) -> HashSet<String> {
+    let chars = text.chars().collect::<Vec<char>>();
+    chars
+        .windows(3)
+        .map(|window| window.iter().collect())
+        .collect()
+}
+
+// Trigrams that every string matched by hir contains. Only literals that must match
+// are considered, so the set may be empty, for instance for alternations or case
+// insensitive patterns, in which case no document can be pruned.
+pub(crate) fn required_trigrams(hir: &Hir) -> HashSet<String> {
+    let mut literals = Vec::new();
+    required_literals(hir, &mut literals);
+    literals
+        .iter()
+        .flat_map(|literal| trigrams(literal))
+        .collect()
+}
+
+fn literal_str(hir: &Hir) -> Option<&str> {
+    match hir.kind() {
+        HirKind::Literal(Literal(bytes)) => std::str::from_utf8(bytes).ok(),
+        _ => None,
+    }
+}
+
+fn required_literals(hir: &Hir, literals: &mut Vec<String>) {
+    match hir.kind() {
+        HirKind::Literal(_) => {
+            if let Some(literal) = literal_str(hir) {
+                literals.push(literal.to_string());
+            }
+        }
+        HirKind::Capture(capture) => required_literals(&capture.sub, literals),
+        HirKind::Repetition(repetition) if repetition.min > 0 => {
+            required_literals(&repetition.sub, literals)
+        }
+        HirKind::Concat(subs) => {
+            // Adjacent literals form a single literal. Look-arounds have no width so
+            // they do not separate them.
+            let mut run = String::new();
+            for sub in subs {
+                match (literal_str(sub), sub.kind()) {
+                    (Some(literal), _) => run.push_str(literal),
+                    (None, HirKind::Look(_)) => {}
+                    (None, _) => {
+                        if !run.is_empty() {
+                            literals.push(std::mem::take(&mut run));
+                        }
+                        required_literals(sub, literals);
+                    }
+                }
+            }
+            if !run.is_empty() {
+                literals.push(run);
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn required(pattern: &str) -> Vec<String> {
+        let hir = regex_syntax::parse(pattern).unwrap();
+        let mut trigrams = required_trigrams(&hir).into_iter().collect::<Vec<String>>();
+        trigrams.sort();
+        trigrams
+    }
+
+    #[test]
+    fn test_trigrams() {
+        let mut res = trigrams("héllo").into_iter().collect::<Vec<String>>();
+        res.sort();
+        assert_eq!(res, vec!["hél", "llo", "éll"]);
+        assert!(trigrams("ab").is_empty());
+    }
+
+    #[test]
+    fn test_required_trigrams() {
+        assert_eq!(required("hello"), vec!["ell", "hel", "llo"]);
+        assert_eq!(required("^abc.*xyz$"), vec!["abc", "xyz"]);
+        assert_eq!(required("(abcd)+e?"), vec!["abc", "bcd"]);
+        assert_eq!(required("ab\\bcd"), vec!["abc", "bcd"]);
+        assert!(required("abc|xyz").is_empty());
+        assert!(required("(?i)abc").is_empty());
+        assert!(required("(abc)?").is_empty());
+    }
+}
@@ -1,6 +1,11 @@
-use crate::blockstore::{Blockfile, BlockfileKey, Key, PositionalPostingListBuilder, Value};
+use crate::blockstore::{
+    Blockfile, BlockfileKey, Key, PositionalPostingList, PositionalPostingListBuilder, Value,
+};
 use crate::errors::{ChromaError, ErrorCodes};
 use crate::index::fulltext::tokenizer::ChromaTokenizer;
+use crate::index::fulltext::trigram::{required_trigrams, trigrams};
+use regex::Regex;
+use roaring::RoaringBitmap;
 use std::collections::HashMap;
 use thiserror::Error;
 
@@ -10,6 +15,12 @@ pub enum FullTextIndexError {
     AlreadyInTransaction,
     #[error("Not in a transaction")]
     NotInTransaction,
+    #[error("The index does not store documents")]
+    NoDocumentStore,
+    #[error("Invalid regex: {0}")]
+    InvalidRegex(String),
+    #[error("A commit of the index failed, the index must be reopened")]
+    Poisoned,
 }
 
 impl ChromaError for FullTextIndexError {
@@ -17,6 +28,9 @@ impl ChromaError for FullTextIndexError {
         match self {
             FullTextIndexError::AlreadyInTransaction => ErrorCodes::FailedPrecondition,
             FullTextIndexError::NotInTransaction => ErrorCodes::FailedPrecondition,
+            FullTextIndexError::NoDocumentStore => ErrorCodes::FailedPrecondition,
+            FullTextIndexError::InvalidRegex(_) => ErrorCodes::InvalidArgument,
+            FullTextIndexError::Poisoned => ErrorCodes::Internal,
         }
     }
 }
@@ -27,19 +41,51 @@ pub(crate) trait FullTextIndex {
 
     // Must be done inside a transaction.
     fn add_document(&mut self, document: &str, offset_id: i32) -> Result<(), Box<dyn ChromaError>>;
-    // Only searches committed state.
+    // Only searches committed state. Returns the sorted offset ids of the documents
+    // that contain query as a substring, as far as the tokenizer can tell.
     fn search(&mut self, query: &str) -> Result<Vec<i32>, Box<dyn ChromaError>>;
+    // Only searches committed state. Returns the sorted offset ids of every added
+    // document that search does not return for query.
+    fn search_not_contains(&mut self, query: &str) -> Result<Vec<i32>, Box<dyn ChromaError>>;
+    // Only searches committed state. Ranks the documents containing any token of query
+    // by BM25 and returns the k best (offset_id, score) pairs, best first.
+    fn search_bm25(
+        &mut self,
+        query: &str,
+        k: usize,
+    ) -> Result<Vec<(i32, f32)>, Box<dyn ChromaError>>;
+    // Only searches committed state. Returns the sorted offset ids of the documents
+    // matching pattern anywhere. Requires a document store.
+    fn search_regex(&mut self, pattern: &str) -> Result<Vec<i32>, Box<dyn ChromaError>>;
 }
 
+// BM25 term frequency saturation and document length normalization.
+const BM25_K1: f32 = 1.2;
+const BM25_B: f32 = 0.75;
+
 pub(crate) struct BlockfileFullTextIndex {
     posting_lists_blockfile: Box<dyn Blockfile>,
     frequencies_blockfile: Box<dyn Blockfile>,
     tokenizer: Box<dyn ChromaTokenizer>,
     in_transaction: bool,
+    // Set when a commit failed, some blockfiles may hold part of its writes then.
+    poisoned: bool,
 
     // term -> positional posting list builder for that term
     uncommitted: HashMap<String, PositionalPostingListBuilder>,
     uncommitted_frequencies: HashMap<String, i32>,
+    // Documents added in the transaction, including those without tokens.
+    uncommitted_documents: RoaringBitmap,
+    // Number of tokens of each document added in the transaction, and the number of
+    // tokens added in total.
+    uncommitted_document_lengths: HashMap<i32, i32>,
+    uncommitted_total_length: i32,
+
+    // Optional store of the text of each document, written in the same transactions,
+    // and the documents containing each trigram of the uncommitted documents.
+    documents_blockfile: Option<Box<dyn Blockfile>>,
+    uncommitted_document_texts: HashMap<i32, String>,
+    uncommitted_trigrams: HashMap<String, RoaringBitmap>,
 }
 
 impl BlockfileFullTextIndex {
@@ -53,19 +99,218 @@ impl BlockfileFullTextIndex {
             frequencies_blockfile,
             tokenizer,
             in_transaction: false,
+            poisoned: false,
             uncommitted: HashMap::new(),
             uncommitted_frequencies: HashMap::new(),
+            uncommitted_documents: RoaringBitmap::new(),
+            uncommitted_document_lengths: HashMap::new(),
+            uncommitted_total_length: 0,
+            documents_blockfile: None,
+            uncommitted_document_texts: HashMap::new(),
+            uncommitted_trigrams: HashMap::new(),
+        }
+    }
+
+    // Keeps the text of each document in documents_blockfile, along with a trigram
+    // index over it, so that search_regex can match patterns against the documents.
+    pub(crate) fn with_document_store(mut self, documents_blockfile: Box<dyn Blockfile>) -> Self {
+        self.documents_blockfile = Some(documents_blockfile);
+        self
+    }
+
+    // The doc ids of a posting list stored without positions.
+    fn get_doc_ids(&self, key: BlockfileKey) -> RoaringBitmap {
+        match self.posting_lists_blockfile.get(key) {
+            Ok(Value::PositionalPostingListValue(positional_posting_list)) => {
+                positional_posting_list
+                    .get_doc_ids()
+                    .values()
+                    .iter()
+                    .map(|doc_id| *doc_id as u32)
+                    .collect()
+            }
+            _ => RoaringBitmap::new(),
+        }
+    }
+
+    // Writes every change of the transaction to the blockfiles, then commits them in a
+    // fixed order: posting lists, frequencies and documents. All writes are made before
+    // the first commit, so a failed write leaves every blockfile uncommitted.
+    fn commit(&mut self) -> Result<(), Box<dyn ChromaError>> {
+        let mut posting_list_writes = Vec::new();
+        for (token, mut builder) in self.uncommitted.drain() {
+            posting_list_writes.push((
+                token_to_blockfile_key(&token),
+                Value::PositionalPostingListValue(builder.build()),
+            ));
+        }
+        let uncommitted_documents = std::mem::take(&mut self.uncommitted_documents);
+        if !uncommitted_documents.is_empty() {
+            let documents = self.all_documents() | uncommitted_documents;
+            posting_list_writes.push((documents_blockfile_key(), doc_ids_value(documents)?));
+        }
+        for (trigram, doc_ids) in std::mem::take(&mut self.uncommitted_trigrams) {
+            let doc_ids = self.get_doc_ids(trigram_blockfile_key(&trigram)) | doc_ids;
+            posting_list_writes.push((trigram_blockfile_key(&trigram), doc_ids_value(doc_ids)?));
+        }
+
+        let mut frequency_writes = Vec::new();
+        for (token, frequency) in self.uncommitted_frequencies.drain() {
+            frequency_writes.push((token_to_blockfile_key(&token), Value::Int32Value(frequency)));
+        }
+        for (offset_id, length) in self.uncommitted_document_lengths.drain() {
+            frequency_writes.push((
+                document_length_blockfile_key(offset_id),
+                Value::Int32Value(length),
+            ));
+        }
+        let uncommitted_total_length = std::mem::take(&mut self.uncommitted_total_length);
+        if uncommitted_total_length > 0 {
+            let total_length =
+                self.get_frequency(total_length_blockfile_key()) + uncommitted_total_length;
+            frequency_writes.push((
+                total_length_blockfile_key(),
+                Value::Int32Value(total_length),
+            ));
+        }
+
+        for (key, value) in posting_list_writes {
+            self.posting_lists_blockfile.set(key, value)?;
+        }
+        for (key, value) in frequency_writes {
+            self.frequencies_blockfile.set(key, value)?;
+        }
+        let uncommitted_document_texts = std::mem::take(&mut self.uncommitted_document_texts);
+        if let Some(documents_blockfile) = self.documents_blockfile.as_mut() {
+            for (offset_id, document) in uncommitted_document_texts {
+                documents_blockfile.set(
+                    document_blockfile_key(offset_id),
+                    Value::StringValue(document),
+                )?;
+            }
+        }
+
+        self.posting_lists_blockfile.commit_transaction()?;
+        self.frequencies_blockfile.commit_transaction()?;
+        if let Some(documents_blockfile) = self.documents_blockfile.as_mut() {
+            documents_blockfile.commit_transaction()?;
+        }
+        Ok(())
+    }
+
+    fn get_frequency(&self, key: BlockfileKey) -> i32 {
+        match self.frequencies_blockfile.get(key) {
+            Ok(Value::Int32Value(frequency)) => frequency,
+            _ => 0,
         }
     }
+
+    // Every committed document, kept as a posting list without positions under a
+    // reserved key.
+    fn all_documents(&self) -> RoaringBitmap {
+        self.get_doc_ids(documents_blockfile_key())
+    }
+
+    // Blockfiles have no merge, so the committed posting list and frequency of a token
+    // are copied into the transaction the first time the token is written to.
+    fn look_up_token_and_populate_uncommitted(
+        &mut self,
+        token: &str,
+    ) -> Result<(), Box<dyn ChromaError>> {
+        if self.uncommitted.contains_key(token) {
+            return Ok(());
+        }
+        let mut builder = PositionalPostingListBuilder::new();
+        if let Some(positional_posting_list) = self.get_posting_list(token) {
+            for doc_id in positional_posting_list.get_doc_ids().values() {
+                let positions = positional_posting_list
+                    .get_positions_for_doc_id(*doc_id)
+                    .unwrap();
+                if let Err(e) =
+                    builder.add_doc_id_and_positions(*doc_id, positions.values().to_vec())
+                {
+                    return Err(Box::new(e));
+                }
+            }
+        }
+        self.uncommitted.insert(token.to_string(), builder);
+        let frequency = self.get_frequency(token_to_blockfile_key(token));
+        self.uncommitted_frequencies
+            .insert(token.to_string(), frequency);
+        Ok(())
+    }
+
+    fn get_posting_list(&self, token: &str) -> Option<PositionalPostingList> {
+        match self
+            .posting_lists_blockfile
+            .get(token_to_blockfile_key(token))
+        {
+            Ok(Value::PositionalPostingListValue(positional_posting_list)) => {
+                Some(positional_posting_list)
+            }
+            // TODO: Distinguish a missing token from a blockfile error.
+            _ => None,
+        }
+    }
+}
+
+// A posting list of the doc ids without positions.
+fn doc_ids_value(doc_ids: RoaringBitmap) -> Result<Value, Box<dyn ChromaError>> {
+    let mut builder = PositionalPostingListBuilder::new();
+    for doc_id in doc_ids {
+        if let Err(e) = builder.add_doc_id_and_positions(doc_id as i32, vec![]) {
+            return Err(Box::new(e));
+        }
+    }
+    Ok(Value::PositionalPostingListValue(builder.build()))
+}
+
+fn token_to_blockfile_key(token: &str) -> BlockfileKey {
+    BlockfileKey::new("".to_string(), Key::String(token.to_string()))
+}
+
+// Tokens have an empty prefix, so a NUL prefix cannot collide with them.
+fn documents_blockfile_key() -> BlockfileKey {
+    BlockfileKey::new("\0".to_string(), Key::String("documents".to_string()))
+}
+
+// Keys of the frequencies blockfile holding the number of tokens of a document and of
+// all documents.
+fn document_length_blockfile_key(offset_id: i32) -> BlockfileKey {
+    BlockfileKey::new("\0length".to_string(), Key::String(offset_id.to_string()))
+}
+
+fn total_length_blockfile_key() -> BlockfileKey {
+    BlockfileKey::new("\0".to_string(), Key::String("total_length".to_string()))
+}
+
+fn trigram_blockfile_key(trigram: &str) -> BlockfileKey {
+    BlockfileKey::new("\0trigram".to_string(), Key::String(trigram.to_string()))
+}
+
+// Key of the text of a document in the document store.
+fn document_blockfile_key(offset_id: i32) -> BlockfileKey {
+    BlockfileKey::new("".to_string(), Key::String(offset_id.to_string()))
+}
+
+// Whether the sorted positions contain position.
+fn contains_position(positions: &[i32], position: i32) -> bool {
+    positions.binary_search(&position).is_ok()
 }
 
 impl FullTextIndex for BlockfileFullTextIndex {
     fn begin_transaction(&mut self) -> Result<(), Box<dyn ChromaError>> {
+        if self.poisoned {
+            return Err(Box::new(FullTextIndexError::Poisoned));
+        }
         if self.in_transaction {
             return Err(Box::new(FullTextIndexError::AlreadyInTransaction));
         }
         self.posting_lists_blockfile.begin_transaction()?;
         self.frequencies_blockfile.begin_transaction()?;
+        if let Some(documents_blockfile) = self.documents_blockfile.as_mut() {
+            documents_blockfile.begin_transaction()?;
+        }
         self.in_transaction = true;
         Ok(())
     }
@@ -74,40 +319,42 @@ impl FullTextIndex for BlockfileFullTextIndex {
         if !self.in_transaction {
             return Err(Box::new(FullTextIndexError::NotInTransaction));
         }
-        self.in_transaction = false;
-        for (key, mut value) in self.uncommitted.drain() {
-            let positional_posting_list = value.build();
-            let blockfilekey = BlockfileKey::new("".to_string(), Key::String(key.to_string()));
-            self.posting_lists_blockfile.set(
-                blockfilekey,
-                Value::PositionalPostingListValue(positional_posting_list),
-            );
-        }
-        for (key, value) in self.uncommitted_frequencies.drain() {
-            let blockfilekey = BlockfileKey::new("".to_string(), Key::String(key.to_string()));
-            self.frequencies_blockfile
-                .set(blockfilekey, Value::Int32Value(value));
+        let res = self.commit();
+        if res.is_err() {
+            self.poisoned = true;
         }
-        self.posting_lists_blockfile.commit_transaction()?;
-        self.frequencies_blockfile.commit_transaction()?;
-        self.uncommitted.clear();
-        Ok(())
+        self.in_transaction = false;
+        res
     }
 
     fn add_document(&mut self, document: &str, offset_id: i32) -> Result<(), Box<dyn ChromaError>> {
         if !self.in_transaction {
             return Err(Box::new(FullTextIndexError::NotInTransaction));
         }
+        self.uncommitted_documents.insert(offset_id as u32);
+        if self.documents_blockfile.is_some() {
+            for trigram in trigrams(document) {
+                self.uncommitted_trigrams
+                    .entry(trigram)
+                    .or_default()
+                    .insert(offset_id as u32);
+            }
+            self.uncommitted_document_texts
+                .insert(offset_id, document.to_string());
+        }
         let tokens = self.tokenizer.encode(document);
+        let length = tokens.get_tokens().len() as i32;
+        let previous_length = match self.uncommitted_document_lengths.get(&offset_id) {
+            Some(previous_length) => *previous_length,
+            None => self.get_frequency(document_length_blockfile_key(offset_id)),
+        };
+        self.uncommitted_document_lengths
+            .insert(offset_id, previous_length + length);
+        self.uncommitted_total_length += length;
         for token in tokens.get_tokens() {
-            self.uncommitted_frequencies
-                .entry(token.text.to_string())
-                .and_modify(|e| *e += 1)
-                .or_insert(1);
-            let mut builder = self
-                .uncommitted
-                .entry(token.text.to_string())
-                .or_insert(PositionalPostingListBuilder::new());
+            self.look_up_token_and_populate_uncommitted(&token.text)?;
+            *self.uncommitted_frequencies.get_mut(&token.text).unwrap() += 1;
+            let builder = self.uncommitted.get_mut(&token.text).unwrap();
 
             // Store starting positions of tokens. These are NOT affected by token filters.
             // For search, we can use the start and end positions to compute offsets to
@@ -127,90 +374,171 @@ impl FullTextIndex for BlockfileFullTextIndex {
     fn search(&mut self, query: &str) -> Result<Vec<i32>, Box<dyn ChromaError>> {
         let binding = self.tokenizer.encode(query);
         let tokens = binding.get_tokens();
+        if tokens.is_empty() {
+            return Ok(vec![]);
+        }
 
-        // Get query tokens sorted by frequency.
-        let mut token_frequencies = vec![];
+        // Look up the posting list of each distinct query token once.
+        let mut posting_lists: HashMap<&str, PositionalPostingList> = HashMap::new();
         for token in tokens {
-            let blockfilekey =
-                BlockfileKey::new("".to_string(), Key::String(token.text.to_string()));
-            let value = self.frequencies_blockfile.get(blockfilekey);
-            match value {
-                Ok(Value::Int32Value(frequency)) => {
-                    token_frequencies.push((token.text.to_string(), frequency));
-                }
-                Ok(_) => {
-                    return Ok(vec![]);
-                }
-                Err(_) => {
-                    // TODO error handling from blockfile
-                    return Ok(vec![]);
+            if posting_lists.contains_key(token.text.as_str()) {
+                continue;
+            }
+            match self.get_posting_list(&token.text) {
+                Some(positional_posting_list) => {
+                    posting_lists.insert(&token.text, positional_posting_list);
                 }
+                None => return Ok(vec![]),
             }
         }
-        token_frequencies.sort_by(|a, b| a.1.cmp(&b.1));
-
-        // Populate initial candidates with the least-frequent token's posting list.
-        // doc ID -> possible starting locations for the query.
-        let mut candidates: HashMap<i32, Vec<i32>> = HashMap::new();
-        let blockfilekey =
-            BlockfileKey::new("".to_string(), Key::String(tokens[0].text.to_string()));
-        let first_token_positional_posting_list =
-            match self.posting_lists_blockfile.get(blockfilekey).unwrap() {
-                Value::PositionalPostingListValue(arr) => arr,
-                _ => panic!("Value is not an arrow struct array"),
-            };
-        let first_token_offset = tokens[0].offset_from as i32;
-        for doc_id in first_token_positional_posting_list.get_doc_ids().values() {
-            let positions = first_token_positional_posting_list
-                .get_positions_for_doc_id(*doc_id)
-                .unwrap();
-            let positions_vec: Vec<i32> = positions
-                .values()
-                .iter()
-                .map(|x| *x - first_token_offset)
-                .collect();
-            candidates.insert(*doc_id, positions_vec);
-        }
-
-        // Iterate through the rest of the tokens, intersecting the posting lists with the candidates.
-        for (token, _) in token_frequencies[1..].iter() {
-            let blockfilekey = BlockfileKey::new("".to_string(), Key::String(token.to_string()));
-            let positional_posting_list =
-                match self.posting_lists_blockfile.get(blockfilekey).unwrap() {
-                    Value::PositionalPostingListValue(arr) => arr,
-                    _ => panic!("Value is not an arrow struct array"),
-                };
-            let token_offset = tokens
+
+        // Candidates are the documents containing every query token, intersected
+        // starting from the shortest posting list.
+        let mut doc_id_bitmaps = posting_lists
+            .values()
+            .map(|positional_posting_list| {
+                positional_posting_list
+                    .get_doc_ids()
+                    .values()
+                    .iter()
+                    .map(|doc_id| *doc_id as u32)
+                    .collect::<RoaringBitmap>()
+            })
+            .collect::<Vec<RoaringBitmap>>();
+        doc_id_bitmaps.sort_by_key(|bitmap| bitmap.len());
+        let mut doc_id_bitmaps = doc_id_bitmaps.into_iter();
+        let mut candidates = doc_id_bitmaps.next().unwrap();
+        for bitmap in doc_id_bitmaps {
+            candidates &= bitmap;
+        }
+
+        // A candidate matches if, for some occurrence of the first query token, every
+        // other query token occurs at the same distance from it as in the query.
+        let mut results = vec![];
+        for doc_id in candidates.iter() {
+            let doc_id = doc_id as i32;
+            let positions = tokens
                 .iter()
-                .find(|t| t.text == *token)
-                .unwrap()
-                .offset_from as i32;
-            let mut new_candidates: HashMap<i32, Vec<i32>> = HashMap::new();
-            for (doc_id, positions) in candidates.iter() {
-                let mut new_positions = vec![];
-                for position in positions {
-                    if let Some(positions_for_doc_id) =
-                        positional_posting_list.get_positions_for_doc_id(*doc_id)
-                    {
-                        for position_for_doc_id in positions_for_doc_id.values() {
-                            if position_for_doc_id - token_offset == *position {
-                                new_positions.push(*position);
-                            }
-                        }
-                    }
-                }
-                if !new_positions.is_empty() {
-                    new_candidates.insert(*doc_id, new_positions);
-                }
+                .map(|token| {
+                    let positions = posting_lists[token.text.as_str()]
+                        .get_positions_for_doc_id(doc_id)
+                        .unwrap();
+                    (token.offset_from as i32, positions)
+                })
+                .collect::<Vec<_>>();
+            let (first_token_offset, first_token_positions) = &positions[0];
+            let is_match = first_token_positions.values().iter().any(|position| {
+                let start = position - first_token_offset;
+                positions.iter().all(|(token_offset, token_positions)| {
+                    contains_position(token_positions.values(), start + token_offset)
+                })
+            });
+            if is_match {
+                results.push(doc_id);
             }
-            candidates = new_candidates;
         }
 
-        let mut results = vec![];
-        for (doc_id, _) in candidates.drain() {
-            results.push(doc_id);
+        Ok(results)
+    }
+
+    fn search_not_contains(&mut self, query: &str) -> Result<Vec<i32>, Box<dyn ChromaError>> {
+        let mut documents = self.all_documents();
+        for doc_id in self.search(query)? {
+            documents.remove(doc_id as u32);
+        }
+        Ok(documents.iter().map(|doc_id| doc_id as i32).collect())
+    }
+
+    fn search_bm25(
+        &mut self,
+        query: &str,
+        k: usize,
+    ) -> Result<Vec<(i32, f32)>, Box<dyn ChromaError>> {
+        let document_count = self.all_documents().len() as f32;
+        if document_count == 0.0 {
+            return Ok(vec![]);
         }
+        let average_length =
+            (self.get_frequency(total_length_blockfile_key()) as f32 / document_count).max(1.0);
 
+        let binding = self.tokenizer.encode(query);
+        let mut tokens = binding
+            .get_tokens()
+            .iter()
+            .map(|token| token.text.as_str())
+            .collect::<Vec<&str>>();
+        tokens.sort();
+        tokens.dedup();
+
+        let mut document_lengths: HashMap<i32, f32> = HashMap::new();
+        let mut scores: HashMap<i32, f32> = HashMap::new();
+        for token in tokens {
+            let positional_posting_list = match self.get_posting_list(token) {
+                Some(positional_posting_list) => positional_posting_list,
+                None => continue,
+            };
+            let doc_ids = positional_posting_list.get_doc_ids();
+            let document_frequency = doc_ids.len() as f32;
+            let idf = (1.0
+                + (document_count - document_frequency + 0.5) / (document_frequency + 0.5))
+                .ln();
+            for doc_id in doc_ids.values() {
+                let term_frequency = positional_posting_list
+                    .get_positions_for_doc_id(*doc_id)
+                    .unwrap()
+                    .len() as f32;
+                let document_length = *document_lengths.entry(*doc_id).or_insert_with(|| {
+                    self.get_frequency(document_length_blockfile_key(*doc_id)) as f32
+                });
+                let normalization =
+                    1.0 - BM25_B + BM25_B * document_length / average_length;
+                *scores.entry(*doc_id).or_insert(0.0) += idf * term_frequency * (BM25_K1 + 1.0)
+                    / (term_frequency + BM25_K1 * normalization);
+            }
+        }
+
+        let mut results = scores.into_iter().collect::<Vec<(i32, f32)>>();
+        results.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+        results.truncate(k);
+        Ok(results)
+    }
+
+    fn search_regex(&mut self, pattern: &str) -> Result<Vec<i32>, Box<dyn ChromaError>> {
+        let documents_blockfile = match &self.documents_blockfile {
+            Some(documents_blockfile) => documents_blockfile,
+            None => return Err(Box::new(FullTextIndexError::NoDocumentStore)),
+        };
+        let regex = match Regex::new(pattern) {
+            Ok(regex) => regex,
+            Err(e) => return Err(Box::new(FullTextIndexError::InvalidRegex(e.to_string()))),
+        };
+        let hir = match regex_syntax::parse(pattern) {
+            Ok(hir) => hir,
+            Err(e) => return Err(Box::new(FullTextIndexError::InvalidRegex(e.to_string()))),
+        };
+
+        // Only documents containing every trigram the pattern requires can match.
+        let mut candidates = self.all_documents();
+        for trigram in required_trigrams(&hir) {
+            candidates &= self.get_doc_ids(trigram_blockfile_key(&trigram));
+            if candidates.is_empty() {
+                break;
+            }
+        }
+
+        let mut results = vec![];
+        for doc_id in candidates {
+            let doc_id = doc_id as i32;
+            match documents_blockfile.get(document_blockfile_key(doc_id)) {
+                Ok(Value::StringValue(document)) => {
+                    if regex.is_match(&document) {
+                        results.push(doc_id);
+                    }
+                }
+                // TODO: Log error
+                _ => continue,
+            }
+        }
         Ok(results)
     }
 }
@@ -218,9 +546,13 @@ impl FullTextIndex for BlockfileFullTextIndex {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::blockstore::arrow_blockfile::provider::ArrowBlockfileProvider;
     use crate::blockstore::provider::{BlockfileProvider, HashMapBlockfileProvider};
     use crate::blockstore::{HashMapBlockfile, KeyType, ValueType};
-    use crate::index::fulltext::tokenizer::TantivyChromaTokenizer;
+    use crate::index::fulltext::tokenizer::{
+        AnalyzerConfig, TantivyChromaTokenizer, TokenizerConfig,
+    };
+    use tantivy::tokenizer::Language;
     use tantivy::tokenizer::NgramTokenizer;
 
     #[test]
@@ -232,9 +564,9 @@ mod tests {
         let freq_blockfile = provider
             .create("freq", KeyType::String, ValueType::Int32)
             .unwrap();
-        let tokenizer = Box::new(TantivyChromaTokenizer::new(Box::new(
+        let tokenizer = Box::new(TantivyChromaTokenizer::new(
             NgramTokenizer::new(1, 1, false).unwrap(),
-        )));
+        ));
         let _index = BlockfileFullTextIndex::new(pl_blockfile, freq_blockfile, tokenizer);
     }
 
@@ -247,9 +579,9 @@ mod tests {
         let freq_blockfile = provider
             .create("freq", KeyType::String, ValueType::Int32)
             .unwrap();
-        let tokenizer = Box::new(TantivyChromaTokenizer::new(Box::new(
+        let tokenizer = Box::new(TantivyChromaTokenizer::new(
             NgramTokenizer::new(1, 1, false).unwrap(),
-        )));
+        ));
         let mut index = BlockfileFullTextIndex::new(pl_blockfile, freq_blockfile, tokenizer);
         index.begin_transaction().unwrap();
         index.add_document("hello world", 1).unwrap();
@@ -268,9 +600,9 @@ mod tests {
         let freq_blockfile = provider
             .create("freq", KeyType::String, ValueType::Int32)
             .unwrap();
-        let tokenizer = Box::new(TantivyChromaTokenizer::new(Box::new(
+        let tokenizer = Box::new(TantivyChromaTokenizer::new(
             NgramTokenizer::new(1, 1, false).unwrap(),
-        )));
+        ));
         let mut index = BlockfileFullTextIndex::new(pl_blockfile, freq_blockfile, tokenizer);
         index.begin_transaction().unwrap();
         index.add_document("hello world", 1).unwrap();
@@ -289,9 +621,9 @@ mod tests {
         let freq_blockfile = provider
             .create("freq", KeyType::String, ValueType::Int32)
             .unwrap();
-        let tokenizer = Box::new(TantivyChromaTokenizer::new(Box::new(
+        let tokenizer = Box::new(TantivyChromaTokenizer::new(
             NgramTokenizer::new(1, 1, false).unwrap(),
-        )));
+        ));
         let mut index = BlockfileFullTextIndex::new(pl_blockfile, freq_blockfile, tokenizer);
         index.begin_transaction().unwrap();
         index.add_document("hello world", 1).unwrap();
@@ -320,9 +652,9 @@ mod tests {
         let freq_blockfile = provider
             .create("freq", KeyType::String, ValueType::Int32)
             .unwrap();
-        let tokenizer = Box::new(TantivyChromaTokenizer::new(Box::new(
+        let tokenizer = Box::new(TantivyChromaTokenizer::new(
             NgramTokenizer::new(1, 1, false).unwrap(),
-        )));
+        ));
         let mut index = BlockfileFullTextIndex::new(pl_blockfile, freq_blockfile, tokenizer);
         index.begin_transaction().unwrap();
         index.add_document("!!!!", 1).unwrap();
@@ -339,4 +671,232 @@ mod tests {
         assert!(res.contains(&3));
         assert!(res.contains(&4));
     }
+
+    #[test]
+    fn test_search_across_transactions() {
+        let mut provider = HashMapBlockfileProvider::new();
+        let pl_blockfile = provider
+            .create("pl", KeyType::String, ValueType::PositionalPostingList)
+            .unwrap();
+        let freq_blockfile = provider
+            .create("freq", KeyType::String, ValueType::Int32)
+            .unwrap();
+        let tokenizer = Box::new(TantivyChromaTokenizer::new(
+            NgramTokenizer::new(1, 1, false).unwrap(),
+        ));
+        let mut index = BlockfileFullTextIndex::new(pl_blockfile, freq_blockfile, tokenizer);
+        index.begin_transaction().unwrap();
+        index.add_document("hello world", 1).unwrap();
+        index.commit_transaction().unwrap();
+        index.begin_transaction().unwrap();
+        index.add_document("hello chroma", 2).unwrap();
+        index.commit_transaction().unwrap();
+
+        let res = index.search("hello").unwrap();
+        assert_eq!(res, vec![1, 2]);
+        let res = index.search("world").unwrap();
+        assert_eq!(res, vec![1]);
+    }
+
+    #[test]
+    fn test_search_repeated_tokens() {
+        let mut provider = HashMapBlockfileProvider::new();
+        let pl_blockfile = provider
+            .create("pl", KeyType::String, ValueType::PositionalPostingList)
+            .unwrap();
+        let freq_blockfile = provider
+            .create("freq", KeyType::String, ValueType::Int32)
+            .unwrap();
+        let tokenizer = Box::new(TantivyChromaTokenizer::new(
+            NgramTokenizer::new(1, 1, false).unwrap(),
+        ));
+        let mut index = BlockfileFullTextIndex::new(pl_blockfile, freq_blockfile, tokenizer);
+        index.begin_transaction().unwrap();
+        index.add_document("abc", 1).unwrap();
+        index.add_document("cab", 2).unwrap();
+        index.add_document("abab", 3).unwrap();
+        index.commit_transaction().unwrap();
+
+        let res = index.search("aba").unwrap();
+        assert_eq!(res, vec![3]);
+        let res = index.search("ab").unwrap();
+        assert_eq!(res, vec![1, 2, 3]);
+        let res = index.search("").unwrap();
+        assert!(res.is_empty());
+    }
+
+    #[test]
+    fn test_search_not_contains() {
+        let mut provider = HashMapBlockfileProvider::new();
+        let pl_blockfile = provider
+            .create("pl", KeyType::String, ValueType::PositionalPostingList)
+            .unwrap();
+        let freq_blockfile = provider
+            .create("freq", KeyType::String, ValueType::Int32)
+            .unwrap();
+        let tokenizer = Box::new(TantivyChromaTokenizer::new(
+            NgramTokenizer::new(1, 1, false).unwrap(),
+        ));
+        let mut index = BlockfileFullTextIndex::new(pl_blockfile, freq_blockfile, tokenizer);
+        index.begin_transaction().unwrap();
+        index.add_document("hello world", 1).unwrap();
+        index.add_document("hello chroma", 2).unwrap();
+        index.commit_transaction().unwrap();
+        index.begin_transaction().unwrap();
+        index.add_document("chroma world", 3).unwrap();
+        index.add_document("", 4).unwrap();
+        index.commit_transaction().unwrap();
+
+        let res = index.search_not_contains("hello").unwrap();
+        assert_eq!(res, vec![3, 4]);
+        let res = index.search_not_contains("chroma world").unwrap();
+        assert_eq!(res, vec![1, 2, 4]);
+        let res = index.search_not_contains("absent").unwrap();
+        assert_eq!(res, vec![1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn test_search_bm25() {
+        let mut provider = HashMapBlockfileProvider::new();
+        let pl_blockfile = provider
+            .create("pl", KeyType::String, ValueType::PositionalPostingList)
+            .unwrap();
+        let freq_blockfile = provider
+            .create("freq", KeyType::String, ValueType::Int32)
+            .unwrap();
+        let tokenizer = TokenizerConfig::Unicode.build().unwrap();
+        let mut index = BlockfileFullTextIndex::new(pl_blockfile, freq_blockfile, tokenizer);
+        index.begin_transaction().unwrap();
+        index.add_document("the quick brown fox", 1).unwrap();
+        index.add_document("the lazy dog", 2).unwrap();
+        index.commit_transaction().unwrap();
+        index.begin_transaction().unwrap();
+        index.add_document("the fox chased the other fox", 3).unwrap();
+        index.add_document("a dog and a cat", 4).unwrap();
+        index.commit_transaction().unwrap();
+
+        // Document 3 mentions fox twice, so it ranks above document 1.
+        let res = index.search_bm25("fox", 10).unwrap();
+        assert_eq!(
+            res.iter().map(|(doc_id, _)| *doc_id).collect::<Vec<i32>>(),
+            vec![3, 1]
+        );
+        assert!(res[0].1 > res[1].1);
+
+        // Rare tokens weigh more than common ones.
+        let res = index.search_bm25("the cat", 1).unwrap();
+        assert_eq!(res.len(), 1);
+        assert_eq!(res[0].0, 4);
+
+        let res = index.search_bm25("absent", 10).unwrap();
+        assert!(res.is_empty());
+    }
+
+    #[test]
+    fn test_search_regex() {
+        let mut provider = HashMapBlockfileProvider::new();
+        let pl_blockfile = provider
+            .create("pl", KeyType::String, ValueType::PositionalPostingList)
+            .unwrap();
+        let freq_blockfile = provider
+            .create("freq", KeyType::String, ValueType::Int32)
+            .unwrap();
+        let documents_blockfile = provider
+            .create("documents", KeyType::String, ValueType::String)
+            .unwrap();
+        let tokenizer = TokenizerConfig::Unicode.build().unwrap();
+        let mut index = BlockfileFullTextIndex::new(pl_blockfile, freq_blockfile, tokenizer)
+            .with_document_store(documents_blockfile);
+        index.begin_transaction().unwrap();
+        index.add_document("error 404: not found", 1).unwrap();
+        index.add_document("error 500: internal", 2).unwrap();
+        index.commit_transaction().unwrap();
+        index.begin_transaction().unwrap();
+        index.add_document("warning 404", 3).unwrap();
+        index.add_document("ERROR 403", 4).unwrap();
+        index.commit_transaction().unwrap();
+
+        let res = index.search_regex("error [0-9]+").unwrap();
+        assert_eq!(res, vec![1, 2]);
+        let res = index.search_regex("404").unwrap();
+        assert_eq!(res, vec![1, 3]);
+        let res = index.search_regex("(?i)^error 40[34]").unwrap();
+        assert_eq!(res, vec![1, 4]);
+        let res = index.search_regex("missing").unwrap();
+        assert!(res.is_empty());
+
+        let res = index.search_regex("error (");
+        assert_eq!(res.unwrap_err().code(), ErrorCodes::InvalidArgument);
+    }
+
+    #[test]
+    fn test_search_regex_without_document_store() {
+        let mut provider = HashMapBlockfileProvider::new();
+        let pl_blockfile = provider
+            .create("pl", KeyType::String, ValueType::PositionalPostingList)
+            .unwrap();
+        let freq_blockfile = provider
+            .create("freq", KeyType::String, ValueType::Int32)
+            .unwrap();
+        let tokenizer = TokenizerConfig::Unicode.build().unwrap();
+        let mut index = BlockfileFullTextIndex::new(pl_blockfile, freq_blockfile, tokenizer);
+        let res = index.search_regex("error");
+        assert_eq!(res.unwrap_err().code(), ErrorCodes::FailedPrecondition);
+    }
+
+    #[test]
+    fn test_search_with_stopwords_and_stemming() {
+        let mut provider = HashMapBlockfileProvider::new();
+        let pl_blockfile = provider
+            .create("pl", KeyType::String, ValueType::PositionalPostingList)
+            .unwrap();
+        let freq_blockfile = provider
+            .create("freq", KeyType::String, ValueType::Int32)
+            .unwrap();
+        let config = AnalyzerConfig {
+            tokenizer: TokenizerConfig::Unicode,
+            stopwords: Some(Language::English),
+            stemmer: Some(Language::English),
+        };
+        let mut index =
+            BlockfileFullTextIndex::new(pl_blockfile, freq_blockfile, config.build().unwrap());
+        index.begin_transaction().unwrap();
+        index.add_document("The fox was running", 1).unwrap();
+        index.add_document("Dogs run", 2).unwrap();
+        index.add_document("the end", 3).unwrap();
+        index.commit_transaction().unwrap();
+
+        let res = index.search("runs").unwrap();
+        assert_eq!(res, vec![1, 2]);
+        let res = index.search("dog").unwrap();
+        assert_eq!(res, vec![2]);
+        // Queries are analyzed like documents, so stopwords alone match nothing.
+        let res = index.search("the").unwrap();
+        assert!(res.is_empty());
+    }
+
+    #[test]
+    fn test_failed_commit_poisons_index() {
+        let mut provider = ArrowBlockfileProvider::new();
+        let pl_blockfile = provider
+            .create("pl", KeyType::String, ValueType::PositionalPostingList)
+            .unwrap();
+        // Frequencies can't be written to a blockfile of strings
+        let freq_blockfile = provider
+            .create("freq", KeyType::String, ValueType::String)
+            .unwrap();
+        let tokenizer = TokenizerConfig::Unicode.build().unwrap();
+        let mut index = BlockfileFullTextIndex::new(pl_blockfile, freq_blockfile, tokenizer);
+        index.begin_transaction().unwrap();
+        index.add_document("hello world", 1).unwrap();
+        assert!(index.commit_transaction().is_err());
+
+        // The posting lists were written but not committed
+        let res = index.search("hello").unwrap();
+        assert!(res.is_empty());
+        let res = index.begin_transaction();
+        assert_eq!(res.unwrap_err().code(), ErrorCodes::Internal);
+        let res = index.commit_transaction();
+        assert_eq!(res.unwrap_err().code(), ErrorCodes::FailedPrecondition);
+    }
 }
@@ -5,6 +5,7 @@ use crate::errors::{ChromaError, ErrorCodes};
 
 use super::{Index, IndexConfig, PersistentIndex};
 use crate::types::{Metadata, MetadataValue, MetadataValueConversionError, Segment};
+use rayon::prelude::*;
 use thiserror::Error;
 
 // https://doc.rust-lang.org/nomicon/ffi.html#representing-opaque-structs
@@ -254,7 +255,58 @@ impl PersistentIndex<HnswIndexConfig> for HnswIndex {
     }
 }
 
+#[derive(Error, Debug)]
+pub(crate) enum HnswIndexAddError {
+    #[error("Got {0} ids for {1} vectors")]
+    MismatchedLength(usize, usize),
+    #[error("Vector has dimensionality {0}, expected {1}")]
+    InvalidDimensionality(usize, usize),
+}
+
+impl ChromaError for HnswIndexAddError {
+    fn code(&self) -> ErrorCodes {
+        crate::errors::ErrorCodes::InvalidArgument
+    }
+}
+
 impl HnswIndex {
+    /// Adds a batch of vectors to the index using the threads of the given pool.
+    /// # Description
+    /// Insertion into the underlying index is thread safe, so the batch is split across the
+    /// pool and graph construction happens concurrently. The size of the pool controls how
+    /// many threads are used for the build.
+    /// # Notes
+    /// All vectors are validated before any of them is added, so an invalid batch leaves
+    /// the index untouched.
+    pub(crate) fn add_batch(
+        &self,
+        ids: &[usize],
+        vectors: &[&[f32]],
+        pool: &rayon::ThreadPool,
+    ) -> Result<(), Box<dyn ChromaError>> {
+        if ids.len() != vectors.len() {
+            return Err(Box::new(HnswIndexAddError::MismatchedLength(
+                ids.len(),
+                vectors.len(),
+            )));
+        }
+        let dimensionality = self.dimensionality as usize;
+        for vector in vectors {
+            if vector.len() != dimensionality {
+                return Err(Box::new(HnswIndexAddError::InvalidDimensionality(
+                    vector.len(),
+                    dimensionality,
+                )));
+            }
+        }
+        pool.install(|| {
+            ids.par_iter()
+                .zip(vectors.par_iter())
+                .for_each(|(id, vector)| self.add(*id, vector));
+        });
+        Ok(())
+    }
+
     pub fn set_ef(&self, ef: usize) {
         unsafe { set_ef(self.ffi_ptr, ef as c_int) }
     }
@@ -310,7 +362,6 @@ pub mod test {
     use crate::index::types::DistanceFunction;
     use crate::index::utils;
     use rand::Rng;
-    use rayon::prelude::*;
     use rayon::ThreadPoolBuilder;
     use tempfile::tempdir;
 
@@ -413,6 +464,46 @@ pub mod test {
         }
     }
 
+    #[test]
+    fn it_can_add_batch() {
+        let n = 100;
+        let d: usize = 16;
+        let tmp_dir = tempdir().unwrap();
+        let persist_path = tmp_dir.path().to_str().unwrap().to_string();
+        let index = HnswIndex::init(
+            &IndexConfig {
+                dimensionality: d as i32,
+                distance_function: DistanceFunction::Euclidean,
+            },
+            Some(&HnswIndexConfig {
+                max_elements: n,
+                m: 16,
+                ef_construction: 100,
+                ef_search: 100,
+                random_seed: 0,
+                persist_path: persist_path,
+            }),
+        )
+        .unwrap();
+        let pool = ThreadPoolBuilder::new().num_threads(4).build().unwrap();
+
+        let data: Vec<f32> = utils::generate_random_data(n, d);
+        let ids: Vec<usize> = (0..n).collect();
+        let vectors: Vec<&[f32]> = data.chunks(d).collect();
+
+        let res = index.add_batch(&ids[..1], &vectors, &pool);
+        assert!(res.is_err());
+        let res = index.add_batch(&ids[..1], &[&data[..d - 1]], &pool);
+        assert!(res.is_err());
+
+        index.add_batch(&ids, &vectors, &pool).unwrap();
+        for (id, vector) in ids.iter().zip(vectors.iter()) {
+            assert_eq!(index.get(*id).unwrap(), vector.to_vec());
+            let (result_ids, _) = index.query(vector, 1);
+            assert_eq!(result_ids[0], *id);
+        }
+    }
+
     #[test]
     fn it_can_add_and_basic_query() {
         let n = 1;
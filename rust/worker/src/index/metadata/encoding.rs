@@ -0,0 +1,336 @@
+use super::types::{canonicalize_float, MetadataIndexError, MetadataIndexValue};
+use crate::blockstore::{Blockfile, BlockfileKey, Key, Value};
+use crate::errors::ChromaError;
+use arrow::array::Int32Array;
+use roaring::RoaringBitmap;
+use std::collections::HashMap;
+
+pub(super) fn kv_to_blockfile_key(
+    key: &str,
+    value: MetadataIndexValue,
+) -> Result<BlockfileKey, Box<dyn ChromaError>> {
+    let blockfilekey_key = match value {
+        MetadataIndexValue::String(s) => Key::String(s),
+        MetadataIndexValue::Float(f) => Key::Float(canonicalize_float(f)),
+        MetadataIndexValue::Bool(b) => Key::Bool(b),
+        MetadataIndexValue::Int(i) => Key::Int(i),
+        MetadataIndexValue::Timestamp(t) => Key::Int(t),
+        MetadataIndexValue::List(_) => {
+            return Err(Box::new(MetadataIndexError::UnexpectedList(
+                key.to_string(),
+            )))
+        }
+        MetadataIndexValue::Null => return Ok(null_blockfile_key(key)),
+    };
+    Ok(BlockfileKey::new(key.to_string(), blockfilekey_key))
+}
+
+// Null postings live outside of the prefix of key, so range scans over key never
+// reach them.
+pub(super) fn range_blockfile_key(
+    key: &str,
+    value: MetadataIndexValue,
+) -> Result<BlockfileKey, Box<dyn ChromaError>> {
+    match value {
+        MetadataIndexValue::Null => Err(Box::new(MetadataIndexError::NullNotComparable(
+            key.to_string(),
+        ))),
+        value => kv_to_blockfile_key(key, value),
+    }
+}
+
+// An int and a float of the same value are distinct keys, ordered float first. Range
+// queries compare values, so the bound of a numeric range is moved to the first key of
+// its value for gte and lt, and to the last for gt and lte.
+pub(super) fn range_bound_key(key: Key, last: bool) -> Key {
+    // 2^63 is the smallest float past every i64
+    const LIMIT: f64 = 9_223_372_036_854_775_808.0;
+    match key {
+        Key::Int(i) if !last => {
+            let f = i as f64;
+            if f < LIMIT && f as i64 == i {
+                Key::Float(f)
+            } else {
+                Key::Int(i)
+            }
+        }
+        Key::Float(f) if last && f.fract() == 0.0 && (-LIMIT..LIMIT).contains(&f) => {
+            Key::Int(f as i64)
+        }
+        key => key,
+    }
+}
+
+// The null posting and the exists posting of each key, holding every offset with a
+// value for the key, are stored under NUL prefixes keyed by the name of the key.
+pub(super) const EXISTS_PREFIX: &str = "\0exists";
+
+pub(super) fn null_blockfile_key(key: &str) -> BlockfileKey {
+    BlockfileKey::new("\0null".to_string(), Key::String(key.to_string()))
+}
+
+pub(super) fn exists_blockfile_key(key: &str) -> BlockfileKey {
+    BlockfileKey::new(EXISTS_PREFIX.to_string(), Key::String(key.to_string()))
+}
+
+// Postings with at most this many offsets are stored inline as an array of offsets,
+// larger ones as a RoaringBitmap. Most postings are small and the array avoids the
+// bitmap's container overhead. Blockfiles that only hold values of the type they were
+// created with, such as arrow blockfiles, store every posting as a RoaringBitmap.
+pub(super) const INLINE_POSTING_MAX_LEN: u64 = 8;
+
+pub(super) fn posting_to_value(posting: RoaringBitmap, inline: bool) -> Value {
+    if inline && posting.len() <= INLINE_POSTING_MAX_LEN {
+        // Offsets are u32 and stored bit for bit in the i32 array.
+        let offsets: Vec<i32> = posting.iter().map(|offset_id| offset_id as i32).collect();
+        Value::Int32ArrayValue(Int32Array::from(offsets))
+    } else {
+        Value::RoaringBitmapValue(posting)
+    }
+}
+
+pub(super) fn value_to_posting(value: Value) -> Option<RoaringBitmap> {
+    match value {
+        Value::RoaringBitmapValue(posting) => Some(posting),
+        Value::Int32ArrayValue(offsets) => Some(
+            offsets
+                .values()
+                .iter()
+                .map(|offset_id| *offset_id as u32)
+                .collect(),
+        ),
+        _ => None,
+    }
+}
+
+// Postings are RoaringBitmaps, which only hold u32 offset ids.
+pub(super) fn offset_id_to_u32(offset_id: usize) -> Result<u32, Box<dyn ChromaError>> {
+    match u32::try_from(offset_id) {
+        Ok(offset_id) => Ok(offset_id),
+        Err(_) => Err(Box::new(MetadataIndexError::OffsetIdExhausted(offset_id))),
+    }
+}
+
+pub(super) fn blockfile_key_to_value(key: Key) -> MetadataIndexValue {
+    match key {
+        Key::String(s) => MetadataIndexValue::String(s),
+        Key::Float(f) => MetadataIndexValue::Float(f),
+        Key::Bool(b) => MetadataIndexValue::Bool(b),
+        Key::Int(i) => MetadataIndexValue::Int(i),
+    }
+}
+
+// Composite postings live under a prefix joining both keys with a NUL byte, keyed by
+// the JSON encoding of the value pair.
+pub(super) fn composite_blockfile_key(
+    key_a: &str,
+    value_a: &MetadataIndexValue,
+    key_b: &str,
+    value_b: &MetadataIndexValue,
+) -> BlockfileKey {
+    let values = serde_json::to_string(&(value_a, value_b)).unwrap();
+    BlockfileKey::new(format!("{}\0{}", key_a, key_b), Key::String(values))
+}
+
+pub(super) fn composite_blockfile_keys(
+    composite_indexes: &[(String, String)],
+    metadata: &HashMap<String, MetadataIndexValue>,
+) -> Vec<BlockfileKey> {
+    let mut blockfilekeys = Vec::new();
+    for (key_a, key_b) in composite_indexes {
+        match (metadata.get(key_a), metadata.get(key_b)) {
+            (Some(MetadataIndexValue::List(_)), _) | (_, Some(MetadataIndexValue::List(_))) => {}
+            (Some(value_a), Some(value_b)) => {
+                blockfilekeys.push(composite_blockfile_key(key_a, value_a, key_b, value_b));
+            }
+            _ => {}
+        }
+    }
+    blockfilekeys
+}
+
+// The offsets set in the index are stored alongside the postings under a NUL prefix,
+// which composite prefixes only produce for two empty keys, with a non-JSON key.
+pub(super) fn offsets_blockfile_key() -> BlockfileKey {
+    BlockfileKey::new("\0".to_string(), Key::String("offsets".to_string()))
+}
+
+// Forward index keys are integers so that offsets are ordered numerically.
+pub(super) fn offset_to_blockfile_key(offset_id: u32) -> BlockfileKey {
+    BlockfileKey::new("".to_string(), Key::Int(offset_id as i64))
+}
+
+pub(super) fn blockfile_key_to_offset(blockfilekey: &BlockfileKey) -> Option<u32> {
+    match blockfilekey.key {
+        Key::Int(offset_id) => u32::try_from(offset_id).ok(),
+        _ => None,
+    }
+}
+
+// Reads the committed metadata of offset_id from a forward index. The blockfile has no
+// delete, so cleared metadata is stored as an empty map.
+pub(super) fn read_metadata(
+    forward_blockfile: &dyn Blockfile,
+    offset_id: u32,
+) -> Result<HashMap<String, MetadataIndexValue>, Box<dyn ChromaError>> {
+    match forward_blockfile.get(offset_to_blockfile_key(offset_id)) {
+        Ok(Value::StringValue(metadata)) => match serde_json::from_str(&metadata) {
+            Ok(metadata) => Ok(metadata),
+            Err(e) => Err(Box::new(MetadataIndexError::MetadataEncodingError(e))),
+        },
+        _ => Ok(HashMap::new()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blockstore::arrow_blockfile::provider::ArrowBlockfileProvider;
+    use crate::blockstore::provider::{BlockfileProvider, HashMapBlockfileProvider};
+    use crate::blockstore::{KeyType, ValueType};
+    use crate::index::metadata::types::{MetadataIndexReader, MetadataIndexWriter};
+    use crate::index::metadata::writer::BlockfileMetadataIndexWriter;
+
+    #[test]
+    fn test_range_bound_key() {
+        assert_eq!(range_bound_key(Key::Int(2), false), Key::Float(2.0));
+        assert_eq!(range_bound_key(Key::Int(2), true), Key::Int(2));
+        assert_eq!(
+            range_bound_key(Key::Int(i64::MAX), false),
+            Key::Int(i64::MAX)
+        );
+        assert_eq!(range_bound_key(Key::Float(2.0), true), Key::Int(2));
+        assert_eq!(range_bound_key(Key::Float(2.5), true), Key::Float(2.5));
+        assert_eq!(range_bound_key(Key::Float(2.0), false), Key::Float(2.0));
+        assert_eq!(
+            range_bound_key(Key::Float(f64::INFINITY), true),
+            Key::Float(f64::INFINITY)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_metadata_index_inline_small_postings() {
+        let mut provider = HashMapBlockfileProvider::new();
+        let blockfile = provider
+            .create("test", KeyType::String, ValueType::RoaringBitmap)
+            .unwrap();
+        let mut index = BlockfileMetadataIndexWriter::new(blockfile);
+        let reader = index.reader();
+        index.begin_transaction().await.unwrap();
+        for offset_id in 0..8 {
+            index
+                .set("small", MetadataIndexValue::Int(1), offset_id)
+                .await
+                .unwrap();
+        }
+        for offset_id in 0..9 {
+            index
+                .set("large", MetadataIndexValue::Int(1), offset_id)
+                .await
+                .unwrap();
+        }
+        index
+            .set("small", MetadataIndexValue::Int(2), u32::MAX as usize)
+            .await
+            .unwrap();
+        index.commit_transaction().await.unwrap();
+
+        let stored = reader
+            .blockfile
+            .get(kv_to_blockfile_key("small", MetadataIndexValue::Int(1)).unwrap())
+            .unwrap();
+        assert!(matches!(stored, Value::Int32ArrayValue(_)));
+        let stored = reader
+            .blockfile
+            .get(kv_to_blockfile_key("large", MetadataIndexValue::Int(1)).unwrap())
+            .unwrap();
+        assert!(matches!(stored, Value::RoaringBitmapValue(_)));
+
+        let bitmap = reader
+            .get("small", MetadataIndexValue::Int(1))
+            .await
+            .unwrap();
+        assert_eq!(
+            bitmap.iter().collect::<Vec<u32>>(),
+            (0..8).collect::<Vec<u32>>()
+        );
+        let bitmap = reader
+            .get("small", MetadataIndexValue::Int(2))
+            .await
+            .unwrap();
+        assert_eq!(bitmap.iter().collect::<Vec<u32>>(), vec![u32::MAX]);
+        let count = reader
+            .count("small", MetadataIndexValue::Int(1))
+            .await
+            .unwrap();
+        assert_eq!(count, 8);
+
+        // Growing an inline posting past the threshold switches it to a bitmap.
+        index.begin_transaction().await.unwrap();
+        index
+            .set("small", MetadataIndexValue::Int(1), 8)
+            .await
+            .unwrap();
+        index.commit_transaction().await.unwrap();
+        let bitmap = reader
+            .get("small", MetadataIndexValue::Int(1))
+            .await
+            .unwrap();
+        assert_eq!(bitmap.len(), 9);
+        let stored = reader
+            .blockfile
+            .get(kv_to_blockfile_key("small", MetadataIndexValue::Int(1)).unwrap())
+            .unwrap();
+        assert!(matches!(stored, Value::RoaringBitmapValue(_)));
+    }
+
+    #[tokio::test]
+    async fn test_metadata_index_postings_in_typed_blockfile() {
+        let mut provider = ArrowBlockfileProvider::new();
+        let blockfile = provider
+            .create("test", KeyType::String, ValueType::RoaringBitmap)
+            .unwrap();
+        let mut index = BlockfileMetadataIndexWriter::new(blockfile);
+        let reader = index.reader();
+        index.begin_transaction().await.unwrap();
+        for offset_id in 0..3 {
+            index
+                .set(
+                    "small",
+                    MetadataIndexValue::String("a".to_string()),
+                    offset_id,
+                )
+                .await
+                .unwrap();
+        }
+        for offset_id in 0..20 {
+            index
+                .set(
+                    "large",
+                    MetadataIndexValue::String("b".to_string()),
+                    offset_id,
+                )
+                .await
+                .unwrap();
+        }
+        index.commit_transaction().await.unwrap();
+
+        // The blockfile only holds bitmaps, so small postings are not stored inline.
+        let stored = reader
+            .blockfile
+            .get(kv_to_blockfile_key("small", MetadataIndexValue::String("a".to_string())).unwrap())
+            .unwrap();
+        assert!(matches!(stored, Value::RoaringBitmapValue(_)));
+        let bitmap = reader
+            .get("small", MetadataIndexValue::String("a".to_string()))
+            .await
+            .unwrap();
+        assert_eq!(bitmap.iter().collect::<Vec<u32>>(), vec![0, 1, 2]);
+        let bitmap = reader
+            .get("large", MetadataIndexValue::String("b".to_string()))
+            .await
+            .unwrap();
+        assert_eq!(bitmap.len(), 20);
+        assert_eq!(reader.exists("small").await.unwrap().len(), 3);
+    }
+}
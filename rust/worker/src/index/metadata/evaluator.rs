@@ -0,0 +1,581 @@
+use super::types::{MetadataIndexReader, MetadataIndexValue};
+use crate::errors::ChromaError;
+use crate::types::{
+    BooleanOperator, DirectComparison, Where, WhereClauseComparator, WhereClauseListOperator,
+    WhereComparison, WhereConversionError,
+};
+use futures::future::BoxFuture;
+use futures::FutureExt;
+use roaring::RoaringBitmap;
+use std::ops::{BitAndAssign, BitOrAssign, SubAssign};
+
+/// Evaluates a where clause into the set of offset ids that match it.
+/// # Description
+/// Each comparison is answered by the metadata indices and the results are combined
+/// with bitmap algebra following the boolean operators of the clause. $ne and $nin are
+/// answered by the indices' complement queries, and $not by the complement of its
+/// child clause.
+/// # Fields
+/// - indices: The metadata indices to evaluate against. Each index is expected to hold
+///   a disjoint set of offset ids, so the clause is evaluated against every index on
+///   its own and the results are unioned.
+/// # Notes
+/// Doubles are canonicalized like on write. Ints and doubles are compared by value, so
+/// a comparison against 2 matches records set to 2 or 2.0.
+pub(crate) struct WhereEvaluator<'a> {
+    indices: Vec<&'a dyn MetadataIndexReader>,
+}
+
+impl<'a> WhereEvaluator<'a> {
+    pub(crate) fn new(indices: Vec<&'a dyn MetadataIndexReader>) -> Self {
+        WhereEvaluator { indices }
+    }
+
+    pub(crate) async fn evaluate(
+        &self,
+        clause: &Where,
+    ) -> Result<RoaringBitmap, Box<dyn ChromaError>> {
+        let mut result = RoaringBitmap::new();
+        for index in self.indices.iter() {
+            result.bitor_assign(evaluate_in(*index, clause).await?);
+        }
+        Ok(result)
+    }
+}
+
+// Boxed since the evaluation recurses into child clauses. $not is the complement within
+// the offsets of index, so that the result never holds offsets of another index.
+fn evaluate_in<'b>(
+    index: &'b dyn MetadataIndexReader,
+    clause: &'b Where,
+) -> BoxFuture<'b, Result<RoaringBitmap, Box<dyn ChromaError>>> {
+    async move {
+        match clause {
+            Where::DirectComparison(comparison) => evaluate_comparison(index, comparison).await,
+            Where::WhereChildren(children) => {
+                let mut result: Option<RoaringBitmap> = None;
+                for child in children.children.iter() {
+                    let child_result = evaluate_in(index, child).await?;
+                    result = match result {
+                        None => Some(child_result),
+                        Some(mut result) => {
+                            match children.operator {
+                                BooleanOperator::And => result.bitand_assign(child_result),
+                                BooleanOperator::Or => result.bitor_assign(child_result),
+                            }
+                            Some(result)
+                        }
+                    };
+                }
+                match result {
+                    Some(result) => Ok(result),
+                    None => {
+                        Err(Box::new(WhereConversionError::EmptyChildren) as Box<dyn ChromaError>)
+                    }
+                }
+            }
+            Where::Not(child) => {
+                let mut result = index.all_offsets().await?;
+                result.sub_assign(evaluate_in(index, child).await?);
+                Ok(result)
+            }
+        }
+    }
+    .boxed()
+}
+
+async fn evaluate_comparison(
+    index: &dyn MetadataIndexReader,
+    comparison: &DirectComparison,
+) -> Result<RoaringBitmap, Box<dyn ChromaError>> {
+    let key = comparison.key.as_str();
+    match &comparison.comparison {
+        WhereComparison::SingleString(value, comparator) => {
+            compare(
+                index,
+                key,
+                MetadataIndexValue::String(value.clone()),
+                comparator,
+            )
+            .await
+        }
+        WhereComparison::SingleInt(value, comparator) => {
+            compare(index, key, MetadataIndexValue::Int(*value), comparator).await
+        }
+        WhereComparison::SingleDouble(value, comparator) => {
+            compare(index, key, MetadataIndexValue::from(*value), comparator).await
+        }
+        WhereComparison::SingleBool(value, comparator) => {
+            compare(index, key, MetadataIndexValue::Bool(*value), comparator).await
+        }
+        WhereComparison::StringList(values, operator) => {
+            let values = values
+                .iter()
+                .map(|value| MetadataIndexValue::String(value.clone()))
+                .collect();
+            compare_list(index, key, values, operator).await
+        }
+        WhereComparison::IntList(values, operator) => {
+            let values = values
+                .iter()
+                .map(|value| MetadataIndexValue::Int(*value))
+                .collect();
+            compare_list(index, key, values, operator).await
+        }
+        WhereComparison::DoubleList(values, operator) => {
+            let values = values
+                .iter()
+                .map(|value| MetadataIndexValue::from(*value))
+                .collect();
+            compare_list(index, key, values, operator).await
+        }
+        WhereComparison::BoolList(values, operator) => {
+            let values = values
+                .iter()
+                .map(|value| MetadataIndexValue::Bool(*value))
+                .collect();
+            compare_list(index, key, values, operator).await
+        }
+    }
+}
+
+// An int and a float of the same value are distinct keys of the index. Range queries
+// already compare numbers by value, equality looks up the key of both types.
+fn equal_values(value: MetadataIndexValue) -> Vec<MetadataIndexValue> {
+    // 2^63 is the smallest float past every i64
+    const LIMIT: f64 = 9_223_372_036_854_775_808.0;
+    match value {
+        MetadataIndexValue::Int(i) => {
+            let f = i as f64;
+            if f < LIMIT && f as i64 == i {
+                vec![MetadataIndexValue::Int(i), MetadataIndexValue::Float(f)]
+            } else {
+                vec![MetadataIndexValue::Int(i)]
+            }
+        }
+        MetadataIndexValue::Float(f) if f.fract() == 0.0 && (-LIMIT..LIMIT).contains(&f) => {
+            vec![
+                MetadataIndexValue::Float(f),
+                MetadataIndexValue::Int(f as i64),
+            ]
+        }
+        value => vec![value],
+    }
+}
+
+async fn compare(
+    index: &dyn MetadataIndexReader,
+    key: &str,
+    value: MetadataIndexValue,
+    comparator: &WhereClauseComparator,
+) -> Result<RoaringBitmap, Box<dyn ChromaError>> {
+    match comparator {
+        WhereClauseComparator::Equal => index.get_in(key, equal_values(value)).await,
+        WhereClauseComparator::NotEqual => index.not_in(key, equal_values(value)).await,
+        WhereClauseComparator::GreaterThan => index.gt(key, value).await,
+        WhereClauseComparator::GreaterThanOrEqual => index.gte(key, value).await,
+        WhereClauseComparator::LessThan => index.lt(key, value).await,
+        WhereClauseComparator::LessThanOrEqual => index.lte(key, value).await,
+    }
+}
+
+async fn compare_list(
+    index: &dyn MetadataIndexReader,
+    key: &str,
+    values: Vec<MetadataIndexValue>,
+    operator: &WhereClauseListOperator,
+) -> Result<RoaringBitmap, Box<dyn ChromaError>> {
+    let values = values.into_iter().flat_map(equal_values).collect();
+    match operator {
+        WhereClauseListOperator::In => index.get_in(key, values).await,
+        WhereClauseListOperator::NotIn => index.not_in(key, values).await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blockstore::provider::{BlockfileProvider, HashMapBlockfileProvider};
+    use crate::blockstore::{KeyType, ValueType};
+    use crate::errors::ErrorCodes;
+    use crate::index::metadata::reader::BlockfileMetadataIndexReader;
+    use crate::index::metadata::types::MetadataIndexWriter;
+    use crate::index::metadata::writer::BlockfileMetadataIndexWriter;
+    use crate::types::WhereChildren;
+
+    fn comparison(key: &str, comparison: WhereComparison) -> Where {
+        Where::DirectComparison(DirectComparison {
+            key: key.to_string(),
+            comparison,
+        })
+    }
+
+    fn children(operator: BooleanOperator, children: Vec<Where>) -> Where {
+        Where::WhereChildren(WhereChildren { children, operator })
+    }
+
+    fn offsets(bitmap: RoaringBitmap) -> Vec<u32> {
+        bitmap.iter().collect()
+    }
+
+    // Sets year, genre and rating for every offset in offsets. Ratings alternate between
+    // ints and floats of the same values.
+    async fn index_movies(
+        name: &str,
+        offsets: std::ops::Range<usize>,
+    ) -> BlockfileMetadataIndexReader {
+        let mut provider = HashMapBlockfileProvider::new();
+        let blockfile = provider
+            .create(name, KeyType::String, ValueType::RoaringBitmap)
+            .unwrap();
+        let mut index = BlockfileMetadataIndexWriter::new(blockfile);
+        index.begin_transaction().await.unwrap();
+        for offset in offsets {
+            index
+                .set(
+                    "year",
+                    MetadataIndexValue::Int(2020 + offset as i64),
+                    offset,
+                )
+                .await
+                .unwrap();
+            let genre = if offset % 2 == 0 { "drama" } else { "comedy" };
+            index
+                .set(
+                    "genre",
+                    MetadataIndexValue::String(genre.to_string()),
+                    offset,
+                )
+                .await
+                .unwrap();
+            let rating = match offset % 3 {
+                0 => MetadataIndexValue::Int(1),
+                1 => MetadataIndexValue::Float(1.0),
+                _ => MetadataIndexValue::Float(2.5),
+            };
+            index.set("rating", rating, offset).await.unwrap();
+        }
+        index.commit_transaction().await.unwrap();
+        index.reader()
+    }
+
+    #[tokio::test]
+    async fn test_where_evaluator() {
+        let mut provider = HashMapBlockfileProvider::new();
+        let blockfile = provider
+            .create("test", KeyType::String, ValueType::RoaringBitmap)
+            .unwrap();
+        let mut index = BlockfileMetadataIndexWriter::new(blockfile);
+        index.begin_transaction().await.unwrap();
+        for i in 0..6 {
+            index
+                .set("year", MetadataIndexValue::Int(2020 + i), i as usize)
+                .await
+                .unwrap();
+            let genre = if i % 2 == 0 { "drama" } else { "comedy" };
+            index
+                .set(
+                    "genre",
+                    MetadataIndexValue::String(genre.to_string()),
+                    i as usize,
+                )
+                .await
+                .unwrap();
+            index
+                .set("released", MetadataIndexValue::Bool(i < 4), i as usize)
+                .await
+                .unwrap();
+        }
+        index.commit_transaction().await.unwrap();
+        let reader = index.reader();
+        let evaluator = WhereEvaluator::new(vec![&reader]);
+
+        let clause = Where::WhereChildren(WhereChildren {
+            children: vec![
+                comparison(
+                    "year",
+                    WhereComparison::SingleInt(2022, WhereClauseComparator::GreaterThanOrEqual),
+                ),
+                comparison(
+                    "genre",
+                    WhereComparison::SingleString(
+                        "drama".to_string(),
+                        WhereClauseComparator::Equal,
+                    ),
+                ),
+            ],
+            operator: BooleanOperator::And,
+        });
+        let bitmap = evaluator.evaluate(&clause).await.unwrap();
+        assert_eq!(bitmap.iter().collect::<Vec<u32>>(), vec![2, 4]);
+
+        let clause = Where::WhereChildren(WhereChildren {
+            children: vec![
+                comparison(
+                    "year",
+                    WhereComparison::IntList(vec![2020, 2021], WhereClauseListOperator::In),
+                ),
+                comparison(
+                    "genre",
+                    WhereComparison::StringList(
+                        vec!["drama".to_string()],
+                        WhereClauseListOperator::NotIn,
+                    ),
+                ),
+            ],
+            operator: BooleanOperator::Or,
+        });
+        let bitmap = evaluator.evaluate(&clause).await.unwrap();
+        assert_eq!(bitmap.iter().collect::<Vec<u32>>(), vec![0, 1, 3, 5]);
+
+        let clause = comparison(
+            "year",
+            WhereComparison::SingleInt(i64::MAX, WhereClauseComparator::Equal),
+        );
+        assert_eq!(evaluator.evaluate(&clause).await.unwrap().len(), 0);
+
+        let clause = comparison(
+            "released",
+            WhereComparison::SingleBool(true, WhereClauseComparator::NotEqual),
+        );
+        let bitmap = evaluator.evaluate(&clause).await.unwrap();
+        assert_eq!(bitmap.iter().collect::<Vec<u32>>(), vec![4, 5]);
+        let clause = comparison(
+            "released",
+            WhereComparison::BoolList(vec![true], WhereClauseListOperator::In),
+        );
+        let bitmap = evaluator.evaluate(&clause).await.unwrap();
+        assert_eq!(bitmap.iter().collect::<Vec<u32>>(), vec![0, 1, 2, 3]);
+    }
+
+    #[tokio::test]
+    async fn test_where_evaluator_not() {
+        let reader = index_movies("test", 0..6).await;
+        let evaluator = WhereEvaluator::new(vec![&reader]);
+        let drama = comparison(
+            "genre",
+            WhereComparison::SingleString("drama".to_string(), WhereClauseComparator::Equal),
+        );
+        let recent = comparison(
+            "year",
+            WhereComparison::SingleInt(2023, WhereClauseComparator::GreaterThanOrEqual),
+        );
+
+        let clause = Where::Not(Box::new(drama.clone()));
+        assert_eq!(
+            offsets(evaluator.evaluate(&clause).await.unwrap()),
+            vec![1, 3, 5]
+        );
+
+        let clause = Where::Not(Box::new(Where::Not(Box::new(drama.clone()))));
+        assert_eq!(
+            offsets(evaluator.evaluate(&clause).await.unwrap()),
+            vec![0, 2, 4]
+        );
+
+        // Offsets without the key are matched by the complement.
+        let clause = Where::Not(Box::new(comparison(
+            "missing",
+            WhereComparison::SingleInt(1, WhereClauseComparator::Equal),
+        )));
+        assert_eq!(
+            offsets(evaluator.evaluate(&clause).await.unwrap()),
+            vec![0, 1, 2, 3, 4, 5]
+        );
+
+        // not (drama and recent) == not drama or not recent
+        let clause = Where::Not(Box::new(children(
+            BooleanOperator::And,
+            vec![drama.clone(), recent.clone()],
+        )));
+        assert_eq!(
+            offsets(evaluator.evaluate(&clause).await.unwrap()),
+            vec![0, 1, 2, 3, 5]
+        );
+        let clause = children(
+            BooleanOperator::Or,
+            vec![
+                Where::Not(Box::new(drama.clone())),
+                Where::Not(Box::new(recent.clone())),
+            ],
+        );
+        assert_eq!(
+            offsets(evaluator.evaluate(&clause).await.unwrap()),
+            vec![0, 1, 2, 3, 5]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_where_evaluator_nested_clauses() {
+        let reader = index_movies("test", 0..6).await;
+        let evaluator = WhereEvaluator::new(vec![&reader]);
+
+        // (comedy or year < 2021) and not (rating == 2.5 or year in [2025])
+        let clause = children(
+            BooleanOperator::And,
+            vec![
+                children(
+                    BooleanOperator::Or,
+                    vec![
+                        comparison(
+                            "genre",
+                            WhereComparison::SingleString(
+                                "comedy".to_string(),
+                                WhereClauseComparator::Equal,
+                            ),
+                        ),
+                        comparison(
+                            "year",
+                            WhereComparison::SingleInt(2021, WhereClauseComparator::LessThan),
+                        ),
+                    ],
+                ),
+                Where::Not(Box::new(children(
+                    BooleanOperator::Or,
+                    vec![
+                        comparison(
+                            "rating",
+                            WhereComparison::SingleDouble(2.5, WhereClauseComparator::Equal),
+                        ),
+                        comparison(
+                            "year",
+                            WhereComparison::IntList(vec![2025], WhereClauseListOperator::In),
+                        ),
+                    ],
+                ))),
+            ],
+        );
+        assert_eq!(
+            offsets(evaluator.evaluate(&clause).await.unwrap()),
+            vec![0, 1, 3]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_where_evaluator_empty_children() {
+        let reader = index_movies("test", 0..6).await;
+        let evaluator = WhereEvaluator::new(vec![&reader]);
+
+        let clause = children(BooleanOperator::And, vec![]);
+        let err = evaluator.evaluate(&clause).await.unwrap_err();
+        assert_eq!(err.code(), ErrorCodes::InvalidArgument);
+
+        let clause = children(
+            BooleanOperator::Or,
+            vec![
+                comparison(
+                    "year",
+                    WhereComparison::SingleInt(2020, WhereClauseComparator::Equal),
+                ),
+                Where::Not(Box::new(children(BooleanOperator::Or, vec![]))),
+            ],
+        );
+        let err = evaluator.evaluate(&clause).await.unwrap_err();
+        assert_eq!(err.code(), ErrorCodes::InvalidArgument);
+    }
+
+    #[tokio::test]
+    async fn test_where_evaluator_multiple_indices() {
+        let first = index_movies("first", 0..3).await;
+        let second = index_movies("second", 3..6).await;
+        let evaluator = WhereEvaluator::new(vec![&first, &second]);
+
+        let clause = children(
+            BooleanOperator::And,
+            vec![
+                comparison(
+                    "genre",
+                    WhereComparison::SingleString(
+                        "drama".to_string(),
+                        WhereClauseComparator::Equal,
+                    ),
+                ),
+                comparison(
+                    "year",
+                    WhereComparison::SingleInt(2021, WhereClauseComparator::GreaterThan),
+                ),
+            ],
+        );
+        assert_eq!(
+            offsets(evaluator.evaluate(&clause).await.unwrap()),
+            vec![2, 4]
+        );
+
+        let clause = Where::Not(Box::new(comparison(
+            "year",
+            WhereComparison::IntList(vec![2021, 2024], WhereClauseListOperator::In),
+        )));
+        assert_eq!(
+            offsets(evaluator.evaluate(&clause).await.unwrap()),
+            vec![0, 2, 3, 5]
+        );
+
+        let clause = comparison(
+            "genre",
+            WhereComparison::SingleString("drama".to_string(), WhereClauseComparator::NotEqual),
+        );
+        assert_eq!(
+            offsets(evaluator.evaluate(&clause).await.unwrap()),
+            vec![1, 3, 5]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_where_evaluator_mixes_ints_and_floats() {
+        let reader = index_movies("test", 0..6).await;
+        let evaluator = WhereEvaluator::new(vec![&reader]);
+
+        // Ratings are 1, 1.0, 2.5, 1, 1.0, 2.5
+        let cases = vec![
+            (
+                WhereComparison::SingleInt(1, WhereClauseComparator::Equal),
+                vec![0, 1, 3, 4],
+            ),
+            (
+                WhereComparison::SingleDouble(1.0, WhereClauseComparator::Equal),
+                vec![0, 1, 3, 4],
+            ),
+            (
+                WhereComparison::SingleInt(1, WhereClauseComparator::NotEqual),
+                vec![2, 5],
+            ),
+            (
+                WhereComparison::SingleDouble(1.0, WhereClauseComparator::NotEqual),
+                vec![2, 5],
+            ),
+            (
+                WhereComparison::SingleInt(1, WhereClauseComparator::GreaterThan),
+                vec![2, 5],
+            ),
+            (
+                WhereComparison::SingleDouble(1.0, WhereClauseComparator::LessThanOrEqual),
+                vec![0, 1, 3, 4],
+            ),
+            (
+                WhereComparison::SingleInt(2, WhereClauseComparator::LessThan),
+                vec![0, 1, 3, 4],
+            ),
+            (
+                WhereComparison::IntList(vec![1], WhereClauseListOperator::In),
+                vec![0, 1, 3, 4],
+            ),
+            (
+                WhereComparison::DoubleList(vec![1.0], WhereClauseListOperator::NotIn),
+                vec![2, 5],
+            ),
+            (
+                WhereComparison::DoubleList(vec![2.5], WhereClauseListOperator::In),
+                vec![2, 5],
+            ),
+        ];
+        for (rating, expected) in cases {
+            let clause = comparison("rating", rating.clone());
+            assert_eq!(
+                offsets(evaluator.evaluate(&clause).await.unwrap()),
+                expected,
+                "{:?}",
+                rating
+            );
+        }
+    }
+}
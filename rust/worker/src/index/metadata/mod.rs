@@ -1,3 +1,11 @@
+mod encoding;
+mod evaluator;
+mod reader;
+mod stats;
 mod types;
+mod writer;
 
-// TODO reexport the types module
\ No newline at end of file
+pub(crate) use evaluator::WhereEvaluator;
+pub(crate) use reader::BlockfileMetadataIndexReader;
+pub(crate) use types::{MetadataIndexReader, MetadataIndexValue, MetadataIndexWriter};
+pub(crate) use writer::BlockfileMetadataIndexWriter;
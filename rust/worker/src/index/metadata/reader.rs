@@ -0,0 +1,1027 @@
+use super::encoding::{
+    blockfile_key_to_value, composite_blockfile_key, exists_blockfile_key, kv_to_blockfile_key,
+    offset_id_to_u32, range_blockfile_key, range_bound_key, read_metadata, value_to_posting,
+};
+use super::stats::compute_key_stats;
+use super::types::{KeyStats, MetadataIndexError, MetadataIndexReader, MetadataIndexValue};
+use crate::blockstore::{Blockfile, BlockfileIterator, Key, KeyType};
+use crate::errors::ChromaError;
+use async_trait::async_trait;
+use parking_lot::RwLock;
+use roaring::RoaringBitmap;
+use std::{
+    collections::{HashMap, HashSet},
+    ops::{BitAndAssign, BitOrAssign, SubAssign},
+    sync::Arc,
+};
+
+// Serves committed reads of the index a writer maintains. Readers share the writer's
+// blockfiles, so they observe each commit, and are cheap to clone for every query.
+#[derive(Clone)]
+pub(crate) struct BlockfileMetadataIndexReader {
+    pub(super) blockfile: Box<dyn Blockfile>,
+    pub(super) forward_blockfile: Option<Box<dyn Blockfile>>,
+    // Used as the universe when complementing postings.
+    pub(super) committed_offsets: Arc<RwLock<RoaringBitmap>>,
+    pub(super) key_stats: Arc<RwLock<HashMap<String, Option<KeyStats>>>>,
+    pub(super) unindexed_keys: Arc<HashSet<String>>,
+    pub(super) composite_indexes: Arc<Vec<(String, String)>>,
+}
+
+impl BlockfileMetadataIndexReader {
+    fn check_indexed(&self, key: &str) -> Result<(), Box<dyn ChromaError>> {
+        if self.unindexed_keys.contains(key) {
+            return Err(Box::new(MetadataIndexError::KeyNotIndexed(key.to_string())));
+        }
+        Ok(())
+    }
+
+    // Unions the postings returned by a blockfile range scan. The blockfile orders
+    // keys of different type groups by group, so entries outside the group of the
+    // queried type are skipped. Ints and floats share a group, so a numeric range
+    // matches values of both types.
+    fn union_range(
+        &self,
+        key_type: KeyType,
+        range: Result<BlockfileIterator, Box<dyn ChromaError>>,
+    ) -> Result<RoaringBitmap, Box<dyn ChromaError>> {
+        let mut result = RoaringBitmap::new();
+        for (blockfilekey, value) in range? {
+            if KeyType::from(&blockfilekey).order() != key_type.order() {
+                continue;
+            }
+            if let Some(rbm) = value_to_posting(value) {
+                result.bitor_assign(rbm);
+            }
+        }
+        Ok(result)
+    }
+
+    // Subtracts the committed postings of values under key from committed_offsets.
+    async fn complement(
+        &self,
+        key: &str,
+        values: Vec<MetadataIndexValue>,
+    ) -> Result<RoaringBitmap, Box<dyn ChromaError>> {
+        let mut result = self.committed_offsets.read().clone();
+        result.sub_assign(self.get_in(key, values).await?);
+        Ok(result)
+    }
+}
+
+#[async_trait]
+impl MetadataIndexReader for BlockfileMetadataIndexReader {
+    async fn get(
+        &self,
+        key: &str,
+        value: MetadataIndexValue,
+    ) -> Result<RoaringBitmap, Box<dyn ChromaError>> {
+        self.check_indexed(key)?;
+        let blockfilekey = kv_to_blockfile_key(key, value)?;
+        match self.blockfile.get(blockfilekey).map(value_to_posting) {
+            Ok(Some(rbm)) => Ok(rbm),
+            _ => Err(Box::new(MetadataIndexError::NotFoundError)),
+        }
+    }
+
+    async fn get_composite(
+        &self,
+        key_a: &str,
+        value_a: MetadataIndexValue,
+        key_b: &str,
+        value_b: MetadataIndexValue,
+    ) -> Result<RoaringBitmap, Box<dyn ChromaError>> {
+        if self.forward_blockfile.is_none() {
+            return Err(Box::new(MetadataIndexError::NoForwardIndex));
+        }
+        let composite_index = (key_a.to_string(), key_b.to_string());
+        let reversed_index = (key_b.to_string(), key_a.to_string());
+        let blockfilekey = if self.composite_indexes.contains(&composite_index) {
+            composite_blockfile_key(key_a, &value_a, key_b, &value_b)
+        } else if self.composite_indexes.contains(&reversed_index) {
+            composite_blockfile_key(key_b, &value_b, key_a, &value_a)
+        } else {
+            return Err(Box::new(MetadataIndexError::NoCompositeIndex(
+                key_a.to_string(),
+                key_b.to_string(),
+            )));
+        };
+        match self.blockfile.get(blockfilekey).map(value_to_posting) {
+            Ok(Some(rbm)) => Ok(rbm),
+            _ => Ok(RoaringBitmap::new()),
+        }
+    }
+
+    async fn count(
+        &self,
+        key: &str,
+        value: MetadataIndexValue,
+    ) -> Result<u64, Box<dyn ChromaError>> {
+        self.check_indexed(key)?;
+        Ok(self
+            .blockfile
+            .get_len(kv_to_blockfile_key(key, value)?)
+            .unwrap_or(0))
+    }
+
+    async fn get_in(
+        &self,
+        key: &str,
+        values: Vec<MetadataIndexValue>,
+    ) -> Result<RoaringBitmap, Box<dyn ChromaError>> {
+        self.check_indexed(key)?;
+        let blockfilekeys = values
+            .into_iter()
+            .map(|value| kv_to_blockfile_key(key, value))
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut result = RoaringBitmap::new();
+        for value in self
+            .blockfile
+            .get_many(&blockfilekeys)?
+            .into_iter()
+            .flatten()
+        {
+            if let Some(rbm) = value_to_posting(value) {
+                result.bitor_assign(rbm);
+            }
+        }
+        Ok(result)
+    }
+
+    async fn get_prefix(
+        &self,
+        key: &str,
+        prefix: &str,
+    ) -> Result<RoaringBitmap, Box<dyn ChromaError>> {
+        self.check_indexed(key)?;
+        let mut result = RoaringBitmap::new();
+        let entries = self
+            .blockfile
+            .get_gte(key.to_string(), Key::String(prefix.to_string()))?;
+        // Keys are sorted, so the strings starting with prefix come first
+        for (blockfilekey, value) in entries {
+            match blockfilekey.key {
+                Key::String(s) if s.starts_with(prefix) => {
+                    if let Some(rbm) = value_to_posting(value) {
+                        result.bitor_assign(rbm);
+                    }
+                }
+                _ => break,
+            }
+        }
+        Ok(result)
+    }
+
+    async fn gt(
+        &self,
+        key: &str,
+        value: MetadataIndexValue,
+    ) -> Result<RoaringBitmap, Box<dyn ChromaError>> {
+        self.check_indexed(key)?;
+        let blockfilekey = range_blockfile_key(key, value)?;
+        self.union_range(
+            KeyType::from(&blockfilekey),
+            self.blockfile
+                .get_gt(blockfilekey.prefix, range_bound_key(blockfilekey.key, true)),
+        )
+    }
+
+    async fn gte(
+        &self,
+        key: &str,
+        value: MetadataIndexValue,
+    ) -> Result<RoaringBitmap, Box<dyn ChromaError>> {
+        self.check_indexed(key)?;
+        let blockfilekey = range_blockfile_key(key, value)?;
+        self.union_range(
+            KeyType::from(&blockfilekey),
+            self.blockfile.get_gte(
+                blockfilekey.prefix,
+                range_bound_key(blockfilekey.key, false),
+            ),
+        )
+    }
+
+    async fn lt(
+        &self,
+        key: &str,
+        value: MetadataIndexValue,
+    ) -> Result<RoaringBitmap, Box<dyn ChromaError>> {
+        self.check_indexed(key)?;
+        let blockfilekey = range_blockfile_key(key, value)?;
+        self.union_range(
+            KeyType::from(&blockfilekey),
+            self.blockfile.get_lt(
+                blockfilekey.prefix,
+                range_bound_key(blockfilekey.key, false),
+            ),
+        )
+    }
+
+    async fn lte(
+        &self,
+        key: &str,
+        value: MetadataIndexValue,
+    ) -> Result<RoaringBitmap, Box<dyn ChromaError>> {
+        self.check_indexed(key)?;
+        let blockfilekey = range_blockfile_key(key, value)?;
+        self.union_range(
+            KeyType::from(&blockfilekey),
+            self.blockfile
+                .get_lte(blockfilekey.prefix, range_bound_key(blockfilekey.key, true)),
+        )
+    }
+
+    async fn exists(&self, key: &str) -> Result<RoaringBitmap, Box<dyn ChromaError>> {
+        self.check_indexed(key)?;
+        match self
+            .blockfile
+            .get(exists_blockfile_key(key))
+            .map(value_to_posting)
+        {
+            Ok(Some(rbm)) => Ok(rbm),
+            _ => Ok(RoaringBitmap::new()),
+        }
+    }
+
+    async fn not_exists(&self, key: &str) -> Result<RoaringBitmap, Box<dyn ChromaError>> {
+        let mut result = self.committed_offsets.read().clone();
+        result.sub_assign(self.exists(key).await?);
+        Ok(result)
+    }
+
+    async fn contains_any(
+        &self,
+        key: &str,
+        values: Vec<MetadataIndexValue>,
+    ) -> Result<RoaringBitmap, Box<dyn ChromaError>> {
+        self.get_in(key, values).await
+    }
+
+    async fn contains_all(
+        &self,
+        key: &str,
+        values: Vec<MetadataIndexValue>,
+    ) -> Result<RoaringBitmap, Box<dyn ChromaError>> {
+        let mut result = self.committed_offsets.read().clone();
+        for value in values {
+            if result.is_empty() {
+                break;
+            }
+            result.bitand_assign(self.get_in(key, vec![value]).await?);
+        }
+        Ok(result)
+    }
+
+    async fn not_eq(
+        &self,
+        key: &str,
+        value: MetadataIndexValue,
+    ) -> Result<RoaringBitmap, Box<dyn ChromaError>> {
+        self.complement(key, vec![value]).await
+    }
+
+    async fn not_in(
+        &self,
+        key: &str,
+        values: Vec<MetadataIndexValue>,
+    ) -> Result<RoaringBitmap, Box<dyn ChromaError>> {
+        self.complement(key, values).await
+    }
+
+    async fn all_offsets(&self) -> Result<RoaringBitmap, Box<dyn ChromaError>> {
+        Ok(self.committed_offsets.read().clone())
+    }
+
+    async fn stats(&self, key: &str) -> Result<Option<KeyStats>, Box<dyn ChromaError>> {
+        self.check_indexed(key)?;
+        if let Some(stats) = self.key_stats.read().get(key) {
+            return Ok(stats.clone());
+        }
+        // Not written since the index was opened. Only the writer caches statistics,
+        // so that a reader cannot overwrite them with a computation older than a commit.
+        compute_key_stats(self.blockfile.as_ref(), key)
+    }
+
+    async fn values_for_key(
+        &self,
+        key: &str,
+    ) -> Result<Vec<(MetadataIndexValue, u64)>, Box<dyn ChromaError>> {
+        self.check_indexed(key)?;
+        let mut values = Vec::new();
+        for (blockfilekey, value) in self.blockfile.get_by_prefix(key)? {
+            if let Some(rbm) = value_to_posting(value) {
+                if !rbm.is_empty() {
+                    values.push((blockfile_key_to_value(blockfilekey.key), rbm.len()));
+                }
+            }
+        }
+        Ok(values)
+    }
+
+    async fn get_metadata(
+        &self,
+        offset_id: usize,
+    ) -> Result<HashMap<String, MetadataIndexValue>, Box<dyn ChromaError>> {
+        match &self.forward_blockfile {
+            Some(forward_blockfile) => {
+                read_metadata(forward_blockfile.as_ref(), offset_id_to_u32(offset_id)?)
+            }
+            None => Err(Box::new(MetadataIndexError::NoForwardIndex)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blockstore::provider::{BlockfileProvider, HashMapBlockfileProvider};
+    use crate::blockstore::ValueType;
+    use crate::errors::ErrorCodes;
+    use crate::index::metadata::types::MetadataIndexWriter;
+    use crate::index::metadata::writer::BlockfileMetadataIndexWriter;
+
+    #[tokio::test]
+    async fn test_metadata_index_range_queries() {
+        let mut provider = HashMapBlockfileProvider::new();
+        let blockfile = provider
+            .create("test", KeyType::String, ValueType::RoaringBitmap)
+            .unwrap();
+        let mut index = BlockfileMetadataIndexWriter::new(blockfile);
+        let reader = index.reader();
+        index.begin_transaction().await.unwrap();
+        for i in 0..5 {
+            index
+                .set("key", MetadataIndexValue::Int(i), i as usize)
+                .await
+                .unwrap();
+        }
+        index
+            .set("key", MetadataIndexValue::Bool(true), 5)
+            .await
+            .unwrap();
+        index
+            .set("key", MetadataIndexValue::Float(1.5), 6)
+            .await
+            .unwrap();
+        index
+            .set("other", MetadataIndexValue::Int(3), 7)
+            .await
+            .unwrap();
+        assert_eq!(
+            reader
+                .gt("key", MetadataIndexValue::Int(2))
+                .await
+                .unwrap()
+                .len(),
+            0
+        );
+        index.commit_transaction().await.unwrap();
+
+        let bitmap = reader.gt("key", MetadataIndexValue::Int(2)).await.unwrap();
+        assert_eq!(bitmap.iter().collect::<Vec<u32>>(), vec![3, 4]);
+        let bitmap = reader.gte("key", MetadataIndexValue::Int(2)).await.unwrap();
+        assert_eq!(bitmap.iter().collect::<Vec<u32>>(), vec![2, 3, 4]);
+        let bitmap = reader.lt("key", MetadataIndexValue::Int(2)).await.unwrap();
+        assert_eq!(bitmap.iter().collect::<Vec<u32>>(), vec![0, 1, 6]);
+        let bitmap = reader.lte("key", MetadataIndexValue::Int(2)).await.unwrap();
+        assert_eq!(bitmap.iter().collect::<Vec<u32>>(), vec![0, 1, 2, 6]);
+        let bitmap = reader
+            .gt("key", MetadataIndexValue::Float(0.0))
+            .await
+            .unwrap();
+        assert_eq!(bitmap.iter().collect::<Vec<u32>>(), vec![1, 2, 3, 4, 6]);
+        let bitmap = reader.gt("key", MetadataIndexValue::Int(10)).await.unwrap();
+        assert_eq!(bitmap.len(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_metadata_index_range_queries_mix_ints_and_floats() {
+        let mut provider = HashMapBlockfileProvider::new();
+        let blockfile = provider
+            .create("test", KeyType::String, ValueType::RoaringBitmap)
+            .unwrap();
+        let mut index = BlockfileMetadataIndexWriter::new(blockfile);
+        let reader = index.reader();
+        let values = [
+            MetadataIndexValue::Int(1),
+            MetadataIndexValue::Float(1.5),
+            MetadataIndexValue::Int(2),
+            MetadataIndexValue::Float(2.0),
+            MetadataIndexValue::Float(2.5),
+            MetadataIndexValue::Int(3),
+            MetadataIndexValue::String("4".to_string()),
+            MetadataIndexValue::Bool(true),
+        ];
+        index.begin_transaction().await.unwrap();
+        for (offset_id, value) in values.into_iter().enumerate() {
+            index.set("year", value, offset_id).await.unwrap();
+        }
+        index.commit_transaction().await.unwrap();
+
+        let bitmap = reader.gt("year", MetadataIndexValue::Int(2)).await.unwrap();
+        assert_eq!(bitmap.iter().collect::<Vec<u32>>(), vec![4, 5]);
+        let bitmap = reader
+            .gte("year", MetadataIndexValue::Int(2))
+            .await
+            .unwrap();
+        assert_eq!(bitmap.iter().collect::<Vec<u32>>(), vec![2, 3, 4, 5]);
+        let bitmap = reader
+            .gt("year", MetadataIndexValue::Float(1.5))
+            .await
+            .unwrap();
+        assert_eq!(bitmap.iter().collect::<Vec<u32>>(), vec![2, 3, 4, 5]);
+        let bitmap = reader
+            .lt("year", MetadataIndexValue::Float(2.5))
+            .await
+            .unwrap();
+        assert_eq!(bitmap.iter().collect::<Vec<u32>>(), vec![0, 1, 2, 3]);
+        let bitmap = reader
+            .lte("year", MetadataIndexValue::Int(2))
+            .await
+            .unwrap();
+        assert_eq!(bitmap.iter().collect::<Vec<u32>>(), vec![0, 1, 2, 3]);
+        let bitmap = reader.lt("year", MetadataIndexValue::Int(2)).await.unwrap();
+        assert_eq!(bitmap.iter().collect::<Vec<u32>>(), vec![0, 1]);
+        let bitmap = reader
+            .gt("year", MetadataIndexValue::Float(2.0))
+            .await
+            .unwrap();
+        assert_eq!(bitmap.iter().collect::<Vec<u32>>(), vec![4, 5]);
+        let bitmap = reader
+            .lte("year", MetadataIndexValue::Float(2.0))
+            .await
+            .unwrap();
+        assert_eq!(bitmap.iter().collect::<Vec<u32>>(), vec![0, 1, 2, 3]);
+    }
+
+    #[tokio::test]
+    async fn test_metadata_index_not_eq_not_in() {
+        let mut provider = HashMapBlockfileProvider::new();
+        let blockfile = provider
+            .create("test", KeyType::String, ValueType::RoaringBitmap)
+            .unwrap();
+        let mut index = BlockfileMetadataIndexWriter::new(blockfile);
+        let reader = index.reader();
+        index.begin_transaction().await.unwrap();
+        index
+            .set("key", MetadataIndexValue::Int(1), 1)
+            .await
+            .unwrap();
+        index
+            .set("key", MetadataIndexValue::Int(2), 2)
+            .await
+            .unwrap();
+        index
+            .set("key", MetadataIndexValue::Int(3), 3)
+            .await
+            .unwrap();
+        index
+            .set("other", MetadataIndexValue::Bool(true), 4)
+            .await
+            .unwrap();
+        index
+            .set("other", MetadataIndexValue::Bool(true), 5)
+            .await
+            .unwrap();
+        index.commit_transaction().await.unwrap();
+
+        let bitmap = reader
+            .not_eq("key", MetadataIndexValue::Int(2))
+            .await
+            .unwrap();
+        assert_eq!(bitmap.iter().collect::<Vec<u32>>(), vec![1, 3, 4, 5]);
+        let bitmap = reader
+            .not_in(
+                "key",
+                vec![MetadataIndexValue::Int(1), MetadataIndexValue::Int(3)],
+            )
+            .await
+            .unwrap();
+        assert_eq!(bitmap.iter().collect::<Vec<u32>>(), vec![2, 4, 5]);
+
+        index.begin_transaction().await.unwrap();
+        index.delete_all(4).await.unwrap();
+        index
+            .delete("other", MetadataIndexValue::Bool(true), 5)
+            .await
+            .unwrap();
+        index.commit_transaction().await.unwrap();
+
+        let bitmap = reader
+            .not_eq("key", MetadataIndexValue::Int(2))
+            .await
+            .unwrap();
+        assert_eq!(bitmap.iter().collect::<Vec<u32>>(), vec![1, 3]);
+    }
+
+    #[tokio::test]
+    async fn test_metadata_index_get_in() {
+        let mut provider = HashMapBlockfileProvider::new();
+        let blockfile = provider
+            .create("test", KeyType::String, ValueType::RoaringBitmap)
+            .unwrap();
+        let mut index = BlockfileMetadataIndexWriter::new(blockfile);
+        let reader = index.reader();
+        index.begin_transaction().await.unwrap();
+        index
+            .set("key", MetadataIndexValue::String("a".to_string()), 1)
+            .await
+            .unwrap();
+        index
+            .set("key", MetadataIndexValue::String("b".to_string()), 2)
+            .await
+            .unwrap();
+        index
+            .set("key", MetadataIndexValue::String("c".to_string()), 3)
+            .await
+            .unwrap();
+        index.commit_transaction().await.unwrap();
+
+        let bitmap = reader
+            .get_in(
+                "key",
+                vec![
+                    MetadataIndexValue::String("a".to_string()),
+                    MetadataIndexValue::String("c".to_string()),
+                    MetadataIndexValue::String("missing".to_string()),
+                ],
+            )
+            .await
+            .unwrap();
+        assert_eq!(bitmap.iter().collect::<Vec<u32>>(), vec![1, 3]);
+        let bitmap = reader.get_in("key", vec![]).await.unwrap();
+        assert_eq!(bitmap.len(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_metadata_index_get_metadata_without_forward_index() {
+        let mut provider = HashMapBlockfileProvider::new();
+        let blockfile = provider
+            .create("test", KeyType::String, ValueType::RoaringBitmap)
+            .unwrap();
+        let index = BlockfileMetadataIndexWriter::new(blockfile);
+        let reader = index.reader();
+        let result = reader.get_metadata(1).await;
+        assert_eq!(result.unwrap_err().code(), ErrorCodes::FailedPrecondition);
+    }
+
+    #[tokio::test]
+    async fn test_metadata_index_values_for_key() {
+        let mut provider = HashMapBlockfileProvider::new();
+        let blockfile = provider
+            .create("test", KeyType::String, ValueType::RoaringBitmap)
+            .unwrap();
+        let mut index = BlockfileMetadataIndexWriter::new(blockfile);
+        let reader = index.reader();
+        index.begin_transaction().await.unwrap();
+        for (offset_id, genre) in ["drama", "comedy", "drama", "horror"].iter().enumerate() {
+            index
+                .set(
+                    "genre",
+                    MetadataIndexValue::String(genre.to_string()),
+                    offset_id,
+                )
+                .await
+                .unwrap();
+        }
+        index
+            .set("year", MetadataIndexValue::Int(2020), 0)
+            .await
+            .unwrap();
+        index.commit_transaction().await.unwrap();
+
+        index.begin_transaction().await.unwrap();
+        index
+            .delete("genre", MetadataIndexValue::String("horror".to_string()), 3)
+            .await
+            .unwrap();
+        index.commit_transaction().await.unwrap();
+
+        let values = reader.values_for_key("genre").await.unwrap();
+        assert_eq!(
+            values,
+            vec![
+                (MetadataIndexValue::String("comedy".to_string()), 1),
+                (MetadataIndexValue::String("drama".to_string()), 2),
+            ]
+        );
+        assert_eq!(reader.values_for_key("missing").await.unwrap().len(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_metadata_index_count() {
+        let mut provider = HashMapBlockfileProvider::new();
+        let blockfile = provider
+            .create("test", KeyType::String, ValueType::RoaringBitmap)
+            .unwrap();
+        let mut index = BlockfileMetadataIndexWriter::new(blockfile);
+        let reader = index.reader();
+        index.begin_transaction().await.unwrap();
+        index
+            .set("key", MetadataIndexValue::Bool(true), 1)
+            .await
+            .unwrap();
+        index
+            .set("key", MetadataIndexValue::Bool(true), 2)
+            .await
+            .unwrap();
+        index
+            .set("key", MetadataIndexValue::Bool(false), 3)
+            .await
+            .unwrap();
+        assert_eq!(
+            reader
+                .count("key", MetadataIndexValue::Bool(true))
+                .await
+                .unwrap(),
+            0
+        );
+        index.commit_transaction().await.unwrap();
+
+        let count = reader.count("key", MetadataIndexValue::Bool(true)).await;
+        assert_eq!(count.unwrap(), 2);
+        let count = reader.count("key", MetadataIndexValue::Bool(false)).await;
+        assert_eq!(count.unwrap(), 1);
+        let count = reader
+            .count("missing", MetadataIndexValue::Bool(true))
+            .await;
+        assert_eq!(count.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_metadata_index_get_prefix() {
+        let mut provider = HashMapBlockfileProvider::new();
+        let blockfile = provider
+            .create("test", KeyType::String, ValueType::RoaringBitmap)
+            .unwrap();
+        let mut index = BlockfileMetadataIndexWriter::new(blockfile);
+        let reader = index.reader();
+        index.begin_transaction().await.unwrap();
+        let paths = ["eu/west", "eu/west/1", "eu/north", "euro", "us/east"];
+        for (offset_id, path) in paths.iter().enumerate() {
+            index
+                .set(
+                    "path",
+                    MetadataIndexValue::String(path.to_string()),
+                    offset_id,
+                )
+                .await
+                .unwrap();
+        }
+        index
+            .set("path", MetadataIndexValue::Int(7), 5)
+            .await
+            .unwrap();
+        index
+            .set(
+                "other",
+                MetadataIndexValue::String("eu/west".to_string()),
+                6,
+            )
+            .await
+            .unwrap();
+        index.commit_transaction().await.unwrap();
+
+        let bitmap = reader.get_prefix("path", "eu/").await.unwrap();
+        assert_eq!(bitmap.iter().collect::<Vec<u32>>(), vec![0, 1, 2]);
+        let bitmap = reader.get_prefix("path", "eu/west").await.unwrap();
+        assert_eq!(bitmap.iter().collect::<Vec<u32>>(), vec![0, 1]);
+        let bitmap = reader.get_prefix("path", "").await.unwrap();
+        assert_eq!(bitmap.iter().collect::<Vec<u32>>(), vec![0, 1, 2, 3, 4]);
+        let bitmap = reader.get_prefix("path", "asia").await.unwrap();
+        assert_eq!(bitmap.len(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_metadata_index_snapshot_reads_during_transaction() {
+        let mut provider = HashMapBlockfileProvider::new();
+        let blockfile = provider
+            .create("test", KeyType::String, ValueType::RoaringBitmap)
+            .unwrap();
+        let mut index = BlockfileMetadataIndexWriter::new(blockfile);
+        let reader = index.reader();
+        index.begin_transaction().await.unwrap();
+        index
+            .set("year", MetadataIndexValue::Int(2020), 1)
+            .await
+            .unwrap();
+        index
+            .set("year", MetadataIndexValue::Int(2021), 2)
+            .await
+            .unwrap();
+        index.commit_transaction().await.unwrap();
+
+        index.begin_transaction().await.unwrap();
+        index
+            .set("year", MetadataIndexValue::Int(2020), 3)
+            .await
+            .unwrap();
+        index
+            .delete("year", MetadataIndexValue::Int(2021), 2)
+            .await
+            .unwrap();
+
+        // Reads serve the last committed state while the transaction is open.
+        let bitmap = reader
+            .get("year", MetadataIndexValue::Int(2020))
+            .await
+            .unwrap();
+        assert_eq!(bitmap.iter().collect::<Vec<u32>>(), vec![1]);
+        let bitmap = reader
+            .get("year", MetadataIndexValue::Int(2021))
+            .await
+            .unwrap();
+        assert_eq!(bitmap.iter().collect::<Vec<u32>>(), vec![2]);
+        let bitmap = reader
+            .gte("year", MetadataIndexValue::Int(2020))
+            .await
+            .unwrap();
+        assert_eq!(bitmap.iter().collect::<Vec<u32>>(), vec![1, 2]);
+        let bitmap = reader
+            .not_eq("year", MetadataIndexValue::Int(2021))
+            .await
+            .unwrap();
+        assert_eq!(bitmap.iter().collect::<Vec<u32>>(), vec![1]);
+        let count = reader
+            .count("year", MetadataIndexValue::Int(2020))
+            .await
+            .unwrap();
+        assert_eq!(count, 1);
+
+        index.commit_transaction().await.unwrap();
+        let bitmap = reader
+            .get("year", MetadataIndexValue::Int(2020))
+            .await
+            .unwrap();
+        assert_eq!(bitmap.iter().collect::<Vec<u32>>(), vec![1, 3]);
+        let bitmap = reader
+            .not_eq("year", MetadataIndexValue::Int(2021))
+            .await
+            .unwrap();
+        assert_eq!(bitmap.iter().collect::<Vec<u32>>(), vec![1, 3]);
+    }
+
+    #[tokio::test]
+    async fn test_metadata_index_readers_observe_commits() {
+        let mut provider = HashMapBlockfileProvider::new();
+        let blockfile = provider
+            .create("test", KeyType::String, ValueType::RoaringBitmap)
+            .unwrap();
+        let mut index = BlockfileMetadataIndexWriter::new(blockfile);
+        let reader = index.reader();
+        index.begin_transaction().await.unwrap();
+        index
+            .set("year", MetadataIndexValue::Int(2020), 1)
+            .await
+            .unwrap();
+        index.commit_transaction().await.unwrap();
+
+        let mut handles = Vec::new();
+        for _ in 0..4 {
+            let reader = reader.clone();
+            handles.push(tokio::spawn(async move {
+                reader
+                    .not_eq("year", MetadataIndexValue::Int(2021))
+                    .await
+                    .unwrap()
+            }));
+        }
+        for handle in handles {
+            let bitmap = handle.await.unwrap();
+            assert_eq!(bitmap.iter().collect::<Vec<u32>>(), vec![1]);
+        }
+
+        index.begin_transaction().await.unwrap();
+        index
+            .set("year", MetadataIndexValue::Int(2021), 2)
+            .await
+            .unwrap();
+        index.commit_transaction().await.unwrap();
+        let bitmap = reader
+            .get("year", MetadataIndexValue::Int(2021))
+            .await
+            .unwrap();
+        assert_eq!(bitmap.iter().collect::<Vec<u32>>(), vec![2]);
+        let bitmap = reader
+            .not_eq("year", MetadataIndexValue::Int(2021))
+            .await
+            .unwrap();
+        assert_eq!(bitmap.iter().collect::<Vec<u32>>(), vec![1]);
+    }
+
+    #[tokio::test]
+    async fn test_metadata_index_null_and_exists() {
+        let mut provider = HashMapBlockfileProvider::new();
+        let blockfile = provider
+            .create("test", KeyType::String, ValueType::RoaringBitmap)
+            .unwrap();
+        let mut index = BlockfileMetadataIndexWriter::new(blockfile);
+        let reader = index.reader();
+        index.begin_transaction().await.unwrap();
+        index
+            .set("genre", MetadataIndexValue::String("drama".to_string()), 1)
+            .await
+            .unwrap();
+        index
+            .set("genre", MetadataIndexValue::Null, 2)
+            .await
+            .unwrap();
+        index
+            .set("year", MetadataIndexValue::Int(2020), 3)
+            .await
+            .unwrap();
+        index.commit_transaction().await.unwrap();
+
+        let bitmap = reader.get("genre", MetadataIndexValue::Null).await.unwrap();
+        assert_eq!(bitmap.iter().collect::<Vec<u32>>(), vec![2]);
+        let bitmap = reader.exists("genre").await.unwrap();
+        assert_eq!(bitmap.iter().collect::<Vec<u32>>(), vec![1, 2]);
+        let bitmap = reader.not_exists("genre").await.unwrap();
+        assert_eq!(bitmap.iter().collect::<Vec<u32>>(), vec![3]);
+        let result = reader.gt("genre", MetadataIndexValue::Null).await;
+        assert_eq!(result.unwrap_err().code(), ErrorCodes::InvalidArgument);
+
+        index.begin_transaction().await.unwrap();
+        // Offset 1 keeps a value for genre until its last value is deleted.
+        index
+            .set("genre", MetadataIndexValue::String("comedy".to_string()), 1)
+            .await
+            .unwrap();
+        index
+            .delete("genre", MetadataIndexValue::String("drama".to_string()), 1)
+            .await
+            .unwrap();
+        index.commit_transaction().await.unwrap();
+        let bitmap = reader.exists("genre").await.unwrap();
+        assert_eq!(bitmap.iter().collect::<Vec<u32>>(), vec![1, 2]);
+
+        index.begin_transaction().await.unwrap();
+        index
+            .delete("genre", MetadataIndexValue::String("comedy".to_string()), 1)
+            .await
+            .unwrap();
+        index.delete_key("genre", 2).await.unwrap();
+        index.rename_key("year", "released").await.unwrap();
+        index.commit_transaction().await.unwrap();
+        let bitmap = reader.not_exists("genre").await.unwrap();
+        assert_eq!(bitmap.iter().collect::<Vec<u32>>(), vec![3]);
+        assert_eq!(reader.exists("genre").await.unwrap().len(), 0);
+        assert_eq!(reader.exists("year").await.unwrap().len(), 0);
+        let bitmap = reader.exists("released").await.unwrap();
+        assert_eq!(bitmap.iter().collect::<Vec<u32>>(), vec![3]);
+    }
+
+    #[tokio::test]
+    async fn test_metadata_index_non_finite_floats() {
+        let mut provider = HashMapBlockfileProvider::new();
+        let blockfile = provider
+            .create("test", KeyType::String, ValueType::RoaringBitmap)
+            .unwrap();
+        let forward_blockfile = provider
+            .create("forward", KeyType::Int, ValueType::String)
+            .unwrap();
+        let mut index = BlockfileMetadataIndexWriter::new(blockfile)
+            .with_forward_index(forward_blockfile)
+            .with_composite_index("score", "name")
+            .unwrap();
+        let reader = index.reader();
+        let values = [f64::NEG_INFINITY, -1.0, -0.0, 1.0, f64::INFINITY, -f64::NAN];
+        index.begin_transaction().await.unwrap();
+        for (offset_id, value) in values.iter().enumerate() {
+            // Built directly rather than through From, so not canonicalized yet.
+            index
+                .set("score", MetadataIndexValue::Float(*value), offset_id)
+                .await
+                .unwrap();
+            index
+                .set(
+                    "name",
+                    MetadataIndexValue::String("a".to_string()),
+                    offset_id,
+                )
+                .await
+                .unwrap();
+        }
+        index.commit_transaction().await.unwrap();
+
+        let bitmap = reader
+            .get("score", MetadataIndexValue::Float(0.0))
+            .await
+            .unwrap();
+        assert_eq!(bitmap.iter().collect::<Vec<u32>>(), vec![2]);
+        let bitmap = reader
+            .get("score", MetadataIndexValue::Float(f64::NAN))
+            .await
+            .unwrap();
+        assert_eq!(bitmap.iter().collect::<Vec<u32>>(), vec![5]);
+        let bitmap = reader
+            .gt("score", MetadataIndexValue::Float(1.0))
+            .await
+            .unwrap();
+        assert_eq!(bitmap.iter().collect::<Vec<u32>>(), vec![4, 5]);
+        let bitmap = reader
+            .lt("score", MetadataIndexValue::Float(f64::INFINITY))
+            .await
+            .unwrap();
+        assert_eq!(bitmap.iter().collect::<Vec<u32>>(), vec![0, 1, 2, 3]);
+        let bitmap = reader
+            .lte("score", MetadataIndexValue::Float(f64::NEG_INFINITY))
+            .await
+            .unwrap();
+        assert_eq!(bitmap.iter().collect::<Vec<u32>>(), vec![0]);
+
+        // Non-finite values round trip through the forward index and composite keys.
+        let metadata = reader.get_metadata(0).await.unwrap();
+        assert_eq!(
+            metadata.get("score"),
+            Some(&MetadataIndexValue::Float(f64::NEG_INFINITY))
+        );
+        let metadata = reader.get_metadata(5).await.unwrap();
+        match metadata.get("score") {
+            Some(MetadataIndexValue::Float(value)) => assert!(value.is_nan()),
+            _ => panic!("Expected a float"),
+        }
+        let bitmap = reader
+            .get_composite(
+                "score",
+                MetadataIndexValue::Float(f64::NAN),
+                "name",
+                MetadataIndexValue::String("a".to_string()),
+            )
+            .await
+            .unwrap();
+        assert_eq!(bitmap.iter().collect::<Vec<u32>>(), vec![5]);
+
+        let stats = reader.stats("score").await.unwrap().unwrap();
+        assert_eq!((stats.count, stats.min, stats.max), (3, -1.0, 1.0));
+    }
+
+    #[tokio::test]
+    async fn test_metadata_index_composite_index() {
+        let mut provider = HashMapBlockfileProvider::new();
+        let blockfile = provider
+            .create("test", KeyType::String, ValueType::RoaringBitmap)
+            .unwrap();
+        let forward_blockfile = provider
+            .create("forward", KeyType::Int, ValueType::String)
+            .unwrap();
+        let mut index = BlockfileMetadataIndexWriter::new(blockfile)
+            .with_forward_index(forward_blockfile)
+            .with_composite_index("tenant", "type")
+            .unwrap();
+        let reader = index.reader();
+        let tenant = |t: &str| MetadataIndexValue::String(t.to_string());
+        index.begin_transaction().await.unwrap();
+        index.set("tenant", tenant("a"), 1).await.unwrap();
+        index
+            .set("type", MetadataIndexValue::Int(1), 1)
+            .await
+            .unwrap();
+        index.set("tenant", tenant("a"), 2).await.unwrap();
+        index
+            .set("type", MetadataIndexValue::Int(2), 2)
+            .await
+            .unwrap();
+        index.set("tenant", tenant("b"), 3).await.unwrap();
+        index
+            .set("type", MetadataIndexValue::Int(1), 3)
+            .await
+            .unwrap();
+        index.commit_transaction().await.unwrap();
+
+        let bitmap = reader
+            .get_composite("tenant", tenant("a"), "type", MetadataIndexValue::Int(1))
+            .await
+            .unwrap();
+        assert_eq!(bitmap.iter().collect::<Vec<u32>>(), vec![1]);
+        let bitmap = reader
+            .get_composite("type", MetadataIndexValue::Int(1), "tenant", tenant("b"))
+            .await
+            .unwrap();
+        assert_eq!(bitmap.iter().collect::<Vec<u32>>(), vec![3]);
+
+        index.begin_transaction().await.unwrap();
+        index
+            .set("type", MetadataIndexValue::Int(1), 2)
+            .await
+            .unwrap();
+        index.delete_key("tenant", 1).await.unwrap();
+        index.commit_transaction().await.unwrap();
+
+        let bitmap = reader
+            .get_composite("tenant", tenant("a"), "type", MetadataIndexValue::Int(1))
+            .await
+            .unwrap();
+        assert_eq!(bitmap.iter().collect::<Vec<u32>>(), vec![2]);
+        let bitmap = reader
+            .get_composite("tenant", tenant("a"), "type", MetadataIndexValue::Int(2))
+            .await
+            .unwrap();
+        assert_eq!(bitmap.len(), 0);
+        let result = reader
+            .get_composite("tenant", tenant("a"), "year", MetadataIndexValue::Int(1))
+            .await;
+        assert_eq!(result.is_err(), true);
+    }
+}
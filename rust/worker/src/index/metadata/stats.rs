@@ -0,0 +1,204 @@
+use super::encoding::value_to_posting;
+use super::types::{KeyStats, STATS_HISTOGRAM_BUCKETS};
+use crate::blockstore::{Blockfile, Key};
+use crate::errors::ChromaError;
+
+pub(super) fn compute_key_stats(
+    blockfile: &dyn Blockfile,
+    key: &str,
+) -> Result<Option<KeyStats>, Box<dyn ChromaError>> {
+    let mut values = Vec::new();
+    for (blockfilekey, value) in blockfile.get_by_prefix(key)? {
+        if let Some(numeric_value) = numeric_key_value(&blockfilekey.key) {
+            let count = value_to_posting(value).map_or(0, |rbm| rbm.len());
+            values.push((numeric_value, count));
+        }
+    }
+    Ok(key_stats_from_counts(values))
+}
+
+pub(super) fn key_stats_from_counts(values: Vec<(f64, u64)>) -> Option<KeyStats> {
+    let values = values
+        .into_iter()
+        .filter(|(_, count)| *count > 0)
+        .collect::<Vec<_>>();
+    if values.is_empty() {
+        return None;
+    }
+    let min = values.iter().map(|(v, _)| *v).fold(f64::INFINITY, f64::min);
+    let max = values
+        .iter()
+        .map(|(v, _)| *v)
+        .fold(f64::NEG_INFINITY, f64::max);
+    let mut histogram = vec![0; STATS_HISTOGRAM_BUCKETS];
+    for (value, count) in values.iter() {
+        histogram[histogram_bucket(*value, min, max)] += count;
+    }
+    Some(KeyStats {
+        count: values.iter().map(|(_, count)| count).sum(),
+        min,
+        max,
+        histogram,
+    })
+}
+
+// Applies the change from the committed to the new length of the posting of each value
+// to the statistics of a key. Returns None if the statistics need a scan of the key,
+// because a value outside of [min, max] was added or the posting of min or max emptied.
+pub(super) fn update_key_stats(
+    stats: &Option<KeyStats>,
+    deltas: &[(f64, u64, u64)],
+) -> Option<Option<KeyStats>> {
+    let stats = match stats {
+        Some(stats) => stats,
+        // The key had no numeric value, so the written values are all of them.
+        None => {
+            let values = deltas.iter().map(|(value, _, len)| (*value, *len));
+            return Some(key_stats_from_counts(values.collect()));
+        }
+    };
+    let mut updated = stats.clone();
+    for (value, committed_len, len) in deltas {
+        if *value < stats.min || *value > stats.max {
+            return None;
+        }
+        if *len == 0 && (*value == stats.min || *value == stats.max) {
+            return None;
+        }
+        let bucket = histogram_bucket(*value, stats.min, stats.max);
+        updated.count = updated.count + len - committed_len;
+        updated.histogram[bucket] = updated.histogram[bucket] + len - committed_len;
+    }
+    Some(Some(updated))
+}
+
+pub(super) fn histogram_bucket(value: f64, min: f64, max: f64) -> usize {
+    let bucket = if max > min {
+        ((value - min) / (max - min) * STATS_HISTOGRAM_BUCKETS as f64) as usize
+    } else {
+        0
+    };
+    bucket.min(STATS_HISTOGRAM_BUCKETS - 1)
+}
+
+// Statistics only cover finite floats and integers.
+pub(super) fn numeric_key_value(key: &Key) -> Option<f64> {
+    match key {
+        Key::Float(f) if f.is_finite() => Some(*f),
+        Key::Int(i) => Some(*i as f64),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blockstore::provider::{BlockfileProvider, HashMapBlockfileProvider};
+    use crate::index::metadata::types::{
+        MetadataIndexReader, MetadataIndexValue, MetadataIndexWriter,
+    };
+    use crate::index::metadata::writer::BlockfileMetadataIndexWriter;
+    use uuid::Uuid;
+
+    #[tokio::test]
+    async fn test_metadata_index_stats() {
+        let mut provider = HashMapBlockfileProvider::new();
+        let id = Uuid::new_v4();
+        let mut index = BlockfileMetadataIndexWriter::create(&mut provider, &id).unwrap();
+        let reader = index.reader();
+        index.begin_transaction().await.unwrap();
+        for offset_id in 0..10 {
+            index
+                .set(
+                    "score",
+                    MetadataIndexValue::Int(offset_id as i64),
+                    offset_id,
+                )
+                .await
+                .unwrap();
+        }
+        index
+            .set("score", MetadataIndexValue::Float(20.0), 10)
+            .await
+            .unwrap();
+        index
+            .set("score", MetadataIndexValue::Float(f64::NAN), 11)
+            .await
+            .unwrap();
+        index
+            .set("name", MetadataIndexValue::String("a".to_string()), 0)
+            .await
+            .unwrap();
+        index.commit_transaction().await.unwrap();
+
+        let stats = reader.stats("score").await.unwrap().unwrap();
+        assert_eq!(stats.count, 11);
+        assert_eq!(stats.min, 0.0);
+        assert_eq!(stats.max, 20.0);
+        assert_eq!(stats.histogram.len(), STATS_HISTOGRAM_BUCKETS);
+        assert_eq!(stats.histogram.iter().sum::<u64>(), 11);
+        // Buckets are 1.25 wide, so 0 and 1 share the first one.
+        assert_eq!(stats.histogram[0], 2);
+        assert_eq!(stats.histogram[STATS_HISTOGRAM_BUCKETS - 1], 1);
+        assert_eq!(reader.stats("name").await.unwrap(), None);
+
+        index.begin_transaction().await.unwrap();
+        index.delete_all(10).await.unwrap();
+        index.commit_transaction().await.unwrap();
+        let stats = reader.stats("score").await.unwrap().unwrap();
+        assert_eq!(stats.count, 10);
+        assert_eq!(stats.max, 9.0);
+
+        // Statistics of a reopened index are computed from its postings.
+        drop(index);
+        let index = BlockfileMetadataIndexWriter::open(&provider, &id).unwrap();
+        let reopened_stats = index.reader().stats("score").await.unwrap().unwrap();
+        assert_eq!(reopened_stats, stats);
+    }
+
+    #[tokio::test]
+    async fn test_metadata_index_stats_updated_on_commit() {
+        let mut provider = HashMapBlockfileProvider::new();
+        let id = Uuid::new_v4();
+        let mut index = BlockfileMetadataIndexWriter::create(&mut provider, &id).unwrap();
+        let reader = index.reader();
+        index.begin_transaction().await.unwrap();
+        for offset_id in 0..10 {
+            index
+                .set(
+                    "score",
+                    MetadataIndexValue::Int(offset_id as i64),
+                    offset_id,
+                )
+                .await
+                .unwrap();
+        }
+        index.commit_transaction().await.unwrap();
+
+        // Within [min, max] the statistics are updated in place, otherwise the key is
+        // scanned. Either way they match a scan of the committed postings.
+        let writes: Vec<(bool, MetadataIndexValue, usize)> = vec![
+            (true, MetadataIndexValue::Int(5), 20),
+            (false, MetadataIndexValue::Int(3), 3),
+            (true, MetadataIndexValue::Float(4.5), 21),
+            (true, MetadataIndexValue::Float(100.0), 22),
+            (false, MetadataIndexValue::Float(100.0), 22),
+            (false, MetadataIndexValue::Int(0), 0),
+        ];
+        for (set, value, offset_id) in writes {
+            index.begin_transaction().await.unwrap();
+            if set {
+                index.set("score", value, offset_id).await.unwrap();
+            } else {
+                index.delete("score", value, offset_id).await.unwrap();
+            }
+            index.commit_transaction().await.unwrap();
+            assert_eq!(
+                reader.stats("score").await.unwrap(),
+                compute_key_stats(reader.blockfile.as_ref(), "score").unwrap()
+            );
+        }
+        let stats = reader.stats("score").await.unwrap().unwrap();
+        assert_eq!((stats.count, stats.min, stats.max), (10, 1.0, 9.0));
+    }
+}
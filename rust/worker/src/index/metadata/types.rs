@@ -1,12 +1,9 @@
-use crate::blockstore::provider::BlockfileProvider;
-use crate::blockstore::{Blockfile, BlockfileKey, HashMapBlockfile, Key, Value};
-use crate::errors::{ChromaError, ErrorCodes};
+use crate::errors::{ChromaError, ErrorCategory, ErrorCodes};
+use crate::types::MetadataValue;
 use async_trait::async_trait;
 use roaring::RoaringBitmap;
-use std::{
-    collections::HashMap,
-    ops::{BitOrAssign, SubAssign},
-};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use thiserror::Error;
 
 #[derive(Debug, Error)]
@@ -17,6 +14,24 @@ pub(crate) enum MetadataIndexError {
     InTransaction,
     #[error("This operation can only be done in a transaction")]
     NotInTransaction,
+    #[error("The index has no forward index")]
+    NoForwardIndex,
+    #[error("Key `{0}` is stored but not indexed")]
+    KeyNotIndexed(String),
+    #[error("No composite index on keys `{0}` and `{1}`")]
+    NoCompositeIndex(String, String),
+    #[error("Offset id {0} exceeds the largest offset id the index can hold")]
+    OffsetIdExhausted(usize),
+    #[error("A list value of key `{0}` cannot be used as a single value")]
+    UnexpectedList(String),
+    #[error("Null values of key `{0}` cannot be compared")]
+    NullNotComparable(String),
+    #[error("The transaction holds about {0} bytes of uncommitted state, over the limit")]
+    TransactionTooLarge(usize),
+    #[error("A commit of the index failed, the writer must be reopened")]
+    WriterPoisoned,
+    #[error("Stored metadata could not be encoded or decoded")]
+    MetadataEncodingError(#[from] serde_json::Error),
 }
 
 impl ChromaError for MetadataIndexError {
@@ -25,370 +40,346 @@ impl ChromaError for MetadataIndexError {
             MetadataIndexError::NotFoundError => ErrorCodes::InvalidArgument,
             MetadataIndexError::InTransaction => ErrorCodes::InvalidArgument,
             MetadataIndexError::NotInTransaction => ErrorCodes::InvalidArgument,
+            MetadataIndexError::NoForwardIndex => ErrorCodes::FailedPrecondition,
+            MetadataIndexError::KeyNotIndexed(_) => ErrorCodes::InvalidArgument,
+            MetadataIndexError::NoCompositeIndex(_, _) => ErrorCodes::InvalidArgument,
+            MetadataIndexError::OffsetIdExhausted(_) => ErrorCodes::ResourceExhausted,
+            MetadataIndexError::UnexpectedList(_) => ErrorCodes::InvalidArgument,
+            MetadataIndexError::NullNotComparable(_) => ErrorCodes::InvalidArgument,
+            MetadataIndexError::TransactionTooLarge(_) => ErrorCodes::ResourceExhausted,
+            MetadataIndexError::WriterPoisoned => ErrorCodes::Internal,
+            MetadataIndexError::MetadataEncodingError(_) => ErrorCodes::DataLoss,
+        }
+    }
+
+    // Exhausting the offset ids or the transaction budget is not transient, retrying the
+    // same writes fails the same way.
+    fn category(&self) -> ErrorCategory {
+        match self {
+            MetadataIndexError::OffsetIdExhausted(_)
+            | MetadataIndexError::TransactionTooLarge(_) => ErrorCategory::User,
+            _ => self.code().category(),
         }
     }
 }
 
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub(crate) enum MetadataIndexValue {
     String(String),
-    Float(f32),
+    Float(#[serde(with = "float_encoding")] f64),
     Bool(bool),
+    Int(i64),
+    // Milliseconds since the Unix epoch. Timestamps are indexed as integer keys so that
+    // time windows are answered by range scans. A key should not hold both integers and
+    // timestamps: equal values share a posting and values_for_key reports them as Int.
+    Timestamp(i64),
+    // Sets the offset under every element of the list. Lists are only accepted by the
+    // write methods, and are not covered by composite postings.
+    List(Vec<MetadataIndexValue>),
+    // The key is set without a value. Null is matched by get and counts as existing,
+    // but cannot be used in range queries.
+    Null,
 }
 
-pub(crate) trait MetadataIndex {
-    fn begin_transaction(&mut self) -> Result<(), Box<dyn ChromaError>>;
-    fn commit_transaction(&mut self) -> Result<(), Box<dyn ChromaError>>;
-
-    // Must be in a transaction to put or delete.
-    fn set(
-        &mut self,
-        key: &str,
-        value: MetadataIndexValue,
-        offset_id: usize,
-    ) -> Result<(), Box<dyn ChromaError>>;
-    // Can delete anything -- if it's not in committed state the delete will be silently discarded.
-    fn delete(
-        &mut self,
-        key: &str,
-        value: MetadataIndexValue,
-        offset_id: usize,
-    ) -> Result<(), Box<dyn ChromaError>>;
-
-    // Always reads from committed state.
-    fn get(
-        &self,
-        key: &str,
-        value: MetadataIndexValue,
-    ) -> Result<RoaringBitmap, Box<dyn ChromaError>>;
+impl MetadataIndexValue {
+    // Returns the values an offset is set under, flattening nested lists.
+    pub(super) fn elements(self) -> Vec<MetadataIndexValue> {
+        match self {
+            MetadataIndexValue::List(values) => values
+                .into_iter()
+                .flat_map(MetadataIndexValue::elements)
+                .collect(),
+            value => vec![value],
+        }
+    }
 }
 
-struct BlockfileMetadataIndex {
-    blockfile: Box<dyn Blockfile>,
-    in_transaction: bool,
-    uncommitted_rbms: HashMap<BlockfileKey, RoaringBitmap>,
+/// Float keys are ordered by their bits (see Key), so values that compare equal as
+/// floats but differ in bits would land in different postings. Every f64 is passed
+/// through this conversion when it is turned into a key or encoded, on write and on
+/// query, so that equal inputs always map to the same key.
+/// # Notes
+/// NaN and the infinities are accepted. -0.0 is folded into 0.0 and every NaN into
+/// f64::NAN, which sorts after infinity so that it is matched by gt and gte of any other
+/// value and by lt and lte of NaN only. -inf and inf are the smallest and largest
+/// values other than NaN.
+pub(crate) fn canonicalize_float(value: f64) -> f64 {
+    if value == 0.0 {
+        0.0
+    } else if value.is_nan() {
+        f64::NAN
+    } else {
+        value
+    }
 }
 
-impl BlockfileMetadataIndex {
-    pub fn new(init_blockfile: Box<dyn Blockfile>) -> Self {
-        BlockfileMetadataIndex {
-            blockfile: init_blockfile,
-            in_transaction: false,
-            uncommitted_rbms: HashMap::new(),
-        }
+impl From<f64> for MetadataIndexValue {
+    fn from(value: f64) -> Self {
+        MetadataIndexValue::Float(canonicalize_float(value))
     }
+}
 
-    fn look_up_key_and_populate_uncommitted_rbms(
-        &mut self,
-        key: &BlockfileKey,
-    ) -> Result<(), Box<dyn ChromaError>> {
-        if !self.uncommitted_rbms.contains_key(&key) {
-            match self.blockfile.get(key.clone()) {
-                Ok(Value::RoaringBitmapValue(rbm)) => {
-                    self.uncommitted_rbms.insert(key.clone(), rbm);
-                }
-                _ => {
-                    let rbm = RoaringBitmap::new();
-                    self.uncommitted_rbms.insert(key.clone(), rbm);
-                }
-            };
+impl From<&MetadataValue> for MetadataIndexValue {
+    fn from(value: &MetadataValue) -> Self {
+        match value {
+            MetadataValue::Int(value) => MetadataIndexValue::Int(*value as i64),
+            MetadataValue::Float(value) => MetadataIndexValue::from(*value),
+            MetadataValue::Str(value) => MetadataIndexValue::String(value.clone()),
         }
-        Ok(())
     }
 }
 
-impl MetadataIndex for BlockfileMetadataIndex {
-    fn begin_transaction(&mut self) -> Result<(), Box<dyn ChromaError>> {
-        if self.in_transaction {
-            return Err(Box::new(MetadataIndexError::InTransaction));
-        }
-        self.blockfile.begin_transaction()?;
-        self.in_transaction = true;
-        Ok(())
+// JSON has no representation for NaN and the infinities, so the forward index and
+// composite keys encode them as strings.
+mod float_encoding {
+    use super::canonicalize_float;
+    use serde::{de, Deserialize, Deserializer, Serializer};
+
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum EncodedFloat {
+        Number(f64),
+        String(String),
     }
 
-    fn commit_transaction(&mut self) -> Result<(), Box<dyn ChromaError>> {
-        if !self.in_transaction {
-            return Err(Box::new(MetadataIndexError::NotInTransaction));
+    pub(super) fn serialize<S: Serializer>(value: &f64, serializer: S) -> Result<S::Ok, S::Error> {
+        let value = canonicalize_float(*value);
+        if value.is_nan() {
+            serializer.serialize_str("NaN")
+        } else if value == f64::INFINITY {
+            serializer.serialize_str("Infinity")
+        } else if value == f64::NEG_INFINITY {
+            serializer.serialize_str("-Infinity")
+        } else {
+            serializer.serialize_f64(value)
         }
-        for (key, rbm) in self.uncommitted_rbms.drain() {
-            self.blockfile
-                .set(key.clone(), Value::RoaringBitmapValue(rbm.clone()));
+    }
+
+    pub(super) fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<f64, D::Error> {
+        match EncodedFloat::deserialize(deserializer)? {
+            EncodedFloat::Number(value) => Ok(value),
+            EncodedFloat::String(value) => match value.as_str() {
+                "NaN" => Ok(f64::NAN),
+                "Infinity" => Ok(f64::INFINITY),
+                "-Infinity" => Ok(f64::NEG_INFINITY),
+                _ => Err(de::Error::custom(format!("invalid float `{}`", value))),
+            },
         }
-        self.blockfile.commit_transaction()?;
-        self.in_transaction = false;
-        self.uncommitted_rbms.clear();
-        Ok(())
     }
+}
 
-    fn set(
+/// Summary of the numeric values of a key, used to estimate the selectivity of filters.
+/// # Fields
+/// - count: The number of (offset, value) pairs with a numeric value. An offset set to
+///   several values of the key through a list is counted once per value.
+/// - min, max: The smallest and largest numeric value. Integers and timestamps are
+///   converted to f64. NaN and the infinities are left out.
+/// - histogram: The count of values in each of STATS_HISTOGRAM_BUCKETS equal-width
+///   buckets between min and max. Every value is in the first bucket if min == max.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct KeyStats {
+    pub(crate) count: u64,
+    pub(crate) min: f64,
+    pub(crate) max: f64,
+    pub(crate) histogram: Vec<u64>,
+}
+
+pub(crate) const STATS_HISTOGRAM_BUCKETS: usize = 16;
+
+// The writer owns the transaction and the uncommitted state. Writes are buffered until
+// commit_transaction, so readers never see uncommitted state and may serve reads while
+// a transaction is open.
+#[async_trait]
+pub(crate) trait MetadataIndexWriter: Send + Sync {
+    async fn begin_transaction(&mut self) -> Result<(), Box<dyn ChromaError>>;
+    async fn commit_transaction(&mut self) -> Result<(), Box<dyn ChromaError>>;
+    // Estimated size in bytes of the postings and metadata held in memory until the
+    // current transaction is committed.
+    fn current_memory_bytes(&self) -> usize;
+
+    // Must be in a transaction to put or delete.
+    async fn set(
         &mut self,
         key: &str,
         value: MetadataIndexValue,
         offset_id: usize,
-    ) -> Result<(), Box<dyn ChromaError>> {
-        if !self.in_transaction {
-            return Err(Box::new(MetadataIndexError::NotInTransaction));
-        }
-        let blockfilekey = kv_to_blockfile_key(key, value);
-        self.look_up_key_and_populate_uncommitted_rbms(&blockfilekey)?;
-        let mut rbm = self.uncommitted_rbms.get_mut(&blockfilekey).unwrap();
-        rbm.insert(offset_id.try_into().unwrap());
-        Ok(())
-    }
-
-    fn delete(
+    ) -> Result<(), Box<dyn ChromaError>>;
+    // Must be in a transaction. Same as calling set for every entry, but groups the
+    // entries by posting first so that each posting is looked up only once.
+    async fn set_many(
+        &mut self,
+        entries: Vec<(&str, MetadataIndexValue, usize)>,
+    ) -> Result<(), Box<dyn ChromaError>>;
+    // Can delete anything -- if it's not in committed state the delete will be silently discarded.
+    async fn delete(
         &mut self,
         key: &str,
         value: MetadataIndexValue,
         offset_id: usize,
-    ) -> Result<(), Box<dyn ChromaError>> {
-        if !self.in_transaction {
-            return Err(Box::new(MetadataIndexError::NotInTransaction));
-        }
-        let blockfilekey = kv_to_blockfile_key(key, value);
-        self.look_up_key_and_populate_uncommitted_rbms(&blockfilekey)?;
-        let mut rbm = self.uncommitted_rbms.get_mut(&blockfilekey).unwrap();
-        rbm.remove(offset_id.try_into().unwrap());
-        Ok(())
-    }
+    ) -> Result<(), Box<dyn ChromaError>>;
 
-    fn get(
-        &self,
+    // Must be in a transaction. Replaces the value of key for offset_id, deleting old
+    // and setting new, either of which may be None. If old is None the previous value
+    // is read from the forward index, which is then required.
+    async fn update(
+        &mut self,
         key: &str,
-        value: MetadataIndexValue,
-    ) -> Result<RoaringBitmap, Box<dyn ChromaError>> {
-        if self.in_transaction {
-            return Err(Box::new(MetadataIndexError::InTransaction));
-        }
-        let blockfilekey = kv_to_blockfile_key(key, value);
-        match self.blockfile.get(blockfilekey) {
-            Ok(Value::RoaringBitmapValue(rbm)) => Ok(rbm),
-            _ => Err(Box::new(MetadataIndexError::NotFoundError)),
-        }
-    }
-}
-
-fn kv_to_blockfile_key(key: &str, value: MetadataIndexValue) -> BlockfileKey {
-    let blockfilekey_key = match value {
-        MetadataIndexValue::String(s) => Key::String(s),
-        MetadataIndexValue::Float(f) => Key::Float(f),
-        MetadataIndexValue::Bool(b) => Key::Bool(b),
-    };
-    BlockfileKey::new(key.to_string(), blockfilekey_key)
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::blockstore::provider::HashMapBlockfileProvider;
-    use crate::blockstore::{KeyType, ValueType};
-
-    #[test]
-    fn test_string_value_metadata_index_error_when_not_in_transaction() {
-        let mut provider = HashMapBlockfileProvider::new();
-        let blockfile = provider
-            .create("test", KeyType::String, ValueType::RoaringBitmap)
-            .unwrap();
-        let mut index = BlockfileMetadataIndex::new(blockfile);
-        let result = index.set("key", MetadataIndexValue::String("value".to_string()), 1);
-        assert_eq!(result.is_err(), true);
-        let result = index.delete("key", MetadataIndexValue::String("value".to_string()), 1);
-        assert_eq!(result.is_err(), true);
-        let result = index.commit_transaction();
-        assert_eq!(result.is_err(), true);
-    }
-
-    #[test]
-    fn test_string_value_metadata_index_empty_transaction() {
-        let mut provider = HashMapBlockfileProvider::new();
-        let blockfile = provider
-            .create("test", KeyType::String, ValueType::RoaringBitmap)
-            .unwrap();
-        let mut index = BlockfileMetadataIndex::new(blockfile);
-        index.begin_transaction().unwrap();
-        index.commit_transaction().unwrap();
-    }
-
-    #[test]
-    fn test_string_value_metadata_index_set_get() {
-        let mut provider = HashMapBlockfileProvider::new();
-        let blockfile = provider
-            .create("test", KeyType::String, ValueType::RoaringBitmap)
-            .unwrap();
-        let mut index = BlockfileMetadataIndex::new(blockfile);
-        index.begin_transaction().unwrap();
-        index
-            .set("key", MetadataIndexValue::String("value".to_string()), 1)
-            .unwrap();
-        index.commit_transaction().unwrap();
-
-        let bitmap = index
-            .get("key", MetadataIndexValue::String("value".to_string()))
-            .unwrap();
-        assert_eq!(bitmap.len(), 1);
-        assert_eq!(bitmap.contains(1), true);
-    }
-
-    #[test]
-    fn test_float_value_metadata_index_set_get() {
-        let mut provider = HashMapBlockfileProvider::new();
-        let blockfile = provider
-            .create("test", KeyType::String, ValueType::RoaringBitmap)
-            .unwrap();
-        let mut index = BlockfileMetadataIndex::new(blockfile);
-        index.begin_transaction().unwrap();
-        index.set("key", MetadataIndexValue::Float(1.0), 1).unwrap();
-        index.commit_transaction().unwrap();
-
-        let bitmap = index.get("key", MetadataIndexValue::Float(1.0)).unwrap();
-        assert_eq!(bitmap.len(), 1);
-        assert_eq!(bitmap.contains(1), true);
-    }
-
-    #[test]
-    fn test_bool_value_metadata_index_set_get() {
-        let mut provider = HashMapBlockfileProvider::new();
-        let blockfile = provider
-            .create("test", KeyType::String, ValueType::RoaringBitmap)
-            .unwrap();
-        let mut index = BlockfileMetadataIndex::new(blockfile);
-        index.begin_transaction().unwrap();
-        index.set("key", MetadataIndexValue::Bool(true), 1).unwrap();
-        index.commit_transaction().unwrap();
+        old: Option<MetadataIndexValue>,
+        new: Option<MetadataIndexValue>,
+        offset_id: usize,
+    ) -> Result<(), Box<dyn ChromaError>>;
 
-        let bitmap = index.get("key", MetadataIndexValue::Bool(true)).unwrap();
-        assert_eq!(bitmap.len(), 1);
-        assert_eq!(bitmap.contains(1), true);
-    }
+    // Must be in a transaction. Removes offset_id from every posting it was set in.
+    async fn delete_all(&mut self, offset_id: usize) -> Result<(), Box<dyn ChromaError>>;
 
-    #[test]
-    fn test_string_value_metadata_index_set_delete_get() {
-        let mut provider = HashMapBlockfileProvider::new();
-        let blockfile = provider
-            .create("test", KeyType::String, ValueType::RoaringBitmap)
-            .unwrap();
-        let mut index = BlockfileMetadataIndex::new(blockfile);
-        index.begin_transaction().unwrap();
-        index
-            .set("key", MetadataIndexValue::String("value".to_string()), 1)
-            .unwrap();
-        index
-            .delete("key", MetadataIndexValue::String("value".to_string()), 1)
-            .unwrap();
-        index.commit_transaction().unwrap();
+    // Must be in a transaction. Moves every posting of old_key to new_key, merging
+    // with any postings new_key already has.
+    async fn rename_key(
+        &mut self,
+        old_key: &str,
+        new_key: &str,
+    ) -> Result<(), Box<dyn ChromaError>>;
+    // Must be in a transaction. Clears every posting of key.
+    async fn drop_key(&mut self, key: &str) -> Result<(), Box<dyn ChromaError>>;
+    // Must be in a transaction. Removes offset_id from the postings of every value of key.
+    async fn delete_key(&mut self, key: &str, offset_id: usize)
+        -> Result<(), Box<dyn ChromaError>>;
+}
 
-        let bitmap = index
-            .get("key", MetadataIndexValue::String("value".to_string()))
-            .unwrap();
-        assert_eq!(bitmap.len(), 0);
-    }
+#[async_trait]
+pub(crate) trait MetadataIndexReader: Send + Sync {
+    // Always reads from committed state.
+    async fn get(
+        &self,
+        key: &str,
+        value: MetadataIndexValue,
+    ) -> Result<RoaringBitmap, Box<dyn ChromaError>>;
+    // Always reads from committed state. Returns the offsets whose value for key_a is
+    // value_a and whose value for key_b is value_b, in a single lookup. Requires a
+    // composite index on the two keys.
+    async fn get_composite(
+        &self,
+        key_a: &str,
+        value_a: MetadataIndexValue,
+        key_b: &str,
+        value_b: MetadataIndexValue,
+    ) -> Result<RoaringBitmap, Box<dyn ChromaError>>;
+    // Always reads from committed state. Returns the number of offsets in the posting
+    // of value, 0 if it is not in the index.
+    async fn count(
+        &self,
+        key: &str,
+        value: MetadataIndexValue,
+    ) -> Result<u64, Box<dyn ChromaError>>;
+    // Always reads from committed state. Returns the union of the postings of values,
+    // skipping values that are not in the index.
+    async fn get_in(
+        &self,
+        key: &str,
+        values: Vec<MetadataIndexValue>,
+    ) -> Result<RoaringBitmap, Box<dyn ChromaError>>;
 
-    #[test]
-    fn test_string_value_metadata_index_set_delete_set_get() {
-        let mut provider = HashMapBlockfileProvider::new();
-        let blockfile = provider
-            .create("test", KeyType::String, ValueType::RoaringBitmap)
-            .unwrap();
-        let mut index = BlockfileMetadataIndex::new(blockfile);
-        index.begin_transaction().unwrap();
-        index
-            .set("key", MetadataIndexValue::String("value".to_string()), 1)
-            .unwrap();
-        index
-            .delete("key", MetadataIndexValue::String("value".to_string()), 1)
-            .unwrap();
-        index
-            .set("key", MetadataIndexValue::String("value".to_string()), 1)
-            .unwrap();
-        index.commit_transaction().unwrap();
+    // Always reads from committed state. Returns the union of the postings of every
+    // string value of key that starts with prefix, using the order of string keys to
+    // scan only from prefix onwards.
+    async fn get_prefix(
+        &self,
+        key: &str,
+        prefix: &str,
+    ) -> Result<RoaringBitmap, Box<dyn ChromaError>>;
 
-        let bitmap = index
-            .get("key", MetadataIndexValue::String("value".to_string()))
-            .unwrap();
-        assert_eq!(bitmap.len(), 1);
-        assert_eq!(bitmap.contains(1), true);
-    }
+    // Always read from committed state. Return the union of the postings of every
+    // value of key that compares against value. Ints and floats are compared by value,
+    // other types only against values of the same type. Returns an empty bitmap if
+    // nothing matches.
+    async fn gt(
+        &self,
+        key: &str,
+        value: MetadataIndexValue,
+    ) -> Result<RoaringBitmap, Box<dyn ChromaError>>;
+    async fn gte(
+        &self,
+        key: &str,
+        value: MetadataIndexValue,
+    ) -> Result<RoaringBitmap, Box<dyn ChromaError>>;
+    async fn lt(
+        &self,
+        key: &str,
+        value: MetadataIndexValue,
+    ) -> Result<RoaringBitmap, Box<dyn ChromaError>>;
+    async fn lte(
+        &self,
+        key: &str,
+        value: MetadataIndexValue,
+    ) -> Result<RoaringBitmap, Box<dyn ChromaError>>;
 
-    #[test]
-    fn test_string_value_metadata_index_multiple_keys() {
-        let mut provider = HashMapBlockfileProvider::new();
-        let blockfile = provider
-            .create("test", KeyType::String, ValueType::RoaringBitmap)
-            .unwrap();
-        let mut index = BlockfileMetadataIndex::new(blockfile);
-        index.begin_transaction().unwrap();
-        index
-            .set("key1", MetadataIndexValue::String("value".to_string()), 1)
-            .unwrap();
-        index
-            .set("key2", MetadataIndexValue::String("value".to_string()), 2)
-            .unwrap();
-        index.commit_transaction().unwrap();
+    // Always read from committed state. Return the offsets with a value for key, null
+    // included (for exists), or without one (for not_exists), so that a key set to a
+    // value that is not queried is told apart from an absent key.
+    async fn exists(&self, key: &str) -> Result<RoaringBitmap, Box<dyn ChromaError>>;
+    async fn not_exists(&self, key: &str) -> Result<RoaringBitmap, Box<dyn ChromaError>>;
 
-        let bitmap = index
-            .get("key1", MetadataIndexValue::String("value".to_string()))
-            .unwrap();
-        assert_eq!(bitmap.len(), 1);
-        assert_eq!(bitmap.contains(1), true);
+    // Always read from committed state. Return the offsets whose values for key
+    // include any of values (for contains_any) or all of values (for contains_all).
+    // contains_all of no values returns every offset set in the index.
+    async fn contains_any(
+        &self,
+        key: &str,
+        values: Vec<MetadataIndexValue>,
+    ) -> Result<RoaringBitmap, Box<dyn ChromaError>>;
+    async fn contains_all(
+        &self,
+        key: &str,
+        values: Vec<MetadataIndexValue>,
+    ) -> Result<RoaringBitmap, Box<dyn ChromaError>>;
 
-        let bitmap = index
-            .get("key2", MetadataIndexValue::String("value".to_string()))
-            .unwrap();
-        assert_eq!(bitmap.len(), 1);
-        assert_eq!(bitmap.contains(2), true);
-    }
+    // Always read from committed state. Return every offset set in the index that is
+    // not in the postings of value (for not_eq) or of any of values (for not_in),
+    // including offsets that have no value for key at all.
+    async fn not_eq(
+        &self,
+        key: &str,
+        value: MetadataIndexValue,
+    ) -> Result<RoaringBitmap, Box<dyn ChromaError>>;
+    async fn not_in(
+        &self,
+        key: &str,
+        values: Vec<MetadataIndexValue>,
+    ) -> Result<RoaringBitmap, Box<dyn ChromaError>>;
 
-    #[test]
-    fn test_string_value_metadata_index_multiple_values() {
-        let mut provider = HashMapBlockfileProvider::new();
-        let blockfile = provider
-            .create("test", KeyType::String, ValueType::RoaringBitmap)
-            .unwrap();
-        let mut index = BlockfileMetadataIndex::new(blockfile);
-        index.begin_transaction().unwrap();
-        index
-            .set("key", MetadataIndexValue::String("value1".to_string()), 1)
-            .unwrap();
-        index
-            .set("key", MetadataIndexValue::String("value2".to_string()), 2)
-            .unwrap();
-        index.commit_transaction().unwrap();
+    // Always reads from committed state. Returns every offset set in the index, the
+    // universe not_eq and not_in complement against.
+    async fn all_offsets(&self) -> Result<RoaringBitmap, Box<dyn ChromaError>>;
 
-        let bitmap = index
-            .get("key", MetadataIndexValue::String("value1".to_string()))
-            .unwrap();
-        assert_eq!(bitmap.len(), 1);
-        assert_eq!(bitmap.contains(1), true);
+    // Always reads from committed state. Returns the statistics of the float and int
+    // values of key, None if it has none.
+    async fn stats(&self, key: &str) -> Result<Option<KeyStats>, Box<dyn ChromaError>>;
 
-        let bitmap = index
-            .get("key", MetadataIndexValue::String("value2".to_string()))
-            .unwrap();
-        assert_eq!(bitmap.len(), 1);
-        assert_eq!(bitmap.contains(2), true);
-    }
+    // Always reads from committed state. Returns every value indexed under key with the
+    // number of offsets it is set for, ordered by value. Values with no offsets left are
+    // skipped.
+    async fn values_for_key(
+        &self,
+        key: &str,
+    ) -> Result<Vec<(MetadataIndexValue, u64)>, Box<dyn ChromaError>>;
 
-    #[test]
-    fn test_string_value_metadata_index_delete_in_standalone_transaction() {
-        let mut provider = HashMapBlockfileProvider::new();
-        let blockfile = provider
-            .create("test", KeyType::String, ValueType::RoaringBitmap)
-            .unwrap();
-        let mut index = BlockfileMetadataIndex::new(blockfile);
-        index.begin_transaction().unwrap();
-        index
-            .set("key", MetadataIndexValue::String("value".to_string()), 1)
-            .unwrap();
-        index.commit_transaction().unwrap();
+    // Always reads from committed state. Returns the metadata set for offset_id, empty
+    // if nothing is set. Requires a forward index.
+    async fn get_metadata(
+        &self,
+        offset_id: usize,
+    ) -> Result<HashMap<String, MetadataIndexValue>, Box<dyn ChromaError>>;
+}
 
-        index.begin_transaction().unwrap();
-        index
-            .delete("key", MetadataIndexValue::String("value".to_string()), 1)
-            .unwrap();
-        index.commit_transaction().unwrap();
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-        let bitmap = index
-            .get("key", MetadataIndexValue::String("value".to_string()))
-            .unwrap();
-        assert_eq!(bitmap.len(), 0);
+    #[tokio::test]
+    async fn test_canonicalize_float() {
+        assert_eq!(canonicalize_float(1.5), 1.5);
+        assert_eq!(canonicalize_float(0.1), 0.1);
+        assert_eq!(canonicalize_float(-0.0).to_bits(), 0.0f64.to_bits());
+        assert_eq!(canonicalize_float(-f64::NAN).to_bits(), f64::NAN.to_bits());
     }
 }
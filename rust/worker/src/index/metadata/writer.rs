@@ -0,0 +1,2326 @@
+use super::encoding::{
+    blockfile_key_to_offset, composite_blockfile_keys, exists_blockfile_key, kv_to_blockfile_key,
+    null_blockfile_key, offset_id_to_u32, offset_to_blockfile_key, offsets_blockfile_key,
+    posting_to_value, read_metadata, value_to_posting, EXISTS_PREFIX,
+};
+use super::reader::BlockfileMetadataIndexReader;
+use super::stats::{compute_key_stats, numeric_key_value, update_key_stats};
+use super::types::{KeyStats, MetadataIndexError, MetadataIndexValue, MetadataIndexWriter};
+use crate::blockstore::provider::BlockfileProvider;
+use crate::blockstore::{Blockfile, BlockfileKey, KeyPartitionedBlockfile, Value, ValueType};
+use crate::errors::ChromaError;
+use async_trait::async_trait;
+use parking_lot::RwLock;
+use roaring::RoaringBitmap;
+use std::{
+    collections::{hash_map::Entry, HashMap, HashSet},
+    ops::BitOrAssign,
+    sync::Arc,
+};
+use uuid::Uuid;
+
+pub(crate) struct BlockfileMetadataIndexWriter {
+    blockfile: Box<dyn Blockfile>,
+    in_transaction: bool,
+    uncommitted_rbms: HashMap<BlockfileKey, RoaringBitmap>,
+    // Without a forward index, the keys of the postings each offset is set in, committed
+    // or not, so that delete_all finds them without a scan. Built from the postings on
+    // the first delete_all and kept up to date by every write after that. With a forward
+    // index the keys are derived from the metadata of the offset instead.
+    offset_postings: Option<HashMap<u32, HashSet<BlockfileKey>>>,
+    // Every offset set in at least one posting as of the last commit, shared with every
+    // reader.
+    committed_offsets: Arc<RwLock<RoaringBitmap>>,
+    // Statistics of the keys written since the index was created or opened, updated on
+    // each commit and shared with every reader.
+    key_stats: Arc<RwLock<HashMap<String, Option<KeyStats>>>>,
+    // Optional forward index from an offset to its serialized metadata. It is written
+    // in the same transactions as the postings.
+    forward_blockfile: Option<Box<dyn Blockfile>>,
+    uncommitted_metadata: HashMap<u32, HashMap<String, MetadataIndexValue>>,
+    // Keys whose values are only kept in the forward index, without postings.
+    unindexed_keys: HashSet<String>,
+    // Pairs of keys with a composite posting per pair of values.
+    composite_indexes: Vec<(String, String)>,
+    // Offset ids past which a warning is logged, and the largest offset id written.
+    offset_id_warning_thresholds: Vec<u32>,
+    max_offset_id: u32,
+    // Estimated size of the uncommitted postings and metadata, and the size past which
+    // writes are rejected until the transaction is committed.
+    uncommitted_bytes: usize,
+    max_uncommitted_bytes: Option<usize>,
+    // Set when a commit fails. The committed state may be partially written, so no
+    // further transaction is started.
+    poisoned: bool,
+}
+
+// Estimated overhead of each uncommitted metadata map and of each of its entries.
+const UNCOMMITTED_METADATA_BYTES: usize = 64;
+
+impl BlockfileMetadataIndexWriter {
+    pub fn new(init_blockfile: Box<dyn Blockfile>) -> Self {
+        BlockfileMetadataIndexWriter {
+            blockfile: init_blockfile,
+            in_transaction: false,
+            uncommitted_rbms: HashMap::new(),
+            offset_postings: None,
+            committed_offsets: Arc::new(RwLock::new(RoaringBitmap::new())),
+            key_stats: Arc::new(RwLock::new(HashMap::new())),
+            forward_blockfile: None,
+            uncommitted_metadata: HashMap::new(),
+            unindexed_keys: HashSet::new(),
+            composite_indexes: Vec::new(),
+            offset_id_warning_thresholds: Vec::new(),
+            max_offset_id: 0,
+            uncommitted_bytes: 0,
+            max_uncommitted_bytes: None,
+            poisoned: false,
+        }
+    }
+
+    // Caps the estimated memory held by a transaction. Once a transaction holds more
+    // than max_bytes, writes fail with TransactionTooLarge without being applied, and
+    // the caller is expected to commit and continue in a new transaction. The check
+    // happens before each write, so a single write can overshoot the cap.
+    pub fn with_max_transaction_bytes(mut self, max_bytes: usize) -> Self {
+        self.max_uncommitted_bytes = Some(max_bytes);
+        self
+    }
+
+    // Sets the fractions of the u32 offset id space at which a warning is logged the
+    // first time a write reaches them, so a segment running out of offset ids is
+    // noticed before writes start failing. No warnings are logged by default.
+    pub fn with_offset_id_warning_thresholds(mut self, fractions: &[f64]) -> Self {
+        self.offset_id_warning_thresholds = offset_id_thresholds(fractions);
+        self
+    }
+
+    // Creates a new index whose postings are kept in a blockfile created in provider
+    // under id. Values of different types are keys of different types, so the postings
+    // are kept in one blockfile per key type.
+    pub fn create<P: BlockfileProvider>(
+        provider: &mut P,
+        id: &Uuid,
+    ) -> Result<Self, Box<dyn ChromaError>> {
+        match KeyPartitionedBlockfile::create(provider, &id.to_string(), ValueType::RoaringBitmap) {
+            Ok(blockfile) => Ok(Self::new(Box::new(blockfile))),
+            Err(e) => Err(e),
+        }
+    }
+
+    // Reopens the index created under id in provider. The offsets set in the index are
+    // restored from the last commit so that complement queries cover them.
+    pub fn open<P: BlockfileProvider>(
+        provider: &P,
+        id: &Uuid,
+    ) -> Result<Self, Box<dyn ChromaError>> {
+        let blockfile: Box<dyn Blockfile> =
+            match KeyPartitionedBlockfile::open(provider, &id.to_string()) {
+                Ok(blockfile) => Box::new(blockfile),
+                Err(e) => return Err(e),
+            };
+        let all_offsets = match blockfile.get(offsets_blockfile_key()).map(value_to_posting) {
+            Ok(Some(all_offsets)) => all_offsets,
+            _ => RoaringBitmap::new(),
+        };
+        let mut index = Self::new(blockfile);
+        index.max_offset_id = all_offsets.max().unwrap_or(0);
+        *index.committed_offsets.write() = all_offsets;
+        Ok(index)
+    }
+
+    // Returns a reader of the committed state of this index.
+    pub fn reader(&self) -> BlockfileMetadataIndexReader {
+        BlockfileMetadataIndexReader {
+            blockfile: self.blockfile.clone(),
+            forward_blockfile: self.forward_blockfile.clone(),
+            committed_offsets: self.committed_offsets.clone(),
+            key_stats: self.key_stats.clone(),
+            unindexed_keys: Arc::new(self.unindexed_keys.clone()),
+            composite_indexes: Arc::new(self.composite_indexes.clone()),
+        }
+    }
+
+    fn record_offset_id_written(&mut self, offset_id: u32) {
+        if offset_id <= self.max_offset_id {
+            return;
+        }
+        for threshold in self.offset_id_warning_thresholds.iter() {
+            if self.max_offset_id < *threshold && offset_id >= *threshold {
+                tracing::warn!(
+                    "Metadata index offset id {} passed warning threshold {} of {}",
+                    offset_id,
+                    threshold,
+                    u32::MAX
+                );
+            }
+        }
+        self.max_offset_id = offset_id;
+    }
+
+    // Adds a composite index on key_a and key_b, answering get_composite for the two
+    // keys with a single posting lookup instead of intersecting two postings.
+    // Composite postings are derived from the forward index, so with_forward_index has
+    // to be called first. Fails with NoForwardIndex otherwise.
+    pub fn with_composite_index(
+        mut self,
+        key_a: &str,
+        key_b: &str,
+    ) -> Result<Self, Box<dyn ChromaError>> {
+        if self.forward_blockfile.is_none() {
+            return Err(Box::new(MetadataIndexError::NoForwardIndex));
+        }
+        self.composite_indexes
+            .push((key_a.to_string(), key_b.to_string()));
+        Ok(self)
+    }
+
+    // Applies update to the uncommitted metadata of offset_id and moves the offset
+    // between composite postings to match the metadata after the update.
+    fn update_metadata<F>(&mut self, offset_id: u32, update: F) -> Result<(), Box<dyn ChromaError>>
+    where
+        F: FnOnce(&mut HashMap<String, MetadataIndexValue>),
+    {
+        let composite_indexes = self.composite_indexes.clone();
+        let (before, after) = match self.metadata_for_offset(offset_id)? {
+            Some(metadata) => {
+                let before = composite_blockfile_keys(&composite_indexes, metadata);
+                update(metadata);
+                (
+                    before,
+                    composite_blockfile_keys(&composite_indexes, metadata),
+                )
+            }
+            None => return Ok(()),
+        };
+        for blockfilekey in before.iter().filter(|key| !after.contains(key)) {
+            self.look_up_key_and_populate_uncommitted_rbms(blockfilekey)?;
+            let rbm = self.uncommitted_rbms.get_mut(blockfilekey).unwrap();
+            rbm.remove(offset_id);
+        }
+        for blockfilekey in after.iter().filter(|key| !before.contains(key)) {
+            self.look_up_key_and_populate_uncommitted_rbms(blockfilekey)?;
+            let rbm = self.uncommitted_rbms.get_mut(blockfilekey).unwrap();
+            rbm.insert(offset_id);
+        }
+        Ok(())
+    }
+
+    // Stores the values of unindexed_keys in the forward index only. No postings are
+    // created for them, so they cannot be queried, which keeps verbose free-form
+    // metadata from growing the index. rename_key and drop_key find the offsets of an
+    // unindexed key by scanning the forward index.
+    pub fn with_unindexed_keys(mut self, unindexed_keys: HashSet<String>) -> Self {
+        self.unindexed_keys = unindexed_keys;
+        self
+    }
+
+    // Keeps a forward index from each offset to its metadata in forward_blockfile, a
+    // blockfile of int keys and string values, so that the metadata of filtered offsets
+    // can be read back with get_metadata.
+    pub fn with_forward_index(mut self, forward_blockfile: Box<dyn Blockfile>) -> Self {
+        self.forward_blockfile = Some(forward_blockfile);
+        self
+    }
+
+    // Returns the uncommitted metadata of offset_id, loading it from committed state
+    // first, or None if there is no forward index.
+    fn metadata_for_offset(
+        &mut self,
+        offset_id: u32,
+    ) -> Result<Option<&mut HashMap<String, MetadataIndexValue>>, Box<dyn ChromaError>> {
+        let forward_blockfile = match &self.forward_blockfile {
+            Some(forward_blockfile) => forward_blockfile,
+            None => return Ok(None),
+        };
+        let metadata = match self.uncommitted_metadata.entry(offset_id) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => {
+                let metadata = read_metadata(forward_blockfile.as_ref(), offset_id)?;
+                self.uncommitted_bytes += UNCOMMITTED_METADATA_BYTES
+                    + metadata
+                        .keys()
+                        .map(|key| key.len() + UNCOMMITTED_METADATA_BYTES)
+                        .sum::<usize>();
+                entry.insert(metadata)
+            }
+        };
+        Ok(Some(metadata))
+    }
+
+    // Accounts for offsets added to uncommitted postings.
+    fn add_posting_bytes(&mut self, offset_count: u64) {
+        self.uncommitted_bytes += offset_count as usize * std::mem::size_of::<u32>();
+    }
+
+    fn check_transaction_size(&self) -> Result<(), Box<dyn ChromaError>> {
+        match self.max_uncommitted_bytes {
+            Some(max_bytes) if self.uncommitted_bytes > max_bytes => Err(Box::new(
+                MetadataIndexError::TransactionTooLarge(self.uncommitted_bytes),
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    fn look_up_key_and_populate_uncommitted_rbms(
+        &mut self,
+        key: &BlockfileKey,
+    ) -> Result<(), Box<dyn ChromaError>> {
+        if !self.uncommitted_rbms.contains_key(key) {
+            let rbm = match self.blockfile.get(key.clone()).map(value_to_posting) {
+                Ok(Some(rbm)) => rbm,
+                _ => RoaringBitmap::new(),
+            };
+            self.uncommitted_bytes += key.prefix.len() + key.key.get_size() + rbm.serialized_size();
+            self.uncommitted_rbms.insert(key.clone(), rbm);
+        }
+        Ok(())
+    }
+
+    // Returns the blockfile keys of every value stored under key, committed or not,
+    // along with the null and exists postings of key.
+    fn blockfilekeys_for_key(&self, key: &str) -> Result<Vec<BlockfileKey>, Box<dyn ChromaError>> {
+        let mut blockfilekeys: Vec<BlockfileKey> = self
+            .blockfile
+            .get_by_prefix(key)?
+            .map(|(blockfilekey, _)| blockfilekey)
+            .collect();
+        for blockfilekey in self.uncommitted_rbms.keys() {
+            if blockfilekey.prefix == key && !blockfilekeys.contains(blockfilekey) {
+                blockfilekeys.push(blockfilekey.clone());
+            }
+        }
+        blockfilekeys.push(null_blockfile_key(key));
+        blockfilekeys.push(exists_blockfile_key(key));
+        Ok(blockfilekeys)
+    }
+
+    fn mark_exists(&mut self, key: &str, offset_id: u32) -> Result<(), Box<dyn ChromaError>> {
+        let blockfilekey = exists_blockfile_key(key);
+        self.look_up_key_and_populate_uncommitted_rbms(&blockfilekey)?;
+        let rbm = self.uncommitted_rbms.get_mut(&blockfilekey).unwrap();
+        rbm.insert(offset_id);
+        self.add_posting_bytes(1);
+        self.track_posting(offset_id, &blockfilekey);
+        Ok(())
+    }
+
+    // Records that offset_id is set in the posting of blockfilekey, once offset_postings
+    // is built.
+    fn track_posting(&mut self, offset_id: u32, blockfilekey: &BlockfileKey) {
+        if let Some(offset_postings) = self.offset_postings.as_mut() {
+            offset_postings
+                .entry(offset_id)
+                .or_default()
+                .insert(blockfilekey.clone());
+        }
+    }
+
+    // Maps every offset to the keys of the postings it is set in, committed or not, with
+    // a single scan of the blockfile.
+    fn build_offset_postings(
+        &self,
+    ) -> Result<HashMap<u32, HashSet<BlockfileKey>>, Box<dyn ChromaError>> {
+        let mut offset_postings: HashMap<u32, HashSet<BlockfileKey>> = HashMap::new();
+        let mut add_posting = |blockfilekey: &BlockfileKey, rbm: &RoaringBitmap| {
+            for offset_id in rbm.iter() {
+                offset_postings
+                    .entry(offset_id)
+                    .or_default()
+                    .insert(blockfilekey.clone());
+            }
+        };
+        for entry in self.blockfile.iter()? {
+            let (blockfilekey, value) = entry?;
+            if blockfilekey == offsets_blockfile_key()
+                || self.uncommitted_rbms.contains_key(&blockfilekey)
+            {
+                continue;
+            }
+            if let Some(rbm) = value_to_posting(value) {
+                add_posting(&blockfilekey, &rbm);
+            }
+        }
+        for (blockfilekey, rbm) in self.uncommitted_rbms.iter() {
+            add_posting(blockfilekey, rbm);
+        }
+        Ok(offset_postings)
+    }
+
+    // Returns the keys of the postings offset_id may be set in, committed or not. They
+    // are derived from the metadata of the offset if there is a forward index, and
+    // taken out of offset_postings otherwise.
+    fn take_blockfilekeys_for_offset(
+        &mut self,
+        offset_id: u32,
+    ) -> Result<Vec<BlockfileKey>, Box<dyn ChromaError>> {
+        if self.forward_blockfile.is_some() {
+            let metadata = match self.metadata_for_offset(offset_id)? {
+                Some(metadata) => metadata.clone(),
+                None => HashMap::new(),
+            };
+            let mut blockfilekeys = Vec::new();
+            for (key, value) in metadata {
+                if self.unindexed_keys.contains(&key) {
+                    continue;
+                }
+                for element in value.elements() {
+                    blockfilekeys.push(kv_to_blockfile_key(&key, element)?);
+                }
+                blockfilekeys.push(exists_blockfile_key(&key));
+            }
+            return Ok(blockfilekeys);
+        }
+        if self.offset_postings.is_none() {
+            self.offset_postings = Some(self.build_offset_postings()?);
+        }
+        let offset_postings = self.offset_postings.as_mut().unwrap();
+        Ok(offset_postings
+            .remove(&offset_id)
+            .map(|blockfilekeys| blockfilekeys.into_iter().collect())
+            .unwrap_or_default())
+    }
+
+    // Sets key to value for offset_id, in the postings of every element of value and in
+    // the metadata of offset_id.
+    fn set_offset(
+        &mut self,
+        key: &str,
+        value: MetadataIndexValue,
+        offset_id: u32,
+    ) -> Result<(), Box<dyn ChromaError>> {
+        self.record_offset_id_written(offset_id);
+        if !self.unindexed_keys.contains(key) {
+            for element in value.clone().elements() {
+                let blockfilekey = kv_to_blockfile_key(key, element)?;
+                self.look_up_key_and_populate_uncommitted_rbms(&blockfilekey)?;
+                let rbm = self.uncommitted_rbms.get_mut(&blockfilekey).unwrap();
+                rbm.insert(offset_id);
+                self.add_posting_bytes(1);
+                self.track_posting(offset_id, &blockfilekey);
+            }
+            self.mark_exists(key, offset_id)?;
+        }
+        self.update_metadata(offset_id, |metadata| {
+            metadata.insert(key.to_string(), value);
+        })?;
+        Ok(())
+    }
+
+    // Returns whether offset_id is in the posting of a value of key or in its null
+    // posting, committed or not.
+    fn is_set_for_key(&self, key: &str, offset_id: u32) -> Result<bool, Box<dyn ChromaError>> {
+        let null_blockfilekey = null_blockfile_key(key);
+        let is_posting_of_key = |blockfilekey: &BlockfileKey| {
+            blockfilekey.prefix == key || *blockfilekey == null_blockfilekey
+        };
+        for (blockfilekey, rbm) in self.uncommitted_rbms.iter() {
+            if is_posting_of_key(blockfilekey) && rbm.contains(offset_id) {
+                return Ok(true);
+            }
+        }
+        let null_posting = self
+            .blockfile
+            .get(null_blockfilekey.clone())
+            .ok()
+            .map(|value| (null_blockfilekey.clone(), value));
+        for (blockfilekey, value) in self.blockfile.get_by_prefix(key)?.chain(null_posting) {
+            if self.uncommitted_rbms.contains_key(&blockfilekey) {
+                continue;
+            }
+            if value_to_posting(value).map_or(false, |rbm| rbm.contains(offset_id)) {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    // Removes offset_id from the exists posting of key once it is in no posting of a
+    // value of key.
+    fn unmark_exists_if_unset(
+        &mut self,
+        key: &str,
+        offset_id: u32,
+    ) -> Result<(), Box<dyn ChromaError>> {
+        if self.is_set_for_key(key, offset_id)? {
+            return Ok(());
+        }
+        let blockfilekey = exists_blockfile_key(key);
+        self.look_up_key_and_populate_uncommitted_rbms(&blockfilekey)?;
+        let rbm = self.uncommitted_rbms.get_mut(&blockfilekey).unwrap();
+        rbm.remove(offset_id);
+        Ok(())
+    }
+
+    // Returns the offsets whose metadata holds key, committed or not. Unindexed keys
+    // have no postings, so their offsets are only found by scanning the forward index.
+    fn offsets_with_metadata_key(&self, key: &str) -> Result<RoaringBitmap, Box<dyn ChromaError>> {
+        let mut offsets = RoaringBitmap::new();
+        let forward_blockfile = match &self.forward_blockfile {
+            Some(forward_blockfile) => forward_blockfile,
+            None => return Ok(offsets),
+        };
+        for entry in forward_blockfile.iter()? {
+            let (blockfilekey, value) = entry?;
+            let offset_id = match blockfile_key_to_offset(&blockfilekey) {
+                Some(offset_id) => offset_id,
+                None => continue,
+            };
+            if self.uncommitted_metadata.contains_key(&offset_id) {
+                continue;
+            }
+            if let Value::StringValue(metadata) = value {
+                let metadata: HashMap<String, MetadataIndexValue> =
+                    match serde_json::from_str(&metadata) {
+                        Ok(metadata) => metadata,
+                        Err(e) => {
+                            return Err(Box::new(MetadataIndexError::MetadataEncodingError(e)))
+                        }
+                    };
+                if metadata.contains_key(key) {
+                    offsets.insert(offset_id);
+                }
+            }
+        }
+        for (offset_id, metadata) in self.uncommitted_metadata.iter() {
+            if metadata.contains_key(key) {
+                offsets.insert(*offset_id);
+            }
+        }
+        Ok(offsets)
+    }
+
+    // Takes the postings of every value stored under key, committed or not, leaving
+    // empty postings behind. The blockfile has no delete so the empty postings act
+    // as tombstones once committed.
+    fn take_postings_for_key(
+        &mut self,
+        key: &str,
+    ) -> Result<Vec<(BlockfileKey, RoaringBitmap)>, Box<dyn ChromaError>> {
+        let mut postings = Vec::new();
+        for blockfilekey in self.blockfilekeys_for_key(key)? {
+            self.look_up_key_and_populate_uncommitted_rbms(&blockfilekey)?;
+            let rbm = self.uncommitted_rbms.get_mut(&blockfilekey).unwrap();
+            let posting = std::mem::take(rbm);
+            postings.push((blockfilekey, posting));
+        }
+        Ok(postings)
+    }
+
+    // Writes the uncommitted postings and metadata and commits both blockfiles.
+    fn commit(&mut self) -> Result<(), Box<dyn ChromaError>> {
+        // Every offset set in a posting is in the exists posting of its key.
+        let mut all_offsets = RoaringBitmap::new();
+        for (blockfilekey, value) in self.blockfile.get_by_prefix(EXISTS_PREFIX)? {
+            if self.uncommitted_rbms.contains_key(&blockfilekey) {
+                continue;
+            }
+            if let Some(rbm) = value_to_posting(value) {
+                all_offsets.bitor_assign(rbm);
+            }
+        }
+        for (blockfilekey, rbm) in self.uncommitted_rbms.iter() {
+            if blockfilekey.prefix == EXISTS_PREFIX {
+                all_offsets.bitor_assign(rbm);
+            }
+        }
+
+        // The statistics of a key are updated by the change in length of each numeric
+        // posting written. Reserved and composite prefixes contain a NUL byte.
+        let numeric_keys = self
+            .uncommitted_rbms
+            .keys()
+            .filter(|key| !key.prefix.contains('\0') && numeric_key_value(&key.key).is_some())
+            .cloned()
+            .collect::<Vec<_>>();
+        let committed_postings = self.blockfile.get_many(&numeric_keys)?;
+        let mut stats_deltas: HashMap<String, Vec<(f64, u64, u64)>> = HashMap::new();
+        for (key, committed_posting) in numeric_keys.into_iter().zip(committed_postings) {
+            let committed_len = committed_posting
+                .and_then(value_to_posting)
+                .map_or(0, |rbm| rbm.len());
+            let len = self.uncommitted_rbms[&key].len();
+            if let Some(value) = numeric_key_value(&key.key) {
+                if len != committed_len {
+                    stats_deltas
+                        .entry(key.prefix)
+                        .or_default()
+                        .push((value, committed_len, len));
+                }
+            }
+        }
+        // Every write is staged in both blockfiles before either is committed, so that
+        // an encoding or write error leaves the committed state untouched.
+        let mut forward_entries = Vec::with_capacity(self.uncommitted_metadata.len());
+        if self.forward_blockfile.is_some() {
+            for (offset_id, metadata) in self.uncommitted_metadata.iter() {
+                let metadata = match serde_json::to_string(metadata) {
+                    Ok(metadata) => metadata,
+                    Err(e) => return Err(Box::new(MetadataIndexError::MetadataEncodingError(e))),
+                };
+                forward_entries.push((
+                    offset_to_blockfile_key(*offset_id),
+                    Value::StringValue(metadata),
+                ));
+            }
+        }
+        let inline_postings = self.blockfile.value_type().is_none();
+        for (key, rbm) in self.uncommitted_rbms.drain() {
+            self.blockfile
+                .set(key, posting_to_value(rbm, inline_postings))?;
+        }
+        self.blockfile.set(
+            offsets_blockfile_key(),
+            Value::RoaringBitmapValue(all_offsets.clone()),
+        )?;
+        if let Some(forward_blockfile) = self.forward_blockfile.as_mut() {
+            for (blockfilekey, value) in forward_entries {
+                forward_blockfile.set(blockfilekey, value)?;
+            }
+        }
+        self.blockfile.commit_transaction()?;
+        if let Some(forward_blockfile) = self.forward_blockfile.as_mut() {
+            forward_blockfile.commit_transaction()?;
+        }
+
+        *self.committed_offsets.write() = all_offsets;
+        for (key, deltas) in stats_deltas {
+            let cached = self.key_stats.read().get(&key).cloned();
+            let stats = match cached.and_then(|stats| update_key_stats(&stats, &deltas)) {
+                Some(stats) => Ok(stats),
+                None => compute_key_stats(self.blockfile.as_ref(), &key),
+            };
+            match stats {
+                Ok(stats) => {
+                    self.key_stats.write().insert(key, stats);
+                }
+                // The commit has succeeded, readers compute the statistics of a key
+                // missing from the cache on demand.
+                Err(e) => {
+                    tracing::warn!("Failed to update the statistics of key {}: {}", key, e);
+                    self.key_stats.write().remove(&key);
+                }
+            }
+        }
+        self.in_transaction = false;
+        self.uncommitted_rbms.clear();
+        self.uncommitted_metadata.clear();
+        self.uncommitted_bytes = 0;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl MetadataIndexWriter for BlockfileMetadataIndexWriter {
+    async fn begin_transaction(&mut self) -> Result<(), Box<dyn ChromaError>> {
+        if self.poisoned {
+            return Err(Box::new(MetadataIndexError::WriterPoisoned));
+        }
+        if self.in_transaction {
+            return Err(Box::new(MetadataIndexError::InTransaction));
+        }
+        self.blockfile.begin_transaction()?;
+        if let Some(forward_blockfile) = self.forward_blockfile.as_mut() {
+            forward_blockfile.begin_transaction()?;
+        }
+        self.in_transaction = true;
+        Ok(())
+    }
+
+    async fn commit_transaction(&mut self) -> Result<(), Box<dyn ChromaError>> {
+        if !self.in_transaction {
+            return Err(Box::new(MetadataIndexError::NotInTransaction));
+        }
+        // A failed commit may have committed the postings without the forward index, and
+        // has drained part of the uncommitted state, so the writer refuses any further
+        // transaction and has to be reopened from the committed state.
+        if let Err(e) = self.commit() {
+            self.in_transaction = false;
+            self.poisoned = true;
+            return Err(e);
+        }
+        Ok(())
+    }
+
+    fn current_memory_bytes(&self) -> usize {
+        self.uncommitted_bytes
+    }
+
+    async fn set(
+        &mut self,
+        key: &str,
+        value: MetadataIndexValue,
+        offset_id: usize,
+    ) -> Result<(), Box<dyn ChromaError>> {
+        if !self.in_transaction {
+            return Err(Box::new(MetadataIndexError::NotInTransaction));
+        }
+        self.check_transaction_size()?;
+        self.set_offset(key, value, offset_id_to_u32(offset_id)?)
+    }
+
+    async fn set_many(
+        &mut self,
+        entries: Vec<(&str, MetadataIndexValue, usize)>,
+    ) -> Result<(), Box<dyn ChromaError>> {
+        if !self.in_transaction {
+            return Err(Box::new(MetadataIndexError::NotInTransaction));
+        }
+        self.check_transaction_size()?;
+        // Converted up front so that an exhausted offset id rejects the whole batch.
+        let entries = entries
+            .into_iter()
+            .map(|(key, value, offset_id)| Ok((key, value, offset_id_to_u32(offset_id)?)))
+            .collect::<Result<Vec<(&str, MetadataIndexValue, u32)>, Box<dyn ChromaError>>>()?;
+        let mut postings: HashMap<BlockfileKey, RoaringBitmap> = HashMap::new();
+        for (key, value, offset_id) in entries {
+            self.record_offset_id_written(offset_id);
+            if !self.unindexed_keys.contains(key) {
+                for element in value.clone().elements() {
+                    postings
+                        .entry(kv_to_blockfile_key(key, element)?)
+                        .or_default()
+                        .insert(offset_id);
+                }
+                postings
+                    .entry(exists_blockfile_key(key))
+                    .or_default()
+                    .insert(offset_id);
+            }
+            self.update_metadata(offset_id, |metadata| {
+                metadata.insert(key.to_string(), value);
+            })?;
+        }
+        for (blockfilekey, posting) in postings {
+            self.look_up_key_and_populate_uncommitted_rbms(&blockfilekey)?;
+            self.add_posting_bytes(posting.len());
+            for offset_id in posting.iter() {
+                self.track_posting(offset_id, &blockfilekey);
+            }
+            let rbm = self.uncommitted_rbms.get_mut(&blockfilekey).unwrap();
+            rbm.bitor_assign(posting);
+        }
+        Ok(())
+    }
+
+    async fn delete(
+        &mut self,
+        key: &str,
+        value: MetadataIndexValue,
+        offset_id: usize,
+    ) -> Result<(), Box<dyn ChromaError>> {
+        if !self.in_transaction {
+            return Err(Box::new(MetadataIndexError::NotInTransaction));
+        }
+        self.check_transaction_size()?;
+        let offset_id = offset_id_to_u32(offset_id)?;
+        if !self.unindexed_keys.contains(key) {
+            for element in value.clone().elements() {
+                let blockfilekey = kv_to_blockfile_key(key, element)?;
+                self.look_up_key_and_populate_uncommitted_rbms(&blockfilekey)?;
+                let rbm = self.uncommitted_rbms.get_mut(&blockfilekey).unwrap();
+                rbm.remove(offset_id);
+            }
+            self.unmark_exists_if_unset(key, offset_id)?;
+        }
+        self.update_metadata(offset_id, |metadata| {
+            if metadata.get(key) == Some(&value) {
+                metadata.remove(key);
+            }
+        })?;
+        Ok(())
+    }
+
+    async fn update(
+        &mut self,
+        key: &str,
+        old: Option<MetadataIndexValue>,
+        new: Option<MetadataIndexValue>,
+        offset_id: usize,
+    ) -> Result<(), Box<dyn ChromaError>> {
+        if !self.in_transaction {
+            return Err(Box::new(MetadataIndexError::NotInTransaction));
+        }
+        self.check_transaction_size()?;
+        let old = match old {
+            Some(old) => Some(old),
+            None => match self.metadata_for_offset(offset_id_to_u32(offset_id)?)? {
+                Some(metadata) => metadata.get(key).cloned(),
+                None => return Err(Box::new(MetadataIndexError::NoForwardIndex)),
+            },
+        };
+        if old == new {
+            return Ok(());
+        }
+        if let Some(old) = old {
+            self.delete(key, old, offset_id).await?;
+        }
+        if let Some(new) = new {
+            self.set(key, new, offset_id).await?;
+        }
+        Ok(())
+    }
+
+    async fn delete_all(&mut self, offset_id: usize) -> Result<(), Box<dyn ChromaError>> {
+        if !self.in_transaction {
+            return Err(Box::new(MetadataIndexError::NotInTransaction));
+        }
+        self.check_transaction_size()?;
+        let offset_id = offset_id_to_u32(offset_id)?;
+        let blockfilekeys = self.take_blockfilekeys_for_offset(offset_id)?;
+        self.update_metadata(offset_id, |metadata| metadata.clear())?;
+        for blockfilekey in blockfilekeys {
+            self.look_up_key_and_populate_uncommitted_rbms(&blockfilekey)?;
+        }
+        // Every posting loaded so far is updated, in case a posting holds the offset
+        // without matching its metadata.
+        for rbm in self.uncommitted_rbms.values_mut() {
+            rbm.remove(offset_id);
+        }
+        Ok(())
+    }
+
+    async fn rename_key(
+        &mut self,
+        old_key: &str,
+        new_key: &str,
+    ) -> Result<(), Box<dyn ChromaError>> {
+        if !self.in_transaction {
+            return Err(Box::new(MetadataIndexError::NotInTransaction));
+        }
+        self.check_transaction_size()?;
+        if old_key == new_key {
+            return Ok(());
+        }
+        if self.unindexed_keys.contains(old_key) {
+            for offset_id in self.offsets_with_metadata_key(old_key)?.iter() {
+                let mut value = None;
+                self.update_metadata(offset_id, |metadata| value = metadata.remove(old_key))?;
+                if let Some(value) = value {
+                    self.set_offset(new_key, value, offset_id)?;
+                }
+            }
+            return Ok(());
+        }
+        for (old_blockfilekey, posting) in self.take_postings_for_key(old_key)? {
+            let new_blockfilekey = if old_blockfilekey == null_blockfile_key(old_key) {
+                null_blockfile_key(new_key)
+            } else if old_blockfilekey == exists_blockfile_key(old_key) {
+                exists_blockfile_key(new_key)
+            } else {
+                BlockfileKey::new(new_key.to_string(), old_blockfilekey.key)
+            };
+            self.look_up_key_and_populate_uncommitted_rbms(&new_blockfilekey)?;
+            self.add_posting_bytes(posting.len());
+            for offset_id in posting.iter() {
+                self.update_metadata(offset_id, |metadata| {
+                    if let Some(value) = metadata.remove(old_key) {
+                        metadata.insert(new_key.to_string(), value);
+                    }
+                })?;
+                self.track_posting(offset_id, &new_blockfilekey);
+            }
+            let rbm = self.uncommitted_rbms.get_mut(&new_blockfilekey).unwrap();
+            rbm.bitor_assign(posting);
+        }
+        Ok(())
+    }
+
+    async fn drop_key(&mut self, key: &str) -> Result<(), Box<dyn ChromaError>> {
+        if !self.in_transaction {
+            return Err(Box::new(MetadataIndexError::NotInTransaction));
+        }
+        self.check_transaction_size()?;
+        if self.unindexed_keys.contains(key) {
+            for offset_id in self.offsets_with_metadata_key(key)?.iter() {
+                self.update_metadata(offset_id, |metadata| {
+                    metadata.remove(key);
+                })?;
+            }
+            return Ok(());
+        }
+        for (_, posting) in self.take_postings_for_key(key)? {
+            for offset_id in posting.iter() {
+                self.update_metadata(offset_id, |metadata| {
+                    metadata.remove(key);
+                })?;
+            }
+        }
+        Ok(())
+    }
+
+    async fn delete_key(
+        &mut self,
+        key: &str,
+        offset_id: usize,
+    ) -> Result<(), Box<dyn ChromaError>> {
+        if !self.in_transaction {
+            return Err(Box::new(MetadataIndexError::NotInTransaction));
+        }
+        self.check_transaction_size()?;
+        let offset_id = offset_id_to_u32(offset_id)?;
+        for blockfilekey in self.blockfilekeys_for_key(key)? {
+            self.look_up_key_and_populate_uncommitted_rbms(&blockfilekey)?;
+            let rbm = self.uncommitted_rbms.get_mut(&blockfilekey).unwrap();
+            rbm.remove(offset_id);
+        }
+        self.update_metadata(offset_id, |metadata| {
+            metadata.remove(key);
+        })?;
+        Ok(())
+    }
+}
+
+fn offset_id_thresholds(fractions: &[f64]) -> Vec<u32> {
+    fractions
+        .iter()
+        .map(|fraction| (u32::MAX as f64 * fraction) as u32)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blockstore::arrow_blockfile::cache::BlockCache;
+    use crate::blockstore::arrow_blockfile::provider::ArrowBlockfileProvider;
+    use crate::blockstore::config::{BlockCompression, BlockSizeConfig};
+    use crate::blockstore::provider::HashMapBlockfileProvider;
+    use crate::blockstore::KeyType;
+    use crate::errors::{ErrorCategory, ErrorCodes};
+    use crate::index::metadata::types::MetadataIndexReader;
+    use crate::types::MetadataValue;
+
+    #[tokio::test]
+    async fn test_string_value_metadata_index_error_when_not_in_transaction() {
+        let mut provider = HashMapBlockfileProvider::new();
+        let blockfile = provider
+            .create("test", KeyType::String, ValueType::RoaringBitmap)
+            .unwrap();
+        let mut index = BlockfileMetadataIndexWriter::new(blockfile);
+        let result = index
+            .set("key", MetadataIndexValue::String("value".to_string()), 1)
+            .await;
+        assert_eq!(result.is_err(), true);
+        let result = index
+            .delete("key", MetadataIndexValue::String("value".to_string()), 1)
+            .await;
+        assert_eq!(result.is_err(), true);
+        let result = index.commit_transaction().await;
+        assert_eq!(result.is_err(), true);
+    }
+
+    #[tokio::test]
+    async fn test_string_value_metadata_index_empty_transaction() {
+        let mut provider = HashMapBlockfileProvider::new();
+        let blockfile = provider
+            .create("test", KeyType::String, ValueType::RoaringBitmap)
+            .unwrap();
+        let mut index = BlockfileMetadataIndexWriter::new(blockfile);
+        index.begin_transaction().await.unwrap();
+        index.commit_transaction().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_string_value_metadata_index_set_get() {
+        let mut provider = HashMapBlockfileProvider::new();
+        let blockfile = provider
+            .create("test", KeyType::String, ValueType::RoaringBitmap)
+            .unwrap();
+        let mut index = BlockfileMetadataIndexWriter::new(blockfile);
+        let reader = index.reader();
+        index.begin_transaction().await.unwrap();
+        index
+            .set("key", MetadataIndexValue::String("value".to_string()), 1)
+            .await
+            .unwrap();
+        index.commit_transaction().await.unwrap();
+
+        let bitmap = reader
+            .get("key", MetadataIndexValue::String("value".to_string()))
+            .await
+            .unwrap();
+        assert_eq!(bitmap.len(), 1);
+        assert_eq!(bitmap.contains(1), true);
+    }
+
+    #[tokio::test]
+    async fn test_float_value_metadata_index_set_get() {
+        let mut provider = HashMapBlockfileProvider::new();
+        let blockfile = provider
+            .create("test", KeyType::String, ValueType::RoaringBitmap)
+            .unwrap();
+        let mut index = BlockfileMetadataIndexWriter::new(blockfile);
+        let reader = index.reader();
+        index.begin_transaction().await.unwrap();
+        index
+            .set("key", MetadataIndexValue::Float(1.0), 1)
+            .await
+            .unwrap();
+        index.commit_transaction().await.unwrap();
+
+        let bitmap = reader
+            .get("key", MetadataIndexValue::Float(1.0))
+            .await
+            .unwrap();
+        assert_eq!(bitmap.len(), 1);
+        assert_eq!(bitmap.contains(1), true);
+    }
+
+    #[tokio::test]
+    async fn test_bool_value_metadata_index_set_get() {
+        let mut provider = HashMapBlockfileProvider::new();
+        let blockfile = provider
+            .create("test", KeyType::String, ValueType::RoaringBitmap)
+            .unwrap();
+        let mut index = BlockfileMetadataIndexWriter::new(blockfile);
+        let reader = index.reader();
+        index.begin_transaction().await.unwrap();
+        index
+            .set("key", MetadataIndexValue::Bool(true), 1)
+            .await
+            .unwrap();
+        index.commit_transaction().await.unwrap();
+
+        let bitmap = reader
+            .get("key", MetadataIndexValue::Bool(true))
+            .await
+            .unwrap();
+        assert_eq!(bitmap.len(), 1);
+        assert_eq!(bitmap.contains(1), true);
+    }
+
+    #[tokio::test]
+    async fn test_int_value_metadata_index_set_get() {
+        let mut provider = HashMapBlockfileProvider::new();
+        let blockfile = provider
+            .create("test", KeyType::String, ValueType::RoaringBitmap)
+            .unwrap();
+        let mut index = BlockfileMetadataIndexWriter::new(blockfile);
+        let reader = index.reader();
+        index.begin_transaction().await.unwrap();
+        index
+            .set("key", MetadataIndexValue::Int(16777217), 1)
+            .await
+            .unwrap();
+        index
+            .set("key", MetadataIndexValue::Int(16777216), 2)
+            .await
+            .unwrap();
+        index
+            .set("key", MetadataIndexValue::Int(-1), 3)
+            .await
+            .unwrap();
+        index.commit_transaction().await.unwrap();
+
+        let bitmap = reader
+            .get("key", MetadataIndexValue::Int(16777217))
+            .await
+            .unwrap();
+        assert_eq!(bitmap.len(), 1);
+        assert_eq!(bitmap.contains(1), true);
+        let bitmap = reader
+            .get("key", MetadataIndexValue::Int(-1))
+            .await
+            .unwrap();
+        assert_eq!(bitmap.len(), 1);
+        assert_eq!(bitmap.contains(3), true);
+    }
+
+    #[tokio::test]
+    async fn test_string_value_metadata_index_set_delete_get() {
+        let mut provider = HashMapBlockfileProvider::new();
+        let blockfile = provider
+            .create("test", KeyType::String, ValueType::RoaringBitmap)
+            .unwrap();
+        let mut index = BlockfileMetadataIndexWriter::new(blockfile);
+        let reader = index.reader();
+        index.begin_transaction().await.unwrap();
+        index
+            .set("key", MetadataIndexValue::String("value".to_string()), 1)
+            .await
+            .unwrap();
+        index
+            .delete("key", MetadataIndexValue::String("value".to_string()), 1)
+            .await
+            .unwrap();
+        index.commit_transaction().await.unwrap();
+
+        let bitmap = reader
+            .get("key", MetadataIndexValue::String("value".to_string()))
+            .await
+            .unwrap();
+        assert_eq!(bitmap.len(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_string_value_metadata_index_set_delete_set_get() {
+        let mut provider = HashMapBlockfileProvider::new();
+        let blockfile = provider
+            .create("test", KeyType::String, ValueType::RoaringBitmap)
+            .unwrap();
+        let mut index = BlockfileMetadataIndexWriter::new(blockfile);
+        let reader = index.reader();
+        index.begin_transaction().await.unwrap();
+        index
+            .set("key", MetadataIndexValue::String("value".to_string()), 1)
+            .await
+            .unwrap();
+        index
+            .delete("key", MetadataIndexValue::String("value".to_string()), 1)
+            .await
+            .unwrap();
+        index
+            .set("key", MetadataIndexValue::String("value".to_string()), 1)
+            .await
+            .unwrap();
+        index.commit_transaction().await.unwrap();
+
+        let bitmap = reader
+            .get("key", MetadataIndexValue::String("value".to_string()))
+            .await
+            .unwrap();
+        assert_eq!(bitmap.len(), 1);
+        assert_eq!(bitmap.contains(1), true);
+    }
+
+    #[tokio::test]
+    async fn test_string_value_metadata_index_multiple_keys() {
+        let mut provider = HashMapBlockfileProvider::new();
+        let blockfile = provider
+            .create("test", KeyType::String, ValueType::RoaringBitmap)
+            .unwrap();
+        let mut index = BlockfileMetadataIndexWriter::new(blockfile);
+        let reader = index.reader();
+        index.begin_transaction().await.unwrap();
+        index
+            .set("key1", MetadataIndexValue::String("value".to_string()), 1)
+            .await
+            .unwrap();
+        index
+            .set("key2", MetadataIndexValue::String("value".to_string()), 2)
+            .await
+            .unwrap();
+        index.commit_transaction().await.unwrap();
+
+        let bitmap = reader
+            .get("key1", MetadataIndexValue::String("value".to_string()))
+            .await
+            .unwrap();
+        assert_eq!(bitmap.len(), 1);
+        assert_eq!(bitmap.contains(1), true);
+
+        let bitmap = reader
+            .get("key2", MetadataIndexValue::String("value".to_string()))
+            .await
+            .unwrap();
+        assert_eq!(bitmap.len(), 1);
+        assert_eq!(bitmap.contains(2), true);
+    }
+
+    #[tokio::test]
+    async fn test_string_value_metadata_index_multiple_values() {
+        let mut provider = HashMapBlockfileProvider::new();
+        let blockfile = provider
+            .create("test", KeyType::String, ValueType::RoaringBitmap)
+            .unwrap();
+        let mut index = BlockfileMetadataIndexWriter::new(blockfile);
+        let reader = index.reader();
+        index.begin_transaction().await.unwrap();
+        index
+            .set("key", MetadataIndexValue::String("value1".to_string()), 1)
+            .await
+            .unwrap();
+        index
+            .set("key", MetadataIndexValue::String("value2".to_string()), 2)
+            .await
+            .unwrap();
+        index.commit_transaction().await.unwrap();
+
+        let bitmap = reader
+            .get("key", MetadataIndexValue::String("value1".to_string()))
+            .await
+            .unwrap();
+        assert_eq!(bitmap.len(), 1);
+        assert_eq!(bitmap.contains(1), true);
+
+        let bitmap = reader
+            .get("key", MetadataIndexValue::String("value2".to_string()))
+            .await
+            .unwrap();
+        assert_eq!(bitmap.len(), 1);
+        assert_eq!(bitmap.contains(2), true);
+    }
+
+    #[tokio::test]
+    async fn test_string_value_metadata_index_delete_in_standalone_transaction() {
+        let mut provider = HashMapBlockfileProvider::new();
+        let blockfile = provider
+            .create("test", KeyType::String, ValueType::RoaringBitmap)
+            .unwrap();
+        let mut index = BlockfileMetadataIndexWriter::new(blockfile);
+        let reader = index.reader();
+        index.begin_transaction().await.unwrap();
+        index
+            .set("key", MetadataIndexValue::String("value".to_string()), 1)
+            .await
+            .unwrap();
+        index.commit_transaction().await.unwrap();
+
+        index.begin_transaction().await.unwrap();
+        index
+            .delete("key", MetadataIndexValue::String("value".to_string()), 1)
+            .await
+            .unwrap();
+        index.commit_transaction().await.unwrap();
+
+        let bitmap = reader
+            .get("key", MetadataIndexValue::String("value".to_string()))
+            .await
+            .unwrap();
+        assert_eq!(bitmap.len(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_metadata_index_rename_key() {
+        let mut provider = HashMapBlockfileProvider::new();
+        let blockfile = provider
+            .create("test", KeyType::String, ValueType::RoaringBitmap)
+            .unwrap();
+        let mut index = BlockfileMetadataIndexWriter::new(blockfile);
+        let reader = index.reader();
+        index.begin_transaction().await.unwrap();
+        index
+            .set("old", MetadataIndexValue::String("value".to_string()), 1)
+            .await
+            .unwrap();
+        index
+            .set("old", MetadataIndexValue::Float(1.0), 2)
+            .await
+            .unwrap();
+        index
+            .set("new", MetadataIndexValue::String("value".to_string()), 3)
+            .await
+            .unwrap();
+        index.commit_transaction().await.unwrap();
+
+        index.begin_transaction().await.unwrap();
+        index
+            .set("old", MetadataIndexValue::String("value".to_string()), 4)
+            .await
+            .unwrap();
+        index.rename_key("old", "new").await.unwrap();
+        index.commit_transaction().await.unwrap();
+
+        let bitmap = reader
+            .get("new", MetadataIndexValue::String("value".to_string()))
+            .await
+            .unwrap();
+        assert_eq!(bitmap.iter().collect::<Vec<u32>>(), vec![1, 3, 4]);
+        let bitmap = reader
+            .get("new", MetadataIndexValue::Float(1.0))
+            .await
+            .unwrap();
+        assert_eq!(bitmap.iter().collect::<Vec<u32>>(), vec![2]);
+        let bitmap = reader
+            .get("old", MetadataIndexValue::String("value".to_string()))
+            .await
+            .unwrap();
+        assert_eq!(bitmap.len(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_metadata_index_drop_key() {
+        let mut provider = HashMapBlockfileProvider::new();
+        let blockfile = provider
+            .create("test", KeyType::String, ValueType::RoaringBitmap)
+            .unwrap();
+        let mut index = BlockfileMetadataIndexWriter::new(blockfile);
+        let reader = index.reader();
+        let result = index.drop_key("key").await;
+        assert_eq!(result.is_err(), true);
+
+        index.begin_transaction().await.unwrap();
+        index
+            .set("key", MetadataIndexValue::String("value".to_string()), 1)
+            .await
+            .unwrap();
+        index
+            .set("other", MetadataIndexValue::Bool(true), 1)
+            .await
+            .unwrap();
+        index.commit_transaction().await.unwrap();
+
+        index.begin_transaction().await.unwrap();
+        index.drop_key("key").await.unwrap();
+        index.commit_transaction().await.unwrap();
+
+        let bitmap = reader
+            .get("key", MetadataIndexValue::String("value".to_string()))
+            .await
+            .unwrap();
+        assert_eq!(bitmap.len(), 0);
+        let bitmap = reader
+            .get("other", MetadataIndexValue::Bool(true))
+            .await
+            .unwrap();
+        assert_eq!(bitmap.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_metadata_index_delete_all() {
+        let mut provider = HashMapBlockfileProvider::new();
+        let blockfile = provider
+            .create("test", KeyType::String, ValueType::RoaringBitmap)
+            .unwrap();
+        let mut index = BlockfileMetadataIndexWriter::new(blockfile);
+        let reader = index.reader();
+        let result = index.delete_all(1).await;
+        assert_eq!(result.is_err(), true);
+
+        index.begin_transaction().await.unwrap();
+        index
+            .set("key1", MetadataIndexValue::String("value".to_string()), 1)
+            .await
+            .unwrap();
+        index
+            .set("key1", MetadataIndexValue::String("value".to_string()), 2)
+            .await
+            .unwrap();
+        index
+            .set("key2", MetadataIndexValue::Float(1.0), 1)
+            .await
+            .unwrap();
+        index.commit_transaction().await.unwrap();
+
+        index.begin_transaction().await.unwrap();
+        index
+            .set("key3", MetadataIndexValue::Bool(true), 1)
+            .await
+            .unwrap();
+        index.delete_all(1).await.unwrap();
+        // Deleting an offset without postings is a no-op.
+        index.delete_all(3).await.unwrap();
+        index.commit_transaction().await.unwrap();
+
+        let bitmap = reader
+            .get("key1", MetadataIndexValue::String("value".to_string()))
+            .await
+            .unwrap();
+        assert_eq!(bitmap.iter().collect::<Vec<u32>>(), vec![2]);
+        let bitmap = reader
+            .get("key2", MetadataIndexValue::Float(1.0))
+            .await
+            .unwrap();
+        assert_eq!(bitmap.len(), 0);
+        let bitmap = reader
+            .get("key3", MetadataIndexValue::Bool(true))
+            .await
+            .unwrap();
+        assert_eq!(bitmap.len(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_metadata_index_delete_all_tracks_later_writes() {
+        let mut provider = HashMapBlockfileProvider::new();
+        let blockfile = provider
+            .create("test", KeyType::String, ValueType::RoaringBitmap)
+            .unwrap();
+        let mut index = BlockfileMetadataIndexWriter::new(blockfile);
+        let reader = index.reader();
+        index.begin_transaction().await.unwrap();
+        index
+            .set("year", MetadataIndexValue::Int(2020), 1)
+            .await
+            .unwrap();
+        // Builds the postings of every offset.
+        index.delete_all(1).await.unwrap();
+        index
+            .set("year", MetadataIndexValue::Int(2021), 2)
+            .await
+            .unwrap();
+        index.commit_transaction().await.unwrap();
+
+        index.begin_transaction().await.unwrap();
+        index
+            .set_many(vec![
+                ("genre", MetadataIndexValue::String("drama".to_string()), 2),
+                ("rating", MetadataIndexValue::Null, 2),
+            ])
+            .await
+            .unwrap();
+        index.rename_key("year", "released").await.unwrap();
+        index.commit_transaction().await.unwrap();
+
+        index.begin_transaction().await.unwrap();
+        index.delete_all(2).await.unwrap();
+        index.commit_transaction().await.unwrap();
+        for key in ["released", "genre", "rating"] {
+            assert_eq!(reader.exists(key).await.unwrap().len(), 0);
+        }
+        assert_eq!(
+            reader
+                .count("released", MetadataIndexValue::Int(2021))
+                .await
+                .unwrap(),
+            0
+        );
+        assert_eq!(reader.all_offsets().await.unwrap().len(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_metadata_index_delete_all_with_forward_index() {
+        let mut provider = HashMapBlockfileProvider::new();
+        let blockfile = provider
+            .create("test", KeyType::String, ValueType::RoaringBitmap)
+            .unwrap();
+        let forward_blockfile = provider
+            .create("forward", KeyType::Int, ValueType::String)
+            .unwrap();
+        let mut index = BlockfileMetadataIndexWriter::new(blockfile.clone())
+            .with_forward_index(forward_blockfile.clone());
+        index.begin_transaction().await.unwrap();
+        index
+            .set(
+                "tags",
+                MetadataIndexValue::List(vec![
+                    MetadataIndexValue::String("a".to_string()),
+                    MetadataIndexValue::String("b".to_string()),
+                ]),
+                1,
+            )
+            .await
+            .unwrap();
+        index
+            .set("rating", MetadataIndexValue::Null, 1)
+            .await
+            .unwrap();
+        index
+            .set("year", MetadataIndexValue::Int(2020), 1)
+            .await
+            .unwrap();
+        index
+            .set("year", MetadataIndexValue::Int(2020), 2)
+            .await
+            .unwrap();
+        index.commit_transaction().await.unwrap();
+        drop(index);
+
+        // The postings of the offset are found through its committed metadata.
+        let mut index =
+            BlockfileMetadataIndexWriter::new(blockfile).with_forward_index(forward_blockfile);
+        let reader = index.reader();
+        index.begin_transaction().await.unwrap();
+        index.delete_all(1).await.unwrap();
+        index.commit_transaction().await.unwrap();
+        for value in ["a", "b"] {
+            let count = reader
+                .count("tags", MetadataIndexValue::String(value.to_string()))
+                .await
+                .unwrap();
+            assert_eq!(count, 0);
+        }
+        assert_eq!(reader.exists("tags").await.unwrap().len(), 0);
+        assert_eq!(reader.exists("rating").await.unwrap().len(), 0);
+        let bitmap = reader
+            .get("year", MetadataIndexValue::Int(2020))
+            .await
+            .unwrap();
+        assert_eq!(bitmap.iter().collect::<Vec<u32>>(), vec![2]);
+        assert_eq!(reader.get_metadata(1).await.unwrap().len(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_metadata_index_deletes_postings_set_by_another_writer() {
+        let mut provider = HashMapBlockfileProvider::new();
+        let blockfile = provider
+            .create("test", KeyType::String, ValueType::RoaringBitmap)
+            .unwrap();
+        let mut index = BlockfileMetadataIndexWriter::new(blockfile.clone());
+        index.begin_transaction().await.unwrap();
+        index
+            .set(
+                "tags",
+                MetadataIndexValue::List(vec![
+                    MetadataIndexValue::String("a".to_string()),
+                    MetadataIndexValue::String("b".to_string()),
+                ]),
+                1,
+            )
+            .await
+            .unwrap();
+        index
+            .set("year", MetadataIndexValue::Int(2020), 1)
+            .await
+            .unwrap();
+        index
+            .set("year", MetadataIndexValue::Int(2020), 2)
+            .await
+            .unwrap();
+        index.commit_transaction().await.unwrap();
+        drop(index);
+
+        // A new writer over the same blockfile, as after a restart
+        let mut index = BlockfileMetadataIndexWriter::new(blockfile);
+        let reader = index.reader();
+        index.begin_transaction().await.unwrap();
+        index
+            .delete("tags", MetadataIndexValue::String("a".to_string()), 1)
+            .await
+            .unwrap();
+        index.commit_transaction().await.unwrap();
+        // The offset still has a value for tags
+        assert_eq!(reader.exists("tags").await.unwrap().len(), 1);
+
+        index.begin_transaction().await.unwrap();
+        index.delete_all(1).await.unwrap();
+        index.commit_transaction().await.unwrap();
+        let bitmap = reader
+            .get("tags", MetadataIndexValue::String("b".to_string()))
+            .await
+            .unwrap();
+        assert_eq!(bitmap.len(), 0);
+        let bitmap = reader
+            .get("year", MetadataIndexValue::Int(2020))
+            .await
+            .unwrap();
+        assert_eq!(bitmap.iter().collect::<Vec<u32>>(), vec![2]);
+        assert_eq!(reader.exists("tags").await.unwrap().len(), 0);
+        let bitmap = reader.not_exists("year").await.unwrap();
+        assert_eq!(bitmap.len(), 0);
+        let bitmap = reader.contains_all("year", Vec::new()).await.unwrap();
+        assert_eq!(bitmap.iter().collect::<Vec<u32>>(), vec![2]);
+
+        // Deleting and setting an offset again in one transaction keeps the new postings
+        index.begin_transaction().await.unwrap();
+        index.delete_all(2).await.unwrap();
+        index
+            .set("year", MetadataIndexValue::Int(2021), 2)
+            .await
+            .unwrap();
+        index.commit_transaction().await.unwrap();
+        let bitmap = reader
+            .get("year", MetadataIndexValue::Int(2020))
+            .await
+            .unwrap();
+        assert_eq!(bitmap.len(), 0);
+        let bitmap = reader
+            .get("year", MetadataIndexValue::Int(2021))
+            .await
+            .unwrap();
+        assert_eq!(bitmap.iter().collect::<Vec<u32>>(), vec![2]);
+        assert_eq!(reader.exists("year").await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_f64_value_metadata_index_set_get() {
+        let mut provider = HashMapBlockfileProvider::new();
+        let blockfile = provider
+            .create("test", KeyType::String, ValueType::RoaringBitmap)
+            .unwrap();
+        let mut index = BlockfileMetadataIndexWriter::new(blockfile);
+        let reader = index.reader();
+        index.begin_transaction().await.unwrap();
+        index.set("key", 0.1f64.into(), 1).await.unwrap();
+        index.set("key", (-0.0f64).into(), 2).await.unwrap();
+        index.commit_transaction().await.unwrap();
+
+        let bitmap = reader.get("key", 0.1f64.into()).await.unwrap();
+        assert_eq!(bitmap.iter().collect::<Vec<u32>>(), vec![1]);
+        let bitmap = reader.get("key", 0.0f64.into()).await.unwrap();
+        assert_eq!(bitmap.iter().collect::<Vec<u32>>(), vec![2]);
+    }
+
+    #[tokio::test]
+    async fn test_metadata_index_delete_key() {
+        let mut provider = HashMapBlockfileProvider::new();
+        let blockfile = provider
+            .create("test", KeyType::String, ValueType::RoaringBitmap)
+            .unwrap();
+        let mut index = BlockfileMetadataIndexWriter::new(blockfile);
+        let reader = index.reader();
+        index.begin_transaction().await.unwrap();
+        index
+            .set("key", MetadataIndexValue::Int(1), 1)
+            .await
+            .unwrap();
+        index
+            .set("key", MetadataIndexValue::Int(1), 2)
+            .await
+            .unwrap();
+        index
+            .set("other", MetadataIndexValue::Int(1), 1)
+            .await
+            .unwrap();
+        index.commit_transaction().await.unwrap();
+
+        index.begin_transaction().await.unwrap();
+        index
+            .set("key", MetadataIndexValue::Int(2), 1)
+            .await
+            .unwrap();
+        index.delete_key("key", 1).await.unwrap();
+        index.commit_transaction().await.unwrap();
+
+        let bitmap = reader.get("key", MetadataIndexValue::Int(1)).await.unwrap();
+        assert_eq!(bitmap.iter().collect::<Vec<u32>>(), vec![2]);
+        let bitmap = reader.get("key", MetadataIndexValue::Int(2)).await.unwrap();
+        assert_eq!(bitmap.len(), 0);
+        let bitmap = reader
+            .get("other", MetadataIndexValue::Int(1))
+            .await
+            .unwrap();
+        assert_eq!(bitmap.iter().collect::<Vec<u32>>(), vec![1]);
+    }
+
+    #[tokio::test]
+    async fn test_metadata_index_forward_index() {
+        let mut provider = HashMapBlockfileProvider::new();
+        let blockfile = provider
+            .create("test", KeyType::String, ValueType::RoaringBitmap)
+            .unwrap();
+        let forward_blockfile = provider
+            .create("forward", KeyType::Int, ValueType::String)
+            .unwrap();
+        let mut index =
+            BlockfileMetadataIndexWriter::new(blockfile).with_forward_index(forward_blockfile);
+        let reader = index.reader();
+        index.begin_transaction().await.unwrap();
+        index
+            .set("year", MetadataIndexValue::Int(2020), 1)
+            .await
+            .unwrap();
+        index
+            .set("title", MetadataIndexValue::String("a".to_string()), 1)
+            .await
+            .unwrap();
+        index
+            .set("year", MetadataIndexValue::Int(2021), 2)
+            .await
+            .unwrap();
+        assert_eq!(reader.get_metadata(1).await.unwrap().len(), 0);
+        index.commit_transaction().await.unwrap();
+
+        let metadata = reader.get_metadata(1).await.unwrap();
+        assert_eq!(metadata.len(), 2);
+        assert_eq!(metadata.get("year"), Some(&MetadataIndexValue::Int(2020)));
+        assert_eq!(
+            metadata.get("title"),
+            Some(&MetadataIndexValue::String("a".to_string()))
+        );
+        assert_eq!(reader.get_metadata(3).await.unwrap().len(), 0);
+
+        index.begin_transaction().await.unwrap();
+        index.rename_key("year", "released").await.unwrap();
+        index.delete_key("title", 1).await.unwrap();
+        index.delete_all(2).await.unwrap();
+        index.commit_transaction().await.unwrap();
+
+        let metadata = reader.get_metadata(1).await.unwrap();
+        assert_eq!(metadata.len(), 1);
+        assert_eq!(
+            metadata.get("released"),
+            Some(&MetadataIndexValue::Int(2020))
+        );
+        assert_eq!(reader.get_metadata(2).await.unwrap().len(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_metadata_index_composite_index_requires_forward_index() {
+        let mut provider = HashMapBlockfileProvider::new();
+        let blockfile = provider
+            .create("test", KeyType::String, ValueType::RoaringBitmap)
+            .unwrap();
+        let result =
+            BlockfileMetadataIndexWriter::new(blockfile.clone()).with_composite_index("a", "b");
+        match result {
+            Err(e) => assert_eq!(e.code(), ErrorCodes::FailedPrecondition),
+            Ok(_) => panic!("Expected a composite index without a forward index to fail"),
+        }
+
+        let forward_blockfile = provider
+            .create("forward", KeyType::Int, ValueType::String)
+            .unwrap();
+        assert!(BlockfileMetadataIndexWriter::new(blockfile)
+            .with_forward_index(forward_blockfile)
+            .with_composite_index("a", "b")
+            .is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_metadata_index_failed_commit_poisons_writer() {
+        let mut provider = HashMapBlockfileProvider::new();
+        let blockfile = provider
+            .create("test", KeyType::String, ValueType::RoaringBitmap)
+            .unwrap();
+        // The forward index rejects the metadata on commit, since it only holds bitmaps.
+        let forward_blockfile = ArrowBlockfileProvider::new()
+            .create("forward", KeyType::Int, ValueType::RoaringBitmap)
+            .unwrap();
+        let mut index =
+            BlockfileMetadataIndexWriter::new(blockfile).with_forward_index(forward_blockfile);
+        let reader = index.reader();
+        index.begin_transaction().await.unwrap();
+        index
+            .set("year", MetadataIndexValue::Int(2020), 1)
+            .await
+            .unwrap();
+        assert!(index.commit_transaction().await.is_err());
+
+        // Nothing was committed, since the forward index failed before either commit.
+        let count = reader
+            .count("year", MetadataIndexValue::Int(2020))
+            .await
+            .unwrap();
+        assert_eq!(count, 0);
+        assert_eq!(reader.all_offsets().await.unwrap().len(), 0);
+
+        let err = index.begin_transaction().await.unwrap_err();
+        assert_eq!(err.code(), ErrorCodes::Internal);
+        let err = index
+            .set("year", MetadataIndexValue::Int(2021), 2)
+            .await
+            .unwrap_err();
+        assert_eq!(err.code(), ErrorCodes::InvalidArgument);
+        assert!(index.commit_transaction().await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_metadata_index_unindexed_keys() {
+        let mut provider = HashMapBlockfileProvider::new();
+        let blockfile = provider
+            .create("test", KeyType::String, ValueType::RoaringBitmap)
+            .unwrap();
+        let forward_blockfile = provider
+            .create("forward", KeyType::Int, ValueType::String)
+            .unwrap();
+        let mut index = BlockfileMetadataIndexWriter::new(blockfile)
+            .with_forward_index(forward_blockfile)
+            .with_unindexed_keys(HashSet::from(["notes".to_string()]));
+        let reader = index.reader();
+        index.begin_transaction().await.unwrap();
+        index
+            .set("notes", MetadataIndexValue::String("long".to_string()), 1)
+            .await
+            .unwrap();
+        index
+            .set("year", MetadataIndexValue::Int(2020), 1)
+            .await
+            .unwrap();
+        index.commit_transaction().await.unwrap();
+
+        let result = reader
+            .get("notes", MetadataIndexValue::String("long".to_string()))
+            .await;
+        assert_eq!(result.unwrap_err().code(), ErrorCodes::InvalidArgument);
+        assert_eq!(reader.values_for_key("notes").await.is_err(), true);
+        let bitmap = reader
+            .get("year", MetadataIndexValue::Int(2020))
+            .await
+            .unwrap();
+        assert_eq!(bitmap.len(), 1);
+
+        let metadata = reader.get_metadata(1).await.unwrap();
+        assert_eq!(
+            metadata.get("notes"),
+            Some(&MetadataIndexValue::String("long".to_string()))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_metadata_index_rename_and_drop_unindexed_keys() {
+        let mut provider = HashMapBlockfileProvider::new();
+        let blockfile = provider
+            .create("test", KeyType::String, ValueType::RoaringBitmap)
+            .unwrap();
+        let forward_blockfile = provider
+            .create("forward", KeyType::Int, ValueType::String)
+            .unwrap();
+        let unindexed_keys = HashSet::from(["notes".to_string(), "comment".to_string()]);
+        let mut index = BlockfileMetadataIndexWriter::new(blockfile.clone())
+            .with_forward_index(forward_blockfile.clone())
+            .with_unindexed_keys(unindexed_keys.clone());
+        index.begin_transaction().await.unwrap();
+        index
+            .set("notes", MetadataIndexValue::String("first".to_string()), 1)
+            .await
+            .unwrap();
+        index
+            .set("notes", MetadataIndexValue::String("second".to_string()), 2)
+            .await
+            .unwrap();
+        index
+            .set(
+                "comment",
+                MetadataIndexValue::String("third".to_string()),
+                3,
+            )
+            .await
+            .unwrap();
+        index.commit_transaction().await.unwrap();
+        drop(index);
+
+        let mut index = BlockfileMetadataIndexWriter::new(blockfile)
+            .with_forward_index(forward_blockfile)
+            .with_unindexed_keys(unindexed_keys);
+        let reader = index.reader();
+        index.begin_transaction().await.unwrap();
+        index
+            .set("notes", MetadataIndexValue::String("fourth".to_string()), 4)
+            .await
+            .unwrap();
+        index.rename_key("notes", "summary").await.unwrap();
+        index.drop_key("comment").await.unwrap();
+        index.commit_transaction().await.unwrap();
+
+        for (offset_id, value) in [(1, "first"), (2, "second"), (4, "fourth")] {
+            let metadata = reader.get_metadata(offset_id).await.unwrap();
+            assert_eq!(metadata.get("notes"), None);
+            assert_eq!(
+                metadata.get("summary"),
+                Some(&MetadataIndexValue::String(value.to_string()))
+            );
+        }
+        assert!(reader.get_metadata(3).await.unwrap().is_empty());
+        // The renamed key is indexed
+        let bitmap = reader
+            .get("summary", MetadataIndexValue::String("first".to_string()))
+            .await
+            .unwrap();
+        assert_eq!(bitmap.iter().collect::<Vec<u32>>(), vec![1]);
+    }
+
+    #[tokio::test]
+    async fn test_metadata_index_set_many() {
+        let mut provider = HashMapBlockfileProvider::new();
+        let blockfile = provider
+            .create("test", KeyType::String, ValueType::RoaringBitmap)
+            .unwrap();
+        let mut index = BlockfileMetadataIndexWriter::new(blockfile);
+        let reader = index.reader();
+        let entries = vec![("key", MetadataIndexValue::Int(1), 1)];
+        assert_eq!(index.set_many(entries).await.is_err(), true);
+
+        index.begin_transaction().await.unwrap();
+        index
+            .set("key", MetadataIndexValue::Int(1), 1)
+            .await
+            .unwrap();
+        index.commit_transaction().await.unwrap();
+
+        index.begin_transaction().await.unwrap();
+        let entries = vec![
+            ("key", MetadataIndexValue::Int(1), 2),
+            ("key", MetadataIndexValue::Int(2), 3),
+            ("key", MetadataIndexValue::Int(1), 4),
+            ("other", MetadataIndexValue::Bool(true), 2),
+        ];
+        index.set_many(entries).await.unwrap();
+        index.commit_transaction().await.unwrap();
+
+        let bitmap = reader.get("key", MetadataIndexValue::Int(1)).await.unwrap();
+        assert_eq!(bitmap.iter().collect::<Vec<u32>>(), vec![1, 2, 4]);
+        let bitmap = reader.get("key", MetadataIndexValue::Int(2)).await.unwrap();
+        assert_eq!(bitmap.iter().collect::<Vec<u32>>(), vec![3]);
+        let bitmap = reader
+            .get("other", MetadataIndexValue::Bool(true))
+            .await
+            .unwrap();
+        assert_eq!(bitmap.iter().collect::<Vec<u32>>(), vec![2]);
+
+        index.begin_transaction().await.unwrap();
+        index.delete_all(2).await.unwrap();
+        index.commit_transaction().await.unwrap();
+        let bitmap = reader.get("key", MetadataIndexValue::Int(1)).await.unwrap();
+        assert_eq!(bitmap.iter().collect::<Vec<u32>>(), vec![1, 4]);
+    }
+
+    #[tokio::test]
+    async fn test_metadata_index_offset_id_exhausted() {
+        let mut provider = HashMapBlockfileProvider::new();
+        let blockfile = provider
+            .create("test", KeyType::String, ValueType::RoaringBitmap)
+            .unwrap();
+        let mut index = BlockfileMetadataIndexWriter::new(blockfile);
+        let reader = index.reader();
+        index.begin_transaction().await.unwrap();
+        let max_offset_id = u32::MAX as usize;
+        index
+            .set("key", MetadataIndexValue::Int(1), max_offset_id)
+            .await
+            .unwrap();
+        let result = index
+            .set("key", MetadataIndexValue::Int(1), max_offset_id + 1)
+            .await;
+        let err = result.unwrap_err();
+        assert_eq!(err.code(), ErrorCodes::ResourceExhausted);
+        assert_eq!(err.category(), ErrorCategory::User);
+        let entries = vec![
+            ("key", MetadataIndexValue::Int(2), 1),
+            ("key", MetadataIndexValue::Int(2), max_offset_id + 1),
+        ];
+        let result = index.set_many(entries).await;
+        assert_eq!(result.unwrap_err().code(), ErrorCodes::ResourceExhausted);
+        index.commit_transaction().await.unwrap();
+
+        let bitmap = reader.get("key", MetadataIndexValue::Int(1)).await.unwrap();
+        assert_eq!(bitmap.iter().collect::<Vec<u32>>(), vec![u32::MAX]);
+        let count = reader
+            .count("key", MetadataIndexValue::Int(2))
+            .await
+            .unwrap();
+        assert_eq!(count, 0);
+    }
+
+    #[tokio::test]
+    async fn test_metadata_index_create_and_open() {
+        let mut provider = HashMapBlockfileProvider::new();
+        let id = Uuid::new_v4();
+        let mut index = BlockfileMetadataIndexWriter::create(&mut provider, &id).unwrap();
+        index.begin_transaction().await.unwrap();
+        index
+            .set("year", MetadataIndexValue::Int(2020), 1)
+            .await
+            .unwrap();
+        index
+            .set("year", MetadataIndexValue::Int(2021), 2)
+            .await
+            .unwrap();
+        index.commit_transaction().await.unwrap();
+        drop(index);
+
+        let result = BlockfileMetadataIndexWriter::create(&mut provider, &id);
+        assert_eq!(result.err().unwrap().code(), ErrorCodes::AlreadyExists);
+        let result = BlockfileMetadataIndexWriter::open(&provider, &Uuid::new_v4());
+        assert_eq!(result.err().unwrap().code(), ErrorCodes::NotFound);
+
+        let mut index = BlockfileMetadataIndexWriter::open(&provider, &id).unwrap();
+        let reader = index.reader();
+        let bitmap = reader
+            .get("year", MetadataIndexValue::Int(2020))
+            .await
+            .unwrap();
+        assert_eq!(bitmap.iter().collect::<Vec<u32>>(), vec![1]);
+        let bitmap = reader
+            .not_eq("year", MetadataIndexValue::Int(2020))
+            .await
+            .unwrap();
+        assert_eq!(bitmap.iter().collect::<Vec<u32>>(), vec![2]);
+
+        index.begin_transaction().await.unwrap();
+        index.delete_all(2).await.unwrap();
+        index
+            .set("year", MetadataIndexValue::Int(2022), 3)
+            .await
+            .unwrap();
+        index.commit_transaction().await.unwrap();
+        let bitmap = reader
+            .not_eq("year", MetadataIndexValue::Int(2020))
+            .await
+            .unwrap();
+        assert_eq!(bitmap.iter().collect::<Vec<u32>>(), vec![3]);
+        let bitmap = reader
+            .get("year", MetadataIndexValue::Int(2021))
+            .await
+            .unwrap();
+        assert_eq!(bitmap.len(), 0);
+        assert_eq!(
+            reader
+                .exists("year")
+                .await
+                .unwrap()
+                .iter()
+                .collect::<Vec<u32>>(),
+            vec![1, 3]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_metadata_index_reopen_from_storage() {
+        let tmp_dir = tempfile::tempdir().unwrap();
+        let new_provider = || {
+            ArrowBlockfileProvider::with_config(
+                BlockSizeConfig::default(),
+                Some(tmp_dir.path().to_path_buf()),
+                BlockCompression::None,
+                BlockCache::new(1024 * 1024),
+            )
+        };
+        let id = Uuid::new_v4();
+        let mut provider = new_provider();
+        let mut index = BlockfileMetadataIndexWriter::create(&mut provider, &id).unwrap();
+        index.begin_transaction().await.unwrap();
+        for offset_id in 0..20 {
+            let entries = vec![
+                (
+                    "name",
+                    MetadataIndexValue::String(format!("name{}", offset_id)),
+                    offset_id,
+                ),
+                (
+                    "score",
+                    MetadataIndexValue::Float(offset_id as f64),
+                    offset_id,
+                ),
+                (
+                    "rank",
+                    MetadataIndexValue::Int(offset_id as i64 % 5),
+                    offset_id,
+                ),
+                (
+                    "even",
+                    MetadataIndexValue::Bool(offset_id % 2 == 0),
+                    offset_id,
+                ),
+            ];
+            index.set_many(entries).await.unwrap();
+        }
+        index.commit_transaction().await.unwrap();
+        drop(index);
+        drop(provider);
+
+        let mut index = BlockfileMetadataIndexWriter::open(&new_provider(), &id).unwrap();
+        let reader = index.reader();
+        let bitmap = reader
+            .gte("score", MetadataIndexValue::Float(15.0))
+            .await
+            .unwrap();
+        assert_eq!(
+            bitmap.iter().collect::<Vec<u32>>(),
+            (15..20).collect::<Vec<u32>>()
+        );
+        let bitmap = reader
+            .get("even", MetadataIndexValue::Bool(true))
+            .await
+            .unwrap();
+        assert_eq!(bitmap.len(), 10);
+        let bitmap = reader
+            .get("rank", MetadataIndexValue::Int(3))
+            .await
+            .unwrap();
+        assert_eq!(bitmap.iter().collect::<Vec<u32>>(), vec![3, 8, 13, 18]);
+        let bitmap = reader.get_prefix("name", "name1").await.unwrap();
+        assert_eq!(bitmap.len(), 11);
+
+        index.begin_transaction().await.unwrap();
+        index.delete_all(3).await.unwrap();
+        index.commit_transaction().await.unwrap();
+        let bitmap = reader
+            .get("rank", MetadataIndexValue::Int(3))
+            .await
+            .unwrap();
+        assert_eq!(bitmap.iter().collect::<Vec<u32>>(), vec![8, 13, 18]);
+        let bitmap = reader
+            .lt("score", MetadataIndexValue::Float(5.0))
+            .await
+            .unwrap();
+        assert_eq!(bitmap.iter().collect::<Vec<u32>>(), vec![0, 1, 2, 4]);
+        let bitmap = reader
+            .not_eq("even", MetadataIndexValue::Bool(true))
+            .await
+            .unwrap();
+        assert_eq!(bitmap.len(), 9);
+        let values = reader.values_for_key("rank").await.unwrap();
+        assert_eq!(values[3], (MetadataIndexValue::Int(3), 3));
+    }
+
+    #[tokio::test]
+    async fn test_metadata_index_wide_values() {
+        let mut provider = HashMapBlockfileProvider::new();
+        let blockfile = provider
+            .create("test", KeyType::String, ValueType::RoaringBitmap)
+            .unwrap();
+        let mut index = BlockfileMetadataIndexWriter::new(blockfile);
+        let reader = index.reader();
+        index.begin_transaction().await.unwrap();
+        // Distinct as i64 and f64, equal once narrowed to i32 or f32.
+        index
+            .set("int", MetadataIndexValue::Int(1 << 40), 1)
+            .await
+            .unwrap();
+        index
+            .set("int", MetadataIndexValue::Int(0), 2)
+            .await
+            .unwrap();
+        index.set("float", 0.1f64.into(), 1).await.unwrap();
+        index.set("float", (0.1f32 as f64).into(), 2).await.unwrap();
+        index.set("float", f64::NAN.into(), 3).await.unwrap();
+        index.set("float", f64::INFINITY.into(), 4).await.unwrap();
+        index.commit_transaction().await.unwrap();
+
+        let bitmap = reader
+            .get("int", MetadataIndexValue::Int(1 << 40))
+            .await
+            .unwrap();
+        assert_eq!(bitmap.iter().collect::<Vec<u32>>(), vec![1]);
+        let bitmap = reader.get("float", 0.1f64.into()).await.unwrap();
+        assert_eq!(bitmap.iter().collect::<Vec<u32>>(), vec![1]);
+        let bitmap = reader
+            .gt("int", MetadataIndexValue::Int(i32::MAX as i64))
+            .await
+            .unwrap();
+        assert_eq!(bitmap.iter().collect::<Vec<u32>>(), vec![1]);
+
+        // NaN is a single key that sorts after infinity.
+        let bitmap = reader.get("float", (-f64::NAN).into()).await.unwrap();
+        assert_eq!(bitmap.iter().collect::<Vec<u32>>(), vec![3]);
+        let bitmap = reader.gt("float", 1.0f64.into()).await.unwrap();
+        assert_eq!(bitmap.iter().collect::<Vec<u32>>(), vec![3, 4]);
+        let bitmap = reader.lt("float", f64::NAN.into()).await.unwrap();
+        assert_eq!(bitmap.iter().collect::<Vec<u32>>(), vec![1, 2, 4]);
+    }
+
+    #[tokio::test]
+    async fn test_metadata_index_timestamps() {
+        // Timestamps are milliseconds since the epoch, created_at takes seconds.
+        let created_at = |seconds: i64| MetadataIndexValue::Timestamp(seconds * 1000);
+
+        let mut provider = HashMapBlockfileProvider::new();
+        let blockfile = provider
+            .create("test", KeyType::String, ValueType::RoaringBitmap)
+            .unwrap();
+        let mut index = BlockfileMetadataIndexWriter::new(blockfile);
+        let reader = index.reader();
+        index.begin_transaction().await.unwrap();
+        index
+            .set("created_at", created_at(1704067199), 1)
+            .await
+            .unwrap();
+        index
+            .set("created_at", created_at(1705276800), 2)
+            .await
+            .unwrap();
+        index
+            .set("created_at", created_at(1706745600), 3)
+            .await
+            .unwrap();
+        index.commit_transaction().await.unwrap();
+
+        let bitmap = reader
+            .get("created_at", created_at(1705276800))
+            .await
+            .unwrap();
+        assert_eq!(bitmap.iter().collect::<Vec<u32>>(), vec![2]);
+        let mut bitmap = reader
+            .gte("created_at", created_at(1704067200))
+            .await
+            .unwrap();
+        bitmap &= reader
+            .lt("created_at", created_at(1706745600))
+            .await
+            .unwrap();
+        assert_eq!(bitmap.iter().collect::<Vec<u32>>(), vec![2]);
+    }
+
+    #[tokio::test]
+    async fn test_metadata_index_list_values() {
+        let mut provider = HashMapBlockfileProvider::new();
+        let blockfile = provider
+            .create("test", KeyType::String, ValueType::RoaringBitmap)
+            .unwrap();
+        let forward_blockfile = provider
+            .create("forward", KeyType::Int, ValueType::String)
+            .unwrap();
+        let mut index =
+            BlockfileMetadataIndexWriter::new(blockfile).with_forward_index(forward_blockfile);
+        let reader = index.reader();
+        let tags = |tags: &[&str]| {
+            MetadataIndexValue::List(
+                tags.iter()
+                    .map(|tag| MetadataIndexValue::String(tag.to_string()))
+                    .collect(),
+            )
+        };
+        index.begin_transaction().await.unwrap();
+        index.set("tags", tags(&["a", "b"]), 1).await.unwrap();
+        index.set("tags", tags(&["b", "c"]), 2).await.unwrap();
+        index
+            .set_many(vec![("tags", tags(&["a", "b", "c"]), 3)])
+            .await
+            .unwrap();
+        index.commit_transaction().await.unwrap();
+
+        let metadata = reader.get_metadata(1).await.unwrap();
+        assert_eq!(metadata.get("tags"), Some(&tags(&["a", "b"])));
+        let bitmap = reader
+            .contains_any("tags", tags(&["a"]).elements())
+            .await
+            .unwrap();
+        assert_eq!(bitmap.iter().collect::<Vec<u32>>(), vec![1, 3]);
+        let bitmap = reader
+            .contains_any("tags", tags(&["a", "c"]).elements())
+            .await
+            .unwrap();
+        assert_eq!(bitmap.iter().collect::<Vec<u32>>(), vec![1, 2, 3]);
+        let bitmap = reader
+            .contains_all("tags", tags(&["b", "c"]).elements())
+            .await
+            .unwrap();
+        assert_eq!(bitmap.iter().collect::<Vec<u32>>(), vec![2, 3]);
+        let bitmap = reader
+            .contains_all("tags", tags(&["a", "d"]).elements())
+            .await
+            .unwrap();
+        assert_eq!(bitmap.len(), 0);
+        let bitmap = reader.contains_all("tags", vec![]).await.unwrap();
+        assert_eq!(bitmap.iter().collect::<Vec<u32>>(), vec![1, 2, 3]);
+        let result = reader.get("tags", tags(&["a"])).await;
+        assert_eq!(result.unwrap_err().code(), ErrorCodes::InvalidArgument);
+
+        index.begin_transaction().await.unwrap();
+        index.delete("tags", tags(&["a", "b"]), 1).await.unwrap();
+        index.commit_transaction().await.unwrap();
+        let bitmap = reader
+            .contains_any("tags", tags(&["a", "b"]).elements())
+            .await
+            .unwrap();
+        assert_eq!(bitmap.iter().collect::<Vec<u32>>(), vec![2, 3]);
+        assert_eq!(reader.get_metadata(1).await.unwrap().len(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_metadata_index_update() {
+        let mut provider = HashMapBlockfileProvider::new();
+        let blockfile = provider
+            .create("test", KeyType::String, ValueType::RoaringBitmap)
+            .unwrap();
+        let forward_blockfile = provider
+            .create("forward", KeyType::Int, ValueType::String)
+            .unwrap();
+        let mut index =
+            BlockfileMetadataIndexWriter::new(blockfile).with_forward_index(forward_blockfile);
+        let reader = index.reader();
+        index.begin_transaction().await.unwrap();
+        index
+            .set("year", MetadataIndexValue::Int(2020), 1)
+            .await
+            .unwrap();
+        index
+            .set("year", MetadataIndexValue::Int(2020), 2)
+            .await
+            .unwrap();
+        index.commit_transaction().await.unwrap();
+
+        index.begin_transaction().await.unwrap();
+        index
+            .update("year", None, Some(MetadataIndexValue::Int(2021)), 1)
+            .await
+            .unwrap();
+        index
+            .update(
+                "year",
+                Some(MetadataIndexValue::Int(2020)),
+                Some(MetadataIndexValue::Int(2022)),
+                2,
+            )
+            .await
+            .unwrap();
+        index
+            .update("genre", None, Some(MetadataIndexValue::Bool(true)), 3)
+            .await
+            .unwrap();
+        index.commit_transaction().await.unwrap();
+
+        let bitmap = reader
+            .get("year", MetadataIndexValue::Int(2020))
+            .await
+            .unwrap();
+        assert_eq!(bitmap.len(), 0);
+        let bitmap = reader
+            .get("year", MetadataIndexValue::Int(2021))
+            .await
+            .unwrap();
+        assert_eq!(bitmap.iter().collect::<Vec<u32>>(), vec![1]);
+        let bitmap = reader
+            .get("year", MetadataIndexValue::Int(2022))
+            .await
+            .unwrap();
+        assert_eq!(bitmap.iter().collect::<Vec<u32>>(), vec![2]);
+        let metadata = reader.get_metadata(1).await.unwrap();
+        assert_eq!(metadata.get("year"), Some(&MetadataIndexValue::Int(2021)));
+        let metadata = reader.get_metadata(3).await.unwrap();
+        assert_eq!(metadata.get("genre"), Some(&MetadataIndexValue::Bool(true)));
+
+        index.begin_transaction().await.unwrap();
+        index.update("year", None, None, 1).await.unwrap();
+        index.commit_transaction().await.unwrap();
+        let bitmap = reader
+            .get("year", MetadataIndexValue::Int(2021))
+            .await
+            .unwrap();
+        assert_eq!(bitmap.len(), 0);
+        assert_eq!(reader.get_metadata(1).await.unwrap().len(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_metadata_index_update_without_forward_index() {
+        let mut provider = HashMapBlockfileProvider::new();
+        let blockfile = provider
+            .create("test", KeyType::String, ValueType::RoaringBitmap)
+            .unwrap();
+        let mut index = BlockfileMetadataIndexWriter::new(blockfile);
+        index.begin_transaction().await.unwrap();
+        let result = index
+            .update("year", None, Some(MetadataIndexValue::Int(2021)), 1)
+            .await;
+        assert_eq!(result.unwrap_err().code(), ErrorCodes::FailedPrecondition);
+        index
+            .update(
+                "year",
+                Some(MetadataIndexValue::Int(2020)),
+                Some(MetadataIndexValue::Int(2021)),
+                1,
+            )
+            .await
+            .unwrap();
+        index.commit_transaction().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_metadata_index_max_transaction_bytes() {
+        let mut provider = HashMapBlockfileProvider::new();
+        let blockfile = provider
+            .create("test", KeyType::String, ValueType::RoaringBitmap)
+            .unwrap();
+        let mut index =
+            BlockfileMetadataIndexWriter::new(blockfile).with_max_transaction_bytes(256);
+        let reader = index.reader();
+        assert_eq!(index.current_memory_bytes(), 0);
+
+        index.begin_transaction().await.unwrap();
+        let mut offset_id = 0;
+        let result = loop {
+            let result = index
+                .set("key", MetadataIndexValue::Int(offset_id as i64), offset_id)
+                .await;
+            if result.is_err() {
+                break result;
+            }
+            offset_id += 1;
+        };
+        let err = result.unwrap_err();
+        assert_eq!(err.code(), ErrorCodes::ResourceExhausted);
+        assert_eq!(err.category(), ErrorCategory::User);
+        assert!(index.current_memory_bytes() > 256);
+        // The rejected write is not applied.
+        let result = index.delete("key", MetadataIndexValue::Int(0), 0).await;
+        assert_eq!(result.unwrap_err().code(), ErrorCodes::ResourceExhausted);
+        index.commit_transaction().await.unwrap();
+        assert_eq!(index.current_memory_bytes(), 0);
+        let bitmap = reader.exists("key").await.unwrap();
+        assert_eq!(bitmap.len(), offset_id as u64);
+
+        // Writes go through again in the next transaction.
+        index.begin_transaction().await.unwrap();
+        index
+            .set("key", MetadataIndexValue::Int(0), offset_id)
+            .await
+            .unwrap();
+        assert!(index.current_memory_bytes() > 0);
+        index.commit_transaction().await.unwrap();
+        let bitmap = reader.get("key", MetadataIndexValue::Int(0)).await.unwrap();
+        assert_eq!(
+            bitmap.iter().collect::<Vec<u32>>(),
+            vec![0, offset_id as u32]
+        );
+    }
+}
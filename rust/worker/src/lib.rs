@@ -49,15 +49,51 @@ pub async fn worker_entrypoint() {
             }
         };
 
+    // Every blockfile provider shares the block cache, so it is built once here
+    let block_cache =
+        match blockstore::arrow_blockfile::cache::BlockCache::try_from_config(&config.worker).await
+        {
+            Ok(block_cache) => block_cache,
+            Err(err) => {
+                println!("Failed to create block cache: {:?}", err);
+                return;
+            }
+        };
+    tokio::spawn(block_cache.clone().report_stats());
+
+    // Warm the block cache with the blocks that were hot before the last shutdown, without
+    // holding up startup
+    let blockfile_provider =
+        blockstore::arrow_blockfile::provider::ArrowBlockfileProvider::from_config(
+            &config.worker.blockfile,
+            block_cache.clone(),
+        );
+    let hot_set_size = config.worker.blockfile.block_cache_hot_set_size;
+    let warming_provider = blockfile_provider.clone();
+    tokio::task::spawn_blocking(
+        move || match warming_provider.warm_block_cache(hot_set_size) {
+            Ok(loaded) => tracing::info!("Warmed the block cache with {} blocks", loaded),
+            Err(err) => tracing::warn!("Failed to warm the block cache: {}", err),
+        },
+    );
+
+    if config.worker.blockfile.scrub_interval_secs > 0 {
+        let scrubber = blockfile_provider.scrubber(config.worker.blockfile.scrub_blocks_per_round);
+        tokio::spawn(scrubber.run(std::time::Duration::from_secs(
+            config.worker.blockfile.scrub_interval_secs,
+        )));
+    }
+
     let mut scheduler = ingest::RoundRobinScheduler::new();
 
-    let segment_manager = match segment::SegmentManager::try_from_config(&config.worker).await {
+    let mut segment_manager = match segment::SegmentManager::try_from_config(&config.worker).await {
         Ok(segment_manager) => segment_manager,
         Err(err) => {
             println!("Failed to create segment manager component: {:?}", err);
             return;
         }
     };
+    segment_manager.set_blockfile_provider(blockfile_provider.clone());
 
     let mut segment_ingestor_receivers =
         Vec::with_capacity(config.worker.num_indexing_threads as usize);
@@ -103,4 +139,8 @@ pub async fn worker_entrypoint() {
         memberlist_handle.join(),
         scheduler_handler.join(),
     );
+
+    if let Err(err) = blockfile_provider.save_block_cache_hot_set(hot_set_size) {
+        println!("Failed to save the block cache hot set: {:?}", err);
+    }
 }
@@ -3,7 +3,21 @@ use serde::Deserialize;
 /// The configuration for the custom resource memberlist provider.
 /// # Fields
 /// - storage_path: The path to use for temporary storage in the segment manager, if needed.
+/// - hnsw_build_threads: The number of threads that insert a batch of vectors into an HNSW index. Defaults to the number of cores on the machine.
+/// - metadata_max_transaction_bytes: The estimated size of the uncommitted writes a metadata segment holds before it commits them. Defaults to 64 MiB.
 #[derive(Deserialize)]
 pub(crate) struct SegmentManagerConfig {
     pub(crate) storage_path: String,
+    #[serde(default = "default_hnsw_build_threads")]
+    pub(crate) hnsw_build_threads: usize,
+    #[serde(default = "default_metadata_max_transaction_bytes")]
+    pub(crate) metadata_max_transaction_bytes: usize,
+}
+
+fn default_hnsw_build_threads() -> usize {
+    num_cpus::get()
+}
+
+fn default_metadata_max_transaction_bytes() -> usize {
+    64 * 1024 * 1024
 }
@@ -1,11 +1,11 @@
 use num_bigint::BigInt;
 use parking_lot::{Mutex, RwLock, RwLockUpgradableReadGuard, RwLockWriteGuard};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::atomic::AtomicUsize;
 use std::sync::Arc;
 
 use crate::errors::ChromaError;
-use crate::index::{HnswIndex, HnswIndexConfig, Index, IndexConfig};
+use crate::index::{DistanceFunction, HnswIndex, HnswIndexConfig, Index, IndexConfig};
 use crate::types::{EmbeddingRecord, Operation, Segment, VectorEmbeddingRecord};
 
 pub(crate) struct DistributedHNSWSegment {
@@ -15,12 +15,14 @@ pub(crate) struct DistributedHNSWSegment {
     id_to_user_id: Arc<RwLock<HashMap<usize, String>>>,
     index_config: IndexConfig,
     hnsw_config: HnswIndexConfig,
+    build_pool: Arc<rayon::ThreadPool>,
 }
 
 impl DistributedHNSWSegment {
     pub(crate) fn new(
         index_config: IndexConfig,
         hnsw_config: HnswIndexConfig,
+        build_pool: Arc<rayon::ThreadPool>,
     ) -> Result<Self, Box<dyn ChromaError>> {
         let hnsw_index = HnswIndex::init(&index_config, Some(&hnsw_config));
         let hnsw_index = match hnsw_index {
@@ -38,6 +40,7 @@ impl DistributedHNSWSegment {
             id_to_user_id: Arc::new(RwLock::new(HashMap::new())),
             index_config: index_config,
             hnsw_config,
+            build_pool,
         });
     }
 
@@ -45,18 +48,23 @@ impl DistributedHNSWSegment {
         segment: &Segment,
         persist_path: &std::path::Path,
         dimensionality: usize,
+        build_pool: Arc<rayon::ThreadPool>,
     ) -> Result<Box<DistributedHNSWSegment>, Box<dyn ChromaError>> {
         let index_config = IndexConfig::from_segment(&segment, dimensionality as i32)?;
         let hnsw_config = HnswIndexConfig::from_segment(segment, persist_path)?;
         Ok(Box::new(DistributedHNSWSegment::new(
             index_config,
             hnsw_config,
+            build_pool,
         )?))
     }
 
     pub(crate) fn write_records(&self, records: Vec<Box<EmbeddingRecord>>) {
-        for record in records {
-            let op = Operation::try_from(record.operation);
+        // The added vectors are inserted as one batch, built by the threads of the pool
+        let mut ids = Vec::new();
+        let mut vectors = Vec::new();
+        for record in &records {
+            let op = Operation::try_from(record.operation.clone());
             match op {
                 Ok(Operation::Add) => {
                     // TODO: make lock xor lock
@@ -70,7 +78,8 @@ impl DistributedHNSWSegment {
                                 .write()
                                 .insert(next_id, record.id.clone());
                             println!("Segment adding item: {}", next_id);
-                            self.index.read().add(next_id, &vector);
+                            ids.push(next_id);
+                            vectors.push(vector.as_slice());
                         }
                         None => {
                             // TODO: log an error
@@ -86,6 +95,26 @@ impl DistributedHNSWSegment {
                 }
             }
         }
+        if ids.is_empty() {
+            return;
+        }
+
+        let res = self
+            .index
+            .read()
+            .add_batch(&ids, &vectors, &self.build_pool);
+        if let Err(e) = res {
+            // TODO: return the error to the caller
+            println!("Failed to add vectors to the index: {}", e);
+            // None of the vectors were added, so their ids must not resolve
+            let mut user_id_to_id = self.user_id_to_id.write();
+            let mut id_to_user_id = self.id_to_user_id.write();
+            for id in ids {
+                if let Some(user_id) = id_to_user_id.remove(&id) {
+                    user_id_to_id.remove(&user_id);
+                }
+            }
+        }
     }
 
     pub(crate) fn get_records(&self, ids: Vec<String>) -> Vec<Box<VectorEmbeddingRecord>> {
@@ -118,19 +147,47 @@ impl DistributedHNSWSegment {
         return records;
     }
 
-    pub(crate) fn query(&self, vector: &[f32], k: usize) -> (Vec<String>, Vec<f32>) {
+    pub(crate) fn distance_function(&self) -> DistanceFunction {
+        self.index_config.distance_function.clone()
+    }
+
+    pub(crate) fn query(
+        &self,
+        vector: &[f32],
+        k: usize,
+        allowed_ids: Option<&HashSet<String>>,
+    ) -> (Vec<String>, Vec<f32>) {
         let index = self.index.read();
-        let mut return_user_ids = Vec::new();
-        let (ids, distances) = index.query(vector, k);
         let user_ids = self.id_to_user_id.read();
-        for id in ids {
+        // The index cannot filter while it searches, so a filtered query ranks every
+        // record and keeps the first k that are allowed.
+        // TODO: push the filter down into the index
+        let query_k = match allowed_ids {
+            Some(_) => user_ids.len(),
+            None => k,
+        };
+        let mut return_user_ids = Vec::new();
+        let mut return_distances = Vec::new();
+        let (ids, distances) = index.query(vector, query_k);
+        for (id, distance) in ids.into_iter().zip(distances) {
+            if return_user_ids.len() == k {
+                break;
+            }
             match user_ids.get(&id) {
-                Some(user_id) => return_user_ids.push(user_id.clone()),
+                Some(user_id) => {
+                    if let Some(allowed_ids) = allowed_ids {
+                        if !allowed_ids.contains(user_id) {
+                            continue;
+                        }
+                    }
+                    return_user_ids.push(user_id.clone());
+                    return_distances.push(distance);
+                }
                 None => {
                     // TODO: error
                 }
             };
         }
-        return (return_user_ids, distances);
+        return (return_user_ids, return_distances);
     }
 }
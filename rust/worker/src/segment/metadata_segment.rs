@@ -0,0 +1,627 @@
+use crate::blockstore::provider::{BlockfileProvider, OpenError};
+use crate::blockstore::{Blockfile, KeyType, ValueType};
+use crate::errors::{ChromaError, ErrorCodes};
+use crate::index::tokenizer::AnalyzerConfig;
+use crate::index::{
+    BlockfileFullTextIndex, BlockfileMetadataIndexReader, BlockfileMetadataIndexWriter,
+    FullTextIndex, MetadataIndexReader, MetadataIndexValue, MetadataIndexWriter, WhereEvaluator,
+};
+use crate::types::{
+    BooleanOperator, EmbeddingRecord, MetadataValue, Operation, Segment, UpdateMetadata,
+    UpdateMetadataValue, Where, WhereDocument, WhereDocumentOperator,
+};
+use roaring::RoaringBitmap;
+use std::collections::{HashMap, HashSet};
+use std::ops::{BitAndAssign, BitOrAssign, SubAssign};
+use thiserror::Error;
+
+// The user id and the document of a record are kept in the metadata index under
+// reserved keys. The id is indexed, so that every record is set in a posting and is
+// found again on open. The document is only kept in the forward index, it is searched
+// through the full-text index.
+const ID_KEY: &str = "chroma:id";
+const DOCUMENT_KEY: &str = "chroma:document";
+
+// Fractions of the offset id space at which the metadata index logs a warning. Every
+// write of a record takes a new offset id, so they run out faster than records.
+const OFFSET_ID_WARNING_FRACTIONS: [f64; 3] = [0.8, 0.9, 0.95];
+
+#[derive(Error, Debug)]
+pub(crate) enum MetadataSegmentError {
+    #[error("Invalid composite index `{0}`, expected two comma-separated keys")]
+    InvalidCompositeIndex(String),
+}
+
+impl ChromaError for MetadataSegmentError {
+    fn code(&self) -> ErrorCodes {
+        match self {
+            MetadataSegmentError::InvalidCompositeIndex(_) => ErrorCodes::InvalidArgument,
+        }
+    }
+}
+
+/// A metadata segment whose metadata and documents are indexed in blockfiles.
+/// # Description
+/// The blockfiles are created under the segment id the first time the segment is used
+/// and reopened after a restart, when the user ids of the records are read back from
+/// the forward index. Where clauses are evaluated against the metadata index and
+/// where_document clauses against the full-text index.
+/// # Notes
+/// The full-text index has no deletes, so every write of a record gives it a new offset
+/// id and clears the previous one, and an offset id always holds a single version of a
+/// record.
+/// The indexes are configured by the segment metadata. `metadata:unindexed_keys` is a
+/// comma-separated list of keys kept without postings, `metadata:composite_indexes` a
+/// semicolon-separated list of comma-separated key pairs with a composite posting, and
+/// the analysis chain of the full-text index is read as AnalyzerConfig::from_segment does.
+pub(crate) struct BlockfileMetadataSegment {
+    metadata_index: BlockfileMetadataIndexWriter,
+    metadata_reader: BlockfileMetadataIndexReader,
+    full_text_index: BlockfileFullTextIndex,
+    max_transaction_bytes: usize,
+    user_id_to_offset_id: HashMap<String, usize>,
+    offset_id_to_user_id: HashMap<usize, String>,
+    // The offsets of the records with a document, the universe of $not_contains.
+    document_offsets: RoaringBitmap,
+    next_offset_id: usize,
+}
+
+impl BlockfileMetadataSegment {
+    pub(crate) async fn from_segment<P: BlockfileProvider>(
+        segment: &Segment,
+        provider: &mut P,
+        max_transaction_bytes: usize,
+    ) -> Result<Self, Box<dyn ChromaError>> {
+        let metadata_index = match BlockfileMetadataIndexWriter::open(provider, &segment.id) {
+            Ok(metadata_index) => metadata_index,
+            Err(e) if e.code() == ErrorCodes::NotFound => {
+                BlockfileMetadataIndexWriter::create(provider, &segment.id)?
+            }
+            Err(e) => return Err(e),
+        };
+        let forward_blockfile = open_or_create(
+            provider,
+            &format!("{}/forward", segment.id),
+            KeyType::Int,
+            ValueType::String,
+        )?;
+        let mut unindexed_keys = HashSet::from([DOCUMENT_KEY.to_string()]);
+        unindexed_keys.extend(metadata_list(segment, "metadata:unindexed_keys", ','));
+        let mut metadata_index = metadata_index
+            .with_forward_index(forward_blockfile)
+            .with_unindexed_keys(unindexed_keys)
+            .with_max_transaction_bytes(max_transaction_bytes)
+            .with_offset_id_warning_thresholds(&OFFSET_ID_WARNING_FRACTIONS);
+        for composite_index in metadata_list(segment, "metadata:composite_indexes", ';') {
+            match composite_index.split_once(',') {
+                Some((key_a, key_b)) => {
+                    metadata_index = metadata_index.with_composite_index(key_a, key_b)?;
+                }
+                None => {
+                    return Err(Box::new(MetadataSegmentError::InvalidCompositeIndex(
+                        composite_index,
+                    )))
+                }
+            }
+        }
+
+        let full_text_index = BlockfileFullTextIndex::new(
+            open_or_create(
+                provider,
+                &format!("{}/fulltext/postings", segment.id),
+                KeyType::String,
+                ValueType::PositionalPostingList,
+            )?,
+            open_or_create(
+                provider,
+                &format!("{}/fulltext/frequencies", segment.id),
+                KeyType::String,
+                ValueType::Int32,
+            )?,
+            AnalyzerConfig::from_segment(segment)?.build()?,
+        )
+        .with_document_store(open_or_create(
+            provider,
+            &format!("{}/fulltext/documents", segment.id),
+            KeyType::String,
+            ValueType::String,
+        )?);
+
+        let metadata_reader = metadata_index.reader();
+        let mut metadata_segment = BlockfileMetadataSegment {
+            metadata_index,
+            metadata_reader,
+            full_text_index,
+            max_transaction_bytes,
+            user_id_to_offset_id: HashMap::new(),
+            offset_id_to_user_id: HashMap::new(),
+            document_offsets: RoaringBitmap::new(),
+            next_offset_id: 0,
+        };
+        metadata_segment.load_records().await?;
+        Ok(metadata_segment)
+    }
+
+    // Reads the user id of every committed offset back from the forward index. A write
+    // interrupted by a restart may leave the previous offset of a record set, the latest
+    // offset of each user id is kept then.
+    async fn load_records(&mut self) -> Result<(), Box<dyn ChromaError>> {
+        let offset_ids = self.metadata_reader.all_offsets().await?;
+        for offset_id in offset_ids {
+            let offset_id = offset_id as usize;
+            self.next_offset_id = self.next_offset_id.max(offset_id + 1);
+            let metadata = self.metadata_reader.get_metadata(offset_id).await?;
+            let user_id = match metadata.get(ID_KEY) {
+                Some(MetadataIndexValue::String(user_id)) => user_id.clone(),
+                _ => continue,
+            };
+            if let Some(previous) = self.user_id_to_offset_id.insert(user_id.clone(), offset_id) {
+                self.offset_id_to_user_id.remove(&previous);
+                self.document_offsets.remove(previous as u32);
+            }
+            self.offset_id_to_user_id.insert(offset_id, user_id);
+            if metadata.contains_key(DOCUMENT_KEY) {
+                self.document_offsets.insert(offset_id as u32);
+            }
+        }
+        Ok(())
+    }
+
+    // Writes the records in one transaction, unless it grows past max_transaction_bytes.
+    // A record whose id was already written in the transaction starts a new one, so that
+    // its previous metadata is read from committed state.
+    pub(crate) async fn write_records(
+        &mut self,
+        records: &[Box<EmbeddingRecord>],
+    ) -> Result<(), Box<dyn ChromaError>> {
+        self.begin_transaction().await?;
+        let mut written = HashSet::new();
+        for record in records {
+            if !written.insert(record.id.as_str()) {
+                self.commit_transaction().await?;
+                self.begin_transaction().await?;
+                written.clear();
+                written.insert(record.id.as_str());
+            }
+            self.write_record(record).await?;
+        }
+        self.commit_transaction().await
+    }
+
+    async fn begin_transaction(&mut self) -> Result<(), Box<dyn ChromaError>> {
+        self.metadata_index.begin_transaction().await?;
+        self.full_text_index.begin_transaction()
+    }
+
+    async fn commit_transaction(&mut self) -> Result<(), Box<dyn ChromaError>> {
+        self.metadata_index.commit_transaction().await?;
+        self.full_text_index.commit_transaction()
+    }
+
+    // The metadata index rejects writes once the transaction holds more than
+    // max_transaction_bytes, so the transaction is committed before each write past it.
+    async fn make_room(&mut self) -> Result<(), Box<dyn ChromaError>> {
+        if self.metadata_index.current_memory_bytes() > self.max_transaction_bytes {
+            self.commit_transaction().await?;
+            self.begin_transaction().await?;
+        }
+        Ok(())
+    }
+
+    async fn write_record(&mut self, record: &EmbeddingRecord) -> Result<(), Box<dyn ChromaError>> {
+        let previous = self.user_id_to_offset_id.get(&record.id).copied();
+        let metadata = match (&record.operation, previous) {
+            (Operation::Add, None) | (Operation::Upsert, None) => {
+                let mut metadata = HashMap::new();
+                apply_update(&mut metadata, record.metadata.as_ref());
+                metadata
+            }
+            (Operation::Update, Some(previous)) | (Operation::Upsert, Some(previous)) => {
+                let mut metadata = self.metadata_reader.get_metadata(previous).await?;
+                apply_update(&mut metadata, record.metadata.as_ref());
+                metadata
+            }
+            (Operation::Delete, Some(previous)) => {
+                self.make_room().await?;
+                self.metadata_index.delete_all(previous).await?;
+                self.remove_offset(&record.id, previous);
+                return Ok(());
+            }
+            (Operation::Add, Some(_)) => {
+                println!("Record {} already exists, not adding it", record.id);
+                return Ok(());
+            }
+            (Operation::Update, None) | (Operation::Delete, None) => {
+                println!("Record {} does not exist, not writing it", record.id);
+                return Ok(());
+            }
+        };
+
+        // The new offset is set before the previous one is cleared, so that the record
+        // is never missing from committed state.
+        let offset_id = self.next_offset_id;
+        let mut entries = vec![(
+            ID_KEY,
+            MetadataIndexValue::String(record.id.clone()),
+            offset_id,
+        )];
+        for (key, value) in metadata.iter() {
+            if key != ID_KEY {
+                entries.push((key.as_str(), value.clone(), offset_id));
+            }
+        }
+        self.make_room().await?;
+        self.metadata_index.set_many(entries).await?;
+        self.next_offset_id += 1;
+        if let Some(previous) = previous {
+            self.make_room().await?;
+            self.metadata_index.delete_all(previous).await?;
+            self.remove_offset(&record.id, previous);
+        }
+        if let Some(MetadataIndexValue::String(document)) = metadata.get(DOCUMENT_KEY) {
+            self.full_text_index
+                .add_document(document, offset_id as i32)?;
+            self.document_offsets.insert(offset_id as u32);
+        }
+        self.user_id_to_offset_id
+            .insert(record.id.clone(), offset_id);
+        self.offset_id_to_user_id
+            .insert(offset_id, record.id.clone());
+        Ok(())
+    }
+
+    fn remove_offset(&mut self, user_id: &str, offset_id: usize) {
+        self.user_id_to_offset_id.remove(user_id);
+        self.offset_id_to_user_id.remove(&offset_id);
+        self.document_offsets.remove(offset_id as u32);
+    }
+
+    // Returns the user ids of the records matching both clauses, or of every record if
+    // neither is given, in the order they were last written.
+    pub(crate) async fn query(
+        &mut self,
+        where_clause: Option<&Where>,
+        where_document: Option<&WhereDocument>,
+    ) -> Result<Vec<String>, Box<dyn ChromaError>> {
+        let mut offsets: RoaringBitmap = self
+            .offset_id_to_user_id
+            .keys()
+            .map(|offset_id| *offset_id as u32)
+            .collect();
+        if let Some(where_clause) = where_clause {
+            let evaluator = WhereEvaluator::new(vec![&self.metadata_reader]);
+            offsets.bitand_assign(evaluator.evaluate(where_clause).await?);
+        }
+        if let Some(where_document) = where_document {
+            offsets.bitand_assign(self.evaluate_document(where_document)?);
+        }
+        Ok(offsets
+            .iter()
+            .filter_map(|offset_id| self.offset_id_to_user_id.get(&(offset_id as usize)))
+            .cloned()
+            .collect())
+    }
+
+    // $contains matches the documents holding the text as a substring, $not_contains
+    // every other record with a document.
+    fn evaluate_document(
+        &mut self,
+        where_document: &WhereDocument,
+    ) -> Result<RoaringBitmap, Box<dyn ChromaError>> {
+        match where_document {
+            WhereDocument::DirectDocumentComparison(comparison) => {
+                let contains: RoaringBitmap = self
+                    .full_text_index
+                    .search_regex(&regex::escape(&comparison.document))?
+                    .into_iter()
+                    .map(|offset_id| offset_id as u32)
+                    .collect();
+                match comparison.operator {
+                    WhereDocumentOperator::Contains => Ok(contains),
+                    WhereDocumentOperator::NotContains => {
+                        let mut not_contains = self.document_offsets.clone();
+                        not_contains.sub_assign(contains);
+                        Ok(not_contains)
+                    }
+                }
+            }
+            WhereDocument::WhereDocumentChildren(children) => {
+                let mut result: Option<RoaringBitmap> = None;
+                for child in children.children.iter() {
+                    let child_result = self.evaluate_document(child)?;
+                    result = match result {
+                        None => Some(child_result),
+                        Some(mut result) => {
+                            match children.operator {
+                                BooleanOperator::And => result.bitand_assign(child_result),
+                                BooleanOperator::Or => result.bitor_assign(child_result),
+                            }
+                            Some(result)
+                        }
+                    };
+                }
+                Ok(result.unwrap_or_default())
+            }
+        }
+    }
+}
+
+fn open_or_create<P: BlockfileProvider>(
+    provider: &mut P,
+    path: &str,
+    key_type: KeyType,
+    value_type: ValueType,
+) -> Result<Box<dyn Blockfile>, Box<dyn ChromaError>> {
+    match provider.open(path) {
+        Ok(blockfile) => Ok(blockfile),
+        Err(e) => match *e {
+            OpenError::NotFound => match provider.create(path, key_type, value_type) {
+                Ok(blockfile) => Ok(blockfile),
+                Err(e) => Err(e),
+            },
+            _ => Err(e),
+        },
+    }
+}
+
+// The non-empty items of a list in the segment metadata.
+fn metadata_list(segment: &Segment, key: &str, separator: char) -> Vec<String> {
+    match &segment.metadata {
+        Some(metadata) => match metadata.get(key) {
+            Some(MetadataValue::Str(list)) => list
+                .split(separator)
+                .map(|item| item.trim().to_string())
+                .filter(|item| !item.is_empty())
+                .collect(),
+            _ => Vec::new(),
+        },
+        None => Vec::new(),
+    }
+}
+
+fn apply_update(
+    metadata: &mut HashMap<String, MetadataIndexValue>,
+    update: Option<&UpdateMetadata>,
+) {
+    let update = match update {
+        Some(update) => update,
+        None => return,
+    };
+    for (key, value) in update.iter() {
+        let value = match value {
+            UpdateMetadataValue::Int(value) => MetadataIndexValue::Int(*value as i64),
+            UpdateMetadataValue::Float(value) => MetadataIndexValue::from(*value),
+            UpdateMetadataValue::Str(value) => MetadataIndexValue::String(value.clone()),
+            UpdateMetadataValue::None => {
+                metadata.remove(key);
+                continue;
+            }
+        };
+        metadata.insert(key.clone(), value);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blockstore::provider::HashMapBlockfileProvider;
+    use crate::types::{
+        DirectComparison, DirectDocumentComparison, Metadata, SegmentScope, SegmentType,
+        WhereClauseComparator, WhereComparison, WhereDocumentChildren,
+    };
+    use num_bigint::BigInt;
+    use uuid::Uuid;
+
+    fn segment(id: Uuid, metadata: Option<Metadata>) -> Segment {
+        Segment {
+            id,
+            r#type: SegmentType::BlockfileMetadata,
+            scope: SegmentScope::METADATA,
+            topic: None,
+            collection: None,
+            metadata,
+        }
+    }
+
+    fn record(
+        id: &str,
+        operation: Operation,
+        metadata: Vec<(&str, UpdateMetadataValue)>,
+    ) -> Box<EmbeddingRecord> {
+        Box::new(EmbeddingRecord {
+            id: id.to_string(),
+            seq_id: BigInt::from(0),
+            embedding: None,
+            encoding: None,
+            metadata: Some(
+                metadata
+                    .into_iter()
+                    .map(|(key, value)| (key.to_string(), value))
+                    .collect(),
+            ),
+            operation,
+            collection_id: Uuid::nil(),
+        })
+    }
+
+    fn color_is(color: &str) -> Where {
+        Where::DirectComparison(DirectComparison {
+            key: "color".to_string(),
+            comparison: WhereComparison::SingleString(
+                color.to_string(),
+                WhereClauseComparator::Equal,
+            ),
+        })
+    }
+
+    fn document(text: &str, operator: WhereDocumentOperator) -> WhereDocument {
+        WhereDocument::DirectDocumentComparison(DirectDocumentComparison {
+            document: text.to_string(),
+            operator,
+        })
+    }
+
+    fn sorted(mut ids: Vec<String>) -> Vec<String> {
+        ids.sort();
+        ids
+    }
+
+    #[tokio::test]
+    async fn test_metadata_segment_write_and_query() {
+        let mut provider = HashMapBlockfileProvider::new();
+        let segment = segment(Uuid::new_v4(), None);
+        let mut metadata_segment =
+            BlockfileMetadataSegment::from_segment(&segment, &mut provider, 1024 * 1024)
+                .await
+                .unwrap();
+        let red = || UpdateMetadataValue::Str("red".to_string());
+        let blue = || UpdateMetadataValue::Str("blue".to_string());
+        let text = |text: &str| UpdateMetadataValue::Str(text.to_string());
+        metadata_segment
+            .write_records(&[
+                record(
+                    "a",
+                    Operation::Add,
+                    vec![("color", red()), (DOCUMENT_KEY, text("the quick fox"))],
+                ),
+                record(
+                    "b",
+                    Operation::Add,
+                    vec![("color", blue()), (DOCUMENT_KEY, text("a lazy dog"))],
+                ),
+                record("c", Operation::Add, vec![("color", red())]),
+                record("c", Operation::Update, vec![("color", blue())]),
+                record("d", Operation::Upsert, vec![("color", red())]),
+                record("d", Operation::Delete, vec![]),
+            ])
+            .await
+            .unwrap();
+
+        let ids = metadata_segment.query(None, None).await.unwrap();
+        assert_eq!(sorted(ids), vec!["a", "b", "c"]);
+        let ids = metadata_segment
+            .query(Some(&color_is("blue")), None)
+            .await
+            .unwrap();
+        assert_eq!(sorted(ids), vec!["b", "c"]);
+        let ids = metadata_segment
+            .query(
+                None,
+                Some(&document("quick", WhereDocumentOperator::Contains)),
+            )
+            .await
+            .unwrap();
+        assert_eq!(ids, vec!["a"]);
+        let ids = metadata_segment
+            .query(
+                Some(&color_is("blue")),
+                Some(&document("quick", WhereDocumentOperator::NotContains)),
+            )
+            .await
+            .unwrap();
+        assert_eq!(ids, vec!["b"]);
+        let either = WhereDocument::WhereDocumentChildren(WhereDocumentChildren {
+            children: vec![
+                document("quick", WhereDocumentOperator::Contains),
+                document("lazy", WhereDocumentOperator::Contains),
+            ],
+            operator: BooleanOperator::Or,
+        });
+        let ids = metadata_segment.query(None, Some(&either)).await.unwrap();
+        assert_eq!(sorted(ids), vec!["a", "b"]);
+
+        // An update keeps the document and the keys it does not set.
+        metadata_segment
+            .write_records(&[record("a", Operation::Update, vec![("color", blue())])])
+            .await
+            .unwrap();
+        let ids = metadata_segment
+            .query(
+                Some(&color_is("blue")),
+                Some(&document("fox", WhereDocumentOperator::Contains)),
+            )
+            .await
+            .unwrap();
+        assert_eq!(ids, vec!["a"]);
+        let ids = metadata_segment
+            .query(Some(&color_is("red")), None)
+            .await
+            .unwrap();
+        assert!(ids.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_metadata_segment_reopen() {
+        let mut provider = HashMapBlockfileProvider::new();
+        let segment = segment(Uuid::new_v4(), None);
+        let mut metadata_segment =
+            BlockfileMetadataSegment::from_segment(&segment, &mut provider, 1024 * 1024)
+                .await
+                .unwrap();
+        let color = |color: &str| UpdateMetadataValue::Str(color.to_string());
+        metadata_segment
+            .write_records(&[
+                record("a", Operation::Add, vec![("color", color("red"))]),
+                record("b", Operation::Add, vec![("color", color("blue"))]),
+                record("b", Operation::Update, vec![("color", color("red"))]),
+                record("c", Operation::Add, vec![("color", color("red"))]),
+                record("c", Operation::Delete, vec![]),
+            ])
+            .await
+            .unwrap();
+
+        let mut reopened =
+            BlockfileMetadataSegment::from_segment(&segment, &mut provider, 1024 * 1024)
+                .await
+                .unwrap();
+        let ids = reopened.query(Some(&color_is("red")), None).await.unwrap();
+        assert_eq!(sorted(ids), vec!["a", "b"]);
+
+        // Offsets of the reopened segment don't collide with the committed ones.
+        reopened
+            .write_records(&[record("d", Operation::Add, vec![("color", color("red"))])])
+            .await
+            .unwrap();
+        let ids = reopened.query(Some(&color_is("red")), None).await.unwrap();
+        assert_eq!(sorted(ids), vec!["a", "b", "d"]);
+    }
+
+    #[tokio::test]
+    async fn test_metadata_segment_small_transactions() {
+        let mut provider = HashMapBlockfileProvider::new();
+        let segment = segment(Uuid::new_v4(), None);
+        let mut metadata_segment =
+            BlockfileMetadataSegment::from_segment(&segment, &mut provider, 1)
+                .await
+                .unwrap();
+        let records = (0..100)
+            .map(|i| {
+                record(
+                    &format!("{:03}", i),
+                    Operation::Add,
+                    vec![("parity", UpdateMetadataValue::Int(i % 2))],
+                )
+            })
+            .collect::<Vec<_>>();
+        metadata_segment.write_records(&records).await.unwrap();
+        let odd = Where::DirectComparison(DirectComparison {
+            key: "parity".to_string(),
+            comparison: WhereComparison::SingleInt(1, WhereClauseComparator::Equal),
+        });
+        let ids = metadata_segment.query(Some(&odd), None).await.unwrap();
+        assert_eq!(ids.len(), 50);
+    }
+
+    #[tokio::test]
+    async fn test_metadata_segment_invalid_composite_index() {
+        let mut provider = HashMapBlockfileProvider::new();
+        let mut metadata = Metadata::new();
+        metadata.insert(
+            "metadata:composite_indexes".to_string(),
+            MetadataValue::Str("color,size;shape".to_string()),
+        );
+        let segment = segment(Uuid::new_v4(), Some(metadata));
+        let result = BlockfileMetadataSegment::from_segment(&segment, &mut provider, 1024).await;
+        assert_eq!(result.err().unwrap().code(), ErrorCodes::InvalidArgument);
+    }
+}
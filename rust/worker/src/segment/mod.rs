@@ -1,5 +1,6 @@
 pub(crate) mod config;
 mod distributed_hnsw_segment;
+mod metadata_segment;
 mod segment_ingestor;
 mod segment_manager;
 
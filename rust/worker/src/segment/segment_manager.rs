@@ -1,6 +1,8 @@
 use crate::{
+    blockstore::arrow_blockfile::provider::ArrowBlockfileProvider,
     config::{Configurable, WorkerConfig},
-    errors::ChromaError,
+    errors::{ChromaError, ErrorCodes},
+    index::DistanceFunction,
     sysdb::sysdb::{GrpcSysDb, SysDb},
     types::VectorQueryResult,
 };
@@ -10,17 +12,47 @@ use num_bigint::BigInt;
 use parking_lot::{
     MappedRwLockReadGuard, RwLock, RwLockReadGuard, RwLockUpgradableReadGuard, RwLockWriteGuard,
 };
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
+use thiserror::Error;
+use tokio::sync::Mutex;
 use uuid::Uuid;
 
 use super::distributed_hnsw_segment::DistributedHNSWSegment;
-use crate::types::{EmbeddingRecord, MetadataValue, Segment, SegmentScope, VectorEmbeddingRecord};
+use super::metadata_segment::BlockfileMetadataSegment;
+use crate::types::{
+    EmbeddingRecord, MetadataValue, Segment, SegmentScope, VectorEmbeddingRecord, Where,
+    WhereDocument, BLOCKFILE_METADATA_SEGMENT_TYPE,
+};
+
+#[derive(Error, Debug)]
+pub(crate) enum SegmentManagerError {
+    #[error("No segment found")]
+    SegmentNotFound,
+    #[error("No vector found")]
+    VectorNotFound,
+    #[error("Failed to start the HNSW build threads: {0}")]
+    BuildThreadPool(#[from] rayon::ThreadPoolBuildError),
+    #[error("No blockfile provider to open metadata segments with")]
+    NoBlockfileProvider,
+}
+
+impl ChromaError for SegmentManagerError {
+    fn code(&self) -> ErrorCodes {
+        match self {
+            SegmentManagerError::SegmentNotFound => ErrorCodes::NotFound,
+            SegmentManagerError::VectorNotFound => ErrorCodes::NotFound,
+            SegmentManagerError::BuildThreadPool(_) => ErrorCodes::Internal,
+            SegmentManagerError::NoBlockfileProvider => ErrorCodes::FailedPrecondition,
+        }
+    }
+}
 
 #[derive(Clone)]
 pub(crate) struct SegmentManager {
     inner: Arc<Inner>,
     sysdb: Box<dyn SysDb>,
+    blockfile_provider: Option<ArrowBlockfileProvider>,
 }
 
 ///
@@ -28,38 +60,66 @@ struct Inner {
     vector_segments: RwLock<HashMap<Uuid, Box<DistributedHNSWSegment>>>,
     collection_to_segment_cache: RwLock<HashMap<Uuid, Vec<Arc<Segment>>>>,
     storage_path: Box<std::path::PathBuf>,
+    // Shared by the HNSW indices of all segments, so that concurrent writes to several
+    // segments don't oversubscribe the cores
+    hnsw_build_pool: Arc<rayon::ThreadPool>,
+    // Metadata segments are opened on first use. The map stays locked while a segment is
+    // opened, so that its blockfiles are created once.
+    metadata_segments: Mutex<HashMap<Uuid, Arc<Mutex<BlockfileMetadataSegment>>>>,
+    metadata_max_transaction_bytes: usize,
 }
 
 impl SegmentManager {
-    pub(crate) fn new(sysdb: Box<dyn SysDb>, storage_path: &std::path::Path) -> Self {
+    pub(crate) fn new(
+        sysdb: Box<dyn SysDb>,
+        storage_path: &std::path::Path,
+        hnsw_build_pool: rayon::ThreadPool,
+        metadata_max_transaction_bytes: usize,
+    ) -> Self {
         SegmentManager {
             inner: Arc::new(Inner {
                 vector_segments: RwLock::new(HashMap::new()),
                 collection_to_segment_cache: RwLock::new(HashMap::new()),
                 storage_path: Box::new(storage_path.to_owned()),
+                hnsw_build_pool: Arc::new(hnsw_build_pool),
+                metadata_segments: Mutex::new(HashMap::new()),
+                metadata_max_transaction_bytes,
             }),
             sysdb: sysdb,
+            blockfile_provider: None,
         }
     }
 
+    pub(crate) fn set_blockfile_provider(&mut self, blockfile_provider: ArrowBlockfileProvider) {
+        self.blockfile_provider = Some(blockfile_provider);
+    }
+
     pub(crate) async fn write_record(&mut self, record: Box<EmbeddingRecord>) {
         let collection_id = record.collection_id;
-        let mut target_segment = None;
-        // TODO: don't assume 1:1 mapping between collection and segment
-        {
+        // TODO: don't assume a single segment per scope in a collection
+        let (target_segment, metadata_segment) = {
             let segments = self.get_segments(&collection_id).await;
-            target_segment = match segments {
-                Ok(found_segments) => {
-                    if found_segments.len() == 0 {
-                        return; // TODO: handle no segment found
-                    }
-                    Some(found_segments[0].clone())
-                }
+            match segments {
+                Ok(found_segments) => (
+                    found_segments
+                        .iter()
+                        .find(|segment| segment.scope == SegmentScope::VECTOR)
+                        .cloned(),
+                    found_segments
+                        .iter()
+                        .find(|segment| segment.scope == SegmentScope::METADATA)
+                        .cloned(),
+                ),
                 Err(_) => {
                     // TODO: throw an error and log no segment found
                     return;
                 }
-            };
+            }
+        };
+
+        if let Some(metadata_segment) = metadata_segment {
+            self.write_metadata_records(&metadata_segment, std::slice::from_ref(&record))
+                .await;
         }
 
         let target_segment = match target_segment {
@@ -85,6 +145,7 @@ impl SegmentManager {
                     &self.inner.storage_path,
                     // TODO: Don't unwrap - throw an error
                     record.embedding.as_ref().unwrap().len(),
+                    self.inner.hnsw_build_pool.clone(),
                 );
 
                 match new_segment {
@@ -101,20 +162,109 @@ impl SegmentManager {
         }
     }
 
+    async fn write_metadata_records(&self, segment: &Segment, records: &[Box<EmbeddingRecord>]) {
+        let metadata_segment = match self.metadata_segment(segment).await {
+            Ok(metadata_segment) => metadata_segment,
+            Err(e) => {
+                println!("Failed to open metadata segment {}: {}", segment.id, e);
+                return;
+            }
+        };
+        let mut metadata_segment = metadata_segment.lock().await;
+        if let Err(e) = metadata_segment.write_records(records).await {
+            // TODO: return the error to the caller
+            println!("Failed to write to metadata segment {}: {}", segment.id, e);
+        }
+    }
+
+    // Returns the metadata segment, opening its indexes on first use.
+    async fn metadata_segment(
+        &self,
+        segment: &Segment,
+    ) -> Result<Arc<Mutex<BlockfileMetadataSegment>>, Box<dyn ChromaError>> {
+        let mut metadata_segments = self.inner.metadata_segments.lock().await;
+        if let Some(metadata_segment) = metadata_segments.get(&segment.id) {
+            return Ok(metadata_segment.clone());
+        }
+        let mut blockfile_provider = match self.blockfile_provider {
+            Some(ref blockfile_provider) => blockfile_provider.clone(),
+            None => return Err(Box::new(SegmentManagerError::NoBlockfileProvider)),
+        };
+        let metadata_segment = BlockfileMetadataSegment::from_segment(
+            segment,
+            &mut blockfile_provider,
+            self.inner.metadata_max_transaction_bytes,
+        )
+        .await?;
+        let metadata_segment = Arc::new(Mutex::new(metadata_segment));
+        metadata_segments.insert(segment.id, metadata_segment.clone());
+        Ok(metadata_segment)
+    }
+
+    // Returns the user ids of the records of the metadata segment that match both
+    // clauses. The segment is looked up in the sysdb if it was not used since startup.
+    pub(crate) async fn query_metadata(
+        &self,
+        segment_id: &Uuid,
+        where_clause: Option<&Where>,
+        where_document: Option<&WhereDocument>,
+    ) -> Result<Vec<String>, Box<dyn ChromaError>> {
+        let cached = self
+            .inner
+            .metadata_segments
+            .lock()
+            .await
+            .get(segment_id)
+            .cloned();
+        let metadata_segment = match cached {
+            Some(metadata_segment) => metadata_segment,
+            None => {
+                let segments = self
+                    .sysdb
+                    .clone()
+                    .get_segments(
+                        Some(*segment_id),
+                        Some(BLOCKFILE_METADATA_SEGMENT_TYPE.to_string()),
+                        Some(SegmentScope::METADATA),
+                        None,
+                        None,
+                    )
+                    .await;
+                let segment = match segments {
+                    Ok(segments) => match segments.into_iter().next() {
+                        Some(segment) => segment,
+                        None => return Err(Box::new(SegmentManagerError::SegmentNotFound)),
+                    },
+                    Err(e) => return Err(Box::new(e)),
+                };
+                self.metadata_segment(&segment).await?
+            }
+        };
+        let mut metadata_segment = metadata_segment.lock().await;
+        metadata_segment.query(where_clause, where_document).await
+    }
+
     pub(crate) async fn get_records(
         &self,
         segment_id: &Uuid,
         ids: Vec<String>,
-    ) -> Result<Vec<Box<VectorEmbeddingRecord>>, &'static str> {
+    ) -> Result<Vec<Box<VectorEmbeddingRecord>>, Box<dyn ChromaError>> {
         // TODO: Load segment if not in cache
         let segment_cache = self.inner.vector_segments.read();
         match segment_cache.get(segment_id) {
-            Some(segment) => {
-                return Ok(segment.get_records(ids));
-            }
-            None => {
-                return Err("No segment found");
-            }
+            Some(segment) => Ok(segment.get_records(ids)),
+            None => Err(Box::new(SegmentManagerError::SegmentNotFound)),
+        }
+    }
+
+    pub(crate) fn distance_function(
+        &self,
+        segment_id: &Uuid,
+    ) -> Result<DistanceFunction, Box<dyn ChromaError>> {
+        let segment_cache = self.inner.vector_segments.read();
+        match segment_cache.get(segment_id) {
+            Some(segment) => Ok(segment.distance_function()),
+            None => Err(Box::new(SegmentManagerError::SegmentNotFound)),
         }
     }
 
@@ -124,12 +274,13 @@ impl SegmentManager {
         vectors: &[f32],
         k: usize,
         include_vector: bool,
-    ) -> Result<Vec<Box<VectorQueryResult>>, &'static str> {
+        allowed_ids: Option<&HashSet<String>>,
+    ) -> Result<Vec<Box<VectorQueryResult>>, Box<dyn ChromaError>> {
         let segment_cache = self.inner.vector_segments.read();
         match segment_cache.get(segment_id) {
             Some(segment) => {
                 let mut results = Vec::new();
-                let (ids, distances) = segment.query(vectors, k);
+                let (ids, distances) = segment.query(vectors, k, allowed_ids);
                 for (id, distance) in ids.iter().zip(distances.iter()) {
                     let fetched_vector = match include_vector {
                         true => Some(segment.get_records(vec![id.clone()])),
@@ -141,7 +292,7 @@ impl SegmentManager {
                         target_record = match fetched_vector {
                             Some(fetched_vectors) => {
                                 if fetched_vectors.len() == 0 {
-                                    return Err("No vector found");
+                                    return Err(Box::new(SegmentManagerError::VectorNotFound));
                                 }
                                 let mut target_vec = None;
                                 for vec in fetched_vectors.into_iter() {
@@ -153,7 +304,7 @@ impl SegmentManager {
                                 target_vec
                             }
                             None => {
-                                return Err("No vector found");
+                                return Err(Box::new(SegmentManagerError::VectorNotFound));
                             }
                         };
                     }
@@ -171,11 +322,9 @@ impl SegmentManager {
                     });
                     results.push(result);
                 }
-                return Ok(results);
-            }
-            None => {
-                return Err("No segment found");
+                Ok(results)
             }
+            None => Err(Box::new(SegmentManagerError::SegmentNotFound)),
         }
     }
 
@@ -210,11 +359,22 @@ impl SegmentManager {
                         Some(collection_uuid.clone()),
                     )
                     .await;
-                match segments {
-                    Ok(segments) => {
+                // Metadata segments of other types are not served by the worker
+                let metadata_segments = self
+                    .sysdb
+                    .get_segments(
+                        None,
+                        Some(BLOCKFILE_METADATA_SEGMENT_TYPE.to_string()),
+                        Some(SegmentScope::METADATA),
+                        None,
+                        Some(*collection_uuid),
+                    )
+                    .await;
+                match (segments, metadata_segments) {
+                    (Ok(segments), Ok(metadata_segments)) => {
                         let mut cache_guard = self.inner.collection_to_segment_cache.write();
                         let mut arc_segments = Vec::new();
-                        for segment in segments {
+                        for segment in segments.into_iter().chain(metadata_segments) {
                             arc_segments.push(Arc::new(segment));
                         }
                         cache_guard.insert(collection_uuid.clone(), arc_segments);
@@ -226,7 +386,7 @@ impl SegmentManager {
                         });
                         return Ok(segments);
                     }
-                    Err(e) => {
+                    _ => {
                         return Err("Failed to get segments for collection from SysDB");
                     }
                 }
@@ -247,6 +407,20 @@ impl Configurable for SegmentManager {
             }
         };
         let path = std::path::Path::new(&worker_config.segment_manager.storage_path);
-        Ok(SegmentManager::new(Box::new(sysdb), path))
+        let hnsw_build_pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(worker_config.segment_manager.hnsw_build_threads)
+            .build();
+        let hnsw_build_pool = match hnsw_build_pool {
+            Ok(hnsw_build_pool) => hnsw_build_pool,
+            Err(err) => {
+                return Err(Box::new(SegmentManagerError::BuildThreadPool(err)));
+            }
+        };
+        Ok(SegmentManager::new(
+            Box::new(sysdb),
+            path,
+            hnsw_build_pool,
+            worker_config.segment_manager.metadata_max_transaction_bytes,
+        ))
     }
 }
@@ -0,0 +1,44 @@
+#[cfg(feature = "query-embedding")]
+use super::embedding::EmbeddingConfig;
+use serde::Deserialize;
+use std::net::IpAddr;
+
+// Matches the default decoding limit applied by tonic.
+const DEFAULT_MAX_REQUEST_SIZE_BYTES: usize = 4 * 1024 * 1024;
+
+/// The configuration for the worker gRPC server.
+/// # Fields
+/// - allowed_ips: The source IPs allowed to call the server. If not provided, all sources are allowed.
+/// - max_request_size_bytes: The maximum size of a decoded request message. Defaults to 4MiB.
+/// - max_embeddings_per_request: The maximum number of query embeddings in a single request. If not provided, there is no limit.
+/// - embedding: The endpoint used to embed query texts, only with the query-embedding feature. If not provided, queries must carry vectors.
+/// # Notes
+/// The whole section is optional, omitting it yields the defaults above.
+#[derive(Deserialize)]
+pub(crate) struct ServerConfig {
+    #[serde(default)]
+    pub(crate) allowed_ips: Option<Vec<IpAddr>>,
+    #[serde(default = "default_max_request_size_bytes")]
+    pub(crate) max_request_size_bytes: usize,
+    #[serde(default)]
+    pub(crate) max_embeddings_per_request: Option<usize>,
+    #[cfg(feature = "query-embedding")]
+    #[serde(default)]
+    pub(crate) embedding: Option<EmbeddingConfig>,
+}
+
+fn default_max_request_size_bytes() -> usize {
+    DEFAULT_MAX_REQUEST_SIZE_BYTES
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        ServerConfig {
+            allowed_ips: None,
+            max_request_size_bytes: DEFAULT_MAX_REQUEST_SIZE_BYTES,
+            max_embeddings_per_request: None,
+            #[cfg(feature = "query-embedding")]
+            embedding: None,
+        }
+    }
+}
@@ -0,0 +1,296 @@
+use crate::errors::{ChromaError, ErrorCodes};
+use async_trait::async_trait;
+use hyper::client::HttpConnector;
+use hyper::{Body, Client, Method, Request, Uri};
+use lru::LruCache;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::num::NonZeroUsize;
+use thiserror::Error;
+
+const DEFAULT_BATCH_SIZE: usize = 32;
+const DEFAULT_CACHE_SIZE: usize = 1024;
+
+/// The configuration of the endpoint used to embed query texts.
+/// # Fields
+/// - url: The HTTP endpoint that embeds texts.
+/// - batch_size: The maximum number of texts sent in a single request. Defaults to 32.
+/// - cache_size: The number of text embeddings kept in memory. Defaults to 1024, 0 disables the cache.
+/// # Notes
+/// The endpoint receives a POST with the JSON body `{"texts": [...]}` and must answer with
+/// `{"embeddings": [[...], ...]}`, one embedding per text in the same order.
+#[derive(Deserialize, Clone, Debug)]
+pub(crate) struct EmbeddingConfig {
+    pub(crate) url: String,
+    #[serde(default = "default_batch_size")]
+    pub(crate) batch_size: usize,
+    #[serde(default = "default_cache_size")]
+    pub(crate) cache_size: usize,
+}
+
+fn default_batch_size() -> usize {
+    DEFAULT_BATCH_SIZE
+}
+
+fn default_cache_size() -> usize {
+    DEFAULT_CACHE_SIZE
+}
+
+#[derive(Error, Debug)]
+pub(crate) enum EmbeddingError {
+    #[error("Invalid embedding endpoint url: {0}")]
+    InvalidUrl(String),
+    #[error("Embedding batch size must be positive")]
+    InvalidBatchSize,
+    #[error("Embedding endpoint request failed: {0}")]
+    Request(String),
+    #[error("Embedding endpoint returned status {0}")]
+    Status(u16),
+    #[error("Invalid embedding endpoint response: {0}")]
+    InvalidResponse(String),
+    #[error("Embedding endpoint returned {1} embeddings for {0} texts")]
+    CountMismatch(usize, usize),
+}
+
+impl ChromaError for EmbeddingError {
+    fn code(&self) -> ErrorCodes {
+        match self {
+            EmbeddingError::InvalidUrl(_) => ErrorCodes::InvalidArgument,
+            EmbeddingError::InvalidBatchSize => ErrorCodes::InvalidArgument,
+            EmbeddingError::Request(_) => ErrorCodes::Unavailable,
+            EmbeddingError::Status(_) => ErrorCodes::Unavailable,
+            EmbeddingError::InvalidResponse(_) => ErrorCodes::Internal,
+            EmbeddingError::CountMismatch(_, _) => ErrorCodes::Internal,
+        }
+    }
+}
+
+/// Embeds a single batch of texts, returning one embedding per text in the same order.
+#[async_trait]
+pub(crate) trait EmbeddingEndpoint: Send + Sync {
+    async fn embed_batch(&self, texts: &[String]) -> Result<Vec<Vec<f32>>, EmbeddingError>;
+}
+
+#[derive(Serialize)]
+struct EmbeddingRequestBody<'a> {
+    texts: &'a [String],
+}
+
+#[derive(Deserialize)]
+struct EmbeddingResponseBody {
+    embeddings: Vec<Vec<f32>>,
+}
+
+pub(crate) struct HttpEmbeddingEndpoint {
+    url: Uri,
+    client: Client<HttpConnector>,
+}
+
+impl HttpEmbeddingEndpoint {
+    pub(crate) fn new(url: &str) -> Result<Self, EmbeddingError> {
+        let url = url
+            .parse::<Uri>()
+            .map_err(|_| EmbeddingError::InvalidUrl(url.to_string()))?;
+        Ok(HttpEmbeddingEndpoint {
+            url,
+            client: Client::new(),
+        })
+    }
+}
+
+#[async_trait]
+impl EmbeddingEndpoint for HttpEmbeddingEndpoint {
+    async fn embed_batch(&self, texts: &[String]) -> Result<Vec<Vec<f32>>, EmbeddingError> {
+        let body = serde_json::to_vec(&EmbeddingRequestBody { texts })
+            .map_err(|e| EmbeddingError::Request(e.to_string()))?;
+        let request = Request::builder()
+            .method(Method::POST)
+            .uri(self.url.clone())
+            .header("content-type", "application/json")
+            .body(Body::from(body))
+            .map_err(|e| EmbeddingError::Request(e.to_string()))?;
+        let response = self
+            .client
+            .request(request)
+            .await
+            .map_err(|e| EmbeddingError::Request(e.to_string()))?;
+        if !response.status().is_success() {
+            return Err(EmbeddingError::Status(response.status().as_u16()));
+        }
+        let bytes = hyper::body::to_bytes(response.into_body())
+            .await
+            .map_err(|e| EmbeddingError::Request(e.to_string()))?;
+        let response: EmbeddingResponseBody = serde_json::from_slice(&bytes)
+            .map_err(|e| EmbeddingError::InvalidResponse(e.to_string()))?;
+        Ok(response.embeddings)
+    }
+}
+
+/// Embeds query texts through an endpoint, batching the requests and caching the results.
+/// # Notes
+/// Texts repeated within a call or found in the cache are only sent to the endpoint once.
+pub(crate) struct EmbeddingFunction {
+    endpoint: Box<dyn EmbeddingEndpoint>,
+    batch_size: usize,
+    cache: Option<Mutex<LruCache<String, Vec<f32>>>>,
+}
+
+impl EmbeddingFunction {
+    pub(crate) fn new(
+        endpoint: Box<dyn EmbeddingEndpoint>,
+        batch_size: usize,
+        cache_size: usize,
+    ) -> Result<Self, EmbeddingError> {
+        if batch_size == 0 {
+            return Err(EmbeddingError::InvalidBatchSize);
+        }
+        let cache = NonZeroUsize::new(cache_size).map(|size| Mutex::new(LruCache::new(size)));
+        Ok(EmbeddingFunction {
+            endpoint,
+            batch_size,
+            cache,
+        })
+    }
+
+    pub(crate) fn from_config(config: &EmbeddingConfig) -> Result<Self, EmbeddingError> {
+        let endpoint = HttpEmbeddingEndpoint::new(&config.url)?;
+        Self::new(Box::new(endpoint), config.batch_size, config.cache_size)
+    }
+
+    pub(crate) async fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>, EmbeddingError> {
+        let mut embeddings: HashMap<String, Vec<f32>> = HashMap::new();
+        let mut missing = Vec::new();
+        for text in texts {
+            if embeddings.contains_key(text) || missing.contains(text) {
+                continue;
+            }
+            let cached = match self.cache {
+                Some(ref cache) => cache.lock().get(text).cloned(),
+                None => None,
+            };
+            match cached {
+                Some(embedding) => {
+                    embeddings.insert(text.clone(), embedding);
+                }
+                None => missing.push(text.clone()),
+            }
+        }
+
+        for batch in missing.chunks(self.batch_size) {
+            let batch_embeddings = self.endpoint.embed_batch(batch).await?;
+            if batch_embeddings.len() != batch.len() {
+                return Err(EmbeddingError::CountMismatch(
+                    batch.len(),
+                    batch_embeddings.len(),
+                ));
+            }
+            for (text, embedding) in batch.iter().zip(batch_embeddings) {
+                if let Some(ref cache) = self.cache {
+                    cache.lock().put(text.clone(), embedding.clone());
+                }
+                embeddings.insert(text.clone(), embedding);
+            }
+        }
+
+        Ok(texts.iter().map(|text| embeddings[text].clone()).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    struct CountingEndpoint {
+        batches: Arc<Mutex<Vec<Vec<String>>>>,
+        fail: bool,
+    }
+
+    #[async_trait]
+    impl EmbeddingEndpoint for CountingEndpoint {
+        async fn embed_batch(&self, texts: &[String]) -> Result<Vec<Vec<f32>>, EmbeddingError> {
+            if self.fail {
+                return Err(EmbeddingError::Status(503));
+            }
+            self.batches.lock().push(texts.to_vec());
+            Ok(texts.iter().map(|t| vec![t.len() as f32, 1.0]).collect())
+        }
+    }
+
+    fn texts(values: &[&str]) -> Vec<String> {
+        values.iter().map(|v| v.to_string()).collect()
+    }
+
+    #[tokio::test]
+    async fn test_embedding_function_batches_and_caches() {
+        let batches = Arc::new(Mutex::new(Vec::new()));
+        let endpoint = CountingEndpoint {
+            batches: batches.clone(),
+            fail: false,
+        };
+        let function = EmbeddingFunction::new(Box::new(endpoint), 2, 16).unwrap();
+
+        let embeddings = function
+            .embed(&texts(&["a", "bb", "a", "ccc", "dddd"]))
+            .await
+            .unwrap();
+        assert_eq!(
+            embeddings,
+            vec![
+                vec![1.0, 1.0],
+                vec![2.0, 1.0],
+                vec![1.0, 1.0],
+                vec![3.0, 1.0],
+                vec![4.0, 1.0]
+            ]
+        );
+        assert_eq!(
+            *batches.lock(),
+            vec![texts(&["a", "bb"]), texts(&["ccc", "dddd"])]
+        );
+
+        // Cached texts are not sent again.
+        let embeddings = function.embed(&texts(&["bb", "eeeee"])).await.unwrap();
+        assert_eq!(embeddings, vec![vec![2.0, 1.0], vec![5.0, 1.0]]);
+        assert_eq!(batches.lock().len(), 3);
+        assert_eq!(batches.lock()[2], texts(&["eeeee"]));
+    }
+
+    #[tokio::test]
+    async fn test_embedding_function_without_cache() {
+        let batches = Arc::new(Mutex::new(Vec::new()));
+        let endpoint = CountingEndpoint {
+            batches: batches.clone(),
+            fail: false,
+        };
+        let function = EmbeddingFunction::new(Box::new(endpoint), 8, 0).unwrap();
+        function.embed(&texts(&["a"])).await.unwrap();
+        function.embed(&texts(&["a"])).await.unwrap();
+        assert_eq!(batches.lock().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_embedding_function_errors() {
+        let endpoint = CountingEndpoint {
+            batches: Arc::new(Mutex::new(Vec::new())),
+            fail: true,
+        };
+        let function = EmbeddingFunction::new(Box::new(endpoint), 8, 8).unwrap();
+        let err = function.embed(&texts(&["a"])).await.unwrap_err();
+        assert_eq!(err.code(), ErrorCodes::Unavailable);
+
+        let endpoint = CountingEndpoint {
+            batches: Arc::new(Mutex::new(Vec::new())),
+            fail: false,
+        };
+        assert!(matches!(
+            EmbeddingFunction::new(Box::new(endpoint), 0, 8),
+            Err(EmbeddingError::InvalidBatchSize)
+        ));
+        assert!(matches!(
+            HttpEmbeddingEndpoint::new("not a url"),
+            Err(EmbeddingError::InvalidUrl(_))
+        ));
+    }
+}
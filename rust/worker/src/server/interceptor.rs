@@ -0,0 +1,73 @@
+use std::collections::HashSet;
+use std::net::IpAddr;
+use tonic::{service::Interceptor, Request, Status};
+
+/// An interceptor that only lets requests from an allowlist of source IPs through.
+/// # Notes
+/// If no allowlist is configured every request is let through. If an allowlist is
+/// configured, requests whose source address cannot be determined are rejected.
+#[derive(Clone)]
+pub(super) struct IpAllowlistInterceptor {
+    allowed_ips: Option<HashSet<IpAddr>>,
+}
+
+impl IpAllowlistInterceptor {
+    pub(super) fn new(allowed_ips: Option<&Vec<IpAddr>>) -> Self {
+        IpAllowlistInterceptor {
+            allowed_ips: allowed_ips.map(|ips| ips.iter().cloned().collect()),
+        }
+    }
+}
+
+impl Interceptor for IpAllowlistInterceptor {
+    fn call(&mut self, request: Request<()>) -> Result<Request<()>, Status> {
+        let allowed_ips = match &self.allowed_ips {
+            Some(allowed_ips) => allowed_ips,
+            None => return Ok(request),
+        };
+        match request.remote_addr() {
+            Some(addr) if allowed_ips.contains(&addr.ip()) => Ok(request),
+            Some(addr) => Err(Status::permission_denied(format!(
+                "Source address {} is not allowed",
+                addr.ip()
+            ))),
+            None => Err(Status::permission_denied("Unknown source address")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::SocketAddr;
+    use tonic::transport::server::TcpConnectInfo;
+
+    fn request_from(addr: &str) -> Request<()> {
+        let mut request = Request::new(());
+        request.extensions_mut().insert(TcpConnectInfo {
+            local_addr: None,
+            remote_addr: Some(addr.parse::<SocketAddr>().unwrap()),
+        });
+        request
+    }
+
+    #[test]
+    fn test_no_allowlist_allows_all() {
+        let mut interceptor = IpAllowlistInterceptor::new(None);
+        assert!(interceptor.call(request_from("10.0.0.1:1234")).is_ok());
+        assert!(interceptor.call(Request::new(())).is_ok());
+    }
+
+    #[test]
+    fn test_allowlist() {
+        let allowed = vec!["10.0.0.1".parse().unwrap()];
+        let mut interceptor = IpAllowlistInterceptor::new(Some(&allowed));
+        assert!(interceptor.call(request_from("10.0.0.1:1234")).is_ok());
+
+        let res = interceptor.call(request_from("10.0.0.2:1234"));
+        assert_eq!(res.unwrap_err().code(), tonic::Code::PermissionDenied);
+
+        let res = interceptor.call(Request::new(()));
+        assert_eq!(res.unwrap_err().code(), tonic::Code::PermissionDenied);
+    }
+}
@@ -0,0 +1,448 @@
+pub(crate) mod config;
+#[cfg(feature = "query-embedding")]
+mod embedding;
+mod interceptor;
+
+use std::f32::consts::E;
+
+use crate::chroma_proto;
+use crate::chroma_proto::{
+    GetVectorsRequest, GetVectorsResponse, QueryVectorsRequest, QueryVectorsResponse,
+};
+use crate::config::{Configurable, WorkerConfig};
+use crate::errors::{ChromaError, ErrorCategory, ErrorCodes};
+use crate::index::DistanceFunction;
+use crate::segment::SegmentManager;
+use crate::server::interceptor::IpAllowlistInterceptor;
+use crate::types::{ScalarEncoding, Where, WhereDocument};
+use async_trait::async_trait;
+use kube::core::request;
+use std::collections::HashSet;
+use std::net::IpAddr;
+use thiserror::Error;
+use tonic::{
+    metadata::MetadataValue, service::interceptor::InterceptedService, transport::Server, Request,
+    Response, Status,
+};
+use uuid::Uuid;
+
+// Tells clients whether retrying the failed request may succeed.
+const RETRIABLE_METADATA_KEY: &str = "chroma-retriable";
+
+/// Converts an error into the status returned to the client.
+/// # Notes
+/// The gRPC code is taken from the error code. User and retriable errors are logged as
+/// warnings and returned with their message, data corruption and bugs are logged as errors
+/// and returned with a generic message so internal details don't leak to clients.
+fn status_from_error(err: &dyn ChromaError) -> Status {
+    let code = tonic::Code::from(err.code() as i32);
+    let category = err.category();
+    let mut status = match category {
+        ErrorCategory::User | ErrorCategory::Retriable => {
+            tracing::warn!("Request failed: {}", err);
+            Status::new(code, err.to_string())
+        }
+        ErrorCategory::DataCorruption | ErrorCategory::Bug => {
+            tracing::error!("Internal error: {}", err);
+            Status::new(code, "Internal error")
+        }
+    };
+    let retriable = match category {
+        ErrorCategory::Retriable => "true",
+        _ => "false",
+    };
+    status.metadata_mut().insert(
+        RETRIABLE_METADATA_KEY,
+        MetadataValue::from_static(retriable),
+    );
+    status
+}
+
+#[derive(Error, Debug)]
+pub(crate) enum QueryVectorError {
+    #[error("Query embedding {0} is all zeros")]
+    ZeroVector(usize),
+    #[error("Query embedding {0} contains a NaN or infinite value")]
+    NonFiniteValue(usize),
+}
+
+impl ChromaError for QueryVectorError {
+    fn code(&self) -> ErrorCodes {
+        match self {
+            QueryVectorError::ZeroVector(_) => ErrorCodes::InvalidArgument,
+            QueryVectorError::NonFiniteValue(_) => ErrorCodes::InvalidArgument,
+        }
+    }
+}
+
+/// Rejects query vectors the distance function cannot order meaningfully: a single NaN or
+/// infinite component poisons every distance it takes part in, and an all-zero vector has
+/// no direction for cosine. Zero vectors are valid queries for the other distances.
+fn validate_query_vector(
+    index: usize,
+    vector: &[f32],
+    distance_function: &DistanceFunction,
+) -> Result<(), QueryVectorError> {
+    if vector.iter().any(|value| !value.is_finite()) {
+        return Err(QueryVectorError::NonFiniteValue(index));
+    }
+    if let DistanceFunction::Cosine = distance_function {
+        if vector.iter().all(|value| *value == 0.0) {
+            return Err(QueryVectorError::ZeroVector(index));
+        }
+    }
+    Ok(())
+}
+
+pub struct WorkerServer {
+    segment_manager: Option<SegmentManager>,
+    port: u16,
+    allowed_ips: Option<Vec<IpAddr>>,
+    max_request_size_bytes: usize,
+    max_embeddings_per_request: Option<usize>,
+    #[cfg(feature = "query-embedding")]
+    embedding_function: Option<embedding::EmbeddingFunction>,
+}
+
+#[async_trait]
+impl Configurable for WorkerServer {
+    async fn try_from_config(config: &WorkerConfig) -> Result<Self, Box<dyn ChromaError>> {
+        #[cfg(feature = "query-embedding")]
+        let embedding_function = match config.server.embedding {
+            Some(ref embedding_config) => {
+                match embedding::EmbeddingFunction::from_config(embedding_config) {
+                    Ok(embedding_function) => Some(embedding_function),
+                    Err(e) => return Err(Box::new(e)),
+                }
+            }
+            None => None,
+        };
+        Ok(WorkerServer {
+            segment_manager: None,
+            port: config.my_port,
+            allowed_ips: config.server.allowed_ips.clone(),
+            max_request_size_bytes: config.server.max_request_size_bytes,
+            max_embeddings_per_request: config.server.max_embeddings_per_request,
+            #[cfg(feature = "query-embedding")]
+            embedding_function,
+        })
+    }
+}
+
+impl WorkerServer {
+    pub(crate) async fn run(worker: WorkerServer) -> Result<(), Box<dyn std::error::Error>> {
+        let addr = format!("[::]:{}", worker.port).parse().unwrap();
+        println!("Worker listening on {}", addr);
+        let interceptor = IpAllowlistInterceptor::new(worker.allowed_ips.as_ref());
+        let max_request_size_bytes = worker.max_request_size_bytes;
+        let service = chroma_proto::vector_reader_server::VectorReaderServer::new(worker)
+            .max_decoding_message_size(max_request_size_bytes);
+        let server = Server::builder()
+            .add_service(InterceptedService::new(service, interceptor))
+            .serve(addr)
+            .await?;
+        println!("Worker shutting down");
+
+        Ok(())
+    }
+
+    pub(crate) fn set_segment_manager(&mut self, segment_manager: SegmentManager) {
+        self.segment_manager = Some(segment_manager);
+    }
+
+    // Embeds query texts with the configured embedding function.
+    #[cfg(feature = "query-embedding")]
+    async fn embed_query_texts(&self, texts: &[String]) -> Result<Vec<Vec<f32>>, Status> {
+        let embedding_function = match self.embedding_function {
+            Some(ref embedding_function) => embedding_function,
+            None => {
+                return Err(Status::invalid_argument(
+                    "No embedding function configured for query texts",
+                ));
+            }
+        };
+        match embedding_function.embed(texts).await {
+            Ok(embeddings) => Ok(embeddings),
+            Err(e) => Err(status_from_error(&e)),
+        }
+    }
+
+    #[cfg(not(feature = "query-embedding"))]
+    async fn embed_query_texts(&self, _texts: &[String]) -> Result<Vec<Vec<f32>>, Status> {
+        Err(Status::invalid_argument(
+            "Query texts are not supported, query embeddings are required",
+        ))
+    }
+}
+
+#[tonic::async_trait]
+impl chroma_proto::vector_reader_server::VectorReader for WorkerServer {
+    async fn get_vectors(
+        &self,
+        request: Request<GetVectorsRequest>,
+    ) -> Result<Response<GetVectorsResponse>, Status> {
+        let request = request.into_inner();
+        let segment_uuid = match Uuid::parse_str(&request.segment_id) {
+            Ok(uuid) => uuid,
+            Err(_) => {
+                return Err(Status::invalid_argument("Invalid UUID"));
+            }
+        };
+
+        let segment_manager = match self.segment_manager {
+            Some(ref segment_manager) => segment_manager,
+            None => {
+                return Err(Status::internal("No segment manager found"));
+            }
+        };
+
+        let records = match segment_manager
+            .get_records(&segment_uuid, request.ids)
+            .await
+        {
+            Ok(records) => records,
+            Err(e) => {
+                return Err(status_from_error(e.as_ref()));
+            }
+        };
+
+        let mut proto_records = Vec::new();
+        for record in records {
+            let sed_id_bytes = record.seq_id.to_bytes_le();
+            let dim = record.vector.len();
+            let proto_vector = (record.vector, ScalarEncoding::FLOAT32, dim).try_into();
+            match proto_vector {
+                Ok(proto_vector) => {
+                    let proto_record = chroma_proto::VectorEmbeddingRecord {
+                        id: record.id,
+                        seq_id: sed_id_bytes.1,
+                        vector: Some(proto_vector),
+                    };
+                    proto_records.push(proto_record);
+                }
+                Err(e) => {
+                    return Err(Status::internal(format!("Error converting vector: {}", e)));
+                }
+            }
+        }
+
+        let resp = chroma_proto::GetVectorsResponse {
+            records: proto_records,
+        };
+
+        Ok(Response::new(resp))
+    }
+
+    async fn query_vectors(
+        &self,
+        request: Request<QueryVectorsRequest>,
+    ) -> Result<Response<QueryVectorsResponse>, Status> {
+        let request = request.into_inner();
+        let segment_uuid = match Uuid::parse_str(&request.segment_id) {
+            Ok(uuid) => uuid,
+            Err(_) => {
+                return Err(Status::invalid_argument("Invalid Segment UUID"));
+            }
+        };
+
+        // Query texts are only embedded when the request carries no vectors.
+        let embed_texts = request.vectors.is_empty() && !request.query_texts.is_empty();
+        let num_queries = match embed_texts {
+            true => request.query_texts.len(),
+            false => request.vectors.len(),
+        };
+        match self.max_embeddings_per_request {
+            Some(max_embeddings) if num_queries > max_embeddings => {
+                return Err(Status::resource_exhausted(format!(
+                    "Too many query embeddings: {} > {}",
+                    num_queries, max_embeddings
+                )));
+            }
+            _ => {}
+        }
+
+        let segment_manager = match self.segment_manager {
+            Some(ref segment_manager) => segment_manager,
+            None => {
+                return Err(Status::internal("No segment manager found"));
+            }
+        };
+
+        let mut query_vectors = Vec::with_capacity(num_queries);
+        if embed_texts {
+            query_vectors = self.embed_query_texts(&request.query_texts).await?;
+        } else {
+            for proto_query_vector in request.vectors {
+                let (query_vector, _encoding): (Vec<f32>, ScalarEncoding) =
+                    match proto_query_vector.try_into() {
+                        Ok((vector, encoding)) => (vector, encoding),
+                        Err(e) => {
+                            return Err(status_from_error(&e));
+                        }
+                    };
+                query_vectors.push(query_vector);
+            }
+        }
+        let distance_function = match segment_manager.distance_function(&segment_uuid) {
+            Ok(distance_function) => distance_function,
+            Err(e) => {
+                return Err(status_from_error(e.as_ref()));
+            }
+        };
+        for (index, query_vector) in query_vectors.iter().enumerate() {
+            if let Err(e) = validate_query_vector(index, query_vector, &distance_function) {
+                return Err(status_from_error(&e));
+            }
+        }
+
+        let allowed_ids = match (request.r#where, request.where_document) {
+            (None, None) => None,
+            (proto_where, proto_where_document) => {
+                let metadata_segment_uuid = match Uuid::parse_str(&request.metadata_segment_id) {
+                    Ok(uuid) => uuid,
+                    Err(_) => {
+                        return Err(Status::invalid_argument("Invalid Metadata Segment UUID"));
+                    }
+                };
+                let where_clause: Option<Where> = match proto_where.map(Where::try_from) {
+                    Some(Ok(where_clause)) => Some(where_clause),
+                    Some(Err(e)) => return Err(status_from_error(&e)),
+                    None => None,
+                };
+                let where_document: Option<WhereDocument> =
+                    match proto_where_document.map(WhereDocument::try_from) {
+                        Some(Ok(where_document)) => Some(where_document),
+                        Some(Err(e)) => return Err(status_from_error(&e)),
+                        None => None,
+                    };
+                match segment_manager
+                    .query_metadata(
+                        &metadata_segment_uuid,
+                        where_clause.as_ref(),
+                        where_document.as_ref(),
+                    )
+                    .await
+                {
+                    Ok(ids) => Some(ids.into_iter().collect::<HashSet<String>>()),
+                    Err(e) => {
+                        return Err(status_from_error(e.as_ref()));
+                    }
+                }
+            }
+        };
+
+        let mut proto_results_for_all = Vec::new();
+        for query_vector in query_vectors {
+            let results = match segment_manager
+                .query_vector(
+                    &segment_uuid,
+                    &query_vector,
+                    request.k as usize,
+                    request.include_embeddings,
+                    allowed_ids.as_ref(),
+                )
+                .await
+            {
+                Ok(results) => results,
+                Err(e) => {
+                    return Err(status_from_error(e.as_ref()));
+                }
+            };
+
+            let mut proto_results = Vec::new();
+            for query_result in results {
+                let proto_result = chroma_proto::VectorQueryResult {
+                    id: query_result.id,
+                    seq_id: query_result.seq_id.to_bytes_le().1,
+                    distance: query_result.distance,
+                    vector: match query_result.vector {
+                        Some(vector) => {
+                            match (vector, ScalarEncoding::FLOAT32, query_vector.len()).try_into() {
+                                Ok(proto_vector) => Some(proto_vector),
+                                Err(e) => {
+                                    return Err(Status::internal(format!(
+                                        "Error converting vector: {}",
+                                        e
+                                    )));
+                                }
+                            }
+                        }
+                        None => None,
+                    },
+                };
+                proto_results.push(proto_result);
+            }
+
+            let vector_query_results = chroma_proto::VectorQueryResults {
+                results: proto_results,
+            };
+            proto_results_for_all.push(vector_query_results);
+        }
+
+        let resp = chroma_proto::QueryVectorsResponse {
+            results: proto_results_for_all,
+        };
+
+        return Ok(Response::new(resp));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::segment::SegmentManagerError;
+    use crate::types::{ConversionError, VectorConversionError};
+
+    #[test]
+    fn test_status_from_user_error() {
+        let status = status_from_error(&SegmentManagerError::SegmentNotFound);
+        assert_eq!(status.code(), tonic::Code::NotFound);
+        assert_eq!(status.message(), "No segment found");
+        assert_eq!(
+            status.metadata().get(RETRIABLE_METADATA_KEY).unwrap(),
+            "false"
+        );
+
+        let status = status_from_error(&SegmentManagerError::VectorNotFound);
+        assert_eq!(status.code(), tonic::Code::NotFound);
+        assert_eq!(status.message(), "No vector found");
+
+        let status = status_from_error(&VectorConversionError::InvalidByteLength);
+        assert_eq!(status.code(), tonic::Code::InvalidArgument);
+    }
+
+    #[test]
+    fn test_validate_query_vector() {
+        let cosine = DistanceFunction::Cosine;
+        assert!(validate_query_vector(0, &[0.5, 0.0, -1.0], &cosine).is_ok());
+
+        let err = validate_query_vector(1, &[0.0, 0.0, 0.0], &cosine).unwrap_err();
+        assert!(matches!(err, QueryVectorError::ZeroVector(1)));
+        let status = status_from_error(&err);
+        assert_eq!(status.code(), tonic::Code::InvalidArgument);
+        assert_eq!(status.message(), "Query embedding 1 is all zeros");
+        // Zero vectors only lack a direction for cosine
+        for distance_function in [DistanceFunction::Euclidean, DistanceFunction::InnerProduct] {
+            assert!(validate_query_vector(1, &[0.0, 0.0, 0.0], &distance_function).is_ok());
+        }
+
+        for distance_function in [
+            DistanceFunction::Euclidean,
+            DistanceFunction::Cosine,
+            DistanceFunction::InnerProduct,
+        ] {
+            let err = validate_query_vector(2, &[1.0, f32::NAN], &distance_function).unwrap_err();
+            assert!(matches!(err, QueryVectorError::NonFiniteValue(2)));
+            let err =
+                validate_query_vector(2, &[f32::INFINITY, 1.0], &distance_function).unwrap_err();
+            assert!(matches!(err, QueryVectorError::NonFiniteValue(2)));
+        }
+    }
+
+    #[test]
+    fn test_status_from_internal_error() {
+        let status = status_from_error(&ConversionError::DecodeError);
+        assert_eq!(status.code(), tonic::Code::Internal);
+        assert_eq!(status.message(), "Internal error");
+    }
+}
@@ -0,0 +1,282 @@
+// Protects a storage backend so that an outage fails fast instead of every caller
+// waiting on its own timeout against a backend that is known to be down.
+
+use super::config::CircuitBreakerConfig;
+use parking_lot::Mutex;
+use std::future::Future;
+use std::time::{Duration, Instant};
+
+#[derive(Debug, PartialEq)]
+enum BreakerState {
+    // Requests flow through, counting consecutive failures.
+    Closed { consecutive_failures: u32 },
+    // Requests fail fast until the cooldown has elapsed.
+    Open { opened_at: Instant },
+    // A single probe request is in flight, all others fail fast.
+    HalfOpen,
+}
+
+/// The error of a request made through a circuit breaker.
+/// # Variants
+/// - Backend: The backend failed or could not be reached, e.g. a transport error, a timeout
+/// or a 5xx response. Counts as a failure of the backend.
+/// - Request: The request failed for a reason that says nothing about the health of the
+/// backend, e.g. a missing key or a local file that could not be written.
+#[derive(Debug)]
+pub(super) enum RequestError {
+    Backend(String),
+    Request(String),
+}
+
+/// A circuit breaker guarding the requests made to a storage backend.
+/// # Fields
+/// - failure_threshold: The number of consecutive failures after which the breaker opens
+/// - cooldown: How long the breaker stays open before a probe request is let through
+/// # Notes
+/// Only backend errors count as failures, a request that failed with any other error still
+/// got an answer from the backend and counts as a success.
+/// While the breaker is open every request fails immediately with an error that says so.
+/// After the cooldown one request is let through as a probe, if it succeeds the breaker
+/// closes, otherwise it opens again for another cooldown.
+pub(super) struct CircuitBreaker {
+    failure_threshold: u32,
+    cooldown: Duration,
+    state: Mutex<BreakerState>,
+}
+
+impl CircuitBreaker {
+    pub(super) fn new(failure_threshold: u32, cooldown: Duration) -> Self {
+        CircuitBreaker {
+            failure_threshold,
+            cooldown,
+            state: Mutex::new(BreakerState::Closed {
+                consecutive_failures: 0,
+            }),
+        }
+    }
+
+    pub(super) fn from_config(config: &CircuitBreakerConfig) -> Self {
+        Self::new(
+            config.failure_threshold,
+            Duration::from_millis(config.cooldown_ms),
+        )
+    }
+
+    /// Runs the request unless the breaker is open, and records whether it succeeded.
+    pub(super) async fn call<T, F>(&self, request: F) -> Result<T, String>
+    where
+        F: Future<Output = Result<T, RequestError>>,
+    {
+        let guard = self.before_request()?;
+        let res = request.await;
+        guard.complete(!matches!(res, Err(RequestError::Backend(_))));
+        match res {
+            Ok(value) => Ok(value),
+            Err(RequestError::Backend(e)) | Err(RequestError::Request(e)) => Err(e),
+        }
+    }
+
+    // Returns an error if the request should fail fast.
+    fn before_request(&self) -> Result<RequestGuard<'_>, String> {
+        let mut state = self.state.lock();
+        match *state {
+            BreakerState::Closed { .. } => Ok(RequestGuard::new(self, false)),
+            BreakerState::Open { opened_at } => {
+                if opened_at.elapsed() >= self.cooldown {
+                    *state = BreakerState::HalfOpen;
+                    Ok(RequestGuard::new(self, true))
+                } else {
+                    Err("Storage circuit breaker is open".to_string())
+                }
+            }
+            BreakerState::HalfOpen => {
+                Err("Storage circuit breaker is open, probing recovery".to_string())
+            }
+        }
+    }
+
+    fn after_request(&self, succeeded: bool) {
+        let mut state = self.state.lock();
+        if succeeded {
+            *state = BreakerState::Closed {
+                consecutive_failures: 0,
+            };
+            return;
+        }
+        let consecutive_failures = match *state {
+            BreakerState::Closed {
+                consecutive_failures,
+            } => consecutive_failures + 1,
+            // A failed probe, or a request that started before the breaker opened.
+            BreakerState::Open { .. } | BreakerState::HalfOpen => self.failure_threshold,
+        };
+        if consecutive_failures >= self.failure_threshold {
+            *state = BreakerState::Open {
+                opened_at: Instant::now(),
+            };
+        } else {
+            *state = BreakerState::Closed {
+                consecutive_failures,
+            };
+        }
+    }
+}
+
+// A request let through by the breaker. The request future may be dropped before the
+// request completes, in which case a probe puts the breaker back into the open state
+// rather than leaving it half open, failing every request, forever.
+struct RequestGuard<'a> {
+    breaker: &'a CircuitBreaker,
+    probe: bool,
+    completed: bool,
+}
+
+impl<'a> RequestGuard<'a> {
+    fn new(breaker: &'a CircuitBreaker, probe: bool) -> Self {
+        RequestGuard {
+            breaker,
+            probe,
+            completed: false,
+        }
+    }
+
+    fn complete(mut self, succeeded: bool) {
+        self.completed = true;
+        self.breaker.after_request(succeeded);
+    }
+}
+
+impl Drop for RequestGuard<'_> {
+    fn drop(&mut self) {
+        if self.probe && !self.completed {
+            *self.breaker.state.lock() = BreakerState::Open {
+                opened_at: Instant::now(),
+            };
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::FutureExt;
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+    struct FlakyBackend {
+        failing: AtomicBool,
+        hanging: AtomicBool,
+        rejecting: AtomicBool,
+        calls: AtomicUsize,
+    }
+
+    impl FlakyBackend {
+        fn new() -> Self {
+            FlakyBackend {
+                failing: AtomicBool::new(true),
+                hanging: AtomicBool::new(false),
+                rejecting: AtomicBool::new(false),
+                calls: AtomicUsize::new(0),
+            }
+        }
+
+        async fn request(&self) -> Result<(), RequestError> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            if self.hanging.load(Ordering::SeqCst) {
+                std::future::pending::<()>().await;
+            }
+            if self.rejecting.load(Ordering::SeqCst) {
+                Err(RequestError::Request("not found".to_string()))
+            } else if self.failing.load(Ordering::SeqCst) {
+                Err(RequestError::Backend("unavailable".to_string()))
+            } else {
+                Ok(())
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_circuit_breaker_opens_and_recovers() {
+        let backend = FlakyBackend::new();
+        let breaker = CircuitBreaker::new(2, Duration::from_millis(50));
+
+        assert!(breaker.call(backend.request()).await.is_err());
+        assert!(breaker.call(backend.request()).await.is_err());
+        assert_eq!(backend.calls.load(Ordering::SeqCst), 2);
+
+        // The breaker is open, requests fail without reaching the backend.
+        assert!(breaker.call(backend.request()).await.is_err());
+        assert_eq!(backend.calls.load(Ordering::SeqCst), 2);
+
+        // A failed probe opens the breaker again.
+        tokio::time::sleep(Duration::from_millis(60)).await;
+        assert!(breaker.call(backend.request()).await.is_err());
+        assert_eq!(backend.calls.load(Ordering::SeqCst), 3);
+        assert!(breaker.call(backend.request()).await.is_err());
+        assert_eq!(backend.calls.load(Ordering::SeqCst), 3);
+
+        // A successful probe closes the breaker.
+        backend.failing.store(false, Ordering::SeqCst);
+        tokio::time::sleep(Duration::from_millis(60)).await;
+        assert!(breaker.call(backend.request()).await.is_ok());
+        assert!(breaker.call(backend.request()).await.is_ok());
+        assert_eq!(backend.calls.load(Ordering::SeqCst), 5);
+    }
+
+    #[tokio::test]
+    async fn test_circuit_breaker_resets_on_success() {
+        let backend = FlakyBackend::new();
+        let breaker = CircuitBreaker::new(2, Duration::from_secs(60));
+
+        assert!(breaker.call(backend.request()).await.is_err());
+        backend.failing.store(false, Ordering::SeqCst);
+        assert!(breaker.call(backend.request()).await.is_ok());
+        backend.failing.store(true, Ordering::SeqCst);
+        assert!(breaker.call(backend.request()).await.is_err());
+        // Only one consecutive failure, the breaker is still closed.
+        assert!(breaker.call(backend.request()).await.is_err());
+        assert_eq!(backend.calls.load(Ordering::SeqCst), 4);
+    }
+
+    #[tokio::test]
+    async fn test_circuit_breaker_ignores_request_errors() {
+        let backend = FlakyBackend::new();
+        let breaker = CircuitBreaker::new(2, Duration::from_millis(50));
+        backend.rejecting.store(true, Ordering::SeqCst);
+        for _ in 0..3 {
+            let res = breaker.call(backend.request()).await;
+            assert_eq!(res.unwrap_err(), "not found");
+        }
+        assert!(matches!(*breaker.state.lock(), BreakerState::Closed { .. }));
+
+        // A probe answered with a request error shows the backend is reachable again.
+        backend.rejecting.store(false, Ordering::SeqCst);
+        assert!(breaker.call(backend.request()).await.is_err());
+        assert!(breaker.call(backend.request()).await.is_err());
+        assert!(matches!(*breaker.state.lock(), BreakerState::Open { .. }));
+        backend.rejecting.store(true, Ordering::SeqCst);
+        tokio::time::sleep(Duration::from_millis(60)).await;
+        assert!(breaker.call(backend.request()).await.is_err());
+        assert!(matches!(*breaker.state.lock(), BreakerState::Closed { .. }));
+        assert_eq!(backend.calls.load(Ordering::SeqCst), 6);
+    }
+
+    #[tokio::test]
+    async fn test_circuit_breaker_dropped_probe() {
+        let backend = FlakyBackend::new();
+        let breaker = CircuitBreaker::new(1, Duration::from_millis(50));
+        assert!(breaker.call(backend.request()).await.is_err());
+
+        // The probe is dropped before it completes, the breaker opens again.
+        backend.hanging.store(true, Ordering::SeqCst);
+        tokio::time::sleep(Duration::from_millis(60)).await;
+        assert!(breaker.call(backend.request()).now_or_never().is_none());
+        assert!(matches!(*breaker.state.lock(), BreakerState::Open { .. }));
+
+        // After another cooldown the next probe is let through.
+        backend.hanging.store(false, Ordering::SeqCst);
+        backend.failing.store(false, Ordering::SeqCst);
+        tokio::time::sleep(Duration::from_millis(60)).await;
+        assert!(breaker.call(backend.request()).await.is_ok());
+        assert_eq!(backend.calls.load(Ordering::SeqCst), 3);
+    }
+}
@@ -15,6 +15,29 @@ pub(crate) enum StorageConfig {
 /// The configuration for the s3 storage type
 /// # Fields
 /// - bucket: The name of the bucket to use.
+/// - circuit_breaker: The circuit breaker settings for the bucket. If not provided, the defaults are used.
 pub(crate) struct S3StorageConfig {
     pub(crate) bucket: String,
+    #[serde(default)]
+    pub(crate) circuit_breaker: CircuitBreakerConfig,
+}
+
+#[derive(Deserialize)]
+#[serde(default)]
+/// The configuration for a storage circuit breaker
+/// # Fields
+/// - failure_threshold: The number of consecutive failures after which requests fail fast. Defaults to 5.
+/// - cooldown_ms: How long requests fail fast before a probe request is let through. Defaults to 10000.
+pub(crate) struct CircuitBreakerConfig {
+    pub(crate) failure_threshold: u32,
+    pub(crate) cooldown_ms: u64,
+}
+
+impl Default for CircuitBreakerConfig {
+    fn default() -> Self {
+        CircuitBreakerConfig {
+            failure_threshold: 5,
+            cooldown_ms: 10000,
+        }
+    }
 }
@@ -8,6 +8,7 @@
 // Once we move to our own implementation of hnswlib we can support
 // streaming from s3.
 
+use super::circuit_breaker::{CircuitBreaker, RequestError};
 use super::{config::StorageConfig, Storage};
 use crate::config::{Configurable, WorkerConfig};
 use crate::errors::ChromaError;
@@ -18,18 +19,25 @@ use aws_sdk_s3::operation::create_bucket::CreateBucketError;
 use aws_smithy_types::byte_stream::ByteStream;
 use std::clone::Clone;
 use std::io::Write;
+use std::sync::Arc;
 
+// Clones share the circuit breaker, since they talk to the same bucket. While the breaker
+// is open requests fail fast with an error saying so. Storage keeps no copies of its own,
+// data that is cached is cached by the callers, e.g. in the block cache, and served from
+// there without reaching storage at all.
 #[derive(Clone)]
 struct S3Storage {
     bucket: String,
     client: aws_sdk_s3::Client,
+    breaker: Arc<CircuitBreaker>,
 }
 
 impl S3Storage {
-    fn new(bucket: &str, client: aws_sdk_s3::Client) -> S3Storage {
+    fn new(bucket: &str, client: aws_sdk_s3::Client, breaker: CircuitBreaker) -> S3Storage {
         return S3Storage {
             bucket: bucket.to_string(),
             client: client,
+            breaker: Arc::new(breaker),
         };
     }
 
@@ -80,7 +88,8 @@ impl Configurable for S3Storage {
                 let config = aws_config::load_from_env().await;
                 let client = aws_sdk_s3::Client::new(&config);
 
-                let storage = S3Storage::new(&s3_config.bucket, client);
+                let breaker = CircuitBreaker::from_config(&s3_config.circuit_breaker);
+                let storage = S3Storage::new(&s3_config.bucket, client, breaker);
                 return Ok(storage);
             }
         }
@@ -90,7 +99,16 @@ impl Configurable for S3Storage {
 #[async_trait]
 impl Storage for S3Storage {
     async fn get(&self, key: &str, path: &str) -> Result<(), String> {
-        let mut file = std::fs::File::create(path);
+        self.breaker.call(self.get_object(key, path)).await
+    }
+
+    async fn put(&self, key: &str, path: &str) -> Result<(), String> {
+        self.breaker.call(self.put_object(key, path)).await
+    }
+}
+
+impl S3Storage {
+    async fn get_object(&self, key: &str, path: &str) -> Result<(), RequestError> {
         let res = self
             .client
             .get_object()
@@ -98,43 +116,37 @@ impl Storage for S3Storage {
             .key(key)
             .send()
             .await;
-        match res {
-            Ok(mut res) => {
-                match file {
-                    Ok(mut file) => {
-                        while let bytes = res.body.next().await {
-                            match bytes {
-                                Some(bytes) => match bytes {
-                                    Ok(bytes) => {
-                                        file.write_all(&bytes).unwrap();
-                                    }
-                                    Err(e) => {
-                                        println!("error: {}", e);
-                                        return Err::<(), String>(e.to_string());
-                                    }
-                                },
-                                None => {
-                                    // Stream is done
-                                    return Ok(());
-                                }
-                            }
-                        }
-                    }
-                    Err(e) => {
+        let mut res = match res {
+            Ok(res) => res,
+            Err(e) => return Err(request_error(e)),
+        };
+        // The file is only created once the object was found, so that a failed request
+        // does not truncate a file already at the path.
+        let mut file = match std::fs::File::create(path) {
+            Ok(file) => file,
+            Err(e) => {
+                println!("error: {}", e);
+                return Err(RequestError::Request(e.to_string()));
+            }
+        };
+        while let Some(bytes) = res.body.next().await {
+            match bytes {
+                Ok(bytes) => {
+                    if let Err(e) = file.write_all(&bytes) {
                         println!("error: {}", e);
-                        return Err::<(), String>(e.to_string());
+                        return Err(RequestError::Request(e.to_string()));
                     }
                 }
-                return Ok(());
-            }
-            Err(e) => {
-                println!("error: {}", e);
-                return Err::<(), String>(e.to_string());
+                Err(e) => {
+                    println!("error: {}", e);
+                    return Err(RequestError::Backend(e.to_string()));
+                }
             }
         }
+        Ok(())
     }
 
-    async fn put(&self, key: &str, path: &str) -> Result<(), String> {
+    async fn put_object(&self, key: &str, path: &str) -> Result<(), RequestError> {
         // Puts from a file on disk to s3.
         let bytestream = ByteStream::from_path(path).await;
         match bytestream {
@@ -150,22 +162,35 @@ impl Storage for S3Storage {
                 match res {
                     Ok(_) => {
                         println!("put object {} to bucket {}", key, self.bucket);
-                        return Ok(());
-                    }
-                    Err(e) => {
-                        println!("error: {}", e);
-                        return Err::<(), String>(e.to_string());
+                        Ok(())
                     }
+                    Err(e) => Err(request_error(e)),
                 }
             }
             Err(e) => {
                 println!("error: {}", e);
-                return Err::<(), String>(e.to_string());
+                Err(RequestError::Request(e.to_string()))
             }
         }
     }
 }
 
+// Transport failures, timeouts and 5xx responses mean that the backend is unhealthy. Any
+// other error response, e.g. a missing key, was answered by a healthy backend.
+fn request_error<E>(err: SdkError<E>) -> RequestError {
+    println!("error: {}", err);
+    let backend_failure = match &err {
+        SdkError::ConstructionFailure(_) => false,
+        SdkError::ServiceError(context) => context.raw().status().is_server_error(),
+        _ => true,
+    };
+    if backend_failure {
+        RequestError::Backend(err.to_string())
+    } else {
+        RequestError::Request(err.to_string())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -193,10 +218,11 @@ mod tests {
             .build();
         let client = aws_sdk_s3::Client::from_conf(config);
 
-        let storage = S3Storage {
-            bucket: "test".to_string(),
-            client: client,
-        };
+        let storage = S3Storage::new(
+            "test",
+            client,
+            CircuitBreaker::from_config(&Default::default()),
+        );
         storage.create_bucket().await.unwrap();
 
         // Write some data to a test file, put it in s3, get it back and verify its contents
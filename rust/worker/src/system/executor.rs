@@ -4,10 +4,106 @@ use super::{
     system::System,
     Component,
 };
+use crate::errors::{ChromaError, ErrorCodes};
 use crate::system::ComponentContext;
-use std::sync::Arc;
+use futures::FutureExt;
+use std::any::Any;
+use std::backtrace::Backtrace;
+use std::cell::{Cell, RefCell};
+use std::panic::AssertUnwindSafe;
+use std::sync::{Arc, Once};
+use thiserror::Error;
 use tokio::select;
 
+const MAX_PANIC_BACKTRACE_LINES: usize = 32;
+
+thread_local! {
+    static CAPTURE_PANIC_BACKTRACE: Cell<bool> = Cell::new(false);
+    static LAST_PANIC_BACKTRACE: RefCell<Option<String>> = RefCell::new(None);
+}
+
+static INSTALL_PANIC_HOOK: Once = Once::new();
+
+/// Installs a panic hook that records the backtrace of the panicking thread so that
+/// the executor can attach it to the error after catching the unwind. Backtraces are
+/// only captured while a handler is being polled, other panics go straight to the
+/// previous hook, which is always called.
+fn install_panic_hook() {
+    INSTALL_PANIC_HOOK.call_once(|| {
+        let previous_hook = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |info| {
+            if CAPTURE_PANIC_BACKTRACE.with(|capture| capture.get()) {
+                let backtrace = Backtrace::force_capture().to_string();
+                LAST_PANIC_BACKTRACE.with(|last| *last.borrow_mut() = Some(backtrace));
+            }
+            previous_hook(info);
+        }));
+    });
+}
+
+/// Marks the current thread as running a handler until dropped, so that panics on it
+/// have their backtrace captured.
+struct HandlerPanicScope;
+
+impl HandlerPanicScope {
+    fn enter() -> Self {
+        CAPTURE_PANIC_BACKTRACE.with(|capture| capture.set(true));
+        HandlerPanicScope
+    }
+}
+
+impl Drop for HandlerPanicScope {
+    fn drop(&mut self) {
+        CAPTURE_PANIC_BACKTRACE.with(|capture| capture.set(false));
+    }
+}
+
+/// The error produced when a component handler panics while handling a message.
+/// # Fields
+/// - component: The type name of the component whose handler panicked
+/// - message: The panic payload, if it was a string
+/// - backtrace: The first lines of the backtrace captured at the panic site
+#[derive(Debug, Error)]
+#[error("Handler for {component} panicked: {message}")]
+pub(crate) struct HandlerPanicError {
+    pub(crate) component: String,
+    pub(crate) message: String,
+    pub(crate) backtrace: String,
+}
+
+impl HandlerPanicError {
+    fn new<C>(payload: Box<dyn Any + Send>) -> Self {
+        let message = match payload.downcast_ref::<&str>() {
+            Some(message) => message.to_string(),
+            None => match payload.downcast_ref::<String>() {
+                Some(message) => message.clone(),
+                None => "unknown panic payload".to_string(),
+            },
+        };
+        let backtrace = LAST_PANIC_BACKTRACE
+            .with(|last| last.borrow_mut().take())
+            .map(|backtrace| {
+                backtrace
+                    .lines()
+                    .take(MAX_PANIC_BACKTRACE_LINES)
+                    .collect::<Vec<&str>>()
+                    .join("\n")
+            })
+            .unwrap_or_default();
+        HandlerPanicError {
+            component: std::any::type_name::<C>().to_string(),
+            message,
+            backtrace,
+        }
+    }
+}
+
+impl ChromaError for HandlerPanicError {
+    fn code(&self) -> ErrorCodes {
+        ErrorCodes::Internal
+    }
+}
+
 struct Inner<C>
 where
     C: Component,
@@ -22,6 +118,13 @@ where
 /// # Description
 /// The executor holds the context for a components execution and is responsible for
 /// running the components handler methods
+/// # Notes
+/// A panic in a handler is caught and converted into a HandlerPanicError so that a single
+/// bad message does not take down the component. The component keeps handling messages
+/// afterwards, so handlers should not leave their state inconsistent across await points.
+/// Messages are fire and forget, there is no responder to route the error to. The panicking
+/// handler drops its message, so a reply channel carried in the message is closed and the
+/// caller waiting on it fails instead of waiting forever. The error itself is logged.
 pub(super) struct ComponentExecutor<C>
 where
     C: Component,
@@ -41,6 +144,7 @@ where
         system: System,
         scheduler: Scheduler,
     ) -> Self {
+        install_panic_hook();
         ComponentExecutor {
             inner: Arc::new(Inner {
                 sender,
@@ -61,14 +165,27 @@ where
                 message = channel.recv() => {
                     match message {
                         Some(mut message) => {
-                            message.handle(&mut self.handler,
-                                &ComponentContext{
-                                    system: self.inner.system.clone(),
-                                    sender: self.inner.sender.clone(),
-                                    cancellation_token: self.inner.cancellation_token.clone(),
-                                    scheduler: self.inner.scheduler.clone(),
-                                }
-                            ).await;
+                            let ctx = ComponentContext{
+                                system: self.inner.system.clone(),
+                                sender: self.inner.sender.clone(),
+                                cancellation_token: self.inner.cancellation_token.clone(),
+                                scheduler: self.inner.scheduler.clone(),
+                            };
+                            let mut handle = std::pin::pin!(
+                                AssertUnwindSafe(message.handle(&mut self.handler, &ctx))
+                                    .catch_unwind()
+                            );
+                            // The handler may move between threads across await points, so
+                            // the scope is entered for every poll rather than once.
+                            let res = std::future::poll_fn(|cx| {
+                                let _scope = HandlerPanicScope::enter();
+                                handle.as_mut().poll(cx)
+                            })
+                            .await;
+                            if let Err(payload) = res {
+                                let err = HandlerPanicError::new::<C>(payload);
+                                tracing::error!("{}\n{}", err, err.backtrace);
+                            }
                         }
                         None => {
                             // TODO: Log error
@@ -79,3 +196,75 @@ where
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::system::{Component, Handler, System};
+    use async_trait::async_trait;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use tokio::sync::Notify;
+
+    #[derive(Debug)]
+    struct PanickingComponent {
+        counter: Arc<AtomicUsize>,
+        handled: Arc<Notify>,
+    }
+
+    #[async_trait]
+    impl Handler<usize> for PanickingComponent {
+        async fn handle(&mut self, message: usize, _ctx: &ComponentContext<Self>) -> () {
+            if message == 0 {
+                panic!("zero is not allowed");
+            }
+            self.counter.fetch_add(message, Ordering::SeqCst);
+            self.handled.notify_one();
+        }
+    }
+
+    impl Component for PanickingComponent {
+        fn queue_size(&self) -> usize {
+            10
+        }
+    }
+
+    #[tokio::test]
+    async fn test_handler_panic_does_not_stop_component() {
+        let mut system = System::new();
+        let counter = Arc::new(AtomicUsize::new(0));
+        let handled = Arc::new(Notify::new());
+        let component = PanickingComponent {
+            counter: counter.clone(),
+            handled: handled.clone(),
+        };
+        let mut handle = system.start_component(component);
+        let receiver = handle.receiver::<usize>();
+        receiver.send(1).await.unwrap();
+        receiver.send(0).await.unwrap();
+        handled.notified().await;
+        receiver.send(2).await.unwrap();
+        handled.notified().await;
+        assert_eq!(counter.load(Ordering::SeqCst), 3);
+        handle.stop();
+    }
+
+    #[test]
+    fn test_handler_panic_error() {
+        install_panic_hook();
+        let payload = {
+            let _scope = HandlerPanicScope::enter();
+            std::panic::catch_unwind(|| panic!("boom")).unwrap_err()
+        };
+        let err = HandlerPanicError::new::<PanickingComponent>(payload);
+        assert_eq!(err.message, "boom");
+        assert!(err.component.ends_with("PanickingComponent"));
+        assert!(err.backtrace.lines().count() <= MAX_PANIC_BACKTRACE_LINES);
+        assert!(!err.backtrace.is_empty());
+        assert_eq!(err.code(), ErrorCodes::Internal);
+
+        // Panics outside of a handler don't capture a backtrace
+        let payload = std::panic::catch_unwind(|| panic!("boom")).unwrap_err();
+        let err = HandlerPanicError::new::<PanickingComponent>(payload);
+        assert!(err.backtrace.is_empty());
+    }
+}
@@ -7,6 +7,7 @@ mod operation;
 mod scalar_encoding;
 mod segment;
 mod segment_scope;
+mod where_filter;
 
 // Re-export the types module, so that we can use it as a single import in other modules.
 pub use collection::*;
@@ -17,3 +18,4 @@ pub use scalar_encoding::*;
 pub use segment::*;
 pub use segment_scope::*;
 pub use types::*;
+pub use where_filter::*;
@@ -6,9 +6,13 @@ use crate::{
 use thiserror::Error;
 use uuid::Uuid;
 
+// The type of the metadata segments the worker indexes in blockfiles.
+pub(crate) const BLOCKFILE_METADATA_SEGMENT_TYPE: &str = "urn:chroma:segment/metadata/blockfile";
+
 #[derive(Clone, Debug, PartialEq)]
 pub(crate) enum SegmentType {
     HnswDistributed,
+    BlockfileMetadata,
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -74,6 +78,7 @@ impl TryFrom<chroma_proto::Segment> for Segment {
 
         let segment_type = match proto_segment.r#type.as_str() {
             "urn:chroma:segment/vector/hnsw-distributed" => SegmentType::HnswDistributed,
+            BLOCKFILE_METADATA_SEGMENT_TYPE => SegmentType::BlockfileMetadata,
             _ => {
                 return Err(SegmentConversionError::InvalidUuid);
             }
@@ -0,0 +1,744 @@
+use super::ConversionError;
+use crate::{
+    chroma_proto,
+    errors::{ChromaError, ErrorCodes},
+};
+use thiserror::Error;
+
+/*
+===========================================
+Where
+===========================================
+*/
+
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum BooleanOperator {
+    And,
+    Or,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum WhereClauseComparator {
+    Equal,
+    NotEqual,
+    GreaterThan,
+    GreaterThanOrEqual,
+    LessThan,
+    LessThanOrEqual,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum WhereClauseListOperator {
+    In,
+    NotIn,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum WhereComparison {
+    SingleString(String, WhereClauseComparator),
+    SingleInt(i64, WhereClauseComparator),
+    SingleDouble(f64, WhereClauseComparator),
+    SingleBool(bool, WhereClauseComparator),
+    StringList(Vec<String>, WhereClauseListOperator),
+    IntList(Vec<i64>, WhereClauseListOperator),
+    DoubleList(Vec<f64>, WhereClauseListOperator),
+    BoolList(Vec<bool>, WhereClauseListOperator),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct DirectComparison {
+    pub(crate) key: String,
+    pub(crate) comparison: WhereComparison,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct WhereChildren {
+    pub(crate) children: Vec<Where>,
+    pub(crate) operator: BooleanOperator,
+}
+
+/// A where clause over record metadata.
+/// # Variants
+/// - DirectComparison: A comparison of a single metadata key against one value or a list of values.
+/// - WhereChildren: A boolean combination ($and / $or) of child where clauses.
+/// - Not: The negation ($not) of a child where clause.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum Where {
+    DirectComparison(DirectComparison),
+    WhereChildren(WhereChildren),
+    Not(Box<Where>),
+}
+
+#[derive(Error, Debug)]
+pub(crate) enum WhereConversionError {
+    #[error("Where clause is empty")]
+    MissingClause,
+    #[error("Comparison on key `{0}` has no operand")]
+    MissingComparison(String),
+    #[error("Comparison on key `{0}` has no comparator")]
+    MissingComparator(String),
+    #[error("Comparison on key `{0}` has an empty list of values")]
+    EmptyList(String),
+    #[error("$and and $or must have at least one child clause")]
+    EmptyChildren,
+    #[error(transparent)]
+    DecodeError(#[from] ConversionError),
+}
+
+impl_base_convert_error!(WhereConversionError, {
+    WhereConversionError::MissingClause => ErrorCodes::InvalidArgument,
+    WhereConversionError::MissingComparison(_) => ErrorCodes::InvalidArgument,
+    WhereConversionError::MissingComparator(_) => ErrorCodes::InvalidArgument,
+    WhereConversionError::EmptyList(_) => ErrorCodes::InvalidArgument,
+    WhereConversionError::EmptyChildren => ErrorCodes::InvalidArgument,
+});
+
+impl TryFrom<i32> for BooleanOperator {
+    type Error = WhereConversionError;
+
+    fn try_from(operator: i32) -> Result<Self, Self::Error> {
+        match chroma_proto::BooleanOperator::try_from(operator) {
+            Ok(chroma_proto::BooleanOperator::And) => Ok(BooleanOperator::And),
+            Ok(chroma_proto::BooleanOperator::Or) => Ok(BooleanOperator::Or),
+            Err(_) => Err(WhereConversionError::DecodeError(
+                ConversionError::DecodeError,
+            )),
+        }
+    }
+}
+
+fn generic_comparator_try_from(
+    comparator: i32,
+) -> Result<WhereClauseComparator, WhereConversionError> {
+    match chroma_proto::GenericComparator::try_from(comparator) {
+        Ok(chroma_proto::GenericComparator::Eq) => Ok(WhereClauseComparator::Equal),
+        Ok(chroma_proto::GenericComparator::Ne) => Ok(WhereClauseComparator::NotEqual),
+        Err(_) => Err(WhereConversionError::DecodeError(
+            ConversionError::DecodeError,
+        )),
+    }
+}
+
+fn number_comparator_try_from(
+    comparator: i32,
+) -> Result<WhereClauseComparator, WhereConversionError> {
+    match chroma_proto::NumberComparator::try_from(comparator) {
+        Ok(chroma_proto::NumberComparator::Gt) => Ok(WhereClauseComparator::GreaterThan),
+        Ok(chroma_proto::NumberComparator::Gte) => Ok(WhereClauseComparator::GreaterThanOrEqual),
+        Ok(chroma_proto::NumberComparator::Lt) => Ok(WhereClauseComparator::LessThan),
+        Ok(chroma_proto::NumberComparator::Lte) => Ok(WhereClauseComparator::LessThanOrEqual),
+        Err(_) => Err(WhereConversionError::DecodeError(
+            ConversionError::DecodeError,
+        )),
+    }
+}
+
+impl TryFrom<i32> for WhereClauseListOperator {
+    type Error = WhereConversionError;
+
+    fn try_from(operator: i32) -> Result<Self, Self::Error> {
+        match chroma_proto::ListOperator::try_from(operator) {
+            Ok(chroma_proto::ListOperator::In) => Ok(WhereClauseListOperator::In),
+            Ok(chroma_proto::ListOperator::Nin) => Ok(WhereClauseListOperator::NotIn),
+            Err(_) => Err(WhereConversionError::DecodeError(
+                ConversionError::DecodeError,
+            )),
+        }
+    }
+}
+
+impl TryFrom<chroma_proto::DirectComparison> for DirectComparison {
+    type Error = WhereConversionError;
+
+    fn try_from(proto_comparison: chroma_proto::DirectComparison) -> Result<Self, Self::Error> {
+        use chroma_proto::direct_comparison::Comparison;
+        use chroma_proto::single_double_comparison::Comparator as DoubleComparator;
+        use chroma_proto::single_int_comparison::Comparator as IntComparator;
+
+        let key = proto_comparison.key;
+        let comparison = match proto_comparison.comparison {
+            Some(Comparison::SingleStringOperand(operand)) => WhereComparison::SingleString(
+                operand.value,
+                generic_comparator_try_from(operand.comparator)?,
+            ),
+            Some(Comparison::SingleIntOperand(operand)) => {
+                let comparator = match operand.comparator {
+                    Some(IntComparator::GenericComparator(c)) => generic_comparator_try_from(c)?,
+                    Some(IntComparator::NumberComparator(c)) => number_comparator_try_from(c)?,
+                    None => return Err(WhereConversionError::MissingComparator(key)),
+                };
+                WhereComparison::SingleInt(operand.value, comparator)
+            }
+            Some(Comparison::SingleDoubleOperand(operand)) => {
+                let comparator = match operand.comparator {
+                    Some(DoubleComparator::GenericComparator(c)) => generic_comparator_try_from(c)?,
+                    Some(DoubleComparator::NumberComparator(c)) => number_comparator_try_from(c)?,
+                    None => return Err(WhereConversionError::MissingComparator(key)),
+                };
+                WhereComparison::SingleDouble(operand.value, comparator)
+            }
+            Some(Comparison::SingleBoolOperand(operand)) => WhereComparison::SingleBool(
+                operand.value,
+                generic_comparator_try_from(operand.comparator)?,
+            ),
+            Some(Comparison::StringListOperand(operand)) => {
+                if operand.values.is_empty() {
+                    return Err(WhereConversionError::EmptyList(key));
+                }
+                WhereComparison::StringList(operand.values, operand.list_operator.try_into()?)
+            }
+            Some(Comparison::IntListOperand(operand)) => {
+                if operand.values.is_empty() {
+                    return Err(WhereConversionError::EmptyList(key));
+                }
+                WhereComparison::IntList(operand.values, operand.list_operator.try_into()?)
+            }
+            Some(Comparison::DoubleListOperand(operand)) => {
+                if operand.values.is_empty() {
+                    return Err(WhereConversionError::EmptyList(key));
+                }
+                WhereComparison::DoubleList(operand.values, operand.list_operator.try_into()?)
+            }
+            Some(Comparison::BoolListOperand(operand)) => {
+                if operand.values.is_empty() {
+                    return Err(WhereConversionError::EmptyList(key));
+                }
+                WhereComparison::BoolList(operand.values, operand.list_operator.try_into()?)
+            }
+            None => return Err(WhereConversionError::MissingComparison(key)),
+        };
+        Ok(DirectComparison { key, comparison })
+    }
+}
+
+impl TryFrom<chroma_proto::Where> for Where {
+    type Error = WhereConversionError;
+
+    fn try_from(proto_where: chroma_proto::Where) -> Result<Self, Self::Error> {
+        match proto_where.clause {
+            Some(chroma_proto::r#where::Clause::DirectComparison(comparison)) => {
+                Ok(Where::DirectComparison(comparison.try_into()?))
+            }
+            Some(chroma_proto::r#where::Clause::Children(children)) => {
+                if children.children.is_empty() {
+                    return Err(WhereConversionError::EmptyChildren);
+                }
+                let operator = children.operator.try_into()?;
+                let children = children
+                    .children
+                    .into_iter()
+                    .map(Where::try_from)
+                    .collect::<Result<Vec<Where>, WhereConversionError>>()?;
+                Ok(Where::WhereChildren(WhereChildren { children, operator }))
+            }
+            Some(chroma_proto::r#where::Clause::NotClause(child)) => {
+                Ok(Where::Not(Box::new((*child).try_into()?)))
+            }
+            None => Err(WhereConversionError::MissingClause),
+        }
+    }
+}
+
+/*
+===========================================
+WhereDocument
+===========================================
+*/
+
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum WhereDocumentOperator {
+    Contains,
+    NotContains,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct DirectDocumentComparison {
+    pub(crate) document: String,
+    pub(crate) operator: WhereDocumentOperator,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct WhereDocumentChildren {
+    pub(crate) children: Vec<WhereDocument>,
+    pub(crate) operator: BooleanOperator,
+}
+
+/// A where clause over record documents.
+/// # Variants
+/// - DirectDocumentComparison: A $contains / $not_contains check against the document.
+/// - WhereDocumentChildren: A boolean combination ($and / $or) of child where_document clauses.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum WhereDocument {
+    DirectDocumentComparison(DirectDocumentComparison),
+    WhereDocumentChildren(WhereDocumentChildren),
+}
+
+impl TryFrom<i32> for WhereDocumentOperator {
+    type Error = WhereConversionError;
+
+    fn try_from(operator: i32) -> Result<Self, Self::Error> {
+        match chroma_proto::WhereDocumentOperator::try_from(operator) {
+            Ok(chroma_proto::WhereDocumentOperator::Contains) => {
+                Ok(WhereDocumentOperator::Contains)
+            }
+            Ok(chroma_proto::WhereDocumentOperator::NotContains) => {
+                Ok(WhereDocumentOperator::NotContains)
+            }
+            Err(_) => Err(WhereConversionError::DecodeError(
+                ConversionError::DecodeError,
+            )),
+        }
+    }
+}
+
+impl TryFrom<chroma_proto::WhereDocument> for WhereDocument {
+    type Error = WhereConversionError;
+
+    fn try_from(proto_where_document: chroma_proto::WhereDocument) -> Result<Self, Self::Error> {
+        match proto_where_document.clause {
+            Some(chroma_proto::where_document::Clause::Direct(direct)) => Ok(
+                WhereDocument::DirectDocumentComparison(DirectDocumentComparison {
+                    document: direct.document,
+                    operator: direct.operator.try_into()?,
+                }),
+            ),
+            Some(chroma_proto::where_document::Clause::Children(children)) => {
+                if children.children.is_empty() {
+                    return Err(WhereConversionError::EmptyChildren);
+                }
+                let operator = children.operator.try_into()?;
+                let children = children
+                    .children
+                    .into_iter()
+                    .map(WhereDocument::try_from)
+                    .collect::<Result<Vec<WhereDocument>, WhereConversionError>>()?;
+                Ok(WhereDocument::WhereDocumentChildren(
+                    WhereDocumentChildren { children, operator },
+                ))
+            }
+            None => Err(WhereConversionError::MissingClause),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chroma_proto::direct_comparison::Comparison;
+
+    fn proto_direct(key: &str, comparison: Comparison) -> chroma_proto::Where {
+        chroma_proto::Where {
+            clause: Some(chroma_proto::r#where::Clause::DirectComparison(
+                chroma_proto::DirectComparison {
+                    key: key.to_string(),
+                    comparison: Some(comparison),
+                },
+            )),
+        }
+    }
+
+    fn convert_direct(comparison: Comparison) -> WhereComparison {
+        let converted: Where = proto_direct("key", comparison).try_into().unwrap();
+        match converted {
+            Where::DirectComparison(direct) => {
+                assert_eq!(direct.key, "key");
+                direct.comparison
+            }
+            _ => panic!("Expected a direct comparison"),
+        }
+    }
+
+    #[test]
+    fn test_where_single_string_try_from() {
+        for (proto_comparator, comparator) in [
+            (
+                chroma_proto::GenericComparator::Eq,
+                WhereClauseComparator::Equal,
+            ),
+            (
+                chroma_proto::GenericComparator::Ne,
+                WhereClauseComparator::NotEqual,
+            ),
+        ] {
+            let comparison = convert_direct(Comparison::SingleStringOperand(
+                chroma_proto::SingleStringComparison {
+                    value: "value".to_string(),
+                    comparator: proto_comparator as i32,
+                },
+            ));
+            assert_eq!(
+                comparison,
+                WhereComparison::SingleString("value".to_string(), comparator)
+            );
+        }
+    }
+
+    #[test]
+    fn test_where_single_bool_try_from() {
+        for (proto_comparator, comparator) in [
+            (
+                chroma_proto::GenericComparator::Eq,
+                WhereClauseComparator::Equal,
+            ),
+            (
+                chroma_proto::GenericComparator::Ne,
+                WhereClauseComparator::NotEqual,
+            ),
+        ] {
+            let comparison = convert_direct(Comparison::SingleBoolOperand(
+                chroma_proto::SingleBoolComparison {
+                    value: true,
+                    comparator: proto_comparator as i32,
+                },
+            ));
+            assert_eq!(comparison, WhereComparison::SingleBool(true, comparator));
+        }
+    }
+
+    #[test]
+    fn test_where_single_number_try_from() {
+        use chroma_proto::single_double_comparison::Comparator as DoubleComparator;
+        use chroma_proto::single_int_comparison::Comparator as IntComparator;
+
+        let cases = [
+            (
+                IntComparator::GenericComparator(chroma_proto::GenericComparator::Eq as i32),
+                DoubleComparator::GenericComparator(chroma_proto::GenericComparator::Eq as i32),
+                WhereClauseComparator::Equal,
+            ),
+            (
+                IntComparator::GenericComparator(chroma_proto::GenericComparator::Ne as i32),
+                DoubleComparator::GenericComparator(chroma_proto::GenericComparator::Ne as i32),
+                WhereClauseComparator::NotEqual,
+            ),
+            (
+                IntComparator::NumberComparator(chroma_proto::NumberComparator::Gt as i32),
+                DoubleComparator::NumberComparator(chroma_proto::NumberComparator::Gt as i32),
+                WhereClauseComparator::GreaterThan,
+            ),
+            (
+                IntComparator::NumberComparator(chroma_proto::NumberComparator::Gte as i32),
+                DoubleComparator::NumberComparator(chroma_proto::NumberComparator::Gte as i32),
+                WhereClauseComparator::GreaterThanOrEqual,
+            ),
+            (
+                IntComparator::NumberComparator(chroma_proto::NumberComparator::Lt as i32),
+                DoubleComparator::NumberComparator(chroma_proto::NumberComparator::Lt as i32),
+                WhereClauseComparator::LessThan,
+            ),
+            (
+                IntComparator::NumberComparator(chroma_proto::NumberComparator::Lte as i32),
+                DoubleComparator::NumberComparator(chroma_proto::NumberComparator::Lte as i32),
+                WhereClauseComparator::LessThanOrEqual,
+            ),
+        ];
+        for (int_comparator, double_comparator, comparator) in cases {
+            let comparison = convert_direct(Comparison::SingleIntOperand(
+                chroma_proto::SingleIntComparison {
+                    value: 42,
+                    comparator: Some(int_comparator),
+                },
+            ));
+            assert_eq!(
+                comparison,
+                WhereComparison::SingleInt(42, comparator.clone())
+            );
+
+            let comparison = convert_direct(Comparison::SingleDoubleOperand(
+                chroma_proto::SingleDoubleComparison {
+                    value: 4.2,
+                    comparator: Some(double_comparator),
+                },
+            ));
+            assert_eq!(comparison, WhereComparison::SingleDouble(4.2, comparator));
+        }
+    }
+
+    #[test]
+    fn test_where_list_try_from() {
+        for (proto_operator, operator) in [
+            (chroma_proto::ListOperator::In, WhereClauseListOperator::In),
+            (
+                chroma_proto::ListOperator::Nin,
+                WhereClauseListOperator::NotIn,
+            ),
+        ] {
+            let comparison = convert_direct(Comparison::StringListOperand(
+                chroma_proto::StringListComparison {
+                    values: vec!["a".to_string(), "b".to_string()],
+                    list_operator: proto_operator as i32,
+                },
+            ));
+            assert_eq!(
+                comparison,
+                WhereComparison::StringList(
+                    vec!["a".to_string(), "b".to_string()],
+                    operator.clone()
+                )
+            );
+
+            let comparison = convert_direct(Comparison::IntListOperand(
+                chroma_proto::IntListComparison {
+                    values: vec![1, 2],
+                    list_operator: proto_operator as i32,
+                },
+            ));
+            assert_eq!(
+                comparison,
+                WhereComparison::IntList(vec![1, 2], operator.clone())
+            );
+
+            let comparison = convert_direct(Comparison::DoubleListOperand(
+                chroma_proto::DoubleListComparison {
+                    values: vec![1.5, 2.5],
+                    list_operator: proto_operator as i32,
+                },
+            ));
+            assert_eq!(
+                comparison,
+                WhereComparison::DoubleList(vec![1.5, 2.5], operator.clone())
+            );
+
+            let comparison = convert_direct(Comparison::BoolListOperand(
+                chroma_proto::BoolListComparison {
+                    values: vec![true, false],
+                    list_operator: proto_operator as i32,
+                },
+            ));
+            assert_eq!(
+                comparison,
+                WhereComparison::BoolList(vec![true, false], operator)
+            );
+        }
+    }
+
+    #[test]
+    fn test_where_children_try_from() {
+        let proto_where = chroma_proto::Where {
+            clause: Some(chroma_proto::r#where::Clause::Children(
+                chroma_proto::WhereChildren {
+                    children: vec![
+                        proto_direct(
+                            "a",
+                            Comparison::SingleStringOperand(chroma_proto::SingleStringComparison {
+                                value: "value".to_string(),
+                                comparator: chroma_proto::GenericComparator::Eq as i32,
+                            }),
+                        ),
+                        chroma_proto::Where {
+                            clause: Some(chroma_proto::r#where::Clause::Children(
+                                chroma_proto::WhereChildren {
+                                    children: vec![proto_direct(
+                                        "b",
+                                        Comparison::IntListOperand(
+                                            chroma_proto::IntListComparison {
+                                                values: vec![1],
+                                                list_operator: chroma_proto::ListOperator::In
+                                                    as i32,
+                                            },
+                                        ),
+                                    )],
+                                    operator: chroma_proto::BooleanOperator::Or as i32,
+                                },
+                            )),
+                        },
+                    ],
+                    operator: chroma_proto::BooleanOperator::And as i32,
+                },
+            )),
+        };
+        let converted: Where = proto_where.try_into().unwrap();
+        assert_eq!(
+            converted,
+            Where::WhereChildren(WhereChildren {
+                children: vec![
+                    Where::DirectComparison(DirectComparison {
+                        key: "a".to_string(),
+                        comparison: WhereComparison::SingleString(
+                            "value".to_string(),
+                            WhereClauseComparator::Equal
+                        ),
+                    }),
+                    Where::WhereChildren(WhereChildren {
+                        children: vec![Where::DirectComparison(DirectComparison {
+                            key: "b".to_string(),
+                            comparison: WhereComparison::IntList(
+                                vec![1],
+                                WhereClauseListOperator::In
+                            ),
+                        })],
+                        operator: BooleanOperator::Or,
+                    }),
+                ],
+                operator: BooleanOperator::And,
+            })
+        );
+    }
+
+    #[test]
+    fn test_where_not_try_from() {
+        use chroma_proto::single_int_comparison::Comparator as IntComparator;
+
+        let proto_where = chroma_proto::Where {
+            clause: Some(chroma_proto::r#where::Clause::NotClause(Box::new(
+                proto_direct(
+                    "a",
+                    Comparison::SingleIntOperand(chroma_proto::SingleIntComparison {
+                        value: 1,
+                        comparator: Some(IntComparator::NumberComparator(
+                            chroma_proto::NumberComparator::Gt as i32,
+                        )),
+                    }),
+                ),
+            ))),
+        };
+        let converted: Where = proto_where.try_into().unwrap();
+        assert_eq!(
+            converted,
+            Where::Not(Box::new(Where::DirectComparison(DirectComparison {
+                key: "a".to_string(),
+                comparison: WhereComparison::SingleInt(1, WhereClauseComparator::GreaterThan),
+            })))
+        );
+
+        let res = Where::try_from(chroma_proto::Where {
+            clause: Some(chroma_proto::r#where::Clause::NotClause(Box::new(
+                chroma_proto::Where { clause: None },
+            ))),
+        });
+        assert!(matches!(res, Err(WhereConversionError::MissingClause)));
+    }
+
+    #[test]
+    fn test_where_invalid_try_from() {
+        let res = Where::try_from(chroma_proto::Where { clause: None });
+        assert!(matches!(res, Err(WhereConversionError::MissingClause)));
+
+        let res = Where::try_from(chroma_proto::Where {
+            clause: Some(chroma_proto::r#where::Clause::DirectComparison(
+                chroma_proto::DirectComparison {
+                    key: "key".to_string(),
+                    comparison: None,
+                },
+            )),
+        });
+        assert!(matches!(
+            res,
+            Err(WhereConversionError::MissingComparison(_))
+        ));
+
+        let res = Where::try_from(proto_direct(
+            "key",
+            Comparison::SingleIntOperand(chroma_proto::SingleIntComparison {
+                value: 1,
+                comparator: None,
+            }),
+        ));
+        assert!(matches!(
+            res,
+            Err(WhereConversionError::MissingComparator(_))
+        ));
+
+        let res = Where::try_from(proto_direct(
+            "key",
+            Comparison::StringListOperand(chroma_proto::StringListComparison {
+                values: vec![],
+                list_operator: chroma_proto::ListOperator::In as i32,
+            }),
+        ));
+        assert!(matches!(res, Err(WhereConversionError::EmptyList(_))));
+
+        let res = Where::try_from(proto_direct(
+            "key",
+            Comparison::BoolListOperand(chroma_proto::BoolListComparison {
+                values: vec![],
+                list_operator: chroma_proto::ListOperator::In as i32,
+            }),
+        ));
+        assert!(matches!(res, Err(WhereConversionError::EmptyList(_))));
+
+        let res = Where::try_from(proto_direct(
+            "key",
+            Comparison::SingleBoolOperand(chroma_proto::SingleBoolComparison {
+                value: true,
+                comparator: 42,
+            }),
+        ));
+        assert!(matches!(res, Err(WhereConversionError::DecodeError(_))));
+
+        let res = Where::try_from(proto_direct(
+            "key",
+            Comparison::SingleStringOperand(chroma_proto::SingleStringComparison {
+                value: "value".to_string(),
+                comparator: 42,
+            }),
+        ));
+        assert!(matches!(res, Err(WhereConversionError::DecodeError(_))));
+
+        let res = Where::try_from(chroma_proto::Where {
+            clause: Some(chroma_proto::r#where::Clause::Children(
+                chroma_proto::WhereChildren {
+                    children: vec![],
+                    operator: chroma_proto::BooleanOperator::And as i32,
+                },
+            )),
+        });
+        assert!(matches!(res, Err(WhereConversionError::EmptyChildren)));
+        assert_eq!(res.unwrap_err().code(), ErrorCodes::InvalidArgument);
+    }
+
+    #[test]
+    fn test_where_document_try_from() {
+        let direct = |document: &str, operator: chroma_proto::WhereDocumentOperator| {
+            chroma_proto::WhereDocument {
+                clause: Some(chroma_proto::where_document::Clause::Direct(
+                    chroma_proto::DirectWhereDocument {
+                        document: document.to_string(),
+                        operator: operator as i32,
+                    },
+                )),
+            }
+        };
+        let proto_where_document = chroma_proto::WhereDocument {
+            clause: Some(chroma_proto::where_document::Clause::Children(
+                chroma_proto::WhereDocumentChildren {
+                    children: vec![
+                        direct("foo", chroma_proto::WhereDocumentOperator::Contains),
+                        direct("bar", chroma_proto::WhereDocumentOperator::NotContains),
+                    ],
+                    operator: chroma_proto::BooleanOperator::Or as i32,
+                },
+            )),
+        };
+        let converted: WhereDocument = proto_where_document.try_into().unwrap();
+        assert_eq!(
+            converted,
+            WhereDocument::WhereDocumentChildren(WhereDocumentChildren {
+                children: vec![
+                    WhereDocument::DirectDocumentComparison(DirectDocumentComparison {
+                        document: "foo".to_string(),
+                        operator: WhereDocumentOperator::Contains,
+                    }),
+                    WhereDocument::DirectDocumentComparison(DirectDocumentComparison {
+                        document: "bar".to_string(),
+                        operator: WhereDocumentOperator::NotContains,
+                    }),
+                ],
+                operator: BooleanOperator::Or,
+            })
+        );
+
+        let res = WhereDocument::try_from(chroma_proto::WhereDocument { clause: None });
+        assert!(matches!(res, Err(WhereConversionError::MissingClause)));
+
+        let res = WhereDocument::try_from(chroma_proto::WhereDocument {
+            clause: Some(chroma_proto::where_document::Clause::Children(
+                chroma_proto::WhereDocumentChildren {
+                    children: vec![],
+                    operator: chroma_proto::BooleanOperator::And as i32,
+                },
+            )),
+        });
+        assert!(matches!(res, Err(WhereConversionError::EmptyChildren)));
+    }
+}